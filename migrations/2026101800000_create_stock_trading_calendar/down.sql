@@ -0,0 +1,3 @@
+-- stock_trading_calendar 在本迁移之前已存在于生产库，回滚只撤销本迁移新增的索引，保留表与数据
+DROP INDEX IF EXISTS idx_stock_trading_calendar_holiday;
+DROP INDEX IF EXISTS idx_stock_trading_calendar_trade_date;
@@ -0,0 +1,23 @@
+-- 交易日历表：每个自然日一行，is_holiday 标记休市（周末 / 法定节假日）
+CREATE TABLE IF NOT EXISTS stock_trading_calendar (
+    id SERIAL PRIMARY KEY,
+    trade_date DATE NOT NULL,
+    is_holiday BOOLEAN NOT NULL DEFAULT FALSE,
+    holiday_name VARCHAR(50),
+    created_at TIMESTAMP DEFAULT NOW(),
+    updated_at TIMESTAMP DEFAULT NOW()
+);
+
+-- 导入节假日文件时按 trade_date 做 upsert，需要唯一索引
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stock_trading_calendar_trade_date
+    ON stock_trading_calendar(trade_date);
+CREATE INDEX IF NOT EXISTS idx_stock_trading_calendar_holiday
+    ON stock_trading_calendar(is_holiday, trade_date);
+
+COMMENT ON TABLE stock_trading_calendar IS 'A股交易日历表';
+COMMENT ON COLUMN stock_trading_calendar.id IS '主键';
+COMMENT ON COLUMN stock_trading_calendar.trade_date IS '自然日';
+COMMENT ON COLUMN stock_trading_calendar.is_holiday IS '是否休市（周末或法定节假日）';
+COMMENT ON COLUMN stock_trading_calendar.holiday_name IS '休市原因，如 周末 / 春节';
+COMMENT ON COLUMN stock_trading_calendar.created_at IS '创建时间';
+COMMENT ON COLUMN stock_trading_calendar.updated_at IS '更新时间';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_stock_snapshots_session_created_at;
+ALTER TABLE stock_snapshots DROP COLUMN IF EXISTS session;
@@ -0,0 +1,19 @@
+-- 快照所属交易时段：pre_open_auction / continuous / lunch_break / closing_auction / closed
+ALTER TABLE stock_snapshots ADD COLUMN IF NOT EXISTS session VARCHAR(20);
+
+-- 按上海时间「时:分」回填历史快照（与程序中 MarketSession::at 的划分一致）
+UPDATE stock_snapshots
+SET session = CASE
+    WHEN to_char(created_at AT TIME ZONE 'Asia/Shanghai', 'HH24:MI') BETWEEN '09:15' AND '09:29' THEN 'pre_open_auction'
+    WHEN to_char(created_at AT TIME ZONE 'Asia/Shanghai', 'HH24:MI') BETWEEN '09:30' AND '11:30' THEN 'continuous'
+    WHEN to_char(created_at AT TIME ZONE 'Asia/Shanghai', 'HH24:MI') BETWEEN '11:31' AND '12:59' THEN 'lunch_break'
+    WHEN to_char(created_at AT TIME ZONE 'Asia/Shanghai', 'HH24:MI') BETWEEN '13:00' AND '14:56' THEN 'continuous'
+    WHEN to_char(created_at AT TIME ZONE 'Asia/Shanghai', 'HH24:MI') BETWEEN '14:57' AND '15:00' THEN 'closing_auction'
+    ELSE 'closed'
+END
+WHERE session IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_stock_snapshots_session_created_at
+    ON stock_snapshots(session, created_at);
+
+COMMENT ON COLUMN stock_snapshots.session IS '抓取时所处交易时段：pre_open_auction 开盘集合竞价 / continuous 连续竞价 / lunch_break 午间休市 / closing_auction 收盘集合竞价 / closed 非交易时段';
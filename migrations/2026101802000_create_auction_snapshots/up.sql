@@ -0,0 +1,32 @@
+-- 集合竞价快照：开盘集合竞价阶段按分钟采样的撮合数据
+CREATE TABLE IF NOT EXISTS auction_snapshots (
+    id SERIAL PRIMARY KEY,
+    trade_date DATE NOT NULL,
+    sample_time TIME NOT NULL,
+    stock_code VARCHAR(10) NOT NULL,
+    stock_name VARCHAR(50) NOT NULL,
+    matched_price NUMERIC(10,2) NOT NULL,
+    prev_close NUMERIC(10,2) NOT NULL,
+    change_pct NUMERIC(8,2) NOT NULL,
+    matched_volume BIGINT NOT NULL DEFAULT 0,
+    matched_amount NUMERIC(18,2) NOT NULL DEFAULT 0,
+    unmatched_volume BIGINT,
+    universe VARCHAR(20) NOT NULL DEFAULT 'market',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_auction_snapshots_date_time_code
+    ON auction_snapshots(trade_date, sample_time, stock_code);
+CREATE INDEX IF NOT EXISTS idx_auction_snapshots_code_date
+    ON auction_snapshots(stock_code, trade_date);
+
+COMMENT ON TABLE auction_snapshots IS '开盘集合竞价快照（9:15-9:25 采样）';
+COMMENT ON COLUMN auction_snapshots.trade_date IS '交易日';
+COMMENT ON COLUMN auction_snapshots.sample_time IS '采样时刻（上海时间，精确到分钟）';
+COMMENT ON COLUMN auction_snapshots.matched_price IS '竞价匹配价';
+COMMENT ON COLUMN auction_snapshots.prev_close IS '昨收价';
+COMMENT ON COLUMN auction_snapshots.change_pct IS '匹配价相对昨收涨跌幅(%)';
+COMMENT ON COLUMN auction_snapshots.matched_volume IS '匹配量（手）';
+COMMENT ON COLUMN auction_snapshots.matched_amount IS '匹配金额（元）';
+COMMENT ON COLUMN auction_snapshots.unmatched_volume IS '未匹配量（手），买一量减卖一量，正数为买方未匹配；未抓取明细时为空';
+COMMENT ON COLUMN auction_snapshots.universe IS '采样范围：market 全市场 / watchlist 观察表 / codes 指定代码';
@@ -0,0 +1,7 @@
+-- 按开始时间拆回上午 / 下午任务（原上午任务只在 12:00 前执行）
+UPDATE job_execution_history
+SET job_name = CASE
+    WHEN started_at::time < TIME '12:00' THEN 'stock_filter_morning'
+    ELSE 'stock_filter_afternoon'
+END
+WHERE job_name = 'stock_filter';
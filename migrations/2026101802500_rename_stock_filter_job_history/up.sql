@@ -0,0 +1,4 @@
+-- 上午 / 下午两个股票筛选任务合并为 stock_filter，历史执行记录随之改名
+UPDATE job_execution_history
+SET job_name = 'stock_filter'
+WHERE job_name IN ('stock_filter_morning', 'stock_filter_afternoon');
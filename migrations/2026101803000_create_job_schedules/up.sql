@@ -0,0 +1,20 @@
+-- 定时任务调度配置：每个任务一行，启动时按代码默认值补齐缺失的任务
+CREATE TABLE IF NOT EXISTS job_schedules (
+    id SERIAL PRIMARY KEY,
+    job_name VARCHAR(64) NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    cron_expressions TEXT[] NOT NULL,
+    timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Shanghai',
+    params JSONB,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_job_schedules_job_name ON job_schedules(job_name);
+
+COMMENT ON TABLE job_schedules IS '定时任务调度配置（修改后通过接口热加载，无需重启）';
+COMMENT ON COLUMN job_schedules.job_name IS '任务名称，对应 Job::name';
+COMMENT ON COLUMN job_schedules.enabled IS '是否启用定时触发（不影响手动触发）';
+COMMENT ON COLUMN job_schedules.cron_expressions IS 'cron 表达式列表（六段含秒）';
+COMMENT ON COLUMN job_schedules.timezone IS 'cron 所用时区（IANA 名称）';
+COMMENT ON COLUMN job_schedules.params IS '定时触发时传给任务的参数';
@@ -0,0 +1,2 @@
+ALTER TABLE job_schedules DROP COLUMN IF EXISTS conflict_policy;
+DROP TABLE IF EXISTS job_locks;
@@ -0,0 +1,18 @@
+-- 任务锁：每个任务同一时刻只有一个实例持有，租约到期未续期视为释放（实例崩溃时自动失效）
+CREATE TABLE IF NOT EXISTS job_locks (
+    job_name VARCHAR(64) PRIMARY KEY,
+    holder VARCHAR(128) NOT NULL,
+    execution_id INTEGER,
+    acquired_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    lease_until TIMESTAMP NOT NULL
+);
+
+COMMENT ON TABLE job_locks IS '定时任务执行锁（多实例部署时防止重复执行）';
+COMMENT ON COLUMN job_locks.holder IS '持有锁的实例标识';
+COMMENT ON COLUMN job_locks.execution_id IS '持有者对应的 job_execution_history 记录 ID';
+COMMENT ON COLUMN job_locks.lease_until IS '租约到期时间，执行期间定期续期';
+
+-- 锁冲突策略：skip 跳过 / queue 排队等待 / fail 记录失败
+ALTER TABLE job_schedules ADD COLUMN IF NOT EXISTS conflict_policy VARCHAR(16) NOT NULL DEFAULT 'skip';
+
+COMMENT ON COLUMN job_schedules.conflict_policy IS '任务已在执行时的处理策略：skip / queue / fail';
@@ -0,0 +1,6 @@
+UPDATE job_schedules SET cron_expressions = ARRAY['0 40 15 * * *']
+WHERE job_name = 'profit_analysis' AND cron_expressions = '{}';
+UPDATE job_schedules SET cron_expressions = ARRAY['0 0 18 * * *']
+WHERE job_name = 'stock_plate_sync' AND cron_expressions = '{}';
+
+DROP TABLE IF EXISTS job_pipeline_runs;
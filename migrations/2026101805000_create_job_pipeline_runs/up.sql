@@ -0,0 +1,26 @@
+-- 任务流水线执行记录：上游任务完成后按依赖顺序执行下游，整条链路一条记录
+CREATE TABLE IF NOT EXISTS job_pipeline_runs (
+    id SERIAL PRIMARY KEY,
+    root_job VARCHAR(64) NOT NULL,
+    trigger_type VARCHAR(16) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    steps JSONB NOT NULL DEFAULT '[]'::jsonb,
+    started_at TIMESTAMP NOT NULL,
+    completed_at TIMESTAMP,
+    duration_ms BIGINT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_pipeline_runs_root_started ON job_pipeline_runs(root_job, started_at DESC);
+
+COMMENT ON TABLE job_pipeline_runs IS '任务流水线执行记录（按 Job::depends_on 声明的依赖串联执行）';
+COMMENT ON COLUMN job_pipeline_runs.root_job IS '起点任务名称';
+COMMENT ON COLUMN job_pipeline_runs.trigger_type IS '触发方式：scheduled / manual';
+COMMENT ON COLUMN job_pipeline_runs.status IS '整体状态：running / success / partial / failed';
+COMMENT ON COLUMN job_pipeline_runs.steps IS '各步骤状态：[{job_name, status, execution_id, duration_ms, reason}]';
+
+-- 盈利分析、板块同步改为由上游触发：仍为原默认 cron 的配置清空 cron（已手动修改过的保持不变）
+UPDATE job_schedules SET cron_expressions = '{}', updated_at = NOW()
+WHERE job_name = 'profit_analysis' AND cron_expressions = ARRAY['0 40 15 * * *'];
+UPDATE job_schedules SET cron_expressions = '{}', updated_at = NOW()
+WHERE job_name = 'stock_plate_sync' AND cron_expressions = ARRAY['0 0 18 * * *'];
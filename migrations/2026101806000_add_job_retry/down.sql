@@ -0,0 +1,7 @@
+ALTER TABLE job_schedules DROP COLUMN IF EXISTS retry_backoff_secs;
+ALTER TABLE job_schedules DROP COLUMN IF EXISTS retry_max_attempts;
+
+DROP INDEX IF EXISTS idx_job_execution_history_retry_of;
+ALTER TABLE job_execution_history DROP COLUMN IF EXISTS params;
+ALTER TABLE job_execution_history DROP COLUMN IF EXISTS retry_of;
+ALTER TABLE job_execution_history DROP COLUMN IF EXISTS attempt;
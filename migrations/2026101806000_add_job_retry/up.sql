@@ -0,0 +1,16 @@
+-- 失败重试：执行记录关联被重试的记录，调度配置增加自动重试
+ALTER TABLE job_execution_history ADD COLUMN IF NOT EXISTS attempt INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE job_execution_history ADD COLUMN IF NOT EXISTS retry_of INTEGER;
+ALTER TABLE job_execution_history ADD COLUMN IF NOT EXISTS params JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_job_execution_history_retry_of ON job_execution_history(retry_of);
+
+COMMENT ON COLUMN job_execution_history.attempt IS '第几次执行（首次为 1，每次重试加 1）';
+COMMENT ON COLUMN job_execution_history.retry_of IS '被重试的执行记录 ID';
+COMMENT ON COLUMN job_execution_history.params IS '本次执行的任务参数（重试时为失败项）';
+
+ALTER TABLE job_schedules ADD COLUMN IF NOT EXISTS retry_max_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE job_schedules ADD COLUMN IF NOT EXISTS retry_backoff_secs INTEGER NOT NULL DEFAULT 300;
+
+COMMENT ON COLUMN job_schedules.retry_max_attempts IS '失败（或部分失败）后自动重试的最大次数，0 为不重试';
+COMMENT ON COLUMN job_schedules.retry_backoff_secs IS '首次自动重试的等待秒数，之后每次翻倍';
@@ -0,0 +1,3 @@
+ALTER TABLE job_execution_history DROP COLUMN IF EXISTS cancel_requested;
+
+COMMENT ON COLUMN job_execution_history.status IS '执行状态: running/success/failed/partial';
@@ -0,0 +1,5 @@
+-- 任务取消：接口请求取消时置位，执行中的实例轮询该标记后停止
+ALTER TABLE job_execution_history ADD COLUMN IF NOT EXISTS cancel_requested BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN job_execution_history.cancel_requested IS '是否已请求取消（执行中的任务在批次之间检查）';
+COMMENT ON COLUMN job_execution_history.status IS '执行状态: running/success/failed/partial/cancelled/aborted';
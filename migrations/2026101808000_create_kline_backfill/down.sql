@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS kline_backfill_items;
+DROP TABLE IF EXISTS kline_backfill_runs;
@@ -0,0 +1,51 @@
+-- 历史K线回补：按股票集合与日期区间对照交易日历检测缺口，只拉取缺失区间；
+-- 每只股票一条进度记录，中断（取消 / 重启）后可从未完成的股票继续
+CREATE TABLE IF NOT EXISTS kline_backfill_runs (
+    id SERIAL PRIMARY KEY,
+    stock_set VARCHAR(20) NOT NULL,
+    set_key VARCHAR(64),
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    total_stocks INTEGER NOT NULL DEFAULT 0,
+    completed_stocks INTEGER NOT NULL DEFAULT 0,
+    failed_stocks INTEGER NOT NULL DEFAULT 0,
+    inserted_count INTEGER NOT NULL DEFAULT 0,
+    execution_id INTEGER,
+    error_message TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_kline_backfill_runs_created ON kline_backfill_runs(created_at DESC);
+
+COMMENT ON TABLE kline_backfill_runs IS '历史K线回补任务';
+COMMENT ON COLUMN kline_backfill_runs.stock_set IS '股票集合：all（stock_table 全部）/ plate / watchlist / codes';
+COMMENT ON COLUMN kline_backfill_runs.set_key IS 'stock_set = plate 时为板块代码';
+COMMENT ON COLUMN kline_backfill_runs.status IS '状态：pending / running / success / partial / failed / cancelled';
+COMMENT ON COLUMN kline_backfill_runs.execution_id IS '最近一次执行对应的 job_execution_history 记录 ID';
+
+CREATE TABLE IF NOT EXISTS kline_backfill_items (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES kline_backfill_runs(id) ON DELETE CASCADE,
+    stock_code VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    expected_days INTEGER NOT NULL DEFAULT 0,
+    present_before INTEGER NOT NULL DEFAULT 0,
+    present_after INTEGER,
+    inserted_count INTEGER NOT NULL DEFAULT 0,
+    missing_ranges JSONB,
+    error_message TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (run_id, stock_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_kline_backfill_items_run_status ON kline_backfill_items(run_id, status);
+
+COMMENT ON TABLE kline_backfill_items IS '历史K线回补的逐股进度与覆盖率';
+COMMENT ON COLUMN kline_backfill_items.status IS '状态：pending / success / failed';
+COMMENT ON COLUMN kline_backfill_items.expected_days IS '区间内（上市后）的交易日数';
+COMMENT ON COLUMN kline_backfill_items.present_before IS '回补前已有的K线天数';
+COMMENT ON COLUMN kline_backfill_items.present_after IS '回补后的K线天数';
+COMMENT ON COLUMN kline_backfill_items.missing_ranges IS '回补后仍缺失的区间：[{start, end, days}]';
@@ -0,0 +1,28 @@
+-- 日K线数据质量审计：OHLC 不一致、零成交量、重复K线、非交易日K线、缺口与异常跳空。
+-- 同一股票同一检查项在同一日期只保留一条记录，重复检测时刷新 last_detected_at
+CREATE TABLE IF NOT EXISTS kline_audit_findings (
+    id SERIAL PRIMARY KEY,
+    stock_code VARCHAR(20) NOT NULL,
+    check_type VARCHAR(32) NOT NULL,
+    severity VARCHAR(16) NOT NULL,
+    trade_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    detail TEXT NOT NULL,
+    context JSONB,
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    first_detected_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_detected_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMP,
+    UNIQUE (stock_code, check_type, trade_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_kline_audit_findings_status ON kline_audit_findings(status, check_type);
+CREATE INDEX IF NOT EXISTS idx_kline_audit_findings_stock ON kline_audit_findings(stock_code, trade_date);
+
+COMMENT ON TABLE kline_audit_findings IS '日K线数据质量审计发现的问题';
+COMMENT ON COLUMN kline_audit_findings.check_type IS '检查项：ohlc_invalid / zero_volume / duplicate_bar / non_trading_day / gap / price_jump';
+COMMENT ON COLUMN kline_audit_findings.severity IS '严重程度：error / warning';
+COMMENT ON COLUMN kline_audit_findings.trade_date IS '问题所在交易日（缺口为起始日）';
+COMMENT ON COLUMN kline_audit_findings.end_date IS '问题区间的结束日（单日问题与 trade_date 相同）';
+COMMENT ON COLUMN kline_audit_findings.context IS '相关K线数值等上下文';
+COMMENT ON COLUMN kline_audit_findings.status IS '状态：open / resolved（复检已不存在）/ ignored（人工忽略）';
@@ -0,0 +1,16 @@
+DROP INDEX IF EXISTS idx_stock_table_industry;
+DROP INDEX IF EXISTS idx_stock_table_list_status;
+
+ALTER TABLE stock_table
+    DROP COLUMN IF EXISTS master_synced_at,
+    DROP COLUMN IF EXISTS delisted_at,
+    DROP COLUMN IF EXISTS list_status,
+    DROP COLUMN IF EXISTS is_st,
+    DROP COLUMN IF EXISTS float_market_cap,
+    DROP COLUMN IF EXISTS total_market_cap,
+    DROP COLUMN IF EXISTS float_shares,
+    DROP COLUMN IF EXISTS total_shares,
+    DROP COLUMN IF EXISTS industry,
+    DROP COLUMN IF EXISTS listing_date,
+    DROP COLUMN IF EXISTS board,
+    DROP COLUMN IF EXISTS exchange;
@@ -0,0 +1,31 @@
+-- 证券主数据：stock_table 由东财 A 股列表全量同步，补充交易所、板块、上市日期、行业、股本、市值与上市状态。
+-- 列表中消失的代码标记为 delisted，各任务遍历 stock_table 时跳过
+ALTER TABLE stock_table
+    ADD COLUMN IF NOT EXISTS exchange VARCHAR(4),
+    ADD COLUMN IF NOT EXISTS board VARCHAR(16),
+    ADD COLUMN IF NOT EXISTS listing_date DATE,
+    ADD COLUMN IF NOT EXISTS industry VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS total_shares BIGINT,
+    ADD COLUMN IF NOT EXISTS float_shares BIGINT,
+    ADD COLUMN IF NOT EXISTS total_market_cap NUMERIC(20, 2),
+    ADD COLUMN IF NOT EXISTS float_market_cap NUMERIC(20, 2),
+    ADD COLUMN IF NOT EXISTS is_st BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS list_status VARCHAR(16) NOT NULL DEFAULT 'listed',
+    ADD COLUMN IF NOT EXISTS delisted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS master_synced_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_stock_table_list_status ON stock_table(list_status);
+CREATE INDEX IF NOT EXISTS idx_stock_table_industry ON stock_table(industry);
+
+COMMENT ON COLUMN stock_table.exchange IS '交易所：SH / SZ / BJ';
+COMMENT ON COLUMN stock_table.board IS '板块：main（主板）/ chinext（创业板）/ star（科创板）/ bse（北交所）';
+COMMENT ON COLUMN stock_table.listing_date IS '上市日期';
+COMMENT ON COLUMN stock_table.industry IS '所属行业（东财行业）';
+COMMENT ON COLUMN stock_table.total_shares IS '总股本（股）';
+COMMENT ON COLUMN stock_table.float_shares IS '流通股本（股）';
+COMMENT ON COLUMN stock_table.total_market_cap IS '总市值（元），最近一次同步时';
+COMMENT ON COLUMN stock_table.float_market_cap IS '流通市值（元），最近一次同步时';
+COMMENT ON COLUMN stock_table.is_st IS '是否 ST / *ST';
+COMMENT ON COLUMN stock_table.list_status IS '上市状态：listed / suspended（停牌）/ delisted（已不在 A 股列表中）';
+COMMENT ON COLUMN stock_table.delisted_at IS '标记为 delisted 的时间';
+COMMENT ON COLUMN stock_table.master_synced_at IS '最近一次从 A 股列表同步的时间';
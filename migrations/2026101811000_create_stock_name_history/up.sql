@@ -0,0 +1,23 @@
+-- 股票简称变更历史：每段名称一条记录，valid_to 为 NULL 表示当前名称。
+-- 由证券主数据同步维护，ST 判断按查询交易日当时有效的名称进行
+CREATE TABLE IF NOT EXISTS stock_name_history (
+    id SERIAL PRIMARY KEY,
+    stock_code VARCHAR(20) NOT NULL,
+    stock_name VARCHAR(100) NOT NULL,
+    is_st BOOLEAN NOT NULL DEFAULT FALSE,
+    valid_from DATE NOT NULL,
+    valid_to DATE,
+    source VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (stock_code, valid_from)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stock_name_history_open
+    ON stock_name_history(stock_code) WHERE valid_to IS NULL;
+CREATE INDEX IF NOT EXISTS idx_stock_name_history_st ON stock_name_history(is_st, valid_from);
+
+COMMENT ON TABLE stock_name_history IS '股票简称变更历史与 ST 状态时间线';
+COMMENT ON COLUMN stock_name_history.is_st IS '该名称是否为 ST / *ST / S*ST / SST 特殊处理简称';
+COMMENT ON COLUMN stock_name_history.valid_from IS '名称生效日（含）';
+COMMENT ON COLUMN stock_name_history.valid_to IS '名称最后有效日（含），NULL 表示当前名称';
+COMMENT ON COLUMN stock_name_history.source IS '来源：snapshot（由历史快照回填）/ master（证券主数据同步）';
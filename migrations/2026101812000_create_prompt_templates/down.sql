@@ -0,0 +1,4 @@
+ALTER TABLE ai_trend_analysis
+    DROP COLUMN IF EXISTS prompt_version,
+    DROP COLUMN IF EXISTS prompt_template_id;
+DROP TABLE IF EXISTS prompt_templates;
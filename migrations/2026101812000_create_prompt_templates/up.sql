@@ -0,0 +1,34 @@
+-- AI 分析提示词模板：同一 template_key 下按版本号递增保存，内容创建后不可修改（修改即新建版本），
+-- 每个 key 至多一个启用版本，分析记录保存所用模板版本以便对比
+CREATE TABLE IF NOT EXISTS prompt_templates (
+    id SERIAL PRIMARY KEY,
+    template_key VARCHAR(64) NOT NULL,
+    version INTEGER NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    system_prompt TEXT NOT NULL,
+    user_template TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (template_key, version)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_prompt_templates_active
+    ON prompt_templates(template_key) WHERE is_active;
+
+COMMENT ON TABLE prompt_templates IS 'AI 分析提示词模板（按版本保存）';
+COMMENT ON COLUMN prompt_templates.template_key IS '模板用途，如 trend_prediction';
+COMMENT ON COLUMN prompt_templates.system_prompt IS '系统提示词，可包含 {{变量}}';
+COMMENT ON COLUMN prompt_templates.user_template IS '用户消息模板，可包含 {{变量}}';
+COMMENT ON COLUMN prompt_templates.is_active IS '是否为该用途当前启用的版本';
+
+ALTER TABLE ai_trend_analysis
+    ADD COLUMN IF NOT EXISTS prompt_template_id INTEGER REFERENCES prompt_templates(id),
+    ADD COLUMN IF NOT EXISTS prompt_version INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_ai_trend_analysis_prompt
+    ON ai_trend_analysis(stock_code, prompt_version);
+
+COMMENT ON COLUMN ai_trend_analysis.prompt_template_id IS '所用提示词模板 ID';
+COMMENT ON COLUMN ai_trend_analysis.prompt_version IS '所用提示词模板版本';
@@ -0,0 +1,8 @@
+ALTER TABLE ai_trend_analysis
+    DROP COLUMN IF EXISTS repair_rounds,
+    DROP COLUMN IF EXISTS validation_errors,
+    DROP COLUMN IF EXISTS resistance_level,
+    DROP COLUMN IF EXISTS support_level,
+    DROP COLUMN IF EXISTS trend_stage,
+    DROP COLUMN IF EXISTS action,
+    DROP COLUMN IF EXISTS rating;
@@ -0,0 +1,27 @@
+-- AI 趋势分析结果的可查询字段：通过 Schema 校验后从 current_diagnosis 写入
+ALTER TABLE ai_trend_analysis
+    ADD COLUMN IF NOT EXISTS rating VARCHAR(2),
+    ADD COLUMN IF NOT EXISTS action VARCHAR(16),
+    ADD COLUMN IF NOT EXISTS trend_stage VARCHAR(32),
+    ADD COLUMN IF NOT EXISTS support_level NUMERIC(12, 2),
+    ADD COLUMN IF NOT EXISTS resistance_level NUMERIC(12, 2),
+    ADD COLUMN IF NOT EXISTS validation_errors JSONB,
+    ADD COLUMN IF NOT EXISTS repair_rounds INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_ai_trend_analysis_rating ON ai_trend_analysis(rating, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ai_trend_analysis_action ON ai_trend_analysis(action, created_at DESC);
+
+-- 历史记录：旧提示词没有综合评级与操作归类，只回填趋势阶段与支撑 / 压力位
+UPDATE ai_trend_analysis
+SET trend_stage = LEFT(response_json->'current_diagnosis'->>'status', 32),
+    support_level = CASE WHEN jsonb_typeof(response_json->'current_diagnosis'->'support_level') = 'number'
+        THEN (response_json->'current_diagnosis'->>'support_level')::NUMERIC(12, 2) END,
+    resistance_level = CASE WHEN jsonb_typeof(response_json->'current_diagnosis'->'resistance_level') = 'number'
+        THEN (response_json->'current_diagnosis'->>'resistance_level')::NUMERIC(12, 2) END
+WHERE response_json ? 'current_diagnosis';
+
+COMMENT ON COLUMN ai_trend_analysis.rating IS '当前综合评级：A / B / C / D / F';
+COMMENT ON COLUMN ai_trend_analysis.action IS '操作建议归类：buy / add / hold / reduce / sell / watch';
+COMMENT ON COLUMN ai_trend_analysis.trend_stage IS '当前趋势阶段';
+COMMENT ON COLUMN ai_trend_analysis.validation_errors IS '输出未通过校验时的错误列表';
+COMMENT ON COLUMN ai_trend_analysis.repair_rounds IS '校验失败后重新请求的轮数';
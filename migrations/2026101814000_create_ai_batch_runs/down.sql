@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS ai_batch_items;
+DROP TABLE IF EXISTS ai_batch_runs;
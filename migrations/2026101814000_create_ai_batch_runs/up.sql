@@ -0,0 +1,56 @@
+-- 批量 AI 趋势分析：对一组股票（指定代码 / 观察表 / 板块 / 月线金叉筛选结果）逐只分析，
+-- 并发与 token 预算受控；每只股票一条进度记录，关联写入的 ai_trend_analysis 记录
+CREATE TABLE IF NOT EXISTS ai_batch_runs (
+    id SERIAL PRIMARY KEY,
+    source VARCHAR(20) NOT NULL,
+    source_key VARCHAR(64),
+    source_params JSONB,
+    model_name VARCHAR(100) NOT NULL,
+    prompt_version INTEGER NOT NULL,
+    concurrency INTEGER NOT NULL DEFAULT 2,
+    token_budget BIGINT,
+    tokens_used BIGINT NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    total_stocks INTEGER NOT NULL DEFAULT 0,
+    completed_stocks INTEGER NOT NULL DEFAULT 0,
+    failed_stocks INTEGER NOT NULL DEFAULT 0,
+    skipped_stocks INTEGER NOT NULL DEFAULT 0,
+    execution_id INTEGER,
+    error_message TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_batch_runs_created ON ai_batch_runs(created_at DESC);
+
+COMMENT ON TABLE ai_batch_runs IS '批量 AI 趋势分析任务';
+COMMENT ON COLUMN ai_batch_runs.source IS '股票来源：codes / watchlist / plate / monthly_ma_cross';
+COMMENT ON COLUMN ai_batch_runs.source_key IS 'source = plate 时为板块代码，source = monthly_ma_cross 时为锚定月（YYYY-MM）';
+COMMENT ON COLUMN ai_batch_runs.source_params IS 'source = monthly_ma_cross 时的筛选参数';
+COMMENT ON COLUMN ai_batch_runs.model_name IS '使用的模型：provider/model';
+COMMENT ON COLUMN ai_batch_runs.prompt_version IS '创建时确定的提示词模板版本，整批一致';
+COMMENT ON COLUMN ai_batch_runs.token_budget IS 'token 预算，用尽后剩余股票标记为 skipped；为空表示不限';
+COMMENT ON COLUMN ai_batch_runs.status IS '状态：pending / running / success / partial / failed / cancelled';
+COMMENT ON COLUMN ai_batch_runs.execution_id IS '最近一次执行对应的 job_execution_history 记录 ID';
+
+CREATE TABLE IF NOT EXISTS ai_batch_items (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES ai_batch_runs(id) ON DELETE CASCADE,
+    stock_code VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    analysis_id INTEGER REFERENCES ai_trend_analysis(id) ON DELETE SET NULL,
+    rating VARCHAR(2),
+    action VARCHAR(16),
+    tokens_used BIGINT NOT NULL DEFAULT 0,
+    error_message TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (run_id, stock_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_batch_items_run_status ON ai_batch_items(run_id, status);
+
+COMMENT ON TABLE ai_batch_items IS '批量 AI 趋势分析的逐股进度';
+COMMENT ON COLUMN ai_batch_items.status IS '状态：pending / completed / invalid / failed / skipped';
+COMMENT ON COLUMN ai_batch_items.analysis_id IS '对应的 ai_trend_analysis 记录';
+COMMENT ON COLUMN ai_batch_items.tokens_used IS '该股票分析（含修正轮）消耗的 token';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_ai_trend_analysis_input_hash;
+ALTER TABLE ai_trend_analysis DROP COLUMN IF EXISTS input_hash;
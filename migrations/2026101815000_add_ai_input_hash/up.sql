@@ -0,0 +1,10 @@
+-- AI 分析结果缓存：按输入内容（模型 + 提示词模板版本 + K线 + 信号）计算哈希，
+-- 相同输入已有成功结果时直接复用，不再调用模型
+ALTER TABLE ai_trend_analysis
+    ADD COLUMN IF NOT EXISTS input_hash VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_ai_trend_analysis_input_hash
+    ON ai_trend_analysis(input_hash, created_at DESC)
+    WHERE status = 'completed';
+
+COMMENT ON COLUMN ai_trend_analysis.input_hash IS '输入内容的 SHA-256（模型、提示词模板版本、K线与信号数据），用于复用相同输入的分析结果';
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS llm_model_prices;
+DROP INDEX IF EXISTS idx_ai_trend_analysis_created_at;
+ALTER TABLE ai_trend_analysis
+    DROP COLUMN IF EXISTS prompt_tokens,
+    DROP COLUMN IF EXISTS completion_tokens,
+    DROP COLUMN IF EXISTS total_tokens,
+    DROP COLUMN IF EXISTS cost;
@@ -0,0 +1,36 @@
+-- AI 分析的 token 用量与费用：每条分析记录保存模型返回（或估算）的用量，
+-- 费用按模型单价表计算，用于用量报表与预算控制
+ALTER TABLE ai_trend_analysis
+    ADD COLUMN IF NOT EXISTS prompt_tokens BIGINT,
+    ADD COLUMN IF NOT EXISTS completion_tokens BIGINT,
+    ADD COLUMN IF NOT EXISTS total_tokens BIGINT,
+    ADD COLUMN IF NOT EXISTS cost NUMERIC(14, 6);
+
+CREATE INDEX IF NOT EXISTS idx_ai_trend_analysis_created_at
+    ON ai_trend_analysis(created_at);
+
+COMMENT ON COLUMN ai_trend_analysis.prompt_tokens IS '输入 token 数（含修正轮）';
+COMMENT ON COLUMN ai_trend_analysis.completion_tokens IS '输出 token 数（含修正轮）';
+COMMENT ON COLUMN ai_trend_analysis.total_tokens IS '总 token 数';
+COMMENT ON COLUMN ai_trend_analysis.cost IS '按 llm_model_prices 单价计算的费用（元），未配置单价时为空';
+
+-- 模型单价：model_name 为 provider/model，或 provider/* 作为该后端的默认单价
+CREATE TABLE IF NOT EXISTS llm_model_prices (
+    id SERIAL PRIMARY KEY,
+    model_name VARCHAR(128) NOT NULL UNIQUE,
+    prompt_price NUMERIC(12, 4) NOT NULL,
+    completion_price NUMERIC(12, 4) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE llm_model_prices IS '大模型单价（元 / 百万 token）';
+COMMENT ON COLUMN llm_model_prices.prompt_price IS '输入单价（元 / 百万 token）';
+COMMENT ON COLUMN llm_model_prices.completion_price IS '输出单价（元 / 百万 token）';
+
+-- 本地模型与离线桩不计费
+INSERT INTO llm_model_prices (model_name, prompt_price, completion_price, description)
+VALUES ('local/*', 0, 0, '本地模型服务'),
+       ('stub/*', 0, 0, '离线桩')
+ON CONFLICT (model_name) DO NOTHING;
@@ -0,0 +1,44 @@
+-- AI 预测准确率评估：分析后第 N 个交易日，用 daily_klines 的实际走势检验
+-- 操作建议隐含的方向、综合评级与支撑 / 压力位，按模型与提示词版本统计准确率
+CREATE TABLE IF NOT EXISTS ai_prediction_evaluations (
+    id SERIAL PRIMARY KEY,
+    analysis_id INTEGER NOT NULL REFERENCES ai_trend_analysis(id) ON DELETE CASCADE,
+    horizon_days INTEGER NOT NULL,
+    stock_code VARCHAR(20) NOT NULL,
+    model_name VARCHAR(100) NOT NULL,
+    prompt_version INTEGER,
+    rating VARCHAR(2),
+    action VARCHAR(16) NOT NULL,
+    expected_direction VARCHAR(8) NOT NULL,
+    base_date DATE NOT NULL,
+    base_close NUMERIC(12, 4) NOT NULL,
+    eval_date DATE NOT NULL,
+    eval_close NUMERIC(12, 4) NOT NULL,
+    realized_return_pct NUMERIC(10, 4) NOT NULL,
+    max_gain_pct NUMERIC(10, 4) NOT NULL,
+    max_drawdown_pct NUMERIC(10, 4) NOT NULL,
+    direction_hit BOOLEAN NOT NULL,
+    rating_hit BOOLEAN,
+    resistance_reached BOOLEAN,
+    support_broken BOOLEAN,
+    evaluated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (analysis_id, horizon_days)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_prediction_evaluations_model
+    ON ai_prediction_evaluations(horizon_days, model_name, prompt_version);
+CREATE INDEX IF NOT EXISTS idx_ai_prediction_evaluations_stock
+    ON ai_prediction_evaluations(stock_code, base_date DESC);
+
+COMMENT ON TABLE ai_prediction_evaluations IS 'AI 趋势分析的事后评估（每条分析记录每个评估周期一条）';
+COMMENT ON COLUMN ai_prediction_evaluations.horizon_days IS '评估周期：基准日之后的交易日数';
+COMMENT ON COLUMN ai_prediction_evaluations.expected_direction IS '操作建议隐含的方向：up（buy / add）/ down（sell / reduce）/ flat（hold / watch）';
+COMMENT ON COLUMN ai_prediction_evaluations.base_date IS '基准日：分析时已收盘的最近交易日';
+COMMENT ON COLUMN ai_prediction_evaluations.eval_date IS '评估日：基准日之后第 horizon_days 个交易日（停牌时为此前最后一根K线）';
+COMMENT ON COLUMN ai_prediction_evaluations.realized_return_pct IS '基准日收盘到评估日收盘的涨跌幅（%）';
+COMMENT ON COLUMN ai_prediction_evaluations.max_gain_pct IS '评估期内最高价相对基准收盘的涨幅（%）';
+COMMENT ON COLUMN ai_prediction_evaluations.max_drawdown_pct IS '评估期内最低价相对基准收盘的跌幅（%，为负数或 0）';
+COMMENT ON COLUMN ai_prediction_evaluations.direction_hit IS '方向是否命中：up 收涨、down 收跌、flat 涨跌幅在 ±3% 以内';
+COMMENT ON COLUMN ai_prediction_evaluations.rating_hit IS '评级是否命中：A/B 收涨、D/F 收跌；C 级为空';
+COMMENT ON COLUMN ai_prediction_evaluations.resistance_reached IS '评估期内最高价是否触及压力位';
+COMMENT ON COLUMN ai_prediction_evaluations.support_broken IS '评估期内最低价是否跌破支撑位';
@@ -0,0 +1,28 @@
+-- AI 分析追问：挂在一条 ai_trend_analysis 记录下的多轮对话，
+-- 每次追问都把原始输入数据、分析结果与此前的对话作为上下文
+CREATE TABLE IF NOT EXISTS ai_analysis_messages (
+    id SERIAL PRIMARY KEY,
+    analysis_id INTEGER NOT NULL REFERENCES ai_trend_analysis(id) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL,
+    content TEXT NOT NULL,
+    model_name VARCHAR(100),
+    prompt_template_id INTEGER REFERENCES prompt_templates(id),
+    prompt_version INTEGER,
+    prompt_tokens BIGINT,
+    completion_tokens BIGINT,
+    total_tokens BIGINT,
+    cost NUMERIC(14, 6),
+    duration_ms BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_analysis_messages_analysis
+    ON ai_analysis_messages(analysis_id, id);
+CREATE INDEX IF NOT EXISTS idx_ai_analysis_messages_created_at
+    ON ai_analysis_messages(created_at);
+
+COMMENT ON TABLE ai_analysis_messages IS 'AI 趋势分析的追问对话';
+COMMENT ON COLUMN ai_analysis_messages.role IS '消息角色：user（追问）/ assistant（回答）';
+COMMENT ON COLUMN ai_analysis_messages.model_name IS '回答所用模型：provider/model（仅 assistant）';
+COMMENT ON COLUMN ai_analysis_messages.prompt_version IS '回答所用追问提示词模板（trend_followup）版本（仅 assistant）';
+COMMENT ON COLUMN ai_analysis_messages.cost IS '按 llm_model_prices 单价计算的费用（元），未配置单价时为空';
@@ -0,0 +1,30 @@
+-- AI 每日复盘：收盘后汇总板块统计、信号支数与上一交易日信号表现，交给大模型生成复盘，
+-- 模型不可用时只保存统计部分
+CREATE TABLE IF NOT EXISTS ai_market_reports (
+    id SERIAL PRIMARY KEY,
+    report_date DATE NOT NULL UNIQUE,
+    status VARCHAR(20) NOT NULL,
+    statistics JSONB NOT NULL,
+    summary TEXT,
+    model_name VARCHAR(100),
+    prompt_template_id INTEGER REFERENCES prompt_templates(id),
+    prompt_version INTEGER,
+    prompt_tokens BIGINT,
+    completion_tokens BIGINT,
+    total_tokens BIGINT,
+    cost NUMERIC(14, 6),
+    duration_ms BIGINT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_market_reports_created_at
+    ON ai_market_reports(created_at);
+
+COMMENT ON TABLE ai_market_reports IS 'AI 每日市场与板块复盘';
+COMMENT ON COLUMN ai_market_reports.status IS 'completed（含复盘）/ stats_only（模型不可用或预算已满，仅统计）';
+COMMENT ON COLUMN ai_market_reports.statistics IS '发送给模型的统计数据：信号支数、热门板块、连续出现信号的股票、上一交易日信号表现';
+COMMENT ON COLUMN ai_market_reports.summary IS '模型生成的复盘（Markdown）';
+COMMENT ON COLUMN ai_market_reports.error_message IS '未生成复盘的原因';
+COMMENT ON COLUMN ai_market_reports.cost IS '按 llm_model_prices 单价计算的费用（元），未配置单价时为空';
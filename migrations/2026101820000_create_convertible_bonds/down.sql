@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS convertible_bond_daily;
+DROP TABLE IF EXISTS convertible_bonds;
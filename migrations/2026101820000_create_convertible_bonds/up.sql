@@ -0,0 +1,52 @@
+-- 可转债主数据与每日行情：收盘后由 convertible_bond_sync 任务从东财数据中心同步，
+-- 供溢价率走势与离线查询使用，不再每次请求都全量拉取上游
+CREATE TABLE IF NOT EXISTS convertible_bonds (
+    bond_code VARCHAR(10) PRIMARY KEY,
+    bond_short_name VARCHAR(50) NOT NULL,
+    stock_code VARCHAR(10) NOT NULL,
+    stock_name VARCHAR(50) NOT NULL,
+    rating VARCHAR(20),
+    issue_scale NUMERIC(14, 4),
+    remaining_scale NUMERIC(14, 4),
+    transfer_price NUMERIC(12, 4),
+    resale_trig_price NUMERIC(12, 4),
+    redeem_trig_price NUMERIC(12, 4),
+    listing_date DATE,
+    expire_date DATE,
+    delist_date DATE,
+    last_trading_date DATE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_convertible_bonds_stock_code ON convertible_bonds(stock_code);
+
+CREATE TABLE IF NOT EXISTS convertible_bond_daily (
+    bond_code VARCHAR(10) NOT NULL REFERENCES convertible_bonds(bond_code) ON DELETE CASCADE,
+    trade_date DATE NOT NULL,
+    bond_price NUMERIC(12, 4) NOT NULL,
+    stock_price NUMERIC(12, 4),
+    transfer_price NUMERIC(12, 4),
+    transfer_value NUMERIC(12, 4),
+    transfer_premium_ratio NUMERIC(12, 4),
+    resale_trig_price NUMERIC(12, 4),
+    redeem_trig_price NUMERIC(12, 4),
+    pbv_ratio NUMERIC(12, 4),
+    rating VARCHAR(20),
+    remaining_scale NUMERIC(14, 4),
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (bond_code, trade_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_convertible_bond_daily_trade_date ON convertible_bond_daily(trade_date);
+
+COMMENT ON TABLE convertible_bonds IS '可转债主数据（东财 RPT_BOND_CB_LIST）';
+COMMENT ON COLUMN convertible_bonds.issue_scale IS '实际发行规模（亿元）';
+COMMENT ON COLUMN convertible_bonds.remaining_scale IS '剩余规模（亿元），RPT_BOND_CB_LIST 不含该字段，接入其他数据源前为空';
+COMMENT ON COLUMN convertible_bond_daily.remaining_scale IS '当日剩余规模（亿元），同上';
+COMMENT ON COLUMN convertible_bonds.delist_date IS '摘牌日期';
+COMMENT ON COLUMN convertible_bonds.last_trading_date IS '最后交易日（RPT_CB_IMPORTANTDATE），仅有摘牌日期的债券查询一次';
+COMMENT ON TABLE convertible_bond_daily IS '可转债每日收盘行情，每个交易日一条';
+COMMENT ON COLUMN convertible_bond_daily.transfer_value IS '转股价值';
+COMMENT ON COLUMN convertible_bond_daily.transfer_premium_ratio IS '转股溢价率（%）';
+COMMENT ON COLUMN convertible_bond_daily.pbv_ratio IS '正股市净率';
@@ -0,0 +1,12 @@
+-- 定时触发认领：同一任务的同一计划触发时刻只由一个实例执行（与执行期间的 job_locks 互补）
+CREATE TABLE IF NOT EXISTS job_fire_claims (
+    job_name VARCHAR(64) NOT NULL,
+    scheduled_at TIMESTAMPTZ NOT NULL,
+    holder VARCHAR(128) NOT NULL,
+    claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_name, scheduled_at)
+);
+
+COMMENT ON TABLE job_fire_claims IS '定时任务触发认领（多实例部署时每个触发时刻只执行一次）';
+COMMENT ON COLUMN job_fire_claims.scheduled_at IS 'cron 计划的触发时刻';
+COMMENT ON COLUMN job_fire_claims.holder IS '认领该触发的实例标识';
@@ -0,0 +1,2 @@
+COMMENT ON COLUMN convertible_bonds.remaining_scale IS '剩余规模（亿元），RPT_BOND_CB_LIST 不含该字段，接入其他数据源前为空';
+COMMENT ON COLUMN convertible_bond_daily.remaining_scale IS '当日剩余规模（亿元），同上';
@@ -0,0 +1,3 @@
+-- 剩余规模改为取自集思录可转债列表（curr_iss_amt）
+COMMENT ON COLUMN convertible_bonds.remaining_scale IS '剩余规模（亿元），取自集思录可转债列表，未取到时保留原值';
+COMMENT ON COLUMN convertible_bond_daily.remaining_scale IS '当日剩余规模（亿元），取自集思录可转债列表';
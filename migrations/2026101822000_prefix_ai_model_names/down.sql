@@ -0,0 +1,2 @@
+-- 无法区分迁移前后写入的 qwen/ 前缀，不做回退
+SELECT 1;
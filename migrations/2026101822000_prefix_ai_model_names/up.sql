@@ -0,0 +1,9 @@
+-- 接入多后端之前的分析记录只存了 Qwen 的模型名，统一改为 provider/model，
+-- 与之后的记录一致（追问沿用原模型、按模型分组的用量与评估统计都依赖该格式）
+UPDATE ai_trend_analysis
+SET model_name = 'qwen/' || model_name
+WHERE model_name NOT LIKE '%/%';
+
+UPDATE ai_prediction_evaluations
+SET model_name = 'qwen/' || model_name
+WHERE model_name NOT LIKE '%/%';
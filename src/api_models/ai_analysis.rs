@@ -1,4 +1,5 @@
-use chrono::{DateTime, NaiveDate, Utc};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
@@ -9,6 +10,15 @@ use serde_json::Value as JsonValue;
 pub struct TrendPredictionRequest {
     /// 股票代码
     pub stock_code: String,
+    /// 模型：`provider/model` 或只写 provider（qwen / openai / anthropic / local / stub），缺省使用默认后端
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 提示词模板版本，缺省使用当前启用版本
+    #[serde(default)]
+    pub prompt_version: Option<i32>,
+    /// 为 true 时忽略相同输入的已有结果，重新调用模型
+    #[serde(default)]
+    pub force: bool,
 }
 
 // ==================== 趋势预测响应 ====================
@@ -38,6 +48,30 @@ pub struct TrendPredictionResponse {
     pub error_message: Option<String>,
     /// 分析耗时(毫秒)
     pub duration_ms: Option<i64>,
+    /// 提示词模板版本
+    pub prompt_version: Option<i32>,
+    /// 当前综合评级 A / B / C / D / F（通过校验时）
+    pub rating: Option<String>,
+    /// 操作建议归类 buy / add / hold / reduce / sell / watch
+    pub action: Option<String>,
+    /// 当前趋势阶段
+    pub trend_stage: Option<String>,
+    pub support_level: Option<BigDecimal>,
+    pub resistance_level: Option<BigDecimal>,
+    /// 输出未通过校验时的错误（status 为 invalid）
+    pub validation_errors: Option<JsonValue>,
+    /// 校验失败后重新请求的轮数
+    pub repair_rounds: i32,
+    /// 输入内容哈希（模型、提示词模板版本、K线与信号数据）
+    pub input_hash: Option<String>,
+    /// 是否复用了相同输入的已有结果（此时 id 与 created_at 为该结果的记录）
+    pub cache_hit: bool,
+    /// token 用量（含修正轮；接口未返回用量时为估算值）
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    /// 费用（元），未配置模型单价时为空
+    pub cost: Option<BigDecimal>,
     /// 创建时间
     pub created_at: DateTime<Utc>,
 }
@@ -49,6 +83,12 @@ pub struct TrendPredictionResponse {
 pub struct TrendHistoryRequest {
     /// 股票代码（可选，不传则查所有）
     pub stock_code: Option<String>,
+    /// 提示词模板版本（可选），配合 stock_code 对比不同版本的结果
+    pub prompt_version: Option<i32>,
+    /// 综合评级（可选）：A / B / C / D / F
+    pub rating: Option<String>,
+    /// 操作建议归类（可选）：buy / add / hold / reduce / sell / watch
+    pub action: Option<String>,
     /// 分页: 每页条数
     #[serde(default = "default_page_size")]
     pub page_size: i64,
@@ -77,6 +117,12 @@ pub struct TrendHistoryItem {
     pub kline_start_date: Option<NaiveDate>,
     pub kline_end_date: Option<NaiveDate>,
     pub duration_ms: Option<i64>,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub trend_stage: Option<String>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -91,3 +137,147 @@ pub struct TrendHistoryResponse {
 
 /// 分析详情响应（与 TrendPredictionResponse 相同）
 pub type TrendDetailResponse = TrendPredictionResponse;
+
+// ==================== 提示词模板 ====================
+
+/// 新建模板版本；同一 template_key 的版本号自动递增
+#[derive(Debug, Deserialize)]
+pub struct CreatePromptTemplateRequest {
+    /// 用途，缺省为 trend_prediction
+    #[serde(default)]
+    pub template_key: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    /// 用户消息模板，缺省为 `{{payload}}`
+    #[serde(default)]
+    pub user_template: Option<String>,
+    /// 是否立即启用（停用同用途的其他版本）
+    #[serde(default)]
+    pub activate: bool,
+}
+
+/// 修改模板说明；模板内容不可修改，需新建版本
+#[derive(Debug, Deserialize)]
+pub struct UpdatePromptTemplateRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PromptTemplateQuery {
+    pub template_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PromptTemplateResponse {
+    pub id: i32,
+    pub template_key: String,
+    pub version: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub user_template: String,
+    pub is_active: bool,
+    /// 模板中使用的变量
+    pub variables: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<crate::models::PromptTemplate> for PromptTemplateResponse {
+    fn from(t: crate::models::PromptTemplate) -> Self {
+        let mut variables = crate::services::prompt_template::template_variables(&t.system_prompt);
+        for name in crate::services::prompt_template::template_variables(&t.user_template) {
+            if !variables.contains(&name) {
+                variables.push(name);
+            }
+        }
+        Self {
+            id: t.id,
+            template_key: t.template_key,
+            version: t.version,
+            name: t.name,
+            description: t.description,
+            system_prompt: t.system_prompt,
+            user_template: t.user_template,
+            is_active: t.is_active,
+            variables,
+            created_at: t.created_at,
+            updated_at: t.updated_at,
+        }
+    }
+}
+
+// ==================== 追问对话 ====================
+
+/// 对分析结果的追问
+#[derive(Debug, Deserialize)]
+pub struct FollowUpRequest {
+    /// 问题内容
+    pub content: String,
+    /// 模型，缺省使用原分析的模型
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 追问提示词模板（trend_followup）版本，缺省使用当前启用版本
+    #[serde(default)]
+    pub prompt_version: Option<i32>,
+}
+
+/// 追问对话中的一条消息
+#[derive(Debug, Serialize)]
+pub struct AnalysisMessageResponse {
+    pub id: i32,
+    pub analysis_id: i32,
+    /// user / assistant
+    pub role: String,
+    pub content: String,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    /// 费用（元）
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::models::AiAnalysisMessage> for AnalysisMessageResponse {
+    fn from(m: crate::models::AiAnalysisMessage) -> Self {
+        Self {
+            id: m.id,
+            analysis_id: m.analysis_id,
+            role: m.role,
+            content: m.content,
+            model_name: m.model_name,
+            prompt_template_id: m.prompt_template_id,
+            prompt_version: m.prompt_version,
+            prompt_tokens: m.prompt_tokens,
+            completion_tokens: m.completion_tokens,
+            total_tokens: m.total_tokens,
+            cost: m.cost,
+            duration_ms: m.duration_ms,
+            created_at: m.created_at,
+        }
+    }
+}
+
+/// 分析记录的追问对话
+#[derive(Debug, Serialize)]
+pub struct AnalysisThreadResponse {
+    pub analysis_id: i32,
+    pub stock_code: String,
+    pub stock_name: Option<String>,
+    pub model_name: String,
+    pub status: String,
+    pub messages: Vec<AnalysisMessageResponse>,
+}
+
+/// 一轮追问的结果
+#[derive(Debug, Serialize)]
+pub struct FollowUpResponse {
+    pub question: AnalysisMessageResponse,
+    pub answer: AnalysisMessageResponse,
+}
@@ -0,0 +1,134 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{AiBatchItem, AiBatchRun};
+
+/// 新建批量分析任务
+#[derive(Debug, Deserialize)]
+pub struct AiBatchRequest {
+    /// 股票来源：codes / watchlist / plate / monthly_ma_cross
+    pub source: String,
+    /// source = plate 时的板块代码
+    pub plate_code: Option<String>,
+    /// source = codes 时的股票代码
+    pub codes: Option<Vec<String>>,
+    /// source = monthly_ma_cross 时的筛选参数，同 `POST /api/multi-level-filter/monthly-ma-cross` 的请求体
+    pub screen: Option<Value>,
+    /// 模型，格式同单股分析的 `model`
+    pub model: Option<String>,
+    /// 提示词模板版本，不传时使用当前启用版本
+    pub prompt_version: Option<i32>,
+    /// 同时分析的股票数，默认 2
+    pub concurrency: Option<i32>,
+    /// token 预算，用尽后剩余股票不再分析
+    pub token_budget: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiBatchListQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiBatchRunResponse {
+    pub id: i32,
+    pub source: String,
+    pub source_key: Option<String>,
+    pub source_params: Option<Value>,
+    pub model_name: String,
+    pub prompt_version: i32,
+    pub concurrency: i32,
+    pub token_budget: Option<i64>,
+    pub tokens_used: i64,
+    /// pending / running / success / partial / failed / cancelled；
+    /// 执行被中断（如服务重启）时为对应执行记录的状态
+    pub status: String,
+    pub total_stocks: i32,
+    pub completed_stocks: i32,
+    pub failed_stocks: i32,
+    pub skipped_stocks: i32,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl From<AiBatchRun> for AiBatchRunResponse {
+    fn from(run: AiBatchRun) -> Self {
+        Self {
+            id: run.id,
+            source: run.source,
+            source_key: run.source_key,
+            source_params: run.source_params,
+            model_name: run.model_name,
+            prompt_version: run.prompt_version,
+            concurrency: run.concurrency,
+            token_budget: run.token_budget,
+            tokens_used: run.tokens_used,
+            status: run.status,
+            total_stocks: run.total_stocks,
+            completed_stocks: run.completed_stocks,
+            failed_stocks: run.failed_stocks,
+            skipped_stocks: run.skipped_stocks,
+            execution_id: run.execution_id,
+            error_message: run.error_message,
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+            completed_at: run.completed_at,
+        }
+    }
+}
+
+/// 单只股票的分析进度
+#[derive(Debug, Serialize)]
+pub struct AiBatchItemResponse {
+    pub stock_code: String,
+    /// pending / completed / invalid / failed / skipped
+    pub status: String,
+    /// 对应的分析记录，可通过 `GET /api/ai-analysis/trend-prediction/:id` 查看详情
+    pub analysis_id: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub tokens_used: i64,
+    pub error_message: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<AiBatchItem> for AiBatchItemResponse {
+    fn from(item: AiBatchItem) -> Self {
+        Self {
+            stock_code: item.stock_code,
+            status: item.status,
+            analysis_id: item.analysis_id,
+            rating: item.rating,
+            action: item.action,
+            tokens_used: item.tokens_used,
+            error_message: item.error_message,
+            updated_at: item.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiBatchDetailResponse {
+    pub run: AiBatchRunResponse,
+    pub items: Vec<AiBatchItemResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiBatchListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<AiBatchRunResponse>,
+}
+
+/// 新建 / 继续批量分析任务的结果
+#[derive(Debug, Serialize)]
+pub struct AiBatchStartResponse {
+    pub run: AiBatchRunResponse,
+    pub message: String,
+}
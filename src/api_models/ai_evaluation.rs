@@ -0,0 +1,153 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::AiPredictionEvaluation;
+use crate::repositories::ai_prediction_evaluation::AccuracyRow;
+use crate::services::ai_evaluation::hit_rate;
+
+/// 准确率统计查询
+#[derive(Debug, Deserialize)]
+pub struct AiAccuracyQuery {
+    /// 评估周期（交易日数），缺省为 `AI_EVAL_HORIZON_DAYS`
+    pub horizon_days: Option<i32>,
+    /// 基准日区间，缺省为最近 180 天
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub model_name: Option<String>,
+}
+
+/// 某个模型 + 提示词版本的准确率
+#[derive(Debug, Serialize)]
+pub struct AiAccuracyItem {
+    pub model_name: String,
+    pub prompt_version: Option<i32>,
+    /// 已评估的分析数
+    pub evaluated: i64,
+    pub direction_hits: i64,
+    /// 方向命中率（%）
+    pub direction_accuracy: Option<f64>,
+    /// 参与评级统计的分析数（C 级不计）
+    pub rating_evaluated: i64,
+    pub rating_hits: i64,
+    pub rating_accuracy: Option<f64>,
+    /// 看涨（buy / add）的数量与命中率
+    pub bullish: i64,
+    pub bullish_accuracy: Option<f64>,
+    /// 看跌（sell / reduce）的数量与命中率
+    pub bearish: i64,
+    pub bearish_accuracy: Option<f64>,
+    /// 评估期内触及压力位的比例（%）
+    pub resistance_reached_rate: Option<f64>,
+    /// 评估期内跌破支撑位的比例（%）
+    pub support_broken_rate: Option<f64>,
+    /// 平均实际涨跌幅（%）
+    pub avg_return_pct: Option<BigDecimal>,
+    pub avg_bullish_return_pct: Option<BigDecimal>,
+    pub avg_bearish_return_pct: Option<BigDecimal>,
+}
+
+impl From<AccuracyRow> for AiAccuracyItem {
+    fn from(r: AccuracyRow) -> Self {
+        Self {
+            direction_accuracy: hit_rate(r.direction_hits, r.evaluated),
+            rating_accuracy: hit_rate(r.rating_hits, r.rating_evaluated),
+            bullish_accuracy: hit_rate(r.bullish_hits, r.bullish),
+            bearish_accuracy: hit_rate(r.bearish_hits, r.bearish),
+            resistance_reached_rate: hit_rate(r.resistance_reached, r.with_resistance),
+            support_broken_rate: hit_rate(r.support_broken, r.with_support),
+            model_name: r.model_name,
+            prompt_version: r.prompt_version,
+            evaluated: r.evaluated,
+            direction_hits: r.direction_hits,
+            rating_evaluated: r.rating_evaluated,
+            rating_hits: r.rating_hits,
+            bullish: r.bullish,
+            bearish: r.bearish,
+            avg_return_pct: r.avg_return_pct,
+            avg_bullish_return_pct: r.avg_bullish_return_pct,
+            avg_bearish_return_pct: r.avg_bearish_return_pct,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiAccuracyResponse {
+    pub horizon_days: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub items: Vec<AiAccuracyItem>,
+}
+
+/// 评估记录查询
+#[derive(Debug, Deserialize)]
+pub struct AiEvaluationListQuery {
+    pub analysis_id: Option<i32>,
+    pub stock_code: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_version: Option<i32>,
+    pub horizon_days: Option<i32>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiEvaluationResponse {
+    pub id: i32,
+    pub analysis_id: i32,
+    pub horizon_days: i32,
+    pub stock_code: String,
+    pub model_name: String,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: String,
+    pub expected_direction: String,
+    pub base_date: NaiveDate,
+    pub base_close: BigDecimal,
+    pub eval_date: NaiveDate,
+    pub eval_close: BigDecimal,
+    pub realized_return_pct: BigDecimal,
+    pub max_gain_pct: BigDecimal,
+    pub max_drawdown_pct: BigDecimal,
+    pub direction_hit: bool,
+    pub rating_hit: Option<bool>,
+    pub resistance_reached: Option<bool>,
+    pub support_broken: Option<bool>,
+    pub evaluated_at: NaiveDateTime,
+}
+
+impl From<AiPredictionEvaluation> for AiEvaluationResponse {
+    fn from(e: AiPredictionEvaluation) -> Self {
+        Self {
+            id: e.id,
+            analysis_id: e.analysis_id,
+            horizon_days: e.horizon_days,
+            stock_code: e.stock_code,
+            model_name: e.model_name,
+            prompt_version: e.prompt_version,
+            rating: e.rating,
+            action: e.action,
+            expected_direction: e.expected_direction,
+            base_date: e.base_date,
+            base_close: e.base_close,
+            eval_date: e.eval_date,
+            eval_close: e.eval_close,
+            realized_return_pct: e.realized_return_pct,
+            max_gain_pct: e.max_gain_pct,
+            max_drawdown_pct: e.max_drawdown_pct,
+            direction_hit: e.direction_hit,
+            rating_hit: e.rating_hit,
+            resistance_reached: e.resistance_reached,
+            support_broken: e.support_broken,
+            evaluated_at: e.evaluated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiEvaluationListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<AiEvaluationResponse>,
+}
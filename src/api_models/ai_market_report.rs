@@ -0,0 +1,109 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::models::AiMarketReport;
+use crate::services::market_report::{self, MarketStatistics};
+
+/// 复盘列表查询
+#[derive(Debug, Deserialize)]
+pub struct MarketReportListQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 复盘列表项（不含统计数据与正文）
+#[derive(Debug, Serialize)]
+pub struct MarketReportItem {
+    pub id: i32,
+    pub report_date: NaiveDate,
+    /// completed / stats_only
+    pub status: String,
+    pub model_name: Option<String>,
+    pub prompt_version: Option<i32>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AiMarketReport> for MarketReportItem {
+    fn from(r: AiMarketReport) -> Self {
+        Self {
+            id: r.id,
+            report_date: r.report_date,
+            status: r.status,
+            model_name: r.model_name,
+            prompt_version: r.prompt_version,
+            total_tokens: r.total_tokens,
+            cost: r.cost,
+            error_message: r.error_message,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketReportListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<MarketReportItem>,
+}
+
+/// 复盘详情
+#[derive(Debug, Serialize)]
+pub struct MarketReportResponse {
+    pub id: i32,
+    pub report_date: NaiveDate,
+    pub status: String,
+    /// 统计数据（JSON）
+    pub statistics: JsonValue,
+    /// 统计部分（Markdown），模型不可用时即为报告内容
+    pub statistics_text: Option<String>,
+    /// 模型生成的复盘（Markdown）
+    pub summary: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<AiMarketReport> for MarketReportResponse {
+    fn from(r: AiMarketReport) -> Self {
+        let statistics_text = serde_json::from_value::<MarketStatistics>(r.statistics.clone())
+            .ok()
+            .map(|stats| market_report::render_statistics(&stats));
+        Self {
+            id: r.id,
+            report_date: r.report_date,
+            status: r.status,
+            statistics: r.statistics,
+            statistics_text,
+            summary: r.summary,
+            model_name: r.model_name,
+            prompt_template_id: r.prompt_template_id,
+            prompt_version: r.prompt_version,
+            prompt_tokens: r.prompt_tokens,
+            completion_tokens: r.completion_tokens,
+            total_tokens: r.total_tokens,
+            cost: r.cost,
+            duration_ms: r.duration_ms,
+            error_message: r.error_message,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::LlmModelPrice;
+use crate::repositories::ai_usage::{UsageReportRow, UsageTotals};
+use crate::services::ai_usage::BudgetLimits;
+
+/// 用量报表查询
+#[derive(Debug, Deserialize)]
+pub struct AiUsageQuery {
+    /// 汇总粒度：day（默认，最近 30 天）/ month（最近 12 个月）
+    pub period: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// 只看某个模型（`provider/model`）
+    pub model_name: Option<String>,
+}
+
+/// 用量合计
+#[derive(Debug, Serialize)]
+pub struct AiUsageTotalsResponse {
+    pub calls: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    /// 费用（元），未配置单价的调用不计入
+    pub cost: BigDecimal,
+}
+
+impl From<UsageTotals> for AiUsageTotalsResponse {
+    fn from(t: UsageTotals) -> Self {
+        Self {
+            calls: t.calls,
+            prompt_tokens: t.prompt_tokens,
+            completion_tokens: t.completion_tokens,
+            total_tokens: t.total_tokens,
+            cost: t.cost,
+        }
+    }
+}
+
+/// 报表的一行：周期 × 模型
+#[derive(Debug, Serialize)]
+pub struct AiUsageReportItem {
+    /// `YYYY-MM-DD` 或 `YYYY-MM`
+    pub period: String,
+    pub model_name: String,
+    pub calls: i64,
+    /// 其中得到通过校验结果的次数
+    pub completed: i64,
+    /// 其中追问的次数
+    pub followups: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub cost: BigDecimal,
+}
+
+impl From<UsageReportRow> for AiUsageReportItem {
+    fn from(r: UsageReportRow) -> Self {
+        Self {
+            period: r.period,
+            model_name: r.model_name,
+            calls: r.calls,
+            completed: r.completed,
+            followups: r.followups,
+            prompt_tokens: r.prompt_tokens,
+            completion_tokens: r.completion_tokens,
+            total_tokens: r.total_tokens,
+            cost: r.cost,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AiUsageReportResponse {
+    pub period: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub items: Vec<AiUsageReportItem>,
+    pub total: AiUsageTotalsResponse,
+}
+
+/// 预算配置，未配置的项为空
+#[derive(Debug, Serialize)]
+pub struct AiBudgetLimitsResponse {
+    pub daily_tokens: Option<i64>,
+    pub monthly_tokens: Option<i64>,
+    pub daily_cost: Option<BigDecimal>,
+    pub monthly_cost: Option<BigDecimal>,
+}
+
+impl From<BudgetLimits> for AiBudgetLimitsResponse {
+    fn from(l: BudgetLimits) -> Self {
+        Self {
+            daily_tokens: l.daily_tokens,
+            monthly_tokens: l.monthly_tokens,
+            daily_cost: l.daily_cost,
+            monthly_cost: l.monthly_cost,
+        }
+    }
+}
+
+/// 今日、本月用量与预算
+#[derive(Debug, Serialize)]
+pub struct AiBudgetResponse {
+    pub today: AiUsageTotalsResponse,
+    pub month: AiUsageTotalsResponse,
+    pub limits: AiBudgetLimitsResponse,
+    /// 已达到的上限说明，为空时可以发起新的分析
+    pub exceeded: Option<String>,
+}
+
+/// 新增或覆盖模型单价
+#[derive(Debug, Deserialize)]
+pub struct LlmModelPriceRequest {
+    /// `provider/model`，或 `provider/*` 作为该后端的默认单价
+    pub model_name: String,
+    /// 输入单价（元 / 百万 token）
+    pub prompt_price: BigDecimal,
+    /// 输出单价（元 / 百万 token）
+    pub completion_price: BigDecimal,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LlmModelPriceResponse {
+    pub id: i32,
+    pub model_name: String,
+    pub prompt_price: BigDecimal,
+    pub completion_price: BigDecimal,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<LlmModelPrice> for LlmModelPriceResponse {
+    fn from(p: LlmModelPrice) -> Self {
+        Self {
+            id: p.id,
+            model_name: p.model_name,
+            prompt_price: p.prompt_price,
+            completion_price: p.completion_price,
+            description: p.description,
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+        }
+    }
+}
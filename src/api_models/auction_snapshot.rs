@@ -0,0 +1,119 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::services::auction_capture::AuctionFilterParams;
+
+/// 竞价快照查询参数（阈值缺省值与 `AuctionFilterParams::default()` 一致）
+#[derive(Debug, Deserialize)]
+pub struct AuctionSnapshotQuery {
+    /// 交易日，格式：YYYY-MM-DD，缺省为最近交易日
+    pub trade_date: Option<String>,
+    /// 采样时刻，格式：HH:MM，缺省为当日 9:30 前最后一次采样
+    pub sample_time: Option<String>,
+    #[serde(default = "default_pct_min")]
+    pub pct_min: f64,
+    #[serde(default = "default_pct_max")]
+    pub pct_max: f64,
+    /// 匹配金额下限（元）
+    #[serde(default = "default_amount_min")]
+    pub amount_min: f64,
+    /// 匹配量下限（手）
+    #[serde(default)]
+    pub volume_min: i64,
+    /// 未匹配量下限（手）
+    pub unmatched_min: Option<i64>,
+    /// 仅返回当日盘中也被筛选入库的股票
+    #[serde(default)]
+    pub linked_only: bool,
+    #[serde(default = "default_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size")]
+    pub page_size: i64,
+}
+
+fn default_pct_min() -> f64 {
+    AuctionFilterParams::default().pct_min
+}
+fn default_pct_max() -> f64 {
+    AuctionFilterParams::default().pct_max
+}
+fn default_amount_min() -> f64 {
+    AuctionFilterParams::default().amount_min
+}
+fn default_page() -> i64 {
+    1
+}
+fn default_page_size() -> i64 {
+    20
+}
+
+/// 竞价快照条目（含当日盘中快照关联）
+#[derive(Debug, Serialize)]
+pub struct AuctionSnapshotItem {
+    pub id: i32,
+    pub trade_date: NaiveDate,
+    pub sample_time: String,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub matched_price: BigDecimal,
+    pub prev_close: BigDecimal,
+    pub change_pct: BigDecimal,
+    pub matched_volume: i64,
+    pub matched_amount: BigDecimal,
+    pub unmatched_volume: Option<i64>,
+    pub universe: String,
+    /// 当日 stock_snapshots 中的条数
+    pub snapshot_count: i64,
+    pub first_snapshot_at: Option<DateTime<Utc>>,
+    pub first_snapshot_price: Option<BigDecimal>,
+    pub first_snapshot_session: Option<String>,
+}
+
+impl From<crate::repositories::auction_snapshot::AuctionSnapshotLinked> for AuctionSnapshotItem {
+    fn from(r: crate::repositories::auction_snapshot::AuctionSnapshotLinked) -> Self {
+        Self {
+            id: r.id,
+            trade_date: r.trade_date,
+            sample_time: r.sample_time.format("%H:%M").to_string(),
+            stock_code: r.stock_code,
+            stock_name: r.stock_name,
+            matched_price: r.matched_price,
+            prev_close: r.prev_close,
+            change_pct: r.change_pct,
+            matched_volume: r.matched_volume,
+            matched_amount: r.matched_amount,
+            unmatched_volume: r.unmatched_volume,
+            universe: r.universe,
+            snapshot_count: r.snapshot_count,
+            first_snapshot_at: r.first_snapshot_at,
+            first_snapshot_price: r.first_snapshot_price,
+            first_snapshot_session: r.first_snapshot_session,
+        }
+    }
+}
+
+/// 竞价快照查询响应（分页）
+#[derive(Debug, Serialize)]
+pub struct AuctionSnapshotListResponse {
+    pub trade_date: NaiveDate,
+    /// 本次查询的采样时刻，当日无数据时为空
+    pub sample_time: Option<String>,
+    /// 当日全部采样时刻
+    pub sample_times: Vec<String>,
+    pub data: Vec<AuctionSnapshotItem>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub total_pages: i64,
+}
+
+/// 手动触发竞价采样请求
+#[derive(Debug, Deserialize, Default)]
+pub struct AuctionCaptureRequest {
+    /// market / watchlist / codes，缺省为 market
+    pub universe: Option<String>,
+    /// universe = codes 时的股票代码
+    #[serde(default)]
+    pub codes: Vec<String>,
+}
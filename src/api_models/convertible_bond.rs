@@ -0,0 +1,124 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ConvertibleBond, ConvertibleBondDaily};
+
+/// 已同步可转债列表查询
+#[derive(Debug, Deserialize)]
+pub struct ConvertibleBondListQuery {
+    /// 匹配债券代码、简称、正股代码或正股名称
+    pub keyword: Option<String>,
+    /// 是否包含已摘牌的债券，默认不包含
+    pub include_delisted: Option<bool>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 单日行情
+#[derive(Debug, Serialize)]
+pub struct ConvertibleBondDailyItem {
+    pub trade_date: NaiveDate,
+    pub bond_price: BigDecimal,
+    pub stock_price: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub transfer_value: Option<BigDecimal>,
+    /// 转股溢价率（%）
+    pub transfer_premium_ratio: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub pbv_ratio: Option<BigDecimal>,
+    pub rating: Option<String>,
+    pub remaining_scale: Option<BigDecimal>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<ConvertibleBondDaily> for ConvertibleBondDailyItem {
+    fn from(d: ConvertibleBondDaily) -> Self {
+        Self {
+            trade_date: d.trade_date,
+            bond_price: d.bond_price,
+            stock_price: d.stock_price,
+            transfer_price: d.transfer_price,
+            transfer_value: d.transfer_value,
+            transfer_premium_ratio: d.transfer_premium_ratio,
+            resale_trig_price: d.resale_trig_price,
+            redeem_trig_price: d.redeem_trig_price,
+            pbv_ratio: d.pbv_ratio,
+            rating: d.rating,
+            remaining_scale: d.remaining_scale,
+            created_at: d.created_at,
+        }
+    }
+}
+
+/// 可转债主数据，附最近一个已同步交易日的行情
+#[derive(Debug, Serialize)]
+pub struct ConvertibleBondResponse {
+    pub bond_code: String,
+    pub bond_short_name: String,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub rating: Option<String>,
+    /// 实际发行规模（亿元）
+    pub issue_scale: Option<BigDecimal>,
+    /// 剩余规模（亿元）
+    pub remaining_scale: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub listing_date: Option<NaiveDate>,
+    pub expire_date: Option<NaiveDate>,
+    pub delist_date: Option<NaiveDate>,
+    pub last_trading_date: Option<NaiveDate>,
+    pub latest: Option<ConvertibleBondDailyItem>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ConvertibleBondResponse {
+    pub fn new(b: ConvertibleBond, latest: Option<ConvertibleBondDaily>) -> Self {
+        Self {
+            bond_code: b.bond_code,
+            bond_short_name: b.bond_short_name,
+            stock_code: b.stock_code,
+            stock_name: b.stock_name,
+            rating: b.rating,
+            issue_scale: b.issue_scale,
+            remaining_scale: b.remaining_scale,
+            transfer_price: b.transfer_price,
+            resale_trig_price: b.resale_trig_price,
+            redeem_trig_price: b.redeem_trig_price,
+            listing_date: b.listing_date,
+            expire_date: b.expire_date,
+            delist_date: b.delist_date,
+            last_trading_date: b.last_trading_date,
+            latest: latest.map(Into::into),
+            created_at: b.created_at,
+            updated_at: b.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertibleBondListResponse {
+    /// 行情所属的交易日（尚未同步过行情时为空）
+    pub trade_date: Option<NaiveDate>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<ConvertibleBondResponse>,
+}
+
+/// 行情历史查询
+#[derive(Debug, Deserialize)]
+pub struct ConvertibleBondDailyQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertibleBondDailyResponse {
+    pub bond: ConvertibleBondResponse,
+    pub items: Vec<ConvertibleBondDailyItem>,
+}
@@ -0,0 +1,130 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::KlineAuditFinding;
+
+/// 问题列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct KlineAuditFindingQuery {
+    pub stock_code: Option<String>,
+    /// ohlc_invalid / zero_volume / duplicate_bar / non_trading_day / gap / price_jump
+    pub check_type: Option<String>,
+    /// error / warning
+    pub severity: Option<String>,
+    /// open / resolved / ignored，缺省为 open
+    pub status: Option<String>,
+    /// 与问题区间有交集的日期范围
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineAuditFindingResponse {
+    pub id: i32,
+    pub stock_code: String,
+    pub check_type: String,
+    pub severity: String,
+    pub trade_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub detail: String,
+    pub context: Option<Value>,
+    pub status: String,
+    pub first_detected_at: NaiveDateTime,
+    pub last_detected_at: NaiveDateTime,
+    pub resolved_at: Option<NaiveDateTime>,
+}
+
+impl From<KlineAuditFinding> for KlineAuditFindingResponse {
+    fn from(f: KlineAuditFinding) -> Self {
+        Self {
+            id: f.id,
+            stock_code: f.stock_code,
+            check_type: f.check_type,
+            severity: f.severity,
+            trade_date: f.trade_date,
+            end_date: f.end_date,
+            detail: f.detail,
+            context: f.context,
+            status: f.status,
+            first_detected_at: f.first_detected_at,
+            last_detected_at: f.last_detected_at,
+            resolved_at: f.resolved_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineAuditFindingListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<KlineAuditFindingResponse>,
+}
+
+/// 单个检查项的问题数
+#[derive(Debug, Default, Serialize)]
+pub struct KlineAuditSummaryItem {
+    pub check_type: String,
+    pub severity: String,
+    pub open: i64,
+    pub resolved: i64,
+    pub ignored: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineAuditSummaryResponse {
+    /// 有未处理问题的股票数
+    pub open_stocks: i64,
+    pub open_findings: i64,
+    pub items: Vec<KlineAuditSummaryItem>,
+}
+
+/// 手动审计（参数均可省略，含义同 kline_audit 任务参数）
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct KlineAuditRunRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stock_set: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plate_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineAuditRunResponse {
+    pub message: String,
+}
+
+/// 修改问题状态
+#[derive(Debug, Deserialize)]
+pub struct UpdateKlineAuditFindingRequest {
+    /// ignored（人工确认无需处理）或 open（撤销忽略）
+    pub status: String,
+}
+
+/// 按股票修复，缺省区间覆盖该股全部未处理的问题
+#[derive(Debug, Default, Deserialize)]
+pub struct KlineRepairRequest {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineRepairResponse {
+    pub stock_code: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub deleted_count: usize,
+    pub inserted_count: usize,
+    /// 复检后不再出现的问题数
+    pub resolved_count: usize,
+    /// 复检后仍未处理的问题
+    pub remaining: Vec<KlineAuditFindingResponse>,
+}
@@ -0,0 +1,163 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{KlineBackfillItem, KlineBackfillRun};
+use crate::services::kline_backfill::StockCoverage;
+
+/// 新建回补任务
+#[derive(Debug, Deserialize)]
+pub struct KlineBackfillRequest {
+    /// 股票集合：all（stock_table 全部）/ plate / watchlist / codes
+    pub stock_set: String,
+    /// stock_set = plate 时的板块代码
+    pub plate_code: Option<String>,
+    /// stock_set = codes 时的股票代码
+    pub codes: Option<Vec<String>>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// 覆盖率查询参数（只检测缺口，不拉取数据）
+#[derive(Debug, Deserialize)]
+pub struct KlineCoverageQuery {
+    pub stock_set: String,
+    pub plate_code: Option<String>,
+    /// 逗号分隔的股票代码
+    pub codes: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KlineBackfillListQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineBackfillRunResponse {
+    pub id: i32,
+    pub stock_set: String,
+    pub set_key: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// pending / running / success / partial / failed / cancelled；
+    /// 执行被中断（如服务重启）时为对应执行记录的状态
+    pub status: String,
+    pub total_stocks: i32,
+    pub completed_stocks: i32,
+    pub failed_stocks: i32,
+    pub inserted_count: i32,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl From<KlineBackfillRun> for KlineBackfillRunResponse {
+    fn from(run: KlineBackfillRun) -> Self {
+        Self {
+            id: run.id,
+            stock_set: run.stock_set,
+            set_key: run.set_key,
+            start_date: run.start_date,
+            end_date: run.end_date,
+            status: run.status,
+            total_stocks: run.total_stocks,
+            completed_stocks: run.completed_stocks,
+            failed_stocks: run.failed_stocks,
+            inserted_count: run.inserted_count,
+            execution_id: run.execution_id,
+            error_message: run.error_message,
+            created_at: run.created_at,
+            updated_at: run.updated_at,
+            completed_at: run.completed_at,
+        }
+    }
+}
+
+/// 单只股票的回补进度与覆盖率
+#[derive(Debug, Serialize)]
+pub struct KlineBackfillItemResponse {
+    pub stock_code: String,
+    /// pending / success / failed
+    pub status: String,
+    pub expected_days: i32,
+    pub present_before: i32,
+    pub present_after: Option<i32>,
+    /// 回补后的覆盖率（0 ~ 1），未处理时为空
+    pub coverage: Option<f64>,
+    pub inserted_count: i32,
+    /// 回补后仍缺失的区间（通常为停牌或未上市）
+    pub missing_ranges: Option<Value>,
+    pub error_message: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<KlineBackfillItem> for KlineBackfillItemResponse {
+    fn from(item: KlineBackfillItem) -> Self {
+        let coverage = item.present_after.map(|present| {
+            if item.expected_days == 0 {
+                1.0
+            } else {
+                present as f64 / item.expected_days as f64
+            }
+        });
+        Self {
+            stock_code: item.stock_code,
+            status: item.status,
+            expected_days: item.expected_days,
+            present_before: item.present_before,
+            present_after: item.present_after,
+            coverage,
+            inserted_count: item.inserted_count,
+            missing_ranges: item.missing_ranges,
+            error_message: item.error_message,
+            updated_at: item.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineBackfillDetailResponse {
+    pub run: KlineBackfillRunResponse,
+    pub items: Vec<KlineBackfillItemResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineBackfillListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<KlineBackfillRunResponse>,
+}
+
+/// 新建 / 继续回补任务的结果
+#[derive(Debug, Serialize)]
+pub struct KlineBackfillStartResponse {
+    pub run: KlineBackfillRunResponse,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockCoverageItem {
+    #[serde(flatten)]
+    pub coverage: StockCoverage,
+    pub ratio: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KlineCoverageResponse {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// 区间内的交易日数
+    pub trading_days: usize,
+    pub total_stocks: usize,
+    /// 无缺口的股票数
+    pub complete_stocks: usize,
+    /// 区间内完全没有K线的股票数
+    pub empty_stocks: usize,
+    pub stocks: Vec<StockCoverageItem>,
+}
@@ -1,10 +1,18 @@
 pub mod ai_analysis;
+pub mod ai_batch;
+pub mod ai_evaluation;
+pub mod ai_market_report;
+pub mod ai_usage;
+pub mod auction_snapshot;
 pub mod bagua;
 pub mod basic_data_analysis;
+pub mod convertible_bond;
 pub mod convertible_bond_query;
 pub mod daily_kline;
 pub mod dynamic_backtrack;
 pub mod export_button_config;
+pub mod kline_audit;
+pub mod kline_backfill;
 pub mod kline_import;
 pub mod monthly_kline;
 pub mod multi_level_filter;
@@ -24,6 +32,7 @@ pub mod stock_track_query;
 pub mod stock_trade_date_query;
 pub mod stock_watchlist;
 pub mod stock_watchlist_query;
+pub mod trading_calendar;
 
 #[allow(unused_imports)]
 pub use ai_analysis::{
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::models::job_execution_history::JobExecutionHistory;
+use crate::models::JobPipelineRun;
 
 /// 任务信息
 #[derive(Serialize, Clone, Debug)]
@@ -10,8 +11,245 @@ pub struct JobInfo {
     pub name: String,
     pub display_name: String,
     pub description: String,
+    /// 执行时间说明；cron 被修改过时为实际的 cron 表达式
     pub schedule: String,
     pub enabled: bool,
+    /// 是否正在执行（本实例或其他实例）
+    pub running: bool,
+    /// 正在执行时锁的持有者
+    pub lock_holder: Option<String>,
+    /// 任务已在执行时的处理策略：skip / queue / fail
+    pub conflict_policy: String,
+    pub retry_max_attempts: i32,
+    pub retry_backoff_secs: i32,
+    /// 是否支持「只重试失败项」
+    pub retryable: bool,
+    pub cron_expressions: Vec<String>,
+    pub timezone: String,
+    /// 定时触发时传给任务的参数
+    pub params: Option<Value>,
+    /// 下次触发时间（按调度时区），停用时为空
+    pub next_run_at: Option<String>,
+    /// 调度配置最后修改时间
+    pub updated_at: Option<String>,
+    /// 上游任务（上游完成后自动执行）
+    pub depends_on: Vec<String>,
+    /// 下游任务
+    pub dependents: Vec<String>,
+}
+
+/// 手动触发的查询参数
+#[derive(Deserialize, Debug, Default)]
+pub struct TriggerJobQuery {
+    /// 为 true 时连同下游任务按流水线后台执行
+    pub cascade: Option<bool>,
+}
+
+/// 修改任务调度配置，未传的字段保持不变
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateJobScheduleRequest {
+    pub enabled: Option<bool>,
+    pub cron_expressions: Option<Vec<String>>,
+    pub timezone: Option<String>,
+    /// 传 `{}` 清空参数
+    pub params: Option<Value>,
+    /// skip / queue / fail
+    pub conflict_policy: Option<String>,
+    /// 自动重试次数，0 为不重试
+    pub retry_max_attempts: Option<i32>,
+    /// 首次自动重试的等待秒数，之后每次翻倍
+    pub retry_backoff_secs: Option<i32>,
+}
+
+/// 通用触发接口 `/jobs/:job_name/trigger` 与重试接口的响应；
+/// 旧版 `/trigger-*` 接口仍按各自原有的格式返回，见 [`LegacyTriggerResponse`]
+#[derive(Serialize, Debug)]
+pub struct TriggerJobResponse {
+    pub success: bool,
+    pub message: String,
+    pub job_name: String,
+    /// 后台执行时为空
+    pub status: Option<String>,
+    pub execution_id: Option<i32>,
+    pub total_count: usize,
+    pub success_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    pub duration_ms: Option<i64>,
+    pub details: Option<Value>,
+}
+
+/// 旧版 `/trigger-*` 接口的响应，字段与改造为通用触发接口之前一致
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum LegacyTriggerResponse {
+    /// K线导入、观察表K线导入
+    Stocks(TriggerTaskResponse),
+    ProfitAnalysis(TriggerProfitAnalysisResponse),
+    StockFilter(TriggerStockFilterResponse),
+    StockTableSync(TriggerStockTableSyncResponse),
+    StockPlateSync(TriggerStockPlateSyncResponse),
+    AuctionCapture(TriggerAuctionCaptureResponse),
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerTaskResponse {
+    pub success: bool,
+    pub message: String,
+    pub total_stocks: usize,
+    pub success_count: usize,
+    pub failed_count: usize,
+    pub details: Vec<StockDetail>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockDetail {
+    pub stock_code: String,
+    pub imported_count: usize,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerProfitAnalysisResponse {
+    pub success: bool,
+    pub message: String,
+    pub total_snapshots: usize,
+    pub analyzed_count: usize,
+    pub skipped_count: usize,
+    pub no_kline_count: usize,
+    pub details: Vec<SnapshotDetail>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SnapshotDetail {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub profit_rate: i32,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerStockFilterResponse {
+    pub success: bool,
+    pub message: String,
+    pub items_count: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerStockTableSyncResponse {
+    pub success: bool,
+    pub message: String,
+    pub total_count: usize,
+    pub success_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    pub details: Vec<StockTableSyncDetail>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockTableSyncDetail {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub action: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerStockPlateSyncResponse {
+    pub success: bool,
+    pub message: String,
+    pub total_count: usize,
+    pub success_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    pub details: Vec<StockPlateSyncDetail>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StockPlateSyncDetail {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub plate_total: usize,
+    pub plate_inserted: usize,
+    pub relation_inserted: usize,
+    pub action: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TriggerAuctionCaptureResponse {
+    pub success: bool,
+    pub message: String,
+    /// 采样时刻（HH:MM）
+    pub sample_time: String,
+    pub total_count: usize,
+    pub detail_count: usize,
+    pub saved_count: usize,
+}
+
+/// 取消执行响应
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelExecutionResponse {
+    pub execution_id: i32,
+    pub job_name: String,
+    /// 任务是否在本实例执行（否则由执行所在实例在轮询到取消标记后停止）
+    pub local: bool,
+    pub message: String,
+}
+
+/// 流水线查询参数
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineQueryParams {
+    pub root_job: Option<String>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// 流水线执行记录
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRunItem {
+    pub id: i32,
+    pub root_job: String,
+    pub trigger: String,
+    pub status: String,
+    /// 各步骤：`{job_name, status, execution_id, duration_ms, reason}`
+    pub steps: Value,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl From<JobPipelineRun> for PipelineRunItem {
+    fn from(run: JobPipelineRun) -> Self {
+        Self {
+            id: run.id,
+            root_job: run.root_job,
+            trigger: run.trigger_type,
+            status: run.status,
+            steps: run.steps,
+            started_at: run.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            completed_at: run
+                .completed_at
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+            duration_ms: run.duration_ms,
+        }
+    }
+}
+
+/// 流水线执行记录分页响应
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRunListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<PipelineRunItem>,
 }
 
 /// 查询参数
@@ -50,6 +288,14 @@ pub struct JobExecutionHistoryItem {
     pub details: Option<Value>,
     pub error_message: Option<String>,
     pub duration_ms: Option<i64>,
+    /// 第几次执行（首次为 1）
+    pub attempt: i32,
+    /// 被重试的执行记录 ID
+    pub retry_of: Option<i32>,
+    /// 本次执行的任务参数
+    pub params: Option<Value>,
+    /// 是否已请求取消（执行中时为取消尚未生效）
+    pub cancel_requested: bool,
 }
 
 impl From<JobExecutionHistory> for JobExecutionHistoryItem {
@@ -69,6 +315,10 @@ impl From<JobExecutionHistory> for JobExecutionHistoryItem {
             details: history.details,
             error_message: history.error_message,
             duration_ms: history.duration_ms,
+            attempt: history.attempt,
+            retry_of: history.retry_of,
+            params: history.params,
+            cancel_requested: history.cancel_requested,
         }
     }
 }
@@ -15,6 +15,8 @@ pub struct StockSnapshotResponse {
     pub bid_ask_ratio: BigDecimal,
     pub main_force_inflow: BigDecimal,
     pub created_at: DateTime<Utc>,
+    /// 抓取时所处交易时段
+    pub session: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,6 +30,8 @@ pub struct CreateStockSnapshot {
     pub turnover_rate: BigDecimal,
     pub bid_ask_ratio: BigDecimal,
     pub main_force_inflow: BigDecimal,
+    /// 交易时段，缺省按 created_at 所处时段自动归属
+    pub session: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,10 +40,19 @@ pub struct TodayStockCodesResponse {
     pub stock_codes: Vec<String>,
 }
 
+/// 当天股票代码查询参数
+#[derive(Debug, Deserialize)]
+pub struct TodayCodesQuery {
+    /// 仅统计某一交易时段，如 continuous / closing_auction
+    pub session: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DailyCountQuery {
     pub start: String,
     pub end: String,
+    /// 仅统计某一交易时段，缺省为全部时段
+    pub session: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -47,3 +60,11 @@ pub struct DailyStockCountItem {
     pub date: String,
     pub count: i64,
 }
+
+/// 按交易日 + 交易时段拆分的去重股票支数
+#[derive(Debug, Serialize)]
+pub struct SessionStockCountItem {
+    pub date: String,
+    pub session: String,
+    pub count: i64,
+}
@@ -1,4 +1,5 @@
-use chrono::NaiveDateTime;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -20,4 +21,93 @@ pub struct StockTableResponse {
     pub stock_name: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// SH / SZ / BJ
+    pub exchange: Option<String>,
+    /// main / chinext / star / bse
+    pub board: Option<String>,
+    pub listing_date: Option<NaiveDate>,
+    pub industry: Option<String>,
+    pub total_shares: Option<i64>,
+    pub float_shares: Option<i64>,
+    /// 总市值（元）
+    pub total_market_cap: Option<BigDecimal>,
+    /// 流通市值（元）
+    pub float_market_cap: Option<BigDecimal>,
+    pub is_st: bool,
+    /// listed / suspended / delisted
+    pub list_status: String,
+    pub delisted_at: Option<NaiveDateTime>,
+    pub master_synced_at: Option<NaiveDateTime>,
+}
+
+/// 主数据查询参数
+#[derive(Debug, Deserialize)]
+pub struct StockMasterQuery {
+    /// 代码或名称包含
+    pub keyword: Option<String>,
+    pub exchange: Option<String>,
+    pub board: Option<String>,
+    pub industry: Option<String>,
+    pub is_st: Option<bool>,
+    /// listed / suspended / delisted，缺省不过滤
+    pub list_status: Option<String>,
+    /// 总市值下限（亿元）
+    pub market_cap_min: Option<f64>,
+    /// 总市值上限（亿元）
+    pub market_cap_max: Option<f64>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockMasterListResponse {
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub items: Vec<StockTableResponse>,
+}
+
+/// 简称历史查询
+#[derive(Debug, Deserialize)]
+pub struct StockNameHistoryQuery {
+    pub stock_code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockNameHistoryResponse {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub is_st: bool,
+    pub valid_from: NaiveDate,
+    /// 最后有效日（含），null 表示当前名称
+    pub valid_to: Option<NaiveDate>,
+    /// snapshot（由历史快照回填）/ master（证券主数据同步）
+    pub source: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<crate::models::StockNameHistory> for StockNameHistoryResponse {
+    fn from(h: crate::models::StockNameHistory) -> Self {
+        Self {
+            stock_code: h.stock_code,
+            stock_name: h.stock_name,
+            is_st: h.is_st,
+            valid_from: h.valid_from,
+            valid_to: h.valid_to,
+            source: h.source,
+            created_at: h.created_at,
+        }
+    }
+}
+
+/// 某日使用 ST 类简称的股票，缺省为今天
+#[derive(Debug, Deserialize)]
+pub struct StStocksQuery {
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StStocksResponse {
+    pub date: NaiveDate,
+    pub items: Vec<StockNameHistoryResponse>,
 }
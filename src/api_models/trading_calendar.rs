@@ -0,0 +1,108 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// 交易日历区间请求：补齐（按工作日规则）或按 K 线推断区间内缺失的日期
+#[derive(Debug, Deserialize)]
+pub struct TradingCalendarSyncRequest {
+    /// 起始日期，格式：YYYY-MM-DD
+    pub start_date: String,
+    /// 结束日期，格式：YYYY-MM-DD
+    pub end_date: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradingCalendarSyncResponse {
+    pub inserted: usize,
+    pub total_loaded: usize,
+}
+
+/// 单日交易日信息查询参数
+#[derive(Debug, Deserialize)]
+pub struct TradingDayInfoQuery {
+    /// 查询日期，格式：YYYY-MM-DD，缺省为今天（上海时间）
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradingDayInfoResponse {
+    pub date: NaiveDate,
+    pub is_trading_day: bool,
+    pub holiday_name: Option<String>,
+    pub prev_trading_day: NaiveDate,
+    pub next_trading_day: NaiveDate,
+    pub session_open_now: bool,
+    pub covered_start: Option<NaiveDate>,
+    pub covered_end: Option<NaiveDate>,
+}
+
+/// 区间交易日计数查询参数
+#[derive(Debug, Deserialize)]
+pub struct TradingDayCountQuery {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradingDayCountResponse {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub trading_days: usize,
+}
+
+/// 休市安排导入结果
+#[derive(Debug, Serialize)]
+pub struct HolidayImportResponse {
+    /// 导入的年份
+    pub years: Vec<i32>,
+    /// 写入（新增或覆盖）的日历行数
+    pub rows_written: usize,
+    pub total_loaded: usize,
+}
+
+/// 日历记录列表查询参数，缺省为当年
+#[derive(Debug, Deserialize)]
+pub struct TradingCalendarListQuery {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+/// 新增日历记录请求
+#[derive(Debug, Deserialize)]
+pub struct CreateTradingCalendarDayRequest {
+    /// 日期，格式：YYYY-MM-DD
+    pub trade_date: String,
+    pub is_holiday: bool,
+    pub holiday_name: Option<String>,
+}
+
+/// 更新日历记录请求
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateTradingCalendarDayRequest {
+    pub is_holiday: Option<bool>,
+    /// 传空字符串表示清除名称
+    pub holiday_name: Option<String>,
+}
+
+/// 日历记录响应
+#[derive(Debug, Serialize)]
+pub struct TradingCalendarDayResponse {
+    pub id: i32,
+    pub trade_date: NaiveDate,
+    pub is_holiday: bool,
+    pub holiday_name: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl From<crate::models::StockTradingCalendar> for TradingCalendarDayResponse {
+    fn from(item: crate::models::StockTradingCalendar) -> Self {
+        Self {
+            id: item.id,
+            trade_date: item.trade_date,
+            is_holiday: item.is_holiday,
+            holiday_name: item.holiday_name,
+            created_at: item.created_at,
+            updated_at: item.updated_at,
+        }
+    }
+}
@@ -7,7 +7,9 @@ use tower_http::trace::{
 use tracing::Level;
 
 use crate::routes;
+use crate::scheduler::registry::JobRegistry;
 use crate::services::monthly_ma_cross_screen_cache::MaCrossScreenCache;
+use crate::services::trading_calendar::TradingCalendar;
 use crate::utils::middleware;
 use crate::utils::ws_broadcast::TaskStatusSender;
 
@@ -19,6 +21,10 @@ pub struct AppState {
     pub ws_sender: TaskStatusSender,
     /// 多级筛选月线扫描：同一天 + 快照股列表指纹命中时跳过重复 proxy 批量请求。
     pub ma_cross_screen_cache: MaCrossScreenCache,
+    /// 交易日历缓存（与定时任务共享同一份）
+    pub trading_calendar: TradingCalendar,
+    /// 定时任务注册表（任务列表与手动触发）
+    pub job_registry: JobRegistry,
 }
 
 #[allow(dead_code)]
@@ -29,10 +35,19 @@ pub fn build_app() -> Router {
         .build(manager)
         .expect("Failed to create DB pool");
     let ws_sender = crate::utils::ws_broadcast::create_broadcast_channel();
+    let trading_calendar = TradingCalendar::default();
+    let job_registry = JobRegistry::new(
+        db_pool.clone(),
+        ws_sender.clone(),
+        trading_calendar.clone(),
+        crate::scheduler::all_jobs(),
+    );
     let state = AppState {
         db_pool,
         ws_sender,
         ma_cross_screen_cache: MaCrossScreenCache::default(),
+        trading_calendar,
+        job_registry,
     };
 
     routes::build_routes()
@@ -47,11 +62,18 @@ pub fn build_app() -> Router {
         )
 }
 
-pub fn build_app_with_pool(db_pool: DbPool, ws_sender: TaskStatusSender) -> Router {
+pub fn build_app_with_pool(
+    db_pool: DbPool,
+    ws_sender: TaskStatusSender,
+    trading_calendar: TradingCalendar,
+    job_registry: JobRegistry,
+) -> Router {
     let state = AppState {
         db_pool,
         ws_sender,
         ma_cross_screen_cache: MaCrossScreenCache::default(),
+        trading_calendar,
+        job_registry,
     };
 
     routes::build_routes()
@@ -1,202 +1,179 @@
+use std::convert::Infallible;
+
 use axum::{
     extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
-use chrono::{FixedOffset, NaiveDate, Utc};
+use futures::Stream;
 use serde_json::json;
+use tokio::sync::mpsc;
+
+use chrono::Utc;
 
 use crate::api_models::ai_analysis::{
-    TrendDetailResponse, TrendHistoryItem, TrendHistoryRequest, TrendHistoryResponse,
-    TrendPredictionRequest, TrendPredictionResponse,
+    AnalysisThreadResponse, FollowUpRequest, FollowUpResponse, TrendDetailResponse,
+    TrendHistoryItem, TrendHistoryRequest, TrendHistoryResponse, TrendPredictionRequest,
+    TrendPredictionResponse,
 };
 use crate::app::AppState;
 use crate::handler::error::AppError;
-use crate::models::{NewAiTrendAnalysis, UpdateAiTrendAnalysis};
-use crate::repositories::{ai_trend_analysis, daily_kline};
-use crate::services::{ai_service, kline_service};
-use crate::utils::http_client;
+use crate::models::PromptTemplate;
+use crate::repositories::ai_analysis_message;
+use crate::repositories::ai_trend_analysis::{self, AnalysisHistoryFilter};
+use crate::services::ai_conversation::{self, ConversationError};
+use crate::services::ai_result::{Action, Rating};
+use crate::services::ai_service::AnalysisEvent;
+use crate::services::llm_provider::LlmProvider;
+use crate::services::prompt_template::{
+    self, PromptTemplateError, TEMPLATE_TREND_FOLLOWUP, TEMPLATE_TREND_PREDICTION,
+};
+use crate::services::trend_analysis::{self, TrendAnalysisError};
+use crate::services::{ai_service, llm_provider};
 
-/// POST /api/ai-analysis/trend-prediction
-/// 对指定股票发起趋势分析
-pub async fn trend_prediction(
-    State(state): State<AppState>,
-    Json(payload): Json<TrendPredictionRequest>,
-) -> Result<Json<TrendPredictionResponse>, AppError> {
+/// 校验请求并确定模型后端与提示词模板，配置缺失时不再查询数据
+fn prepare_analysis(
+    state: &AppState,
+    payload: &TrendPredictionRequest,
+) -> Result<(String, Box<dyn LlmProvider>, PromptTemplate), AppError> {
     let stock_code_input = payload.stock_code.trim().to_string();
     if stock_code_input.is_empty() {
         return Err(AppError::BadRequest("stock_code is required".to_string()));
     }
 
-    let start_time = std::time::Instant::now();
-
-    // 1. 查询 stock_snapshots 获取该股票的信号数据
-    let (signals, stock_name) = {
-        let mut conn = state
-            .db_pool
-            .get()
-            .map_err(|_| AppError::InternalServerError)?;
-        query_stock_signals(&mut conn, &stock_code_input)?
-    };
-
-    if signals.is_empty() {
-        return Err(AppError::BadRequest(format!(
-            "股票 {stock_code_input} 没有信号数据（stock_snapshots 中无记录）"
-        )));
-    }
-
-    // 2. 找到最早信号日期
-    let earliest_signal_date = signals.iter().map(|s| s.signal_date).min().unwrap();
-
-    // 3. 查询 stock_trading_calendar，从最早信号日往前找20个交易日
-    let kline_start_date = {
-        let mut conn = state
-            .db_pool
-            .get()
-            .map_err(|_| AppError::InternalServerError)?;
-        find_trading_date_before(&mut conn, earliest_signal_date, 20)?
-    };
-
-    // 4. 结束日期为当前日期（UTC+8）
-    let utc_plus_8 = FixedOffset::east_opt(8 * 3600).unwrap();
-    let today = Utc::now().with_timezone(&utc_plus_8).date_naive();
+    let provider = llm_provider::resolve_provider(payload.model.as_deref())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-    // 5. 查询 daily_klines 获取K线数据
-    let klines = {
+    // 提示词模板：指定版本或当前启用版本
+    let template = {
         let mut conn = state
             .db_pool
             .get()
             .map_err(|_| AppError::InternalServerError)?;
-        query_kline_range(&mut conn, &stock_code_input, kline_start_date, today)?
+        prompt_template::resolve(
+            &mut conn,
+            TEMPLATE_TREND_PREDICTION,
+            payload.prompt_version,
+            Utc::now().naive_utc(),
+        )
+        .map_err(map_prompt_error)?
     };
+    Ok((stock_code_input, provider, template))
+}
 
-    // 6. 检查K线数据是否完整，不完整则自动补齐
-    let klines = if klines.is_empty() || needs_kline_fill(&klines, kline_start_date, today) {
-        tracing::info!(
-            "K线数据不完整，自动补齐 {} 从 {} 到 {}",
-            stock_code_input,
-            kline_start_date,
-            today
-        );
-        fill_missing_klines(&state, &stock_code_input, kline_start_date, today).await?;
-        // 重新查询完整数据
-        let mut conn = state
-            .db_pool
-            .get()
-            .map_err(|_| AppError::InternalServerError)?;
-        query_kline_range(&mut conn, &stock_code_input, kline_start_date, today)?
-    } else {
-        klines
-    };
+/// POST /api/ai-analysis/trend-prediction
+/// 对指定股票发起趋势分析
+pub async fn trend_prediction(
+    State(state): State<AppState>,
+    Json(payload): Json<TrendPredictionRequest>,
+) -> Result<Json<TrendPredictionResponse>, AppError> {
+    let (stock_code_input, provider, template) = prepare_analysis(&state, &payload)?;
+
+    let analysis = trend_analysis::analyze_stock(
+        &state.db_pool,
+        &state.trading_calendar,
+        provider.as_ref(),
+        &template,
+        &stock_code_input,
+        payload.force,
+        None,
+    )
+    .await
+    .map_err(map_analysis_error)?;
 
-    // 7. 组装 send_to_ai.json 格式的 payload
-    let kline_strings: Vec<String> = klines
-        .iter()
-        .map(|k| {
-            format!(
-                "{},{},{},{},{},{}",
-                k.trade_date, k.open_price, k.close_price, k.high_price, k.low_price, k.volume
-            )
-        })
-        .collect();
+    if analysis.record.status == ai_service::STATUS_FAILED {
+        return Err(AppError::BadRequest(
+            analysis
+                .record
+                .error_message
+                .unwrap_or_else(|| "AI分析失败".to_string()),
+        ));
+    }
 
-    let signal_json: Vec<serde_json::Value> = signals
-        .iter()
-        .map(|s| {
-            json!({
-                "datetime": s.datetime,
-                "change_pct": s.change_pct,
-                "volume_ratio": s.volume_ratio,
-                "turnover_rate": s.turnover_rate,
-                "bid_ask_ratio": s.bid_ask_ratio,
-            })
-        })
-        .collect();
+    Ok(Json(to_prediction_response(
+        &analysis.record,
+        analysis.cache_hit,
+    )))
+}
 
-    let ai_payload = json!({
-        "stock_name": format!("{} {}", stock_code_input, stock_name.as_deref().unwrap_or("")),
-        "klines": kline_strings,
-        "signals": signal_json,
+/// GET /api/ai-analysis/trend-prediction/stream?stock_code=&model=&prompt_version=
+/// 以 Server-Sent Events 推送分析过程：`started`（分析记录 ID）、`delta`（模型输出的增量文本）、
+/// `repair`（输出未通过校验，开始修正）、最后为 `result`（与同步接口相同的响应）或 `error`。
+/// 分析在后台任务中执行，客户端断开后仍会完成并写入 `ai_trend_analysis`
+pub async fn trend_prediction_stream(
+    State(state): State<AppState>,
+    Query(payload): Query<TrendPredictionRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let (stock_code_input, provider, template) = prepare_analysis(&state, &payload)?;
+
+    let (tx, rx) = mpsc::unbounded_channel::<Event>();
+    tokio::spawn(async move {
+        let sink = |event: AnalysisEvent| {
+            // 客户端断开后接收端关闭，发送失败可忽略
+            let _ = tx.send(to_sse_event(event));
+        };
+        let result = trend_analysis::analyze_stock(
+            &state.db_pool,
+            &state.trading_calendar,
+            provider.as_ref(),
+            &template,
+            &stock_code_input,
+            payload.force,
+            Some(&sink),
+        )
+        .await;
+        let last = match result {
+            Ok(analysis) if analysis.record.status == ai_service::STATUS_FAILED => error_event(
+                analysis
+                    .record
+                    .error_message
+                    .as_deref()
+                    .unwrap_or("AI分析失败"),
+            ),
+            Ok(analysis) => Event::default()
+                .event("result")
+                .json_data(to_prediction_response(&analysis.record, analysis.cache_hit))
+                .unwrap_or_else(|e| error_event(&e.to_string())),
+            Err(e) => {
+                if !matches!(
+                    e,
+                    TrendAnalysisError::NoSignals(_) | TrendAnalysisError::BudgetExceeded(_)
+                ) {
+                    tracing::error!("Trend analysis stream failed: {}", e);
+                }
+                error_event(&e.to_string())
+            }
+        };
+        let _ = tx.send(last);
     });
 
-    // 8. 先创建一条 pending 状态的记录
-    let new_record = NewAiTrendAnalysis {
-        stock_code: stock_code_input.clone(),
-        stock_name: stock_name.clone(),
-        model_name: std::env::var("QWEN_MODEL")
-            .unwrap_or_else(|_| "qwen3-max-2026-01-23".to_string()),
-        status: "processing".to_string(),
-        request_payload: ai_payload.clone(),
-        response_json: None,
-        raw_response: None,
-        signal_count: Some(signals.len() as i32),
-        kline_start_date: Some(kline_start_date),
-        kline_end_date: Some(today),
-        error_message: None,
-        duration_ms: None,
-    };
-
-    let record = {
-        let mut conn = state
-            .db_pool
-            .get()
-            .map_err(|_| AppError::InternalServerError)?;
-        ai_trend_analysis::create(&mut conn, &new_record).map_err(|e| {
-            tracing::error!("Failed to create ai_trend_analysis record: {}", e);
-            AppError::InternalServerError
-        })?
-    };
-
-    // 9. 调用 Qwen API
-    let ai_client = ai_service::create_ai_client().map_err(|e| {
-        tracing::error!("Failed to create AI client: {}", e);
-        AppError::InternalServerError
-    })?;
-
-    let ai_result = ai_service::call_qwen_analysis(&ai_client, &ai_payload).await;
-
-    let duration_ms = start_time.elapsed().as_millis() as i64;
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
 
-    // 10. 更新记录
-    let (final_status, response_json, raw_response, error_message) = match ai_result {
-        Ok(result) => (
-            "completed".to_string(),
-            result.response_json.clone(),
-            Some(result.raw_response),
-            None,
+fn to_sse_event(event: AnalysisEvent) -> Event {
+    let (name, data) = match event {
+        AnalysisEvent::Started {
+            analysis_id,
+            model_name,
+        } => (
+            "started",
+            json!({"analysis_id": analysis_id, "model_name": model_name}),
         ),
-        Err(e) => {
-            tracing::error!("AI analysis failed for {}: {}", stock_code_input, e);
-            ("failed".to_string(), None, None, Some(e.to_string()))
+        AnalysisEvent::Delta { round, text } => ("delta", json!({"round": round, "text": text})),
+        AnalysisEvent::Repair { round, errors } => {
+            ("repair", json!({"round": round, "errors": errors}))
         }
     };
+    Event::default().event(name).data(data.to_string())
+}
 
-    let update_data = UpdateAiTrendAnalysis {
-        status: Some(final_status.clone()),
-        response_json: response_json.clone(),
-        raw_response: raw_response.clone(),
-        error_message: error_message.clone(),
-        duration_ms: Some(duration_ms),
-        updated_at: Some(Utc::now()),
-    };
-
-    let updated_record = {
-        let mut conn = state
-            .db_pool
-            .get()
-            .map_err(|_| AppError::InternalServerError)?;
-        ai_trend_analysis::update_by_id(&mut conn, record.id, &update_data).map_err(|e| {
-            tracing::error!("Failed to update ai_trend_analysis record: {}", e);
-            AppError::InternalServerError
-        })?
-    };
-
-    if final_status == "failed" {
-        return Err(AppError::BadRequest(
-            error_message.unwrap_or_else(|| "AI分析失败".to_string()),
-        ));
-    }
-
-    Ok(Json(to_prediction_response(&updated_record)))
+fn error_event(message: &str) -> Event {
+    Event::default()
+        .event("error")
+        .data(json!({ "message": message }).to_string())
 }
 
 /// GET /api/ai-analysis/trend-prediction/history
@@ -210,16 +187,46 @@ pub async fn trend_history(
         .get()
         .map_err(|_| AppError::InternalServerError)?;
 
-    let (records, total) = ai_trend_analysis::list_history(
-        &mut conn,
-        params.stock_code.as_deref(),
-        params.page_size,
-        params.page,
-    )
-    .map_err(|e| {
-        tracing::error!("Failed to query ai analysis history: {}", e);
-        AppError::InternalServerError
-    })?;
+    let rating = match params
+        .rating
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+    {
+        Some(r) => Some(
+            Rating::parse(r)
+                .ok_or_else(|| AppError::BadRequest(format!("invalid rating: {r}")))?
+                .as_str()
+                .to_string(),
+        ),
+        None => None,
+    };
+    let action = match params
+        .action
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        Some(a) => Some(
+            Action::parse(a)
+                .ok_or_else(|| AppError::BadRequest(format!("invalid action: {a}")))?
+                .as_str()
+                .to_string(),
+        ),
+        None => None,
+    };
+    let filter = AnalysisHistoryFilter {
+        stock_code: params.stock_code,
+        prompt_version: params.prompt_version,
+        rating,
+        action,
+    };
+    let (records, total) =
+        ai_trend_analysis::list_history(&mut conn, &filter, params.page_size, params.page)
+            .map_err(|e| {
+                tracing::error!("Failed to query ai analysis history: {}", e);
+                AppError::InternalServerError
+            })?;
 
     let data = records
         .into_iter()
@@ -233,6 +240,12 @@ pub async fn trend_history(
             kline_start_date: r.kline_start_date,
             kline_end_date: r.kline_end_date,
             duration_ms: r.duration_ms,
+            prompt_version: r.prompt_version,
+            rating: r.rating,
+            action: r.action,
+            trend_stage: r.trend_stage,
+            total_tokens: r.total_tokens,
+            cost: r.cost,
             created_at: r.created_at,
         })
         .collect();
@@ -258,301 +271,111 @@ pub async fn trend_detail(
         })?
         .ok_or(AppError::NotFound)?;
 
-    Ok(Json(to_prediction_response(&record)))
-}
-
-// ==================== 辅助类型和函数 ====================
-
-/// 信号数据
-struct SignalData {
-    datetime: String,
-    signal_date: NaiveDate,
-    change_pct: f64,
-    volume_ratio: f64,
-    turnover_rate: f64,
-    bid_ask_ratio: f64,
-}
-
-/// K线数据
-struct KlineData {
-    trade_date: NaiveDate,
-    open_price: String,
-    close_price: String,
-    high_price: String,
-    low_price: String,
-    volume: i64,
+    Ok(Json(to_prediction_response(&record, false)))
 }
 
-/// 查询股票的信号数据（从 stock_snapshots）
-fn query_stock_signals(
-    conn: &mut diesel::r2d2::PooledConnection<
-        diesel::r2d2::ConnectionManager<diesel::PgConnection>,
-    >,
-    stock_code_input: &str,
-) -> Result<(Vec<SignalData>, Option<String>), AppError> {
-    use bigdecimal::BigDecimal;
-    use chrono::{DateTime, Utc as ChronoUtc};
-    use diesel::prelude::*;
-    use diesel::sql_types::{Numeric, Text, Timestamptz};
-
-    #[derive(Debug, QueryableByName)]
-    struct SnapshotSignal {
-        #[diesel(sql_type = Timestamptz)]
-        created_at: DateTime<ChronoUtc>,
-        #[diesel(sql_type = Numeric)]
-        change_pct: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        volume_ratio: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        turnover_rate: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        bid_ask_ratio: BigDecimal,
-        #[diesel(sql_type = Text)]
-        stock_name: String,
-    }
-
-    let query = r#"
-        SELECT
-            created_at,
-            change_pct,
-            volume_ratio,
-            turnover_rate,
-            bid_ask_ratio,
-            stock_name
-        FROM stock_snapshots
-        WHERE stock_code = $1
-        ORDER BY created_at ASC
-    "#;
-
-    let results: Vec<SnapshotSignal> = diesel::sql_query(query)
-        .bind::<Text, _>(stock_code_input)
-        .load(conn)
-        .map_err(|e| {
-            tracing::error!("Failed to query stock signals: {}", e);
-            AppError::InternalServerError
-        })?;
-
-    let stock_name = results.first().map(|r| r.stock_name.clone());
-
-    let utc_plus_8 = FixedOffset::east_opt(8 * 3600).unwrap();
-    let signals: Vec<SignalData> = results
-        .into_iter()
-        .map(|r| {
-            let local_time = r.created_at.with_timezone(&utc_plus_8);
-            let signal_date = local_time.date_naive();
-            let datetime = local_time.format("%Y-%m-%d %H:%M:%S%.3f %z").to_string();
-            SignalData {
-                datetime,
-                signal_date,
-                change_pct: bigdecimal_to_f64(&r.change_pct),
-                volume_ratio: bigdecimal_to_f64(&r.volume_ratio),
-                turnover_rate: bigdecimal_to_f64(&r.turnover_rate),
-                bid_ask_ratio: bigdecimal_to_f64(&r.bid_ask_ratio),
-            }
-        })
-        .collect();
-
-    Ok((signals, stock_name))
-}
-
-/// 查询最早信号日之前的第N个交易日
-fn find_trading_date_before(
-    conn: &mut diesel::r2d2::PooledConnection<
-        diesel::r2d2::ConnectionManager<diesel::PgConnection>,
-    >,
-    before_date: NaiveDate,
-    count: i64,
-) -> Result<NaiveDate, AppError> {
-    use diesel::prelude::*;
-    use diesel::sql_types::{Date, Int8};
-
-    #[derive(Debug, QueryableByName)]
-    struct DateResult {
-        #[diesel(sql_type = Date)]
-        trade_date: NaiveDate,
-    }
-
-    let query = r#"
-        SELECT trade_date
-        FROM stock_trading_calendar
-        WHERE trade_date < $1 AND is_holiday = false
-        ORDER BY trade_date DESC
-        LIMIT $2
-    "#;
-
-    let results: Vec<DateResult> = diesel::sql_query(query)
-        .bind::<Date, _>(before_date)
-        .bind::<Int8, _>(count)
-        .load(conn)
-        .map_err(|e| {
-            tracing::error!("Failed to query trading calendar: {}", e);
-            AppError::InternalServerError
-        })?;
-
-    if results.is_empty() {
-        // 如果交易日历完全没有数据，回退到简单日历日减30天
-        tracing::warn!(
-            "Trading calendar has no data before {}, falling back to 30 calendar days",
-            before_date
-        );
-        Ok(before_date - chrono::Duration::days(30))
-    } else if (results.len() as i64) < count {
-        // 交易日历数据不足，以找到的最早日期为基础继续往前推
-        let found_date = results.last().unwrap().trade_date;
-        let days_short = count - results.len() as i64;
-        // 每个交易日约 1.5 个自然日（考虑周末），额外多加几天保险
-        let extra_days = (days_short as f64 * 1.5).ceil() as i64 + 2;
-        tracing::info!(
-            "Trading calendar only has {} of {} trading days before {}, extending {} extra calendar days from {}",
-            results.len(), count, before_date, extra_days, found_date
-        );
-        Ok(found_date - chrono::Duration::days(extra_days))
-    } else {
-        Ok(results.last().unwrap().trade_date)
-    }
-}
-
-/// 查询 K线数据范围
-fn query_kline_range(
-    conn: &mut diesel::r2d2::PooledConnection<
-        diesel::r2d2::ConnectionManager<diesel::PgConnection>,
-    >,
-    stock_code_input: &str,
-    start: NaiveDate,
-    end: NaiveDate,
-) -> Result<Vec<KlineData>, AppError> {
-    use bigdecimal::BigDecimal;
-    use diesel::prelude::*;
-    use diesel::sql_types::{BigInt, Date, Numeric, Text};
-
-    #[derive(Debug, QueryableByName)]
-    struct KlineRow {
-        #[diesel(sql_type = Date)]
-        trade_date: NaiveDate,
-        #[diesel(sql_type = Numeric)]
-        open_price: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        close_price: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        high_price: BigDecimal,
-        #[diesel(sql_type = Numeric)]
-        low_price: BigDecimal,
-        #[diesel(sql_type = BigInt)]
-        volume: i64,
-    }
-
-    let query = r#"
-        SELECT trade_date, open_price, close_price, high_price, low_price, volume
-        FROM daily_klines
-        WHERE stock_code = $1
-          AND trade_date >= $2
-          AND trade_date <= $3
-        ORDER BY trade_date ASC
-    "#;
-
-    let results: Vec<KlineRow> = diesel::sql_query(query)
-        .bind::<Text, _>(stock_code_input)
-        .bind::<Date, _>(start)
-        .bind::<Date, _>(end)
-        .load(conn)
+/// GET /api/ai-analysis/trend-prediction/:id/messages
+/// 查询分析记录的追问对话
+pub async fn list_analysis_messages(
+    State(state): State<AppState>,
+    Path(record_id): Path<i32>,
+) -> Result<Json<AnalysisThreadResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let record = ai_trend_analysis::find_by_id(&mut conn, record_id)
         .map_err(|e| {
-            tracing::error!("Failed to query kline range: {}", e);
+            tracing::error!("Failed to query ai analysis detail: {}", e);
             AppError::InternalServerError
-        })?;
-
-    Ok(results
-        .into_iter()
-        .map(|r| KlineData {
-            trade_date: r.trade_date,
-            open_price: r.open_price.to_string(),
-            close_price: r.close_price.to_string(),
-            high_price: r.high_price.to_string(),
-            low_price: r.low_price.to_string(),
-            volume: r.volume,
-        })
-        .collect())
-}
-
-/// 检查K线数据是否需要补齐
-/// 判断依据：数据条数少于5，或者首条K线日期晚于期望起始日期（说明前面的数据缺失）
-fn needs_kline_fill(klines: &[KlineData], start: NaiveDate, _end: NaiveDate) -> bool {
-    if klines.len() < 5 {
-        return true;
-    }
-    // 如果首条K线的日期比期望起始日期晚超过3个自然日，说明前面的数据缺失
-    if let Some(first) = klines.first() {
-        if first.trade_date > start + chrono::Duration::days(3) {
-            return true;
-        }
-    }
-    false
-}
-
-/// 自动补齐K线数据
-async fn fill_missing_klines(
-    state: &AppState,
-    stock_code_input: &str,
-    start: NaiveDate,
-    end: NaiveDate,
-) -> Result<(), AppError> {
-    let client = http_client::create_em_client().map_err(|e| {
-        tracing::error!("Failed to create HTTP client: {}", e);
+        })?
+        .ok_or(AppError::NotFound)?;
+    let messages = ai_analysis_message::list_by_analysis(&mut conn, record_id).map_err(|e| {
+        tracing::error!("Failed to query ai analysis messages: {}", e);
         AppError::InternalServerError
     })?;
 
-    let start_str = start.format("%Y%m%d").to_string();
-    let end_str = end.format("%Y%m%d").to_string();
-
-    let kline_result =
-        kline_service::fetch_and_parse_kline_data(&client, stock_code_input, &start_str, &end_str)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to fetch kline data for {}: {}", stock_code_input, e);
-                AppError::InternalServerError
-            })?;
-
-    tracing::info!(
-        "Fetched {} klines for {}, inserting into DB",
-        kline_result.parsed.len(),
-        stock_code_input
-    );
+    Ok(Json(AnalysisThreadResponse {
+        analysis_id: record.id,
+        stock_code: record.stock_code,
+        stock_name: record.stock_name,
+        model_name: record.model_name,
+        status: record.status,
+        messages: messages.into_iter().map(Into::into).collect(),
+    }))
+}
 
-    // 批量写入数据库
-    for kline_data in &kline_result.parsed {
+/// POST /api/ai-analysis/trend-prediction/:id/messages
+/// 对分析结果继续追问，模型缺省沿用原分析的模型
+pub async fn follow_up_analysis(
+    State(state): State<AppState>,
+    Path(record_id): Path<i32>,
+    Json(payload): Json<FollowUpRequest>,
+) -> Result<Json<FollowUpResponse>, AppError> {
+    let question =
+        ai_conversation::check_question(&payload.content).map_err(AppError::BadRequest)?;
+    let (record, template) = {
         let mut conn = state
             .db_pool
             .get()
             .map_err(|_| AppError::InternalServerError)?;
-        match daily_kline::create(&mut conn, kline_data) {
-            Ok(_) => {}
-            Err(diesel::result::Error::DatabaseError(
-                diesel::result::DatabaseErrorKind::UniqueViolation,
-                _,
-            )) => {
-                // 已存在，跳过
-            }
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to insert kline for {} on {}: {}",
-                    stock_code_input,
-                    kline_data.trade_date,
-                    e
-                );
-            }
+        let record = ai_trend_analysis::find_by_id(&mut conn, record_id)
+            .map_err(|e| {
+                tracing::error!("Failed to query ai analysis detail: {}", e);
+                AppError::InternalServerError
+            })?
+            .ok_or(AppError::NotFound)?;
+        let template = prompt_template::resolve(
+            &mut conn,
+            TEMPLATE_TREND_FOLLOWUP,
+            payload.prompt_version,
+            Utc::now().naive_utc(),
+        )
+        .map_err(map_prompt_error)?;
+        (record, template)
+    };
+    let model = payload
+        .model
+        .clone()
+        .unwrap_or_else(|| llm_provider::stored_model_spec(&record.model_name));
+    let provider = llm_provider::resolve_provider(Some(&model))
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let turn = ai_conversation::ask(
+        &state.db_pool,
+        provider.as_ref(),
+        &template,
+        &record,
+        &question,
+    )
+    .await
+    .map_err(|e| match e {
+        ConversationError::NotAnswerable(..) | ConversationError::BudgetExceeded(_) => {
+            AppError::BadRequest(e.to_string())
         }
-    }
+        ConversationError::Llm(e) => {
+            tracing::error!("Follow-up on analysis #{} failed: {}", record_id, e);
+            AppError::BadRequest(format!("AI追问失败: {e}"))
+        }
+        other => {
+            tracing::error!("Follow-up on analysis #{} failed: {}", record_id, other);
+            AppError::InternalServerError
+        }
+    })?;
 
-    Ok(())
+    Ok(Json(FollowUpResponse {
+        question: turn.question.into(),
+        answer: turn.answer.into(),
+    }))
 }
 
-/// BigDecimal 转 f64
-fn bigdecimal_to_f64(val: &bigdecimal::BigDecimal) -> f64 {
-    use std::str::FromStr;
-    f64::from_str(&val.to_string()).unwrap_or(0.0)
-}
+// ==================== 辅助类型和函数 ====================
 
 /// 将 DB 模型转换为 API 响应
-fn to_prediction_response(record: &crate::models::AiTrendAnalysis) -> TrendPredictionResponse {
+fn to_prediction_response(
+    record: &crate::models::AiTrendAnalysis,
+    cache_hit: bool,
+) -> TrendPredictionResponse {
     TrendPredictionResponse {
         id: record.id,
         stock_code: record.stock_code.clone(),
@@ -565,6 +388,44 @@ fn to_prediction_response(record: &crate::models::AiTrendAnalysis) -> TrendPredi
         kline_end_date: record.kline_end_date,
         error_message: record.error_message.clone(),
         duration_ms: record.duration_ms,
+        prompt_version: record.prompt_version,
+        rating: record.rating.clone(),
+        action: record.action.clone(),
+        trend_stage: record.trend_stage.clone(),
+        support_level: record.support_level.clone(),
+        resistance_level: record.resistance_level.clone(),
+        validation_errors: record.validation_errors.clone(),
+        repair_rounds: record.repair_rounds,
+        input_hash: record.input_hash.clone(),
+        cache_hit,
+        prompt_tokens: record.prompt_tokens,
+        completion_tokens: record.completion_tokens,
+        total_tokens: record.total_tokens,
+        cost: record.cost.clone(),
         created_at: record.created_at,
     }
 }
+
+/// 提示词模板错误：指定的版本不存在或没有启用版本属于请求问题
+pub(crate) fn map_prompt_error(e: PromptTemplateError) -> AppError {
+    match e {
+        PromptTemplateError::Db(e) => {
+            tracing::error!("Failed to resolve prompt template: {}", e);
+            AppError::InternalServerError
+        }
+        other => AppError::BadRequest(other.to_string()),
+    }
+}
+
+/// 分析流程错误：没有信号数据、用量已达预算属于请求问题
+fn map_analysis_error(e: TrendAnalysisError) -> AppError {
+    match e {
+        TrendAnalysisError::NoSignals(_) | TrendAnalysisError::BudgetExceeded(_) => {
+            AppError::BadRequest(e.to_string())
+        }
+        other => {
+            tracing::error!("Trend analysis failed: {}", other);
+            AppError::InternalServerError
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::Utc;
+use serde_json::json;
+
+use crate::api_models::ai_batch::{
+    AiBatchDetailResponse, AiBatchListQuery, AiBatchListResponse, AiBatchRequest,
+    AiBatchRunResponse, AiBatchStartResponse,
+};
+use crate::api_models::multi_level_filter::MonthlyMaCrossRequest;
+use crate::app::AppState;
+use crate::handler::ai_analysis::map_prompt_error;
+use crate::handler::error::AppError;
+use crate::handler::multi_level_filter::resolve_monthly_ma_cross_response;
+use crate::models::AiBatchRun;
+use crate::repositories::daily_kline::PgPoolConn;
+use crate::repositories::{ai_batch, job_execution_history};
+use crate::scheduler::job::JobStatus;
+use crate::scheduler::registry::{TriggerError, TriggerResult};
+use crate::services::ai_batch::{self as batch, BatchSettings, SOURCE_MONTHLY_MA_CROSS};
+use crate::services::kline_backfill::{self as backfill, StockSet};
+use crate::services::prompt_template::{self, TEMPLATE_TREND_PREDICTION};
+use crate::services::{ai_usage, llm_provider};
+
+const BATCH_JOB: &str = "ai_batch_analysis";
+
+/// 运行中的批量任务若对应的执行已结束（如服务重启被标记为 aborted），以执行记录的状态为准
+fn to_run_response(conn: &mut PgPoolConn, run: AiBatchRun) -> AiBatchRunResponse {
+    let interrupted = match (run.status.as_str(), run.execution_id) {
+        ("running", Some(id)) => job_execution_history::find_by_id(conn, id)
+            .ok()
+            .map(|h| h.status)
+            .filter(|s| s != JobStatus::Running.as_str()),
+        _ => None,
+    };
+    let mut response = AiBatchRunResponse::from(run);
+    if let Some(status) = interrupted {
+        response.status = status;
+    }
+    response
+}
+
+/// 通过任务注册表后台执行批量任务，进度经 WebSocket 任务状态推送；用量已达预算时不启动
+async fn start_run(
+    state: &AppState,
+    run: AiBatchRun,
+) -> Result<Json<AiBatchStartResponse>, AppError> {
+    let run_id = run.id;
+    let exceeded = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        ai_usage::check_budget(&mut conn).map_err(|e| {
+            tracing::error!("Failed to check ai usage budget: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+    if let Some(reason) = exceeded {
+        return Err(AppError::BadRequest(format!(
+            "{reason}，批量分析任务 #{run_id} 可在预算恢复后通过 resume 接口继续"
+        )));
+    }
+    let result = state
+        .job_registry
+        .trigger(BATCH_JOB, json!({ "run_id": run_id }), true, false)
+        .await;
+    let message = match result {
+        Ok(TriggerResult::Queued) => {
+            format!("批量分析任务 #{run_id} 已排队，等待当前批量分析结束后执行")
+        }
+        Ok(_) => format!("批量分析任务 #{run_id} 已开始，后台执行中"),
+        Err(TriggerError::AlreadyRunning(_, holder)) => {
+            return Err(AppError::BadRequest(format!(
+                "已有批量分析任务正在执行（{holder}），批量分析任务 #{run_id} 可稍后通过 resume 接口继续"
+            )));
+        }
+        Err(e) => {
+            tracing::error!("Failed to trigger ai batch analysis #{}: {}", run_id, e);
+            return Err(AppError::InternalServerError);
+        }
+    };
+    Ok(Json(AiBatchStartResponse {
+        run: run.into(),
+        message,
+    }))
+}
+
+/// 展开股票来源，返回（代码列表, source_key, source_params）
+async fn resolve_source(
+    state: &AppState,
+    payload: &AiBatchRequest,
+) -> Result<(Vec<String>, Option<String>, Option<serde_json::Value>), AppError> {
+    match payload.source.as_str() {
+        SOURCE_MONTHLY_MA_CROSS => {
+            let params = payload.screen.clone().unwrap_or_else(|| json!({}));
+            let req: MonthlyMaCrossRequest = serde_json::from_value(params.clone())
+                .map_err(|e| AppError::BadRequest(format!("invalid screen: {e}")))?;
+            let key = req
+                .anchor_year
+                .zip(req.anchor_month)
+                .map(|(y, m)| format!("{y}-{m:02}"));
+            let screen = resolve_monthly_ma_cross_response(state, &req).await?;
+            let codes = screen.items.into_iter().map(|i| i.stock_code).collect();
+            Ok((codes, key, Some(params)))
+        }
+        "codes" | "watchlist" | "plate" => {
+            let set = StockSet::parse(
+                &payload.source,
+                payload.plate_code.clone(),
+                payload.codes.clone(),
+            )
+            .map_err(AppError::BadRequest)?;
+            let mut conn = state
+                .db_pool
+                .get()
+                .map_err(|_| AppError::InternalServerError)?;
+            let codes = backfill::resolve_codes(&mut conn, &set).map_err(|e| {
+                tracing::error!("Failed to resolve stock set: {}", e);
+                AppError::InternalServerError
+            })?;
+            Ok((codes, set.key(), None))
+        }
+        other => Err(AppError::BadRequest(format!(
+            "invalid source: {other} (expected codes / watchlist / plate / monthly_ma_cross)"
+        ))),
+    }
+}
+
+/// POST /api/ai-analysis/batch
+/// 按股票来源创建批量分析任务并在后台执行
+pub async fn create_ai_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<AiBatchRequest>,
+) -> Result<Json<AiBatchStartResponse>, AppError> {
+    let (concurrency, token_budget) =
+        batch::limits(payload.concurrency, payload.token_budget).map_err(AppError::BadRequest)?;
+    let provider = llm_provider::resolve_provider(payload.model.as_deref())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let template = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        prompt_template::resolve(
+            &mut conn,
+            TEMPLATE_TREND_PREDICTION,
+            payload.prompt_version,
+            Utc::now().naive_utc(),
+        )
+        .map_err(map_prompt_error)?
+    };
+
+    let (codes, source_key, source_params) = resolve_source(&state, &payload).await?;
+    if codes.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "source {} has no stocks",
+            payload.source
+        )));
+    }
+    let max_stocks = batch::max_stocks();
+    if codes.len() > max_stocks {
+        return Err(AppError::BadRequest(format!(
+            "source {} has {} stocks, more than the limit of {}",
+            payload.source,
+            codes.len(),
+            max_stocks
+        )));
+    }
+
+    let settings = BatchSettings {
+        model_name: provider.model_name(),
+        prompt_version: template.version,
+        concurrency,
+        token_budget,
+    };
+    let run = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        batch::create_run(
+            &mut conn,
+            &payload.source,
+            source_key,
+            source_params,
+            &codes,
+            &settings,
+        )
+        .map_err(|e| {
+            tracing::error!("Failed to create ai batch run: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+    tracing::info!(
+        "创建批量分析任务 #{}: {} 只股票，来源 {}，模型 {}",
+        run.id,
+        run.total_stocks,
+        run.source,
+        run.model_name
+    );
+    start_run(&state, run).await
+}
+
+/// POST /api/ai-analysis/batch/:id/resume
+/// 继续未完成的批量任务（已得到分析结果的股票不再重复分析）
+pub async fn resume_ai_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<AiBatchStartResponse>, AppError> {
+    let run = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        ai_batch::find_run(&mut conn, id)
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or(AppError::NotFound)?
+    };
+    if run.status == JobStatus::Success.as_str() {
+        return Err(AppError::BadRequest(format!(
+            "ai batch #{id} is already complete"
+        )));
+    }
+    start_run(&state, run).await
+}
+
+/// GET /api/ai-analysis/batch?page=&page_size=
+pub async fn list_ai_batches(
+    State(state): State<AppState>,
+    Query(params): Query<AiBatchListQuery>,
+) -> Result<Json<AiBatchListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 200);
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (runs, total) = ai_batch::paginate_runs(&mut conn, page, page_size)
+        .map_err(|_| AppError::InternalServerError)?;
+    let items = runs
+        .into_iter()
+        .map(|run| to_run_response(&mut conn, run))
+        .collect();
+    Ok(Json(AiBatchListResponse {
+        total,
+        page,
+        page_size,
+        items,
+    }))
+}
+
+/// GET /api/ai-analysis/batch/:id
+/// 批量任务详情与逐股结果
+pub async fn get_ai_batch(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<AiBatchDetailResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let run = ai_batch::find_run(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or(AppError::NotFound)?;
+    let items = ai_batch::list_items(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(Json(AiBatchDetailResponse {
+        run: to_run_response(&mut conn, run),
+        items,
+    }))
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::Duration;
+
+use crate::api_models::ai_evaluation::{
+    AiAccuracyQuery, AiAccuracyResponse, AiEvaluationListQuery, AiEvaluationListResponse,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::repositories::ai_prediction_evaluation::{self, EvaluationFilter};
+use crate::services::ai_evaluation::{self, LOOKBACK_DAYS};
+use crate::services::trading_calendar::shanghai_now;
+
+fn map_err(e: diesel::result::Error) -> AppError {
+    tracing::error!("AI evaluation query failed: {}", e);
+    AppError::InternalServerError
+}
+
+/// 去掉空白，空字符串视为未传
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// GET /api/ai-analysis/accuracy?horizon_days=&start_date=&end_date=&model_name=
+/// 按模型与提示词版本统计预测准确率
+pub async fn ai_accuracy(
+    State(state): State<AppState>,
+    Query(params): Query<AiAccuracyQuery>,
+) -> Result<Json<AiAccuracyResponse>, AppError> {
+    let horizon_days = match params.horizon_days {
+        Some(h) => ai_evaluation::check_horizon(h).map_err(AppError::BadRequest)?,
+        None => ai_evaluation::default_horizon(),
+    };
+    let end_date = params.end_date.unwrap_or_else(|| shanghai_now().date());
+    let start_date = params
+        .start_date
+        .unwrap_or(end_date - Duration::days(LOOKBACK_DAYS));
+    if start_date > end_date {
+        return Err(AppError::BadRequest(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+    let model = non_empty(params.model_name);
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let rows = ai_prediction_evaluation::accuracy_stats(
+        &mut conn,
+        horizon_days,
+        start_date,
+        end_date,
+        model.as_deref(),
+    )
+    .map_err(map_err)?;
+    Ok(Json(AiAccuracyResponse {
+        horizon_days,
+        start_date,
+        end_date,
+        items: rows.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /api/ai-analysis/evaluations?analysis_id=&stock_code=&model_name=&prompt_version=&horizon_days=&page=&page_size=
+pub async fn list_ai_evaluations(
+    State(state): State<AppState>,
+    Query(params): Query<AiEvaluationListQuery>,
+) -> Result<Json<AiEvaluationListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 200);
+    let filter = EvaluationFilter {
+        analysis_id: params.analysis_id,
+        stock_code: non_empty(params.stock_code),
+        model_name: non_empty(params.model_name),
+        prompt_version: params.prompt_version,
+        horizon_days: params.horizon_days,
+    };
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (items, total) =
+        ai_prediction_evaluation::paginate(&mut conn, &filter, page, page_size).map_err(map_err)?;
+    Ok(Json(AiEvaluationListResponse {
+        total,
+        page,
+        page_size,
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
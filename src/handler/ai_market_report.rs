@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+
+use crate::api_models::ai_market_report::{
+    MarketReportListQuery, MarketReportListResponse, MarketReportResponse,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::repositories::ai_market_report;
+
+fn map_err(e: diesel::result::Error) -> AppError {
+    tracing::error!("AI market report query failed: {}", e);
+    AppError::InternalServerError
+}
+
+/// GET /api/ai-analysis/market-reports?start_date=&end_date=&page=&page_size=
+pub async fn list_market_reports(
+    State(state): State<AppState>,
+    Query(params): Query<MarketReportListQuery>,
+) -> Result<Json<MarketReportListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 200);
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (items, total) = ai_market_report::paginate(
+        &mut conn,
+        params.start_date,
+        params.end_date,
+        page,
+        page_size,
+    )
+    .map_err(map_err)?;
+    Ok(Json(MarketReportListResponse {
+        total,
+        page,
+        page_size,
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /api/ai-analysis/market-reports/:date
+/// 某个交易日（YYYY-MM-DD）的复盘详情
+pub async fn get_market_report(
+    State(state): State<AppState>,
+    Path(date): Path<String>,
+) -> Result<Json<MarketReportResponse>, AppError> {
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest(format!("invalid date: {date} (expected YYYY-MM-DD)")))?;
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let report = ai_market_report::find_by_date(&mut conn, date)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(report.into()))
+}
@@ -0,0 +1,147 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::api_models::ai_usage::{
+    AiBudgetResponse, AiUsageQuery, AiUsageReportResponse, LlmModelPriceRequest,
+    LlmModelPriceResponse,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::models::NewLlmModelPrice;
+use crate::repositories::ai_usage::{self as usage_repo, UsageTotals};
+use crate::repositories::llm_model_price;
+use crate::services::ai_usage::{self, BudgetLimits};
+use crate::services::trading_calendar::shanghai_now;
+
+fn map_err(e: diesel::result::Error) -> AppError {
+    tracing::error!("AI usage query failed: {}", e);
+    AppError::InternalServerError
+}
+
+/// GET /api/ai-analysis/usage?period=day|month&start_date=&end_date=&model_name=
+/// 按日 / 按月、模型汇总 token 用量与费用
+pub async fn ai_usage_report(
+    State(state): State<AppState>,
+    Query(params): Query<AiUsageQuery>,
+) -> Result<Json<AiUsageReportResponse>, AppError> {
+    let period = params.period.as_deref().unwrap_or("day").trim().to_string();
+    let (format, start_date, end_date) = ai_usage::report_range(
+        &period,
+        params.start_date,
+        params.end_date,
+        shanghai_now().date(),
+    )
+    .map_err(AppError::BadRequest)?;
+    let model = params
+        .model_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty());
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let rows = usage_repo::usage_report(&mut conn, format, start_date, end_date, model)
+        .map_err(map_err)?;
+
+    let total = rows.iter().fold(UsageTotals::default(), |mut acc, r| {
+        acc.calls += r.calls;
+        acc.prompt_tokens += r.prompt_tokens;
+        acc.completion_tokens += r.completion_tokens;
+        acc.total_tokens += r.total_tokens;
+        acc.cost += &r.cost;
+        acc
+    });
+    Ok(Json(AiUsageReportResponse {
+        period,
+        start_date,
+        end_date,
+        items: rows.into_iter().map(Into::into).collect(),
+        total: total.into(),
+    }))
+}
+
+/// GET /api/ai-analysis/usage/budget
+/// 今日、本月用量与预算配置
+pub async fn ai_usage_budget(
+    State(state): State<AppState>,
+) -> Result<Json<AiBudgetResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (today, month) = ai_usage::current_usage(&mut conn).map_err(map_err)?;
+    let limits = BudgetLimits::from_env();
+    let exceeded = limits.exceeded(&today, &month);
+    Ok(Json(AiBudgetResponse {
+        today: today.into(),
+        month: month.into(),
+        limits: limits.into(),
+        exceeded,
+    }))
+}
+
+/// GET /api/ai-analysis/model-prices
+pub async fn list_model_prices(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<LlmModelPriceResponse>>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let items = llm_model_price::list(&mut conn).map_err(map_err)?;
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+/// POST /api/ai-analysis/model-prices
+/// 新增单价，model_name 已存在时覆盖；只影响之后的分析记录
+pub async fn upsert_model_price(
+    State(state): State<AppState>,
+    Json(payload): Json<LlmModelPriceRequest>,
+) -> Result<Json<LlmModelPriceResponse>, AppError> {
+    let model_name = payload.model_name.trim().to_string();
+    if model_name.is_empty() {
+        return Err(AppError::BadRequest("model_name is required".to_string()));
+    }
+    let zero = bigdecimal::BigDecimal::from(0);
+    if payload.prompt_price < zero || payload.completion_price < zero {
+        return Err(AppError::BadRequest(
+            "prices must not be negative".to_string(),
+        ));
+    }
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let item = llm_model_price::upsert(
+        &mut conn,
+        &NewLlmModelPrice {
+            model_name,
+            prompt_price: payload.prompt_price,
+            completion_price: payload.completion_price,
+            description: payload.description,
+            updated_at: shanghai_now(),
+        },
+    )
+    .map_err(map_err)?;
+    Ok(Json(item.into()))
+}
+
+/// DELETE /api/ai-analysis/model-prices/:id
+pub async fn delete_model_price(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    match llm_model_price::delete_by_id(&mut conn, item_id).map_err(map_err)? {
+        0 => Err(AppError::NotFound),
+        _ => Ok(StatusCode::NO_CONTENT),
+    }
+}
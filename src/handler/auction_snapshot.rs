@@ -0,0 +1,120 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::api_models::auction_snapshot::{AuctionSnapshotListResponse, AuctionSnapshotQuery};
+use crate::api_models::stock_snapshot::StockSnapshotResponse;
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::repositories::auction_snapshot::{self, AuctionSnapshotFilter};
+use crate::repositories::stock_snapshot;
+use crate::services::trading_calendar::shanghai_now;
+
+/// GET /api/auction-snapshots
+/// 按交易日 + 采样时刻查询竞价快照，支持涨幅 / 金额 / 未匹配量过滤，并附带当日盘中快照关联
+pub async fn list_auction_snapshots(
+    State(state): State<AppState>,
+    Query(params): Query<AuctionSnapshotQuery>,
+) -> Result<Json<AuctionSnapshotListResponse>, AppError> {
+    let trade_date = match params.trade_date.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+            AppError::BadRequest("invalid trade_date, expect YYYY-MM-DD".to_string())
+        })?,
+        None => state
+            .trading_calendar
+            .days(&state.db_pool)
+            .await
+            .trading_day_on_or_before(shanghai_now().date()),
+    };
+    let page = params.page.max(1);
+    let page_size = params.page_size.clamp(1, 500);
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let sample_times = auction_snapshot::list_sample_times(&mut conn, trade_date).map_err(|e| {
+        tracing::error!("Failed to list auction sample times: {}", e);
+        AppError::InternalServerError
+    })?;
+    let auction_end = NaiveTime::from_hms_opt(9, 30, 0).unwrap_or_default();
+    let sample_time =
+        match params.sample_time.as_deref().filter(|s| !s.is_empty()) {
+            Some(s) => Some(NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| {
+                AppError::BadRequest("invalid sample_time, expect HH:MM".to_string())
+            })?),
+            None => sample_times
+                .iter()
+                .rev()
+                .find(|t| **t < auction_end)
+                .or(sample_times.last())
+                .copied(),
+        };
+
+    let (data, total) = match sample_time {
+        Some(sample_time) => {
+            let filter = AuctionSnapshotFilter {
+                trade_date,
+                sample_time,
+                pct_min: params.pct_min,
+                pct_max: params.pct_max,
+                amount_min: params.amount_min,
+                volume_min: params.volume_min,
+                unmatched_min: params.unmatched_min,
+                linked_only: params.linked_only,
+            };
+            auction_snapshot::list_with_intraday_link(&mut conn, &filter, page, page_size).map_err(
+                |e| {
+                    tracing::error!("Failed to query auction snapshots: {}", e);
+                    AppError::InternalServerError
+                },
+            )?
+        }
+        None => (Vec::new(), 0),
+    };
+
+    Ok(Json(AuctionSnapshotListResponse {
+        trade_date,
+        sample_time: sample_time.map(|t| t.format("%H:%M").to_string()),
+        sample_times: sample_times
+            .iter()
+            .map(|t| t.format("%H:%M").to_string())
+            .collect(),
+        data: data.into_iter().map(Into::into).collect(),
+        total,
+        page,
+        page_size,
+        total_pages: (total + page_size - 1) / page_size,
+    }))
+}
+
+/// GET /api/auction-snapshots/:id/intraday
+/// 同一股票在竞价当日的盘中快照（stock_snapshots），按时间升序
+pub async fn get_auction_intraday_snapshots(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<Json<Vec<StockSnapshotResponse>>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let auction = auction_snapshot::find_by_id(&mut conn, item_id)
+        .map_err(|e| {
+            tracing::error!("Failed to find auction snapshot: {}", e);
+            AppError::InternalServerError
+        })?
+        .ok_or(AppError::NotFound)?;
+
+    let snapshots =
+        stock_snapshot::find_by_code_on_date(&mut conn, &auction.stock_code, auction.trade_date)
+            .map_err(|e| {
+                tracing::error!("Failed to load intraday snapshots: {}", e);
+                AppError::InternalServerError
+            })?;
+
+    Ok(Json(snapshots.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::api_models::convertible_bond::{
+    ConvertibleBondDailyQuery, ConvertibleBondDailyResponse, ConvertibleBondListQuery,
+    ConvertibleBondListResponse, ConvertibleBondResponse,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::repositories::convertible_bond;
+use crate::services::trading_calendar::shanghai_now;
+
+fn map_err(e: diesel::result::Error) -> AppError {
+    tracing::error!("Convertible bond query failed: {}", e);
+    AppError::InternalServerError
+}
+
+/// GET /api/convertible-bonds?keyword=&include_delisted=&page=&page_size=
+/// 已同步的可转债及最近一个交易日的行情，不请求上游
+pub async fn list_convertible_bonds(
+    State(state): State<AppState>,
+    Query(params): Query<ConvertibleBondListQuery>,
+) -> Result<Json<ConvertibleBondListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(50).clamp(1, 500);
+    let keyword = params
+        .keyword
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let active_on = (!params.include_delisted.unwrap_or(false)).then(|| shanghai_now().date());
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let (bonds, total) = convertible_bond::paginate(&mut conn, keyword, active_on, page, page_size)
+        .map_err(map_err)?;
+    let trade_date = convertible_bond::latest_trade_date(&mut conn).map_err(map_err)?;
+    let mut latest: HashMap<String, _> = match trade_date {
+        Some(date) => {
+            let codes: Vec<String> = bonds.iter().map(|b| b.bond_code.clone()).collect();
+            convertible_bond::list_daily_on(&mut conn, &codes, date)
+                .map_err(map_err)?
+                .into_iter()
+                .map(|d| (d.bond_code.clone(), d))
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    Ok(Json(ConvertibleBondListResponse {
+        trade_date,
+        total,
+        page,
+        page_size,
+        items: bonds
+            .into_iter()
+            .map(|b| {
+                let daily = latest.remove(&b.bond_code);
+                ConvertibleBondResponse::new(b, daily)
+            })
+            .collect(),
+    }))
+}
+
+/// GET /api/convertible-bonds/:code/daily?start_date=&end_date=
+/// 单只可转债的每日价格与溢价率走势
+pub async fn get_convertible_bond_daily(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    Query(params): Query<ConvertibleBondDailyQuery>,
+) -> Result<Json<ConvertibleBondDailyResponse>, AppError> {
+    if let (Some(start), Some(end)) = (params.start_date, params.end_date) {
+        if start > end {
+            return Err(AppError::BadRequest(
+                "start_date must not be after end_date".to_string(),
+            ));
+        }
+    }
+    let code = code.trim();
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let bond = convertible_bond::find_bond(&mut conn, code)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    let items = convertible_bond::list_daily(&mut conn, code, params.start_date, params.end_date)
+        .map_err(map_err)?;
+
+    Ok(Json(ConvertibleBondDailyResponse {
+        bond: ConvertibleBondResponse::new(bond, None),
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
@@ -3,14 +3,27 @@ use axum::{extract::State, Json};
 use crate::api_models::convertible_bond_query::ConvertibleBondQueryResponse;
 use crate::app::AppState;
 use crate::handler::error::AppError;
+use crate::repositories::convertible_bond;
 use crate::services::convertible_bond_query::fetch_filtered_convertible_bonds;
 use crate::utils::http_client::create_em_client;
 
 pub async fn query_convertible_bonds(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> Result<Json<ConvertibleBondQueryResponse>, AppError> {
     let client = create_em_client().map_err(|_| AppError::InternalServerError)?;
-    let data = fetch_filtered_convertible_bonds(&client)
+    // 已同步的最后交易日直接复用；读取失败时退回逐只查询
+    let known_last_trade = state
+        .db_pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            convertible_bond::list_last_trading_dates(&mut conn).map_err(|e| e.to_string())
+        })
+        .unwrap_or_else(|e| {
+            tracing::warn!("读取可转债最后交易日失败: {}", e);
+            Default::default()
+        });
+    let data = fetch_filtered_convertible_bonds(&client, &known_last_trade)
         .await
         .map_err(|err| {
             tracing::error!("query_convertible_bonds failed: {}", err);
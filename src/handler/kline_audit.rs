@@ -0,0 +1,217 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::api_models::kline_audit::{
+    KlineAuditFindingListResponse, KlineAuditFindingQuery, KlineAuditFindingResponse,
+    KlineAuditRunRequest, KlineAuditRunResponse, KlineAuditSummaryItem, KlineAuditSummaryResponse,
+    KlineRepairRequest, KlineRepairResponse, UpdateKlineAuditFindingRequest,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::repositories::daily_kline;
+use crate::repositories::kline_audit::{self, FindingFilter};
+use crate::scheduler::registry::{TriggerError, TriggerResult};
+use crate::services::kline_audit::{
+    self as audit, CheckType, STATUS_IGNORED, STATUS_OPEN, STATUS_RESOLVED,
+};
+use crate::services::kline_backfill::validate_range;
+use crate::services::trading_calendar::shanghai_now;
+
+const AUDIT_JOB: &str = "kline_audit";
+
+/// GET /api/kline-audit/findings?stock_code=&check_type=&severity=&status=&start_date=&end_date=&page=&page_size=
+pub async fn list_findings(
+    State(state): State<AppState>,
+    Query(params): Query<KlineAuditFindingQuery>,
+) -> Result<Json<KlineAuditFindingListResponse>, AppError> {
+    if let Some(check) = params.check_type.as_deref() {
+        if CheckType::parse(check).is_none() {
+            return Err(AppError::BadRequest(format!("invalid check_type: {check}")));
+        }
+    }
+    let status = params.status.unwrap_or_else(|| STATUS_OPEN.to_string());
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 200);
+    let filter = FindingFilter {
+        stock_code: params.stock_code.filter(|c| !c.trim().is_empty()),
+        check_type: params.check_type,
+        severity: params.severity,
+        // status=all 时不按状态过滤
+        status: (status != "all").then_some(status),
+        start_date: params.start_date,
+        end_date: params.end_date,
+    };
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (items, total) = kline_audit::paginate(&mut conn, &filter, page, page_size)
+        .map_err(|_| AppError::InternalServerError)?;
+    Ok(Json(KlineAuditFindingListResponse {
+        total,
+        page,
+        page_size,
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /api/kline-audit/summary
+/// 按检查项统计问题数
+pub async fn get_summary(
+    State(state): State<AppState>,
+) -> Result<Json<KlineAuditSummaryResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let counts =
+        kline_audit::count_by_type(&mut conn).map_err(|_| AppError::InternalServerError)?;
+    let open_stocks =
+        kline_audit::count_open_stocks(&mut conn).map_err(|_| AppError::InternalServerError)?;
+
+    let mut items: Vec<KlineAuditSummaryItem> = CheckType::ALL
+        .iter()
+        .map(|c| KlineAuditSummaryItem {
+            check_type: c.as_str().to_string(),
+            severity: c.severity().to_string(),
+            ..Default::default()
+        })
+        .collect();
+    for (check_type, _, status, count) in counts {
+        let Some(item) = items.iter_mut().find(|i| i.check_type == check_type) else {
+            continue;
+        };
+        match status.as_str() {
+            STATUS_OPEN => item.open += count,
+            STATUS_RESOLVED => item.resolved += count,
+            STATUS_IGNORED => item.ignored += count,
+            _ => {}
+        }
+    }
+    Ok(Json(KlineAuditSummaryResponse {
+        open_stocks,
+        open_findings: items.iter().map(|i| i.open).sum(),
+        items,
+    }))
+}
+
+/// POST /api/kline-audit/run
+/// 后台执行审计任务
+pub async fn run_audit(
+    State(state): State<AppState>,
+    payload: Option<Json<KlineAuditRunRequest>>,
+) -> Result<Json<KlineAuditRunResponse>, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let params = serde_json::to_value(&payload).map_err(|_| AppError::InternalServerError)?;
+    let result = state
+        .job_registry
+        .trigger(AUDIT_JOB, params, true, false)
+        .await;
+    let message = match result {
+        Ok(TriggerResult::Queued) => "审计任务已排队，等待当前审计结束后执行".to_string(),
+        Ok(_) => "审计任务已开始，后台执行中".to_string(),
+        Err(TriggerError::InvalidParams(msg)) => return Err(AppError::BadRequest(msg)),
+        Err(TriggerError::AlreadyRunning(_, holder)) => {
+            return Err(AppError::BadRequest(format!(
+                "审计任务正在执行（{holder}）"
+            )));
+        }
+        Err(e) => {
+            tracing::error!("Failed to trigger kline audit: {}", e);
+            return Err(AppError::InternalServerError);
+        }
+    };
+    Ok(Json(KlineAuditRunResponse { message }))
+}
+
+/// PUT /api/kline-audit/findings/:id
+/// 忽略问题或撤销忽略
+pub async fn update_finding(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateKlineAuditFindingRequest>,
+) -> Result<Json<KlineAuditFindingResponse>, AppError> {
+    let resolved_at = match payload.status.as_str() {
+        STATUS_IGNORED => Some(shanghai_now()),
+        STATUS_OPEN => None,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "invalid status: {other} (expected ignored / open)"
+            )))
+        }
+    };
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    kline_audit::find_by_id(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or(AppError::NotFound)?;
+    let finding = kline_audit::update_status(&mut conn, id, &payload.status, resolved_at)
+        .map_err(|_| AppError::InternalServerError)?;
+    Ok(Json(finding.into()))
+}
+
+/// POST /api/kline-audit/stocks/:code/repair
+/// 重新拉取受影响区间的K线替换库中数据并复检
+pub async fn repair_stock(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    payload: Option<Json<KlineRepairRequest>>,
+) -> Result<Json<KlineRepairResponse>, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let code = code.trim().to_string();
+    let (start, end) = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        match (payload.start_date, payload.end_date) {
+            (Some(start), Some(end)) => (start, end),
+            (None, None) => {
+                let open = kline_audit::list_open_by_stock(&mut conn, &code)
+                    .map_err(|_| AppError::InternalServerError)?;
+                let listed_from = daily_kline::first_trade_date(&mut conn, &code)
+                    .map_err(|_| AppError::InternalServerError)?;
+                audit::repair_range(&open, listed_from).ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "stock {code} has no open findings, specify start_date and end_date"
+                    ))
+                })?
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "start_date and end_date must be given together".to_string(),
+                ))
+            }
+        }
+    };
+    validate_range(start, end, shanghai_now().date()).map_err(AppError::BadRequest)?;
+
+    let days = state.trading_calendar.days(&state.db_pool).await;
+    let repair = audit::repair_stock(&state.db_pool, &days, &code, start, end)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to repair klines for {}: {}", code, e);
+            AppError::BadRequest(e.to_string())
+        })?;
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let remaining = kline_audit::list_open_by_stock(&mut conn, &code)
+        .map_err(|_| AppError::InternalServerError)?;
+    Ok(Json(KlineRepairResponse {
+        stock_code: code,
+        start_date: repair.start,
+        end_date: repair.end,
+        deleted_count: repair.deleted,
+        inserted_count: repair.inserted,
+        resolved_count: repair.audit.resolved,
+        remaining: remaining.into_iter().map(Into::into).collect(),
+    }))
+}
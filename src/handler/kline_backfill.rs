@@ -0,0 +1,245 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde_json::json;
+
+use crate::api_models::kline_backfill::{
+    KlineBackfillDetailResponse, KlineBackfillListQuery, KlineBackfillListResponse,
+    KlineBackfillRequest, KlineBackfillRunResponse, KlineBackfillStartResponse, KlineCoverageQuery,
+    KlineCoverageResponse, StockCoverageItem,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::models::KlineBackfillRun;
+use crate::repositories::daily_kline::PgPoolConn;
+use crate::repositories::{job_execution_history, kline_backfill};
+use crate::scheduler::job::JobStatus;
+use crate::scheduler::registry::{TriggerError, TriggerResult};
+use crate::services::kline_backfill::{self as backfill, StockSet};
+use crate::services::trading_calendar::shanghai_now;
+
+const BACKFILL_JOB: &str = "kline_backfill";
+
+/// 运行中的回补任务若对应的执行已结束（如服务重启被标记为 aborted），以执行记录的状态为准
+fn to_run_response(conn: &mut PgPoolConn, run: KlineBackfillRun) -> KlineBackfillRunResponse {
+    let interrupted = match (run.status.as_str(), run.execution_id) {
+        ("running", Some(id)) => job_execution_history::find_by_id(conn, id)
+            .ok()
+            .map(|h| h.status)
+            .filter(|s| s != JobStatus::Running.as_str()),
+        _ => None,
+    };
+    let mut response = KlineBackfillRunResponse::from(run);
+    if let Some(status) = interrupted {
+        response.status = status;
+    }
+    response
+}
+
+/// 通过任务注册表后台执行回补任务
+async fn start_run(
+    state: &AppState,
+    run: KlineBackfillRun,
+) -> Result<Json<KlineBackfillStartResponse>, AppError> {
+    let run_id = run.id;
+    let result = state
+        .job_registry
+        .trigger(BACKFILL_JOB, json!({ "run_id": run_id }), true, false)
+        .await;
+    let message = match result {
+        Ok(TriggerResult::Queued) => format!("回补任务 #{run_id} 已排队，等待当前回补结束后执行"),
+        Ok(_) => format!("回补任务 #{run_id} 已开始，后台执行中"),
+        Err(TriggerError::AlreadyRunning(_, holder)) => {
+            return Err(AppError::BadRequest(format!(
+                "已有回补任务正在执行（{holder}），回补任务 #{run_id} 可稍后通过 resume 接口继续"
+            )));
+        }
+        Err(e) => {
+            tracing::error!("Failed to trigger kline backfill #{}: {}", run_id, e);
+            return Err(AppError::InternalServerError);
+        }
+    };
+    Ok(Json(KlineBackfillStartResponse {
+        run: run.into(),
+        message,
+    }))
+}
+
+/// POST /api/kline-backfill
+/// 按股票集合与日期区间创建回补任务并在后台执行
+pub async fn create_kline_backfill(
+    State(state): State<AppState>,
+    Json(payload): Json<KlineBackfillRequest>,
+) -> Result<Json<KlineBackfillStartResponse>, AppError> {
+    let set = StockSet::parse(&payload.stock_set, payload.plate_code, payload.codes)
+        .map_err(AppError::BadRequest)?;
+    backfill::validate_range(payload.start_date, payload.end_date, shanghai_now().date())
+        .map_err(AppError::BadRequest)?;
+
+    let run = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        let codes = backfill::resolve_codes(&mut conn, &set).map_err(|e| {
+            tracing::error!("Failed to resolve stock set: {}", e);
+            AppError::InternalServerError
+        })?;
+        if codes.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "stock set {} is empty",
+                set.as_str()
+            )));
+        }
+        backfill::create_run(
+            &mut conn,
+            &set,
+            &codes,
+            payload.start_date,
+            payload.end_date,
+        )
+        .map_err(|e| {
+            tracing::error!("Failed to create kline backfill run: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+    tracing::info!(
+        "创建回补任务 #{}: {} 只股票，{} ~ {}",
+        run.id,
+        run.total_stocks,
+        run.start_date,
+        run.end_date
+    );
+    start_run(&state, run).await
+}
+
+/// POST /api/kline-backfill/:id/resume
+/// 继续未完成的回补任务（只处理尚未成功的股票）
+pub async fn resume_kline_backfill(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<KlineBackfillStartResponse>, AppError> {
+    let run = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        kline_backfill::find_run(&mut conn, id)
+            .map_err(|_| AppError::InternalServerError)?
+            .ok_or(AppError::NotFound)?
+    };
+    if run.status == JobStatus::Success.as_str() {
+        return Err(AppError::BadRequest(format!(
+            "kline backfill #{id} is already complete"
+        )));
+    }
+    start_run(&state, run).await
+}
+
+/// GET /api/kline-backfill?page=&page_size=
+pub async fn list_kline_backfills(
+    State(state): State<AppState>,
+    Query(params): Query<KlineBackfillListQuery>,
+) -> Result<Json<KlineBackfillListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(20).clamp(1, 200);
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (runs, total) = kline_backfill::paginate_runs(&mut conn, page, page_size)
+        .map_err(|_| AppError::InternalServerError)?;
+    let items = runs
+        .into_iter()
+        .map(|run| to_run_response(&mut conn, run))
+        .collect();
+    Ok(Json(KlineBackfillListResponse {
+        total,
+        page,
+        page_size,
+        items,
+    }))
+}
+
+/// GET /api/kline-backfill/:id
+/// 回补任务详情与逐股覆盖率
+pub async fn get_kline_backfill(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<KlineBackfillDetailResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let run = kline_backfill::find_run(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or(AppError::NotFound)?;
+    let items = kline_backfill::list_items(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(Json(KlineBackfillDetailResponse {
+        run: to_run_response(&mut conn, run),
+        items,
+    }))
+}
+
+/// GET /api/kline-backfill/coverage?stock_set=&plate_code=&codes=&start_date=&end_date=
+/// 对照交易日历检测缺口，只统计不拉取
+pub async fn get_kline_coverage(
+    State(state): State<AppState>,
+    Query(params): Query<KlineCoverageQuery>,
+) -> Result<Json<KlineCoverageResponse>, AppError> {
+    let codes = params.codes.map(|s| {
+        s.split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let set = StockSet::parse(&params.stock_set, params.plate_code, codes)
+        .map_err(AppError::BadRequest)?;
+    backfill::validate_range(params.start_date, params.end_date, shanghai_now().date())
+        .map_err(AppError::BadRequest)?;
+
+    let days = state.trading_calendar.days(&state.db_pool).await;
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let codes = backfill::resolve_codes(&mut conn, &set).map_err(|e| {
+        tracing::error!("Failed to resolve stock set: {}", e);
+        AppError::InternalServerError
+    })?;
+
+    let mut stocks = Vec::with_capacity(codes.len());
+    for code in &codes {
+        let coverage =
+            backfill::coverage(&mut conn, &days, code, params.start_date, params.end_date)
+                .map_err(|e| {
+                    tracing::error!("Failed to compute kline coverage for {}: {}", code, e);
+                    AppError::InternalServerError
+                })?;
+        stocks.push(StockCoverageItem {
+            ratio: coverage.ratio(),
+            coverage,
+        });
+    }
+
+    Ok(Json(KlineCoverageResponse {
+        start_date: params.start_date,
+        end_date: params.end_date,
+        trading_days: days.count_trading_days(params.start_date, params.end_date),
+        total_stocks: stocks.len(),
+        complete_stocks: stocks
+            .iter()
+            .filter(|s| s.coverage.missing_ranges.is_empty())
+            .count(),
+        empty_stocks: stocks
+            .iter()
+            .filter(|s| s.coverage.present_days == 0 && s.coverage.expected_days > 0)
+            .count(),
+        stocks,
+    }))
+}
@@ -1,14 +1,23 @@
 pub mod ai_analysis;
+pub mod ai_batch;
+pub mod ai_evaluation;
+pub mod ai_market_report;
+pub mod ai_usage;
+pub mod auction_snapshot;
 pub mod bagua;
 pub mod basic_data_analysis;
+pub mod convertible_bond;
 pub mod convertible_bond_query;
 pub mod daily_kline;
 pub mod dynamic_backtrack;
 pub mod error;
 pub mod export_button_config;
+pub mod kline_audit;
+pub mod kline_backfill;
 pub mod monthly_kline;
 pub mod multi_level_filter;
 pub mod profit_analysis;
+pub mod prompt_template;
 pub mod scheduler;
 pub mod stock;
 pub mod stock_appearance_query;
@@ -25,4 +34,5 @@ pub mod stock_track_query;
 pub mod stock_trade_date_query;
 pub mod stock_watchlist;
 pub mod stock_watchlist_query;
+pub mod trading_calendar;
 pub mod ws_handler;
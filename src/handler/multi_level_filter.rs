@@ -2,6 +2,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{extract::State, Json};
+use chrono::NaiveDate;
 use serde_json::Value;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
@@ -149,8 +150,27 @@ async fn compute_monthly_scan_uncached(
     MonthlyMaCrossResponse { items, skipped }
 }
 
+/// 判断 ST 所用名称的日期：指定锚定月时取该月最后一天（不晚于今天），否则为今天
+fn name_reference_date(
+    anchor_year: Option<i32>,
+    anchor_month: Option<u32>,
+    today: NaiveDate,
+) -> NaiveDate {
+    let (Some(y), Some(m)) = (anchor_year, anchor_month) else {
+        return today;
+    };
+    let next_month = if m == 12 {
+        NaiveDate::from_ymd_opt(y + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(y, m + 1, 1)
+    };
+    next_month
+        .and_then(|d| d.pred_opt())
+        .map_or(today, |d| d.min(today))
+}
+
 /// 与同指纹月线缓存对齐：缓存命中则不重复批量拉月线。
-async fn resolve_monthly_ma_cross_response(
+pub(crate) async fn resolve_monthly_ma_cross_response(
     state: &AppState,
     req: &MonthlyMaCrossRequest,
 ) -> Result<MonthlyMaCrossResponse, AppError> {
@@ -165,7 +185,9 @@ async fn resolve_monthly_ma_cross_response(
         .get()
         .map_err(|_| AppError::InternalServerError)?;
 
-    let snapshots_all = stock_snapshot::list_latest_snapshot_fields_per_stock(&mut conn)
+    let today_sh = shanghai_calendar_date_now();
+    let name_date = name_reference_date(req.anchor_year, req.anchor_month, today_sh);
+    let snapshots_all = stock_snapshot::list_latest_snapshot_fields_per_stock(&mut conn, name_date)
         .map_err(|_| AppError::InternalServerError)?;
 
     let filter_set: HashSet<String> = req.filter_plate_codes.iter().cloned().collect();
@@ -178,7 +200,6 @@ async fn resolve_monthly_ma_cross_response(
             .collect()
     };
 
-    let today_sh = shanghai_calendar_date_now();
     let fingerprint = ma_cross_screen_fingerprint(req, &snapshots);
     let stock_count = snapshots.len();
 
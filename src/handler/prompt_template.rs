@@ -0,0 +1,177 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+
+use crate::api_models::ai_analysis::{
+    CreatePromptTemplateRequest, PromptTemplateQuery, PromptTemplateResponse,
+    UpdatePromptTemplateRequest,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::models::{NewPromptTemplate, UpdatePromptTemplate};
+use crate::repositories::prompt_template;
+use crate::services::prompt_template::{
+    self as prompt_template_service, unknown_variables, variables_for, TEMPLATE_TREND_PREDICTION,
+};
+
+fn map_err(e: diesel::result::Error) -> AppError {
+    tracing::error!("Prompt template query failed: {}", e);
+    AppError::InternalServerError
+}
+
+/// GET /api/ai-analysis/prompt-templates?template_key=
+pub async fn list_prompt_templates(
+    State(state): State<AppState>,
+    Query(params): Query<PromptTemplateQuery>,
+) -> Result<Json<Vec<PromptTemplateResponse>>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let items =
+        prompt_template::list(&mut conn, params.template_key.as_deref()).map_err(map_err)?;
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/ai-analysis/prompt-templates/:id
+pub async fn get_prompt_template(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let item = prompt_template::find_by_id(&mut conn, item_id)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(item.into()))
+}
+
+/// POST /api/ai-analysis/prompt-templates
+/// 新建模板版本，模板中只能使用该用途支持的变量
+pub async fn create_prompt_template(
+    State(state): State<AppState>,
+    Json(payload): Json<CreatePromptTemplateRequest>,
+) -> Result<(StatusCode, Json<PromptTemplateResponse>), AppError> {
+    let key = payload
+        .template_key
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .unwrap_or_else(|| TEMPLATE_TREND_PREDICTION.to_string());
+    let Some(variables) = variables_for(&key) else {
+        return Err(AppError::BadRequest(format!("unknown template_key: {key}")));
+    };
+    if payload.name.trim().is_empty() || payload.system_prompt.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "name and system_prompt are required".to_string(),
+        ));
+    }
+    let user_template = payload
+        .user_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "{{payload}}".to_string());
+    let unknown = unknown_variables(&key, &[&payload.system_prompt, &user_template]);
+    if !unknown.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "unknown variables: {} (supported: {})",
+            unknown.join(", "),
+            variables.join(", ")
+        )));
+    }
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let now = Utc::now().naive_utc();
+    // 内置模板先作为版本 1 写入，新建的版本从 2 开始，未启用时内置模板仍然生效
+    prompt_template_service::seed_builtin(&mut conn, &key, now).map_err(|e| {
+        tracing::error!("Failed to seed builtin prompt template {}: {}", key, e);
+        AppError::InternalServerError
+    })?;
+    let item = NewPromptTemplate {
+        template_key: key,
+        version: 0,
+        name: payload.name.trim().to_string(),
+        description: payload.description,
+        system_prompt: payload.system_prompt,
+        user_template,
+        is_active: payload.activate,
+    };
+    let created = prompt_template::create_version(&mut conn, &item, now).map_err(map_err)?;
+    Ok((StatusCode::CREATED, Json(created.into())))
+}
+
+/// PUT /api/ai-analysis/prompt-templates/:id
+/// 只修改名称与说明，模板内容需新建版本
+pub async fn update_prompt_template(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+    Json(payload): Json<UpdatePromptTemplateRequest>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    prompt_template::find_by_id(&mut conn, item_id)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    let update_data = UpdatePromptTemplate {
+        name: payload.name.filter(|n| !n.trim().is_empty()),
+        description: payload.description,
+        updated_at: Some(Utc::now().naive_utc()),
+    };
+    let updated =
+        prompt_template::update_by_id(&mut conn, item_id, &update_data).map_err(map_err)?;
+    Ok(Json(updated.into()))
+}
+
+/// POST /api/ai-analysis/prompt-templates/:id/activate
+/// 启用该版本，同用途的其他版本停用
+pub async fn activate_prompt_template(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<Json<PromptTemplateResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let template = prompt_template::find_by_id(&mut conn, item_id)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    let activated =
+        prompt_template::activate(&mut conn, &template, Utc::now().naive_utc()).map_err(map_err)?;
+    Ok(Json(activated.into()))
+}
+
+/// DELETE /api/ai-analysis/prompt-templates/:id
+/// 启用中或已被分析记录引用的版本不能删除
+pub async fn delete_prompt_template(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let template = prompt_template::find_by_id(&mut conn, item_id)
+        .map_err(map_err)?
+        .ok_or(AppError::NotFound)?;
+    if template.is_active {
+        return Err(AppError::BadRequest(
+            "cannot delete the active version, activate another version first".to_string(),
+        ));
+    }
+    let used = prompt_template::count_usage(&mut conn, item_id).map_err(map_err)?;
+    if used > 0 {
+        return Err(AppError::BadRequest(format!(
+            "template is referenced by {used} analyses, follow-up messages or market reports"
+        )));
+    }
+    prompt_template::delete_by_id(&mut conn, item_id).map_err(map_err)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -2,535 +2,630 @@ use axum::{
     extract::{Path, Query, State},
     Json,
 };
-use serde::Serialize;
+use chrono_tz::Tz;
+use serde_json::Value;
 
 use crate::api_models::scheduler::{
-    HistoryQueryParams, JobExecutionHistoryItem, JobExecutionHistoryResponse, JobInfo,
+    CancelExecutionResponse, HistoryQueryParams, JobExecutionHistoryItem,
+    JobExecutionHistoryResponse, JobInfo, LegacyTriggerResponse, PipelineQueryParams,
+    PipelineRunItem, PipelineRunListResponse, SnapshotDetail, StockDetail, StockPlateSyncDetail,
+    StockTableSyncDetail, TriggerAuctionCaptureResponse, TriggerJobQuery, TriggerJobResponse,
+    TriggerProfitAnalysisResponse, TriggerStockFilterResponse, TriggerStockPlateSyncResponse,
+    TriggerStockTableSyncResponse, TriggerTaskResponse, UpdateJobScheduleRequest,
 };
 use crate::app::AppState;
 use crate::handler::error::AppError;
-use crate::repositories::job_execution_history;
-use crate::scheduler::{
-    kline_import_job, profit_analysis_job, stock_filter_job, stock_plate_sync_job,
-    stock_table_sync_job, watchlist_kline_job,
-};
-
-#[derive(Serialize)]
-pub struct TriggerTaskResponse {
-    pub success: bool,
-    pub message: String,
-    pub total_stocks: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub details: Vec<StockDetail>,
+use crate::models::{JobLock, JobSchedule, UpdateJobSchedule};
+use crate::repositories::{job_execution_history, job_lock, job_pipeline_run, job_schedule};
+use crate::scheduler::job::{Job, JobOutcome, JobStatus};
+use crate::scheduler::lock::{current_holder, describe_holder, ConflictPolicy};
+use crate::scheduler::registry::{validate_schedule, JobRegistry, TriggerError, TriggerResult};
+
+/// 手动触发任务：请求体作为任务参数（可选），耗时较长的任务后台执行并立即返回；
+/// `?cascade=true` 时连同下游任务按流水线后台执行
+pub async fn trigger_job(
+    State(state): State<AppState>,
+    Path(job_name): Path<String>,
+    Query(query): Query<TriggerJobQuery>,
+    payload: Option<Json<Value>>,
+) -> Result<Json<TriggerJobResponse>, AppError> {
+    tracing::info!("收到手动触发任务 {} 的请求", job_name);
+    let registry = &state.job_registry;
+    let job = registry.get(&job_name).ok_or(AppError::NotFound)?;
+    let params = payload.map(|Json(v)| v).unwrap_or(Value::Null);
+
+    let result = registry
+        .trigger(
+            &job_name,
+            params,
+            job.detach_on_trigger(),
+            query.cascade.unwrap_or(false),
+        )
+        .await
+        .map_err(trigger_error)?;
+
+    trigger_response(job.as_ref(), job_name, result).map(Json)
 }
 
-#[derive(Serialize)]
-pub struct StockDetail {
-    pub stock_code: String,
-    pub imported_count: usize,
-    pub success: bool,
-    pub error: Option<String>,
+/// 已合并的旧任务名映射到现在的任务名（历史记录已由迁移改名）
+fn canonical_job_name(job_name: &str) -> &str {
+    match job_name {
+        "stock_filter_morning" | "stock_filter_afternoon" => "stock_filter",
+        other => other,
+    }
 }
 
-/// 盈利分析任务响应
-#[derive(Serialize)]
-pub struct TriggerProfitAnalysisResponse {
-    pub success: bool,
-    pub message: String,
-    pub total_snapshots: usize,
-    pub analyzed_count: usize,
-    pub skipped_count: usize,
-    pub no_kline_count: usize,
-    pub details: Vec<SnapshotDetail>,
+/// 旧版 `/trigger-*` 接口对应的任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyTrigger {
+    KlineImport,
+    WatchlistKlineImport,
+    ProfitAnalysis,
+    StockFilter,
+    StockTableSync,
+    StockPlateSync,
+    AuctionCapture,
 }
 
-#[derive(Serialize)]
-pub struct SnapshotDetail {
-    pub stock_code: String,
-    pub stock_name: String,
-    pub profit_rate: i32,
-    pub success: bool,
-    pub error: Option<String>,
+impl LegacyTrigger {
+    pub fn job_name(self) -> &'static str {
+        match self {
+            Self::KlineImport => "kline_import",
+            Self::WatchlistKlineImport => "watchlist_kline_import",
+            Self::ProfitAnalysis => "profit_analysis",
+            Self::StockFilter => "stock_filter",
+            Self::StockTableSync => "stock_table_sync",
+            Self::StockPlateSync => "stock_plate_sync",
+            Self::AuctionCapture => "auction_capture",
+        }
+    }
 }
 
-/// 手动触发K线导入任务
-pub async fn trigger_kline_import(
-    State(state): State<AppState>,
-) -> Result<Json<TriggerTaskResponse>, AppError> {
-    tracing::info!("收到手动触发K线导入任务的请求");
-
-    // 广播任务开始
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &state.ws_sender,
-        "kline_import".to_string(),
-        "running".to_string(),
-    );
-
-    // 调用定时任务的核心逻辑
-    match kline_import_job::run_kline_import_task(state.db_pool.clone()).await {
-        Ok(result) => {
-            // 广播任务完成
-            let status = if result.failed_count == 0 {
-                "success"
-            } else if result.success_count > 0 {
-                "partial"
-            } else {
-                "failed"
-            };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "kline_import".to_string(),
-                status.to_string(),
-            );
-
-            let details = result
-                .stock_details
-                .into_iter()
-                .map(|d| StockDetail {
-                    stock_code: d.stock_code,
-                    imported_count: d.imported_count,
-                    success: d.success,
-                    error: d.error,
-                })
-                .collect();
-
-            Ok(Json(TriggerTaskResponse {
-                success: result.failed_count == 0,
-                message: format!(
-                    "K线导入任务执行完成，总计 {} 只股票，成功 {} 只，失败 {} 只",
-                    result.total_stocks, result.success_count, result.failed_count
-                ),
-                total_stocks: result.total_stocks,
-                success_count: result.success_count,
-                failed_count: result.failed_count,
-                details,
-            }))
+/// 旧版触发接口：经注册表触发对应任务（不级联下游），响应转换回该接口原有的格式
+pub async fn trigger_legacy(
+    state: AppState,
+    legacy: LegacyTrigger,
+    payload: Option<Json<Value>>,
+) -> Result<Json<LegacyTriggerResponse>, AppError> {
+    let job_name = legacy.job_name();
+    tracing::info!("收到旧版触发接口的请求: {}", job_name);
+    let registry = &state.job_registry;
+    let job = registry.get(job_name).ok_or(AppError::NotFound)?;
+    let params = payload.map(|Json(v)| v).unwrap_or(Value::Null);
+
+    let result = registry
+        .trigger(job_name, params, job.detach_on_trigger(), false)
+        .await
+        .map_err(trigger_error)?;
+    let execution = match result {
+        TriggerResult::Finished(execution) => execution,
+        TriggerResult::Detached => {
+            let message = format!("{}任务已触发，后台执行中", job.display_name());
+            return Ok(Json(legacy_response(
+                legacy,
+                true,
+                Some(message),
+                &JobOutcome::default(),
+            )));
         }
-        Err(e) => {
-            tracing::error!("手动触发K线导入任务失败: {}", e);
-            // 广播任务失败
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "kline_import".to_string(),
-                "failed".to_string(),
-            );
-            Err(AppError::InternalServerError)
+        TriggerResult::Queued => {
+            let message = format!("{}任务正在执行，已排队等待", job.display_name());
+            return Ok(Json(legacy_response(
+                legacy,
+                true,
+                Some(message),
+                &JobOutcome::default(),
+            )));
         }
+    };
+    if let Some(e) = &execution.error {
+        tracing::error!("手动触发任务 {} 失败: {}", job_name, e);
+        return Err(AppError::InternalServerError);
     }
+    let outcome = execution.outcome.unwrap_or_default();
+    Ok(Json(legacy_response(
+        legacy,
+        execution.status == JobStatus::Success,
+        None,
+        &outcome,
+    )))
 }
 
-/// 手动触发盈利分析任务
-pub async fn trigger_profit_analysis(
-    State(state): State<AppState>,
-) -> Result<Json<TriggerProfitAnalysisResponse>, AppError> {
-    tracing::info!("收到手动触发盈利分析任务的请求");
-
-    // 广播任务开始
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &state.ws_sender,
-        "profit_analysis".to_string(),
-        "running".to_string(),
-    );
+fn legacy_details<T: serde::de::DeserializeOwned>(outcome: &JobOutcome) -> Vec<T> {
+    outcome
+        .details
+        .clone()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
 
-    // 调用定时任务的核心逻辑
-    match profit_analysis_job::run_profit_analysis_task(state.db_pool.clone()).await {
-        Ok(result) => {
-            // 广播任务完成
-            let status = if result.analyzed_count > 0
-                || result.skipped_count > 0
-                || result.total_snapshots == 0
-            {
-                "success"
-            } else {
-                "failed"
-            };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "profit_analysis".to_string(),
-                status.to_string(),
-            );
-
-            let details = result
-                .snapshot_details
-                .into_iter()
-                .map(|d| SnapshotDetail {
-                    stock_code: d.stock_code,
-                    stock_name: d.stock_name,
-                    profit_rate: d.profit_rate,
-                    success: d.success,
-                    error: d.error,
-                })
-                .collect();
-
-            Ok(Json(TriggerProfitAnalysisResponse {
-                success: result.analyzed_count > 0
-                    || result.skipped_count > 0
-                    || result.total_snapshots == 0,
-                message: format!(
+/// 按旧版接口的字段与提示语组装响应；`message` 为空时使用旧版的完成提示
+fn legacy_response(
+    legacy: LegacyTrigger,
+    success: bool,
+    message: Option<String>,
+    outcome: &JobOutcome,
+) -> LegacyTriggerResponse {
+    let o = outcome;
+    match legacy {
+        LegacyTrigger::KlineImport | LegacyTrigger::WatchlistKlineImport => {
+            let message = message.unwrap_or_else(|| {
+                if legacy == LegacyTrigger::KlineImport {
+                    format!(
+                        "K线导入任务执行完成，总计 {} 只股票，成功 {} 只，失败 {} 只",
+                        o.total_count, o.success_count, o.failed_count
+                    )
+                } else {
+                    format!(
+                        "观察表K线导入任务执行完成，总计 {} 只股票，成功 {} 只，失败 {} 只，跳过 {} 只",
+                        o.total_count, o.success_count, o.failed_count, o.skipped_count
+                    )
+                }
+            });
+            LegacyTriggerResponse::Stocks(TriggerTaskResponse {
+                success,
+                message,
+                total_stocks: o.total_count,
+                success_count: o.success_count,
+                failed_count: o.failed_count,
+                details: legacy_details::<StockDetail>(o),
+            })
+        }
+        LegacyTrigger::ProfitAnalysis => {
+            let details = legacy_details::<SnapshotDetail>(o);
+            // 与任务内的计数规则一致：成功但提示缺少K线的快照
+            let no_kline_count = details
+                .iter()
+                .filter(|d| d.success && d.error.as_deref().is_some_and(|e| e.contains("K线")))
+                .count();
+            let message = message.unwrap_or_else(|| {
+                format!(
                     "盈利分析任务执行完成，总计 {} 个快照，分析 {} 个，跳过 {} 个，无K线 {} 个",
-                    result.total_snapshots,
-                    result.analyzed_count,
-                    result.skipped_count,
-                    result.no_kline_count
-                ),
-                total_snapshots: result.total_snapshots,
-                analyzed_count: result.analyzed_count,
-                skipped_count: result.skipped_count,
-                no_kline_count: result.no_kline_count,
+                    o.total_count, o.success_count, o.skipped_count, no_kline_count
+                )
+            });
+            LegacyTriggerResponse::ProfitAnalysis(TriggerProfitAnalysisResponse {
+                success,
+                message,
+                total_snapshots: o.total_count,
+                analyzed_count: o.success_count,
+                skipped_count: o.skipped_count,
+                no_kline_count,
                 details,
-            }))
+            })
+        }
+        LegacyTrigger::StockFilter => {
+            let message = message.unwrap_or_else(|| {
+                format!(
+                    "股票筛选任务执行完成，筛选到 {} 只符合条件的股票",
+                    o.total_count
+                )
+            });
+            LegacyTriggerResponse::StockFilter(TriggerStockFilterResponse {
+                success,
+                message,
+                items_count: o.total_count,
+            })
+        }
+        LegacyTrigger::StockTableSync => {
+            let message = message.unwrap_or_else(|| {
+                format!(
+                    "stock_table 同步任务执行完成，总计 {} 条，成功 {} 条，失败 {} 条，跳过 {} 条",
+                    o.total_count, o.success_count, o.failed_count, o.skipped_count
+                )
+            });
+            LegacyTriggerResponse::StockTableSync(TriggerStockTableSyncResponse {
+                success,
+                message,
+                total_count: o.total_count,
+                success_count: o.success_count,
+                failed_count: o.failed_count,
+                skipped_count: o.skipped_count,
+                details: legacy_details::<StockTableSyncDetail>(o),
+            })
         }
-        Err(e) => {
-            tracing::error!("手动触发盈利分析任务失败: {}", e);
-            // 广播任务失败
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "profit_analysis".to_string(),
-                "failed".to_string(),
-            );
-            Err(AppError::InternalServerError)
+        LegacyTrigger::StockPlateSync => {
+            let message = message.unwrap_or_else(|| {
+                format!(
+                    "stock_plate 同步任务执行完成，总计 {} 条，成功 {} 条，失败 {} 条，跳过 {} 条",
+                    o.total_count, o.success_count, o.failed_count, o.skipped_count
+                )
+            });
+            LegacyTriggerResponse::StockPlateSync(TriggerStockPlateSyncResponse {
+                success,
+                message,
+                total_count: o.total_count,
+                success_count: o.success_count,
+                failed_count: o.failed_count,
+                skipped_count: o.skipped_count,
+                details: legacy_details::<StockPlateSyncDetail>(o),
+            })
+        }
+        LegacyTrigger::AuctionCapture => {
+            let field = |key: &str| o.details.as_ref().and_then(|d| d.get(key));
+            let message = message.unwrap_or_else(|| {
+                format!(
+                    "集合竞价采样完成，采样 {} 只股票，写入 {} 行",
+                    o.total_count, o.success_count
+                )
+            });
+            LegacyTriggerResponse::AuctionCapture(TriggerAuctionCaptureResponse {
+                success,
+                message,
+                sample_time: field("sample_time")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                total_count: o.total_count,
+                detail_count: field("detail_count")
+                    .and_then(Value::as_u64)
+                    .unwrap_or_default() as usize,
+                saved_count: o.success_count,
+            })
         }
     }
 }
 
-/// 股票筛选任务响应
-#[derive(Serialize)]
-pub struct TriggerStockFilterResponse {
-    pub success: bool,
-    pub message: String,
-    pub items_count: usize,
-}
-
-#[derive(Serialize)]
-pub struct TriggerStockTableSyncResponse {
-    pub success: bool,
-    pub message: String,
-    pub total_count: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub skipped_count: usize,
-    pub details: Vec<StockTableSyncDetail>,
+/// 重试一次历史执行：只处理其中的失败项（任务整体报错时按原参数重新执行）
+pub async fn retry_execution(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<TriggerJobResponse>, AppError> {
+    tracing::info!("收到重试执行记录 #{} 的请求", id);
+    let registry = &state.job_registry;
+    let job_name = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        job_execution_history::find_by_id(&mut conn, id)
+            .map_err(|_| AppError::NotFound)?
+            .job_name
+    };
+    let job = registry.get(&job_name).ok_or(AppError::NotFound)?;
+
+    let result = registry
+        .retry_execution(id, job.detach_on_trigger())
+        .await
+        .map_err(trigger_error)?;
+
+    trigger_response(job.as_ref(), job_name, result).map(Json)
 }
 
-#[derive(Serialize)]
-pub struct StockTableSyncDetail {
-    pub stock_code: String,
-    pub stock_name: String,
-    pub action: String,
-    pub error: Option<String>,
+/// 取消执行中的任务：任务在处理完当前批次后停止，执行记录标记为 cancelled
+pub async fn cancel_execution(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<CancelExecutionResponse>, AppError> {
+    tracing::info!("收到取消执行记录 #{} 的请求", id);
+    let local = state
+        .job_registry
+        .cancel_execution(id)
+        .map_err(trigger_error)?;
+    let job_name = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        job_execution_history::find_by_id(&mut conn, id)
+            .map_err(|_| AppError::NotFound)?
+            .job_name
+    };
+    let message = if local {
+        "已请求取消，任务将在当前批次完成后停止".to_string()
+    } else {
+        "已请求取消，执行所在实例将在下次检查时停止任务".to_string()
+    };
+    Ok(Json(CancelExecutionResponse {
+        execution_id: id,
+        job_name,
+        local,
+        message,
+    }))
 }
 
-#[derive(Serialize)]
-pub struct TriggerStockPlateSyncResponse {
-    pub success: bool,
-    pub message: String,
-    pub total_count: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub skipped_count: usize,
-    pub details: Vec<StockPlateSyncDetail>,
+fn trigger_error(e: TriggerError) -> AppError {
+    match e {
+        TriggerError::NotFound(_) | TriggerError::ExecutionNotFound(_) => AppError::NotFound,
+        TriggerError::AlreadyRunning(..)
+        | TriggerError::InvalidParams(_)
+        | TriggerError::NotRetryable(_)
+        | TriggerError::NotRunning(_) => AppError::BadRequest(e.to_string()),
+    }
 }
 
-#[derive(Serialize)]
-pub struct StockPlateSyncDetail {
-    pub stock_code: String,
-    pub stock_name: String,
-    pub plate_total: usize,
-    pub plate_inserted: usize,
-    pub relation_inserted: usize,
-    pub action: String,
-    pub error: Option<String>,
-}
+fn trigger_response(
+    job: &dyn Job,
+    job_name: String,
+    result: TriggerResult,
+) -> Result<TriggerJobResponse, AppError> {
+    let execution = match result {
+        TriggerResult::Finished(execution) => execution,
+        TriggerResult::Detached | TriggerResult::Queued => {
+            let message = if matches!(result, TriggerResult::Queued) {
+                format!("{}任务正在执行，已排队等待", job.display_name())
+            } else {
+                format!("{}任务已触发，后台执行中", job.display_name())
+            };
+            return Ok(TriggerJobResponse {
+                success: true,
+                message,
+                job_name,
+                status: None,
+                execution_id: None,
+                total_count: 0,
+                success_count: 0,
+                failed_count: 0,
+                skipped_count: 0,
+                duration_ms: None,
+                details: None,
+            });
+        }
+    };
 
-/// 手动触发股票筛选任务
-pub async fn trigger_stock_filter(
-    State(state): State<AppState>,
-) -> Result<Json<TriggerStockFilterResponse>, AppError> {
-    tracing::info!("收到手动触发股票筛选任务的请求");
-
-    // 广播任务开始
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &state.ws_sender,
-        "stock_filter".to_string(),
-        "running".to_string(),
+    if let Some(e) = &execution.error {
+        tracing::error!("手动触发任务 {} 失败: {}", job_name, e);
+        return Err(AppError::InternalServerError);
+    }
+    let outcome = execution.outcome.unwrap_or_default();
+    let mut message = format!(
+        "{}任务执行完成，总计 {}，成功 {}，失败 {}，跳过 {}",
+        job.display_name(),
+        outcome.total_count,
+        outcome.success_count,
+        outcome.failed_count,
+        outcome.skipped_count
     );
-
-    // 调用定时任务的核心逻辑
-    match stock_filter_job::run_stock_filter_task(state.db_pool.clone(), "manual").await {
-        Ok(result) => {
-            // 广播任务完成
-            let status = if result.success { "success" } else { "failed" };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "stock_filter".to_string(),
-                status.to_string(),
-            );
-
-            Ok(Json(TriggerStockFilterResponse {
-                success: result.success,
-                message: format!(
-                    "股票筛选任务执行完成，筛选到 {} 只符合条件的股票",
-                    result.items_count
-                ),
-                items_count: result.items_count,
-            }))
-        }
-        Err(e) => {
-            tracing::error!("手动触发股票筛选任务失败: {}", e);
-            // 广播任务失败
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "stock_filter".to_string(),
-                "failed".to_string(),
-            );
-            Err(AppError::InternalServerError)
-        }
+    if let Some(extra) = &outcome.message {
+        message.push_str(&format!("（{extra}）"));
     }
-}
 
-/// 手动触发 stock_table 同步任务
-pub async fn trigger_stock_table_sync(
-    State(state): State<AppState>,
-) -> Result<Json<TriggerStockTableSyncResponse>, AppError> {
-    tracing::info!("收到手动触发 stock_table 同步任务的请求");
+    Ok(TriggerJobResponse {
+        success: execution.status == JobStatus::Success,
+        message,
+        job_name,
+        status: Some(execution.status.as_str().to_string()),
+        execution_id: execution.execution_id,
+        total_count: outcome.total_count,
+        success_count: outcome.success_count,
+        failed_count: outcome.failed_count,
+        skipped_count: outcome.skipped_count,
+        duration_ms: Some(execution.duration_ms),
+        details: outcome.details,
+    })
+}
 
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &state.ws_sender,
-        "stock_table_sync".to_string(),
-        "running".to_string(),
-    );
+/// 组装任务信息；没有调度配置时按代码默认值展示
+async fn build_job_info(
+    registry: &JobRegistry,
+    job: &dyn Job,
+    schedule: Option<&JobSchedule>,
+    lock: Option<&JobLock>,
+) -> JobInfo {
+    let default = JobRegistry::default_schedule(job);
+    let (enabled, cron_expressions, timezone, params) = match schedule {
+        Some(s) => (
+            s.enabled,
+            s.cron_expressions.clone(),
+            s.timezone.clone(),
+            s.params.clone(),
+        ),
+        None => (
+            default.enabled,
+            default.cron_expressions.clone(),
+            default.timezone.clone(),
+            default.params.clone(),
+        ),
+    };
+    let schedule_text = if cron_expressions == default.cron_expressions {
+        job.schedule_desc().to_string()
+    } else {
+        cron_expressions.join(" | ")
+    };
+    let next_run_at = match (registry.next_run(job.name()).await, timezone.parse::<Tz>()) {
+        (Some(next), Ok(tz)) => Some(
+            next.with_timezone(&tz)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        ),
+        _ => None,
+    };
+
+    JobInfo {
+        name: job.name().to_string(),
+        display_name: job.display_name().to_string(),
+        description: job.description().to_string(),
+        schedule: schedule_text,
+        enabled,
+        running: registry.is_running(job.name()) || lock.is_some(),
+        lock_holder: lock.map(describe_holder),
+        conflict_policy: schedule
+            .map(|s| s.conflict_policy.clone())
+            .unwrap_or(default.conflict_policy),
+        retry_max_attempts: schedule
+            .map(|s| s.retry_max_attempts)
+            .unwrap_or(default.retry_max_attempts),
+        retry_backoff_secs: schedule
+            .map(|s| s.retry_backoff_secs)
+            .unwrap_or(default.retry_backoff_secs),
+        retryable: job.retryable(),
+        cron_expressions,
+        timezone,
+        params,
+        next_run_at,
+        updated_at: schedule.map(|s| s.updated_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+        depends_on: job.depends_on().iter().map(|d| d.to_string()).collect(),
+        dependents: registry
+            .jobs()
+            .iter()
+            .filter(|j| j.depends_on().contains(&job.name()))
+            .map(|j| j.name().to_string())
+            .collect(),
+    }
+}
 
-    match stock_table_sync_job::run_stock_table_sync_task(state.db_pool.clone()).await {
-        Ok(result) => {
-            let status = if result.failed_count == 0 {
-                "success"
-            } else if result.success_count > 0 {
-                "partial"
-            } else {
-                "failed"
-            };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "stock_table_sync".to_string(),
-                status.to_string(),
-            );
-
-            let details = result
-                .details
-                .into_iter()
-                .map(|d| StockTableSyncDetail {
-                    stock_code: d.stock_code,
-                    stock_name: d.stock_name,
-                    action: d.action,
-                    error: d.error,
-                })
-                .collect();
-
-            Ok(Json(TriggerStockTableSyncResponse {
-                success: result.failed_count == 0,
-                message: format!(
-                    "stock_table 同步任务执行完成，总计 {} 条，成功 {} 条，失败 {} 条，跳过 {} 条",
-                    result.total_count,
-                    result.success_count,
-                    result.failed_count,
-                    result.skipped_count
-                ),
-                total_count: result.total_count,
-                success_count: result.success_count,
-                failed_count: result.failed_count,
-                skipped_count: result.skipped_count,
-                details,
-            }))
-        }
-        Err(e) => {
-            tracing::error!("手动触发 stock_table 同步任务失败: {}", e);
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "stock_table_sync".to_string(),
-                "failed".to_string(),
-            );
-            Err(AppError::InternalServerError)
-        }
+/// 获取任务列表
+pub async fn get_job_list(State(state): State<AppState>) -> Result<Json<Vec<JobInfo>>, AppError> {
+    let (schedules, locks) = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        (
+            job_schedule::list_all(&mut conn).map_err(|_| AppError::InternalServerError)?,
+            job_lock::list_active(&mut conn).map_err(|_| AppError::InternalServerError)?,
+        )
+    };
+
+    let registry = &state.job_registry;
+    let mut jobs = Vec::with_capacity(registry.jobs().len());
+    for job in registry.jobs() {
+        let schedule = schedules.iter().find(|s| s.job_name == job.name());
+        let lock = locks.iter().find(|l| l.job_name == job.name());
+        jobs.push(build_job_info(registry, job.as_ref(), schedule, lock).await);
     }
+
+    Ok(Json(jobs))
 }
 
-/// 手动触发 stock_plate 同步任务
-pub async fn trigger_stock_plate_sync(
+/// 获取单个任务的调度配置
+pub async fn get_job_schedule(
     State(state): State<AppState>,
-) -> Result<Json<TriggerStockPlateSyncResponse>, AppError> {
-    tracing::info!("收到手动触发 stock_plate 同步任务的请求");
-
-    let pool = state.db_pool.clone();
-    let sender = state.ws_sender.clone();
-
-    tokio::spawn(async move {
-        crate::utils::ws_broadcast::broadcast_task_status(
-            &sender,
-            "stock_plate_sync".to_string(),
-            "running".to_string(),
-        );
-        match stock_plate_sync_job::run_stock_plate_sync_task(pool).await {
-            Ok(result) => {
-                let status = if result.failed_count == 0 {
-                    "success"
-                } else if result.success_count > 0 {
-                    "partial"
-                } else {
-                    "failed"
-                };
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "stock_plate_sync".to_string(),
-                    status.to_string(),
-                );
-            }
-            Err(e) => {
-                tracing::error!("手动触发 stock_plate 同步任务失败: {}", e);
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "stock_plate_sync".to_string(),
-                    "failed".to_string(),
-                );
-            }
-        }
-    });
-
-    Ok(Json(TriggerStockPlateSyncResponse {
-        success: true,
-        message: "stock_plate 同步任务已触发，后台执行中".to_string(),
-        total_count: 0,
-        success_count: 0,
-        failed_count: 0,
-        skipped_count: 0,
-        details: Vec::new(),
-    }))
+    Path(job_name): Path<String>,
+) -> Result<Json<JobInfo>, AppError> {
+    let registry = &state.job_registry;
+    let job = registry.get(&job_name).ok_or(AppError::NotFound)?;
+    let schedule = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        job_schedule::find_by_job_name(&mut conn, &job_name)
+            .map_err(|_| AppError::InternalServerError)?
+    };
+
+    let lock = current_holder(&state.db_pool, &job_name);
+    Ok(Json(
+        build_job_info(registry, job.as_ref(), schedule.as_ref(), lock.as_ref()).await,
+    ))
 }
 
-/// 手动触发观察表K线导入任务
-pub async fn trigger_watchlist_kline_import(
+/// 修改任务调度配置（启停、cron、时区、定时参数、冲突策略、自动重试），保存后立即重新注册，无需重启
+pub async fn update_job_schedule(
     State(state): State<AppState>,
-) -> Result<Json<TriggerTaskResponse>, AppError> {
-    tracing::info!("收到手动触发观察表K线导入任务的请求");
-
-    // 广播任务开始
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &state.ws_sender,
-        "watchlist_kline_import".to_string(),
-        "running".to_string(),
-    );
+    Path(job_name): Path<String>,
+    Json(payload): Json<UpdateJobScheduleRequest>,
+) -> Result<Json<JobInfo>, AppError> {
+    let registry = &state.job_registry;
+    let job = registry.get(&job_name).ok_or(AppError::NotFound)?;
 
-    // 调用定时任务的核心逻辑
-    match watchlist_kline_job::run_watchlist_kline_task(state.db_pool.clone()).await {
-        Ok(result) => {
-            // 广播任务完成
-            let status = if result.failed_count == 0 {
-                "success"
-            } else if result.success_count > 0 {
-                "partial"
-            } else {
-                "failed"
-            };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "watchlist_kline_import".to_string(),
-                status.to_string(),
-            );
-
-            let details = result
-                .stock_details
-                .into_iter()
-                .map(|d| StockDetail {
-                    stock_code: d.stock_code,
-                    imported_count: d.imported_count,
-                    success: d.success,
-                    error: d.error,
-                })
-                .collect();
-
-            Ok(Json(TriggerTaskResponse {
-                success: result.failed_count == 0,
-                message: format!(
-                    "观察表K线导入任务执行完成，总计 {} 只股票，成功 {} 只，失败 {} 只，跳过 {} 只",
-                    result.total_stocks,
-                    result.success_count,
-                    result.failed_count,
-                    result.skipped_count
-                ),
-                total_stocks: result.total_stocks,
-                success_count: result.success_count,
-                failed_count: result.failed_count,
-                details,
-            }))
-        }
-        Err(e) => {
-            tracing::error!("手动触发观察表K线导入任务失败: {}", e);
-            // 广播任务失败
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &state.ws_sender,
-                "watchlist_kline_import".to_string(),
-                "failed".to_string(),
-            );
-            Err(AppError::InternalServerError)
-        }
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let current = job_schedule::find_by_job_name(&mut conn, &job_name)
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or(AppError::NotFound)?;
+
+    let enabled = payload.enabled.unwrap_or(current.enabled);
+    let cron_expressions = payload
+        .cron_expressions
+        .clone()
+        .unwrap_or(current.cron_expressions);
+    let timezone = payload.timezone.clone().unwrap_or(current.timezone);
+    // 有上游的任务可以不配置 cron，仅随上游执行
+    if enabled && cron_expressions.is_empty() && job.depends_on().is_empty() {
+        return Err(AppError::BadRequest(
+            "cronExpressions must not be empty for an enabled job".to_string(),
+        ));
+    }
+    validate_schedule(&cron_expressions, &timezone).map_err(AppError::BadRequest)?;
+    if let Some(policy) = &payload.conflict_policy {
+        ConflictPolicy::parse(policy).map_err(AppError::BadRequest)?;
+    }
+    if payload
+        .retry_max_attempts
+        .is_some_and(|n| !(0..=10).contains(&n))
+    {
+        return Err(AppError::BadRequest(
+            "retryMaxAttempts must be between 0 and 10".to_string(),
+        ));
     }
+    if payload.retry_backoff_secs.is_some_and(|n| n < 1) {
+        return Err(AppError::BadRequest(
+            "retryBackoffSecs must be positive".to_string(),
+        ));
+    }
+
+    // 传 {} 清空参数
+    let params = payload
+        .params
+        .map(|p| Some(p).filter(|v| v.as_object().is_none_or(|o| !o.is_empty())));
+    if let Some(Some(p)) = &params {
+        job.validate_params(p).map_err(AppError::BadRequest)?;
+    }
+
+    let data = UpdateJobSchedule {
+        enabled: payload.enabled,
+        cron_expressions: payload.cron_expressions,
+        timezone: payload.timezone,
+        params,
+        updated_at: Some(chrono::Local::now().naive_local()),
+        conflict_policy: payload.conflict_policy,
+        retry_max_attempts: payload.retry_max_attempts,
+        retry_backoff_secs: payload.retry_backoff_secs,
+    };
+    let updated = job_schedule::update_by_job_name(&mut conn, &job_name, &data)
+        .map_err(|_| AppError::InternalServerError)?;
+    drop(conn);
+
+    if let Err(e) = registry.reload(&job_name).await {
+        tracing::error!("重新加载任务 {} 的调度失败: {}", job_name, e);
+        return Err(AppError::InternalServerError);
+    }
+    tracing::info!(
+        "任务 {} 调度已更新: enabled={}, cron={:?}, timezone={}, conflict_policy={}",
+        job_name,
+        updated.enabled,
+        updated.cron_expressions,
+        updated.timezone,
+        updated.conflict_policy
+    );
+
+    let lock = current_holder(&state.db_pool, &job_name);
+    Ok(Json(
+        build_job_info(registry, job.as_ref(), Some(&updated), lock.as_ref()).await,
+    ))
 }
 
-/// 获取任务列表
-pub async fn get_job_list() -> Result<Json<Vec<JobInfo>>, AppError> {
-    let jobs = vec![
-        JobInfo {
-            name: "kline_import".to_string(),
-            display_name: "K线数据导入".to_string(),
-            description: "自动导入当天的K线数据到数据库".to_string(),
-            schedule: "每天 15:01".to_string(),
-            enabled: true,
-        },
-        JobInfo {
-            name: "stock_table_sync".to_string(),
-            display_name: "stock_table 同步".to_string(),
-            description: "从快照去重写入 stock_table".to_string(),
-            schedule: "每天 04:00".to_string(),
-            enabled: true,
-        },
-        JobInfo {
-            name: "stock_plate_sync".to_string(),
-            display_name: "stock_plate 同步".to_string(),
-            description: "根据 stock_table 同步板块及关系".to_string(),
-            schedule: "每天 04:30".to_string(),
-            enabled: true,
-        },
-        JobInfo {
-            name: "profit_analysis".to_string(),
-            display_name: "盈利分析".to_string(),
-            description: "分析股票快照的盈利情况".to_string(),
-            schedule: "每天 15:40".to_string(),
-            enabled: false,
-        },
-        JobInfo {
-            name: "stock_filter_morning".to_string(),
-            display_name: "股票筛选(上午)".to_string(),
-            description: "交易时段自动筛选符合条件的股票并入库".to_string(),
-            schedule: "工作日 9:30-12:00 每分钟".to_string(),
-            enabled: true,
-        },
-        JobInfo {
-            name: "stock_filter_afternoon".to_string(),
-            display_name: "股票筛选(下午)".to_string(),
-            description: "交易时段自动筛选符合条件的股票并入库".to_string(),
-            schedule: "工作日 13:00-15:00 每分钟".to_string(),
-            enabled: true,
-        },
-        JobInfo {
-            name: "watchlist_kline_import".to_string(),
-            display_name: "观察表K线导入".to_string(),
-            description: "对观察表中的股票进行K线数据检查和导入".to_string(),
-            schedule: "每天 16:00".to_string(),
-            enabled: true,
-        },
-    ];
+/// 将任务调度恢复为代码中的默认配置
+pub async fn reset_job_schedule(
+    State(state): State<AppState>,
+    Path(job_name): Path<String>,
+) -> Result<Json<JobInfo>, AppError> {
+    let registry = &state.job_registry;
+    let job = registry.get(&job_name).ok_or(AppError::NotFound)?;
+
+    let updated = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        let data = JobRegistry::reset_to_default(job.as_ref(), chrono::Local::now().naive_local());
+        job_schedule::update_by_job_name(&mut conn, &job_name, &data)
+            .map_err(|_| AppError::InternalServerError)?
+    };
+
+    if let Err(e) = registry.reload(&job_name).await {
+        tracing::error!("重新加载任务 {} 的调度失败: {}", job_name, e);
+        return Err(AppError::InternalServerError);
+    }
+    tracing::info!("任务 {} 调度已恢复默认配置", job_name);
 
-    Ok(Json(jobs))
+    let lock = current_holder(&state.db_pool, &job_name);
+    Ok(Json(
+        build_job_info(registry, job.as_ref(), Some(&updated), lock.as_ref()).await,
+    ))
 }
 
 /// 获取执行历史
@@ -542,7 +637,10 @@ pub async fn get_execution_history(
     let page_size = params.page_size.unwrap_or(20);
 
     // 将空字符串转换为 None
-    let job_name_filter = params.job_name.filter(|s| !s.is_empty());
+    let job_name_filter = params
+        .job_name
+        .filter(|s| !s.is_empty())
+        .map(|s| canonical_job_name(&s).to_string());
     let status_filter = params.status.filter(|s| !s.is_empty());
 
     let mut conn = state
@@ -590,8 +688,82 @@ pub async fn get_latest_execution(
         .get()
         .map_err(|_| AppError::InternalServerError)?;
 
-    let history = job_execution_history::find_latest_by_job_name(&mut conn, &job_name)
-        .map_err(|_| AppError::InternalServerError)?;
+    let history =
+        job_execution_history::find_latest_by_job_name(&mut conn, canonical_job_name(&job_name))
+            .map_err(|_| AppError::InternalServerError)?;
 
     Ok(Json(history.map(|h| h.into())))
 }
+
+/// 获取流水线执行记录
+pub async fn get_pipeline_runs(
+    Query(params): Query<PipelineQueryParams>,
+    State(state): State<AppState>,
+) -> Result<Json<PipelineRunListResponse>, AppError> {
+    let page = params.page.unwrap_or(1);
+    let page_size = params.page_size.unwrap_or(20);
+    let root_job_filter = params.root_job.filter(|s| !s.is_empty());
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let (items, total) = job_pipeline_run::paginate(&mut conn, root_job_filter, page, page_size)
+        .map_err(|_| AppError::InternalServerError)?;
+
+    Ok(Json(PipelineRunListResponse {
+        total,
+        page,
+        page_size,
+        items: items.into_iter().map(|r| r.into()).collect(),
+    }))
+}
+
+/// 获取流水线执行详情（含各步骤状态）
+pub async fn get_pipeline_run(
+    Path(id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<PipelineRunItem>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let run = job_pipeline_run::find_by_id(&mut conn, id)
+        .map_err(|_| AppError::InternalServerError)?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(run.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_outcome_to_legacy_profit_analysis_response() {
+        let outcome = JobOutcome {
+            total_count: 3,
+            success_count: 1,
+            failed_count: 1,
+            details: Some(json!([
+                {"stock_code": "600000", "stock_name": "浦发银行", "profit_rate": 2, "success": true, "error": null},
+                {"stock_code": "600001", "stock_name": "A", "profit_rate": -1, "success": true, "error": "无K线数据"},
+                {"stock_code": "600002", "stock_name": "B", "profit_rate": -1, "success": false, "error": "db"},
+            ])),
+            ..Default::default()
+        };
+        let response = legacy_response(LegacyTrigger::ProfitAnalysis, false, None, &outcome);
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value["total_snapshots"], 3);
+        assert_eq!(value["analyzed_count"], 1);
+        assert_eq!(value["no_kline_count"], 1);
+        assert_eq!(value["details"].as_array().map(Vec::len), Some(3));
+        assert!(value.get("total_count").is_none());
+
+        let filter = legacy_response(LegacyTrigger::StockFilter, true, None, &outcome);
+        assert_eq!(serde_json::to_value(&filter).unwrap()["items_count"], 3);
+    }
+}
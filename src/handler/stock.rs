@@ -122,6 +122,10 @@ pub struct FilterParamQuery {
     pub hs_min: f64,
     #[serde(default = "default_wb_min")]
     pub wb_min: f64,
+    /// 总市值下限（亿元）
+    pub market_cap_min: Option<f64>,
+    /// 总市值上限（亿元）
+    pub market_cap_max: Option<f64>,
     #[serde(default = "default_concurrency")]
     pub concurrency: i32,
     #[serde(default)]
@@ -28,6 +28,8 @@ pub async fn get_filtered_stocks_param_with_persist(
         lb_min: p.lb_min,
         hs_min: p.hs_min,
         wb_min: p.wb_min,
+        market_cap_min: p.market_cap_min,
+        market_cap_max: p.market_cap_max,
         concurrency: p.concurrency.clamp(1, 64) as usize,
         limit: p.limit.max(0) as usize,
         pz: p.pz,
@@ -101,6 +103,8 @@ async fn persist_to_db(
     state: &AppState,
     items: &[Value],
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // 快照按抓取时刻所处交易时段打标
+    let market_session = state.trading_calendar.session_now(&state.db_pool).await;
     let mut conn = state.db_pool.get()?;
 
     // 1. 插入 stock_requests 记录
@@ -142,6 +146,7 @@ async fn persist_to_db(
             turnover_rate,
             bid_ask_ratio,
             main_force_inflow,
+            session: Some(market_session.as_str().to_string()),
         };
 
         if let Err(e) = stock_snapshot::create(&mut conn, &new_snapshot) {
@@ -8,13 +8,35 @@ use diesel::result::Error as DieselError;
 use serde::Serialize;
 
 use crate::api_models::stock_snapshot::{
-    CreateStockSnapshot, DailyCountQuery, DailyStockCountItem, StockSnapshotResponse,
-    TodayStockCodesResponse,
+    CreateStockSnapshot, DailyCountQuery, DailyStockCountItem, SessionStockCountItem,
+    StockSnapshotResponse, TodayCodesQuery, TodayStockCodesResponse,
 };
 use crate::app::AppState;
 use crate::handler::error::AppError;
 use crate::models::NewStockSnapshot;
 use crate::repositories::stock_snapshot;
+use crate::services::market_session::MarketSession;
+
+/// 校验交易时段参数，空字符串视为未指定
+fn parse_session(value: Option<String>) -> Result<Option<MarketSession>, AppError> {
+    value
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<MarketSession>().map_err(AppError::BadRequest))
+        .transpose()
+}
+
+fn parse_date_range(start: &str, end: &str) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let start = NaiveDate::parse_from_str(start, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("invalid start, expect YYYY-MM-DD".to_string()))?;
+    let end = NaiveDate::parse_from_str(end, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("invalid end, expect YYYY-MM-DD".to_string()))?;
+    if start > end {
+        return Err(AppError::BadRequest(
+            "start must not be after end".to_string(),
+        ));
+    }
+    Ok((start, end))
+}
 
 impl From<crate::models::StockSnapshot> for StockSnapshotResponse {
     fn from(s: crate::models::StockSnapshot) -> Self {
@@ -30,6 +52,7 @@ impl From<crate::models::StockSnapshot> for StockSnapshotResponse {
             bid_ask_ratio: s.bid_ask_ratio,
             main_force_inflow: s.main_force_inflow,
             created_at: s.created_at,
+            session: s.session,
         }
     }
 }
@@ -55,6 +78,10 @@ pub async fn create_stock_snapshot(
     State(state): State<AppState>,
     Json(payload): Json<CreateStockSnapshot>,
 ) -> Result<(StatusCode, Json<InsertResponse>), AppError> {
+    let session = match parse_session(payload.session)? {
+        Some(s) => s,
+        None => state.trading_calendar.session_now(&state.db_pool).await,
+    };
     let mut conn = state
         .db_pool
         .get()
@@ -69,6 +96,7 @@ pub async fn create_stock_snapshot(
         turnover_rate: payload.turnover_rate,
         bid_ask_ratio: payload.bid_ask_ratio,
         main_force_inflow: payload.main_force_inflow,
+        session: Some(session.as_str().to_string()),
     };
     let new_id = stock_snapshot::create(&mut conn, &new_rec).map_err(map_err)?;
     Ok((StatusCode::CREATED, Json(InsertResponse { id: new_id })))
@@ -91,12 +119,16 @@ pub async fn delete_stock_snapshot(
 
 pub async fn get_today_stock_codes(
     State(state): State<AppState>,
+    Query(params): Query<TodayCodesQuery>,
 ) -> Result<Json<TodayStockCodesResponse>, AppError> {
+    let session = parse_session(params.session)?;
     let mut conn = state
         .db_pool
         .get()
         .map_err(|_| AppError::InternalServerError)?;
-    let codes = stock_snapshot::get_distinct_codes_today(&mut conn).map_err(map_err)?;
+    let codes =
+        stock_snapshot::get_distinct_codes_today(&mut conn, session.as_ref().map(|s| s.as_str()))
+            .map_err(map_err)?;
     let count = codes.len();
     Ok(Json(TodayStockCodesResponse {
         count,
@@ -108,21 +140,19 @@ pub async fn get_daily_stock_counts(
     State(state): State<AppState>,
     Query(params): Query<DailyCountQuery>,
 ) -> Result<Json<Vec<DailyStockCountItem>>, AppError> {
-    let start = NaiveDate::parse_from_str(&params.start, "%Y-%m-%d")
-        .map_err(|_| AppError::BadRequest("invalid start, expect YYYY-MM-DD".to_string()))?;
-    let end = NaiveDate::parse_from_str(&params.end, "%Y-%m-%d")
-        .map_err(|_| AppError::BadRequest("invalid end, expect YYYY-MM-DD".to_string()))?;
-    if start > end {
-        return Err(AppError::BadRequest(
-            "start must not be after end".to_string(),
-        ));
-    }
+    let (start, end) = parse_date_range(&params.start, &params.end)?;
+    let session = parse_session(params.session)?;
     let mut conn = state
         .db_pool
         .get()
         .map_err(|_| AppError::InternalServerError)?;
-    let rows = stock_snapshot::count_distinct_codes_by_date_range(&mut conn, start, end)
-        .map_err(map_err)?;
+    let rows = stock_snapshot::count_distinct_codes_by_date_range(
+        &mut conn,
+        start,
+        end,
+        session.as_ref().map(|s| s.as_str()),
+    )
+    .map_err(map_err)?;
     let data = rows
         .into_iter()
         .map(|r| DailyStockCountItem {
@@ -133,6 +163,29 @@ pub async fn get_daily_stock_counts(
     Ok(Json(data))
 }
 
+/// 按交易日 + 交易时段拆分统计去重股票支数
+pub async fn get_session_stock_counts(
+    State(state): State<AppState>,
+    Query(params): Query<DailyCountQuery>,
+) -> Result<Json<Vec<SessionStockCountItem>>, AppError> {
+    let (start, end) = parse_date_range(&params.start, &params.end)?;
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let rows =
+        stock_snapshot::count_distinct_codes_by_session(&mut conn, start, end).map_err(map_err)?;
+    let data = rows
+        .into_iter()
+        .map(|r| SessionStockCountItem {
+            date: r.stat_date.format("%Y-%m-%d").to_string(),
+            session: r.session,
+            count: r.stock_count,
+        })
+        .collect();
+    Ok(Json(data))
+}
+
 fn map_err(err: DieselError) -> AppError {
     match err {
         DieselError::NotFound => AppError::NotFound,
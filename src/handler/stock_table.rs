@@ -1,18 +1,24 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
 use chrono::Utc;
 use diesel::result::Error as DieselError;
 
 use crate::api_models::stock_table::{
-    CreateStockTable, StockTableResponse, UpdateStockTableRequest,
+    CreateStockTable, StStocksQuery, StStocksResponse, StockMasterListResponse, StockMasterQuery,
+    StockNameHistoryQuery, StockNameHistoryResponse, StockTableResponse, UpdateStockTableRequest,
 };
 use crate::app::AppState;
 use crate::handler::error::AppError;
 use crate::models::{NewStockTable, UpdateStockTable};
-use crate::repositories::stock_table;
+use crate::repositories::stock_name_history;
+use crate::repositories::stock_table::{self, StockMasterFilter};
+use crate::services::trading_calendar::shanghai_now;
 
 impl From<crate::models::StockTable> for StockTableResponse {
     fn from(stock: crate::models::StockTable) -> Self {
@@ -22,6 +28,18 @@ impl From<crate::models::StockTable> for StockTableResponse {
             stock_name: stock.stock_name,
             created_at: stock.created_at,
             updated_at: stock.updated_at,
+            exchange: stock.exchange,
+            board: stock.board,
+            listing_date: stock.listing_date,
+            industry: stock.industry,
+            total_shares: stock.total_shares,
+            float_shares: stock.float_shares,
+            total_market_cap: stock.total_market_cap,
+            float_market_cap: stock.float_market_cap,
+            is_st: stock.is_st,
+            list_status: stock.list_status,
+            delisted_at: stock.delisted_at,
+            master_synced_at: stock.master_synced_at,
         }
     }
 }
@@ -66,6 +84,75 @@ pub async fn list_stock_tables(
     Ok(Json(response))
 }
 
+/// GET /api/stock-tables/master?keyword=&exchange=&board=&industry=&is_st=&list_status=&market_cap_min=&market_cap_max=&page=&page_size=
+/// 按主数据筛选股票，市值单位为亿元
+pub async fn list_stock_master(
+    State(state): State<AppState>,
+    Query(params): Query<StockMasterQuery>,
+) -> Result<Json<StockMasterListResponse>, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params.page_size.unwrap_or(50).clamp(1, 500);
+    let yi = |v: f64| BigDecimal::from_str(&format!("{:.2}", v * 1e8)).ok();
+    let filter = StockMasterFilter {
+        keyword: params.keyword.filter(|k| !k.trim().is_empty()),
+        exchange: params.exchange,
+        board: params.board,
+        industry: params.industry,
+        is_st: params.is_st,
+        list_status: params.list_status,
+        market_cap_min: params.market_cap_min.and_then(yi),
+        market_cap_max: params.market_cap_max.and_then(yi),
+    };
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let (items, total) =
+        stock_table::paginate_master(&mut conn, &filter, page, page_size).map_err(map_err)?;
+    Ok(Json(StockMasterListResponse {
+        total,
+        page,
+        page_size,
+        items: items.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// GET /api/stock-tables/name-history?stock_code=
+/// 一只股票的简称变更时间线
+pub async fn list_name_history(
+    State(state): State<AppState>,
+    Query(params): Query<StockNameHistoryQuery>,
+) -> Result<Json<Vec<StockNameHistoryResponse>>, AppError> {
+    let code = params.stock_code.trim();
+    if code.is_empty() {
+        return Err(AppError::BadRequest("stock_code is required".to_string()));
+    }
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let rows = stock_name_history::list_by_code(&mut conn, code).map_err(map_err)?;
+    Ok(Json(rows.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/stock-tables/st?date=
+/// 某日使用 ST 类简称的股票
+pub async fn list_st_stocks(
+    State(state): State<AppState>,
+    Query(params): Query<StStocksQuery>,
+) -> Result<Json<StStocksResponse>, AppError> {
+    let date = params.date.unwrap_or_else(|| shanghai_now().date());
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let rows = stock_name_history::list_st_on(&mut conn, date).map_err(map_err)?;
+    Ok(Json(StStocksResponse {
+        date,
+        items: rows.into_iter().map(Into::into).collect(),
+    }))
+}
+
 pub async fn update_stock_table(
     State(state): State<AppState>,
     Path(id): Path<i32>,
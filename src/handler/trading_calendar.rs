@@ -0,0 +1,342 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, NaiveDate};
+
+use crate::api_models::trading_calendar::{
+    CreateTradingCalendarDayRequest, HolidayImportResponse, TradingCalendarDayResponse,
+    TradingCalendarListQuery, TradingCalendarSyncRequest, TradingCalendarSyncResponse,
+    TradingDayCountQuery, TradingDayCountResponse, TradingDayInfoQuery, TradingDayInfoResponse,
+    UpdateTradingCalendarDayRequest,
+};
+use crate::app::AppState;
+use crate::handler::error::AppError;
+use crate::models::{NewStockTradingCalendar, UpdateStockTradingCalendar};
+use crate::repositories::stock_trading_calendar;
+use crate::services::holiday_calendar::{self, HolidayYear};
+use crate::services::trading_calendar::shanghai_now;
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest(format!("invalid {field}, expect YYYY-MM-DD")))
+}
+
+fn parse_range(payload: &TradingCalendarSyncRequest) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let start = parse_date(&payload.start_date, "start_date")?;
+    let end = parse_date(&payload.end_date, "end_date")?;
+    if start > end {
+        return Err(AppError::BadRequest(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+    Ok((start, end))
+}
+
+/// 写库后刷新内存缓存，使调度任务立即使用新日历
+async fn reload_calendar(state: &AppState) -> Result<usize, AppError> {
+    state
+        .trading_calendar
+        .reload(&state.db_pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to reload trading calendar: {}", e);
+            AppError::InternalServerError
+        })
+}
+
+/// POST /api/trading-calendar/sync
+/// 补齐区间内缺失的日历记录（周末记为休市），并刷新内存缓存
+pub async fn sync_trading_calendar(
+    State(state): State<AppState>,
+    Json(payload): Json<TradingCalendarSyncRequest>,
+) -> Result<Json<TradingCalendarSyncResponse>, AppError> {
+    let (start, end) = parse_range(&payload)?;
+
+    let inserted = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        stock_trading_calendar::seed_missing_days(&mut conn, start, end).map_err(|e| {
+            tracing::error!("Failed to seed trading calendar: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+
+    let total_loaded = reload_calendar(&state).await?;
+
+    Ok(Json(TradingCalendarSyncResponse {
+        inserted,
+        total_loaded,
+    }))
+}
+
+/// GET /api/trading-calendar/info?date=YYYY-MM-DD
+/// 查询某天是否为交易日及其前后交易日
+pub async fn get_trading_day_info(
+    State(state): State<AppState>,
+    Query(params): Query<TradingDayInfoQuery>,
+) -> Result<Json<TradingDayInfoResponse>, AppError> {
+    let now = shanghai_now();
+    let date = match params.date.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => parse_date(s, "date")?,
+        None => now.date(),
+    };
+
+    let days = state.trading_calendar.days(&state.db_pool).await;
+    let covered = days.covered_range();
+
+    Ok(Json(TradingDayInfoResponse {
+        date,
+        is_trading_day: days.is_trading_day(date),
+        holiday_name: days.holiday_name(date).map(str::to_string),
+        prev_trading_day: days.prev_trading_day(date),
+        next_trading_day: days.next_trading_day(date),
+        session_open_now: days.is_session_open_at(now),
+        covered_start: covered.map(|(first, _)| first),
+        covered_end: covered.map(|(_, last)| last),
+    }))
+}
+
+/// GET /api/trading-calendar/count?start=YYYY-MM-DD&end=YYYY-MM-DD
+/// 统计闭区间内的交易日数量
+pub async fn count_trading_days(
+    State(state): State<AppState>,
+    Query(params): Query<TradingDayCountQuery>,
+) -> Result<Json<TradingDayCountResponse>, AppError> {
+    let start = parse_date(&params.start, "start")?;
+    let end = parse_date(&params.end, "end")?;
+    if start > end {
+        return Err(AppError::BadRequest(
+            "start must not be after end".to_string(),
+        ));
+    }
+
+    let days = state.trading_calendar.days(&state.db_pool).await;
+    Ok(Json(TradingDayCountResponse {
+        start,
+        end,
+        trading_days: days.count_trading_days(start, end),
+    }))
+}
+
+/// POST /api/trading-calendar/derive-from-klines
+/// 按 `daily_klines` 中出现过的日期推断区间内缺失的日历记录（无休市文件时的兜底），
+/// 最新一条K线之后的日期不推断
+pub async fn derive_trading_calendar_from_klines(
+    State(state): State<AppState>,
+    Json(payload): Json<TradingCalendarSyncRequest>,
+) -> Result<Json<TradingCalendarSyncResponse>, AppError> {
+    let (start, end) = parse_range(&payload)?;
+
+    let inserted = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        stock_trading_calendar::derive_from_daily_klines(&mut conn, start, end).map_err(|e| {
+            tracing::error!("Failed to derive trading calendar from klines: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+
+    let total_loaded = reload_calendar(&state).await?;
+
+    Ok(Json(TradingCalendarSyncResponse {
+        inserted,
+        total_loaded,
+    }))
+}
+
+async fn import_holiday_years(
+    state: &AppState,
+    years: Vec<HolidayYear>,
+) -> Result<Json<HolidayImportResponse>, AppError> {
+    if years.is_empty() {
+        return Err(AppError::BadRequest("no holiday year found".to_string()));
+    }
+    let rows_written = holiday_calendar::import_years(&state.db_pool, &years).map_err(|e| {
+        tracing::error!("Failed to import holiday calendar: {}", e);
+        AppError::BadRequest(e.to_string())
+    })?;
+    let total_loaded = reload_calendar(state).await?;
+
+    Ok(Json(HolidayImportResponse {
+        years: years.iter().map(|y| y.year).collect(),
+        rows_written,
+        total_loaded,
+    }))
+}
+
+/// POST /api/trading-calendar/import/json
+/// 导入年度休市安排 JSON（`{year, holidays: [{name, start, end?}]}` 或其数组），覆盖对应年份
+pub async fn import_holiday_json(
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<HolidayImportResponse>, AppError> {
+    let years = holiday_calendar::parse_holiday_json(&body)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    import_holiday_years(&state, years).await
+}
+
+/// POST /api/trading-calendar/import/csv
+/// 导入年度休市安排 CSV（`start_date,end_date,name`），覆盖涉及的年份
+pub async fn import_holiday_csv(
+    State(state): State<AppState>,
+    body: String,
+) -> Result<Json<HolidayImportResponse>, AppError> {
+    let years = holiday_calendar::parse_holiday_csv(&body)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    import_holiday_years(&state, years).await
+}
+
+/// POST /api/trading-calendar/import/embedded
+/// 重新导入随程序发布的内置休市安排，覆盖对应年份
+pub async fn import_embedded_holidays(
+    State(state): State<AppState>,
+) -> Result<Json<HolidayImportResponse>, AppError> {
+    import_holiday_years(&state, holiday_calendar::embedded_holiday_years()).await
+}
+
+/// GET /api/trading-calendar/days?start=YYYY-MM-DD&end=YYYY-MM-DD
+/// 列出区间内的日历记录，缺省为当年
+pub async fn list_trading_calendar_days(
+    State(state): State<AppState>,
+    Query(params): Query<TradingCalendarListQuery>,
+) -> Result<Json<Vec<TradingCalendarDayResponse>>, AppError> {
+    let year = shanghai_now().date().year();
+    let start = match params.start.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => parse_date(s, "start")?,
+        None => NaiveDate::from_ymd_opt(year, 1, 1).ok_or(AppError::InternalServerError)?,
+    };
+    let end = match params.end.as_deref().filter(|s| !s.is_empty()) {
+        Some(s) => parse_date(s, "end")?,
+        None => NaiveDate::from_ymd_opt(year, 12, 31).ok_or(AppError::InternalServerError)?,
+    };
+
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+    let items = stock_trading_calendar::list_range(&mut conn, start, end).map_err(|e| {
+        tracing::error!("Failed to list trading calendar: {}", e);
+        AppError::InternalServerError
+    })?;
+
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+/// POST /api/trading-calendar/days
+/// 新增单日记录（如临时休市）
+pub async fn create_trading_calendar_day(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTradingCalendarDayRequest>,
+) -> Result<(StatusCode, Json<TradingCalendarDayResponse>), AppError> {
+    let new_item = NewStockTradingCalendar {
+        trade_date: parse_date(&payload.trade_date, "trade_date")?,
+        is_holiday: payload.is_holiday,
+        holiday_name: payload.holiday_name.filter(|s| !s.trim().is_empty()),
+    };
+
+    let created = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        stock_trading_calendar::create(&mut conn, &new_item).map_err(|e| match e {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ) => AppError::BadRequest(format!("{} already exists", new_item.trade_date)),
+            e => {
+                tracing::error!("Failed to create trading calendar day: {}", e);
+                AppError::InternalServerError
+            }
+        })?
+    };
+    reload_calendar(&state).await?;
+
+    Ok((StatusCode::CREATED, Json(created.into())))
+}
+
+/// GET /api/trading-calendar/days/:id
+pub async fn get_trading_calendar_day(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<Json<TradingCalendarDayResponse>, AppError> {
+    let mut conn = state
+        .db_pool
+        .get()
+        .map_err(|_| AppError::InternalServerError)?;
+
+    let item = stock_trading_calendar::find_by_id(&mut conn, item_id)
+        .map_err(|e| {
+            tracing::error!("Failed to find trading calendar day: {}", e);
+            AppError::InternalServerError
+        })?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(item.into()))
+}
+
+/// PUT /api/trading-calendar/days/:id
+/// 修改单日的休市标记或名称
+pub async fn update_trading_calendar_day(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+    Json(payload): Json<UpdateTradingCalendarDayRequest>,
+) -> Result<Json<TradingCalendarDayResponse>, AppError> {
+    let update_data = UpdateStockTradingCalendar {
+        is_holiday: payload.is_holiday,
+        holiday_name: payload
+            .holiday_name
+            .map(|s| Some(s.trim().to_string()).filter(|s| !s.is_empty())),
+        updated_at: Some(chrono::Utc::now().naive_utc()),
+    };
+
+    let updated = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        stock_trading_calendar::update_by_id(&mut conn, item_id, &update_data).map_err(
+            |e| match e {
+                diesel::result::Error::NotFound => AppError::NotFound,
+                e => {
+                    tracing::error!("Failed to update trading calendar day: {}", e);
+                    AppError::InternalServerError
+                }
+            },
+        )?
+    };
+    reload_calendar(&state).await?;
+
+    Ok(Json(updated.into()))
+}
+
+/// DELETE /api/trading-calendar/days/:id
+/// 删除后该日期按工作日推断（若仍在覆盖范围内则视为休市）
+pub async fn delete_trading_calendar_day(
+    State(state): State<AppState>,
+    Path(item_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let affected = {
+        let mut conn = state
+            .db_pool
+            .get()
+            .map_err(|_| AppError::InternalServerError)?;
+        stock_trading_calendar::delete_by_id(&mut conn, item_id).map_err(|e| {
+            tracing::error!("Failed to delete trading calendar day: {}", e);
+            AppError::InternalServerError
+        })?
+    };
+
+    if affected == 0 {
+        return Err(AppError::NotFound);
+    }
+    reload_calendar(&state).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -45,71 +45,30 @@ async fn main() {
     // 创建 WebSocket 广播通道
     let ws_sender = utils::ws_broadcast::create_broadcast_channel();
 
-    if let Err(e) = scheduler::kline_import_job::create_kline_import_job(
-        &scheduler,
-        db_pool.clone(),
-        ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建K线导入任务失败: {}", e);
-    }
-
-    if let Err(e) = scheduler::profit_analysis_job::create_profit_analysis_job(
-        &scheduler,
-        db_pool.clone(),
-        ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建盈利分析任务失败: {}", e);
+    // 交易日历缓存：调度任务与 HTTP 接口共享
+    let trading_calendar = services::trading_calendar::TradingCalendar::default();
+    match services::holiday_calendar::bootstrap(&db_pool) {
+        Ok(years) if !years.is_empty() => tracing::info!("已导入休市安排年份: {:?}", years),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("导入休市安排失败: {}", e),
     }
-
-    if let Err(e) = scheduler::stock_filter_job::create_stock_filter_jobs(
-        &scheduler,
-        db_pool.clone(),
-        ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建股票筛选定时任务失败: {}", e);
+    if let Err(e) = trading_calendar.reload(&db_pool).await {
+        tracing::warn!("预加载交易日历失败: {}", e);
     }
 
-    if let Err(e) = scheduler::stock_table_sync_job::create_stock_table_sync_job(
-        &scheduler,
+    let job_registry = scheduler::registry::JobRegistry::new(
         db_pool.clone(),
         ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建 stock_table 同步定时任务失败: {}", e);
-    }
-
-    if let Err(e) = scheduler::stock_plate_sync_job::create_stock_plate_sync_job(
-        &scheduler,
-        db_pool.clone(),
-        ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建 stock_plate 同步定时任务失败: {}", e);
-    }
-
-    if let Err(e) = scheduler::watchlist_kline_job::create_watchlist_kline_job(
-        &scheduler,
-        db_pool.clone(),
-        ws_sender.clone(),
-    )
-    .await
-    {
-        tracing::error!("创建观察表K线导入定时任务失败: {}", e);
-    }
+        trading_calendar.clone(),
+        scheduler::all_jobs(),
+    );
+    job_registry.start(scheduler.clone()).await;
 
     scheduler.start().await.expect("启动调度器失败");
     tracing::info!("定时任务调度器已启动");
 
     // 构建并启动 Web 服务
-    let app = app::build_app_with_pool(db_pool, ws_sender);
+    let app = app::build_app_with_pool(db_pool, ws_sender, trading_calendar, job_registry);
 
     let listener = tokio::net::TcpListener::bind(addr)
         .await
@@ -0,0 +1,40 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::schema::ai_analysis_messages;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = ai_analysis_messages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AiAnalysisMessage {
+    pub id: i32,
+    pub analysis_id: i32,
+    pub role: String,
+    pub content: String,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone, Default)]
+#[diesel(table_name = ai_analysis_messages)]
+pub struct NewAiAnalysisMessage {
+    pub analysis_id: i32,
+    pub role: String,
+    pub content: String,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+}
@@ -0,0 +1,95 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::schema::{ai_batch_items, ai_batch_runs};
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = ai_batch_runs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AiBatchRun {
+    pub id: i32,
+    pub source: String,
+    pub source_key: Option<String>,
+    pub source_params: Option<Value>,
+    pub model_name: String,
+    pub prompt_version: i32,
+    pub concurrency: i32,
+    pub token_budget: Option<i64>,
+    pub tokens_used: i64,
+    pub status: String,
+    pub total_stocks: i32,
+    pub completed_stocks: i32,
+    pub failed_stocks: i32,
+    pub skipped_stocks: i32,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = ai_batch_runs)]
+pub struct NewAiBatchRun {
+    pub source: String,
+    pub source_key: Option<String>,
+    pub source_params: Option<Value>,
+    pub model_name: String,
+    pub prompt_version: i32,
+    pub concurrency: i32,
+    pub token_budget: Option<i64>,
+    pub status: String,
+    pub total_stocks: i32,
+}
+
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = ai_batch_runs)]
+pub struct UpdateAiBatchRun {
+    pub status: Option<String>,
+    pub tokens_used: Option<i64>,
+    pub completed_stocks: Option<i32>,
+    pub failed_stocks: Option<i32>,
+    pub skipped_stocks: Option<i32>,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub completed_at: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = ai_batch_items)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AiBatchItem {
+    #[allow(dead_code)]
+    pub id: i32,
+    #[allow(dead_code)]
+    pub run_id: i32,
+    pub stock_code: String,
+    pub status: String,
+    pub analysis_id: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub tokens_used: i64,
+    pub error_message: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = ai_batch_items)]
+pub struct NewAiBatchItem {
+    pub run_id: i32,
+    pub stock_code: String,
+}
+
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = ai_batch_items)]
+pub struct UpdateAiBatchItem {
+    pub status: Option<String>,
+    pub analysis_id: Option<i32>,
+    pub rating: Option<Option<String>>,
+    pub action: Option<Option<String>>,
+    pub tokens_used: Option<i64>,
+    pub error_message: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,49 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde_json::Value as JsonValue;
+
+use crate::schema::ai_market_reports;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = ai_market_reports)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AiMarketReport {
+    pub id: i32,
+    pub report_date: NaiveDate,
+    pub status: String,
+    pub statistics: JsonValue,
+    pub summary: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 同一天重新生成时整行覆盖（`treat_none_as_null`）
+#[derive(Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = ai_market_reports)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewAiMarketReport {
+    pub report_date: NaiveDate,
+    pub status: String,
+    pub statistics: JsonValue,
+    pub summary: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
+    pub duration_ms: Option<i64>,
+    pub error_message: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,57 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+use crate::schema::ai_prediction_evaluations;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = ai_prediction_evaluations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AiPredictionEvaluation {
+    pub id: i32,
+    pub analysis_id: i32,
+    pub horizon_days: i32,
+    pub stock_code: String,
+    pub model_name: String,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: String,
+    pub expected_direction: String,
+    pub base_date: NaiveDate,
+    pub base_close: BigDecimal,
+    pub eval_date: NaiveDate,
+    pub eval_close: BigDecimal,
+    pub realized_return_pct: BigDecimal,
+    pub max_gain_pct: BigDecimal,
+    pub max_drawdown_pct: BigDecimal,
+    pub direction_hit: bool,
+    pub rating_hit: Option<bool>,
+    pub resistance_reached: Option<bool>,
+    pub support_broken: Option<bool>,
+    pub evaluated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = ai_prediction_evaluations)]
+pub struct NewAiPredictionEvaluation {
+    pub analysis_id: i32,
+    pub horizon_days: i32,
+    pub stock_code: String,
+    pub model_name: String,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: String,
+    pub expected_direction: String,
+    pub base_date: NaiveDate,
+    pub base_close: BigDecimal,
+    pub eval_date: NaiveDate,
+    pub eval_close: BigDecimal,
+    pub realized_return_pct: BigDecimal,
+    pub max_gain_pct: BigDecimal,
+    pub max_drawdown_pct: BigDecimal,
+    pub direction_hit: bool,
+    pub rating_hit: Option<bool>,
+    pub resistance_reached: Option<bool>,
+    pub support_broken: Option<bool>,
+    pub evaluated_at: NaiveDateTime,
+}
@@ -1,3 +1,4 @@
+use bigdecimal::BigDecimal;
 use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use serde_json::Value as JsonValue;
@@ -22,6 +23,20 @@ pub struct AiTrendAnalysis {
     pub duration_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub trend_stage: Option<String>,
+    pub support_level: Option<BigDecimal>,
+    pub resistance_level: Option<BigDecimal>,
+    pub validation_errors: Option<JsonValue>,
+    pub repair_rounds: i32,
+    pub input_hash: Option<String>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -39,9 +54,12 @@ pub struct NewAiTrendAnalysis {
     pub kline_end_date: Option<NaiveDate>,
     pub error_message: Option<String>,
     pub duration_ms: Option<i64>,
+    pub prompt_template_id: Option<i32>,
+    pub prompt_version: Option<i32>,
+    pub input_hash: Option<String>,
 }
 
-#[derive(AsChangeset, Debug, Clone)]
+#[derive(AsChangeset, Debug, Default, Clone)]
 #[diesel(table_name = ai_trend_analysis)]
 pub struct UpdateAiTrendAnalysis {
     pub status: Option<String>,
@@ -50,4 +68,15 @@ pub struct UpdateAiTrendAnalysis {
     pub error_message: Option<String>,
     pub duration_ms: Option<i64>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub trend_stage: Option<String>,
+    pub support_level: Option<BigDecimal>,
+    pub resistance_level: Option<BigDecimal>,
+    pub validation_errors: Option<JsonValue>,
+    pub repair_rounds: Option<i32>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cost: Option<BigDecimal>,
 }
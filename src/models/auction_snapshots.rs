@@ -0,0 +1,51 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use diesel::prelude::*;
+
+use crate::schema::auction_snapshots;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = auction_snapshots)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuctionSnapshot {
+    #[allow(dead_code)]
+    pub id: i32,
+    pub trade_date: NaiveDate,
+    #[allow(dead_code)]
+    pub sample_time: NaiveTime,
+    pub stock_code: String,
+    #[allow(dead_code)]
+    pub stock_name: String,
+    #[allow(dead_code)]
+    pub matched_price: BigDecimal,
+    #[allow(dead_code)]
+    pub prev_close: BigDecimal,
+    #[allow(dead_code)]
+    pub change_pct: BigDecimal,
+    #[allow(dead_code)]
+    pub matched_volume: i64,
+    #[allow(dead_code)]
+    pub matched_amount: BigDecimal,
+    #[allow(dead_code)]
+    pub unmatched_volume: Option<i64>,
+    #[allow(dead_code)]
+    pub universe: String,
+    #[allow(dead_code)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = auction_snapshots)]
+pub struct NewAuctionSnapshot {
+    pub trade_date: NaiveDate,
+    pub sample_time: NaiveTime,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub matched_price: BigDecimal,
+    pub prev_close: BigDecimal,
+    pub change_pct: BigDecimal,
+    pub matched_volume: i64,
+    pub matched_amount: BigDecimal,
+    pub unmatched_volume: Option<i64>,
+    pub universe: String,
+}
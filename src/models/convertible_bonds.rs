@@ -0,0 +1,85 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+use crate::schema::{convertible_bond_daily, convertible_bonds};
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = convertible_bonds)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ConvertibleBond {
+    pub bond_code: String,
+    pub bond_short_name: String,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub rating: Option<String>,
+    pub issue_scale: Option<BigDecimal>,
+    pub remaining_scale: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub listing_date: Option<NaiveDate>,
+    pub expire_date: Option<NaiveDate>,
+    pub delist_date: Option<NaiveDate>,
+    pub last_trading_date: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 同步时写入或更新主数据；为 None 的字段不覆盖已有值（如已查到的最后交易日、
+/// 本次未取到的剩余规模）
+#[derive(Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = convertible_bonds)]
+pub struct NewConvertibleBond {
+    pub bond_code: String,
+    pub bond_short_name: String,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub rating: Option<String>,
+    pub issue_scale: Option<BigDecimal>,
+    pub remaining_scale: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub listing_date: Option<NaiveDate>,
+    pub expire_date: Option<NaiveDate>,
+    pub delist_date: Option<NaiveDate>,
+    pub last_trading_date: Option<NaiveDate>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = convertible_bond_daily)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ConvertibleBondDaily {
+    pub bond_code: String,
+    pub trade_date: NaiveDate,
+    pub bond_price: BigDecimal,
+    pub stock_price: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub transfer_value: Option<BigDecimal>,
+    pub transfer_premium_ratio: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub pbv_ratio: Option<BigDecimal>,
+    pub rating: Option<String>,
+    pub remaining_scale: Option<BigDecimal>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = convertible_bond_daily)]
+pub struct NewConvertibleBondDaily {
+    pub bond_code: String,
+    pub trade_date: NaiveDate,
+    pub bond_price: BigDecimal,
+    pub stock_price: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub transfer_value: Option<BigDecimal>,
+    pub transfer_premium_ratio: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub pbv_ratio: Option<BigDecimal>,
+    pub rating: Option<String>,
+    pub remaining_scale: Option<BigDecimal>,
+}
@@ -22,6 +22,10 @@ pub struct JobExecutionHistory {
     pub error_message: Option<String>,
     pub duration_ms: Option<i64>,
     pub created_at: NaiveDateTime,
+    pub attempt: i32,
+    pub retry_of: Option<i32>,
+    pub params: Option<Value>,
+    pub cancel_requested: bool,
 }
 
 #[derive(Insertable, Debug)]
@@ -38,6 +42,9 @@ pub struct NewJobExecutionHistory {
     pub details: Option<Value>,
     pub error_message: Option<String>,
     pub duration_ms: Option<i64>,
+    pub attempt: i32,
+    pub retry_of: Option<i32>,
+    pub params: Option<Value>,
 }
 
 #[derive(AsChangeset, Debug)]
@@ -0,0 +1,16 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::schema::job_locks;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = job_locks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct JobLock {
+    pub job_name: String,
+    pub holder: String,
+    pub execution_id: Option<i32>,
+    pub acquired_at: NaiveDateTime,
+    #[allow(dead_code)]
+    pub lease_until: NaiveDateTime,
+}
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::schema::job_pipeline_runs;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = job_pipeline_runs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct JobPipelineRun {
+    pub id: i32,
+    pub root_job: String,
+    pub trigger_type: String,
+    pub status: String,
+    pub steps: Value,
+    pub started_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub duration_ms: Option<i64>,
+    #[allow(dead_code)]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = job_pipeline_runs)]
+pub struct NewJobPipelineRun {
+    pub root_job: String,
+    pub trigger_type: String,
+    pub status: String,
+    pub steps: Value,
+    pub started_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = job_pipeline_runs)]
+pub struct UpdateJobPipelineRun {
+    pub status: Option<String>,
+    pub steps: Option<Value>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub duration_ms: Option<i64>,
+}
@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::schema::job_schedules;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = job_schedules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct JobSchedule {
+    #[allow(dead_code)]
+    pub id: i32,
+    pub job_name: String,
+    pub enabled: bool,
+    pub cron_expressions: Vec<String>,
+    pub timezone: String,
+    pub params: Option<Value>,
+    #[allow(dead_code)]
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// 任务已在执行时的处理策略，见 `scheduler::lock::ConflictPolicy`
+    pub conflict_policy: String,
+    pub retry_max_attempts: i32,
+    pub retry_backoff_secs: i32,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = job_schedules)]
+pub struct NewJobSchedule {
+    pub job_name: String,
+    pub enabled: bool,
+    pub cron_expressions: Vec<String>,
+    pub timezone: String,
+    pub params: Option<Value>,
+    pub conflict_policy: String,
+    pub retry_max_attempts: i32,
+    pub retry_backoff_secs: i32,
+}
+
+#[derive(AsChangeset, Debug, Default, Clone)]
+#[diesel(table_name = job_schedules)]
+pub struct UpdateJobSchedule {
+    pub enabled: Option<bool>,
+    pub cron_expressions: Option<Vec<String>>,
+    pub timezone: Option<String>,
+    pub params: Option<Option<Value>>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub conflict_policy: Option<String>,
+    pub retry_max_attempts: Option<i32>,
+    pub retry_backoff_secs: Option<i32>,
+}
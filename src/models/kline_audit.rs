@@ -0,0 +1,36 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::schema::kline_audit_findings;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = kline_audit_findings)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct KlineAuditFinding {
+    pub id: i32,
+    pub stock_code: String,
+    pub check_type: String,
+    pub severity: String,
+    pub trade_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub detail: String,
+    pub context: Option<Value>,
+    pub status: String,
+    pub first_detected_at: NaiveDateTime,
+    pub last_detected_at: NaiveDateTime,
+    pub resolved_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = kline_audit_findings)]
+pub struct NewKlineAuditFinding {
+    pub stock_code: String,
+    pub check_type: String,
+    pub severity: String,
+    pub trade_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub detail: String,
+    pub context: Option<Value>,
+    pub last_detected_at: NaiveDateTime,
+}
@@ -0,0 +1,89 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::schema::{kline_backfill_items, kline_backfill_runs};
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = kline_backfill_runs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct KlineBackfillRun {
+    pub id: i32,
+    pub stock_set: String,
+    pub set_key: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub status: String,
+    pub total_stocks: i32,
+    pub completed_stocks: i32,
+    pub failed_stocks: i32,
+    pub inserted_count: i32,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = kline_backfill_runs)]
+pub struct NewKlineBackfillRun {
+    pub stock_set: String,
+    pub set_key: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub status: String,
+    pub total_stocks: i32,
+}
+
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = kline_backfill_runs)]
+pub struct UpdateKlineBackfillRun {
+    pub status: Option<String>,
+    pub completed_stocks: Option<i32>,
+    pub failed_stocks: Option<i32>,
+    pub inserted_count: Option<i32>,
+    pub execution_id: Option<i32>,
+    pub error_message: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub completed_at: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = kline_backfill_items)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct KlineBackfillItem {
+    #[allow(dead_code)]
+    pub id: i32,
+    #[allow(dead_code)]
+    pub run_id: i32,
+    pub stock_code: String,
+    pub status: String,
+    pub expected_days: i32,
+    pub present_before: i32,
+    pub present_after: Option<i32>,
+    pub inserted_count: i32,
+    pub missing_ranges: Option<Value>,
+    pub error_message: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = kline_backfill_items)]
+pub struct NewKlineBackfillItem {
+    pub run_id: i32,
+    pub stock_code: String,
+}
+
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = kline_backfill_items)]
+pub struct UpdateKlineBackfillItem {
+    pub status: Option<String>,
+    pub expected_days: Option<i32>,
+    pub present_before: Option<i32>,
+    pub present_after: Option<i32>,
+    pub inserted_count: Option<i32>,
+    pub missing_ranges: Option<Value>,
+    pub error_message: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,29 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::schema::llm_model_prices;
+
+/// 模型单价（元 / 百万 token）
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = llm_model_prices)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LlmModelPrice {
+    pub id: i32,
+    pub model_name: String,
+    pub prompt_price: BigDecimal,
+    pub completion_price: BigDecimal,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = llm_model_prices)]
+pub struct NewLlmModelPrice {
+    pub model_name: String,
+    pub prompt_price: BigDecimal,
+    pub completion_price: BigDecimal,
+    pub description: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
@@ -1,28 +1,67 @@
+pub mod ai_analysis_messages;
+pub mod ai_batch;
+pub mod ai_market_reports;
+pub mod ai_prediction_evaluations;
 pub mod ai_trend_analysis;
+pub mod auction_snapshots;
+pub mod convertible_bonds;
 pub mod he_luo_lookup;
 pub mod daily_klines;
 pub mod export_button_configs;
 pub mod job_execution_history;
+pub mod job_locks;
+pub mod job_pipeline_runs;
+pub mod job_schedules;
+pub mod kline_audit;
+pub mod kline_backfill;
+pub mod llm_model_prices;
 pub mod profit_analysis;
+pub mod prompt_templates;
+pub mod stock_name_history;
 pub mod stock_plate_stock_tables;
 pub mod stock_plates;
 pub mod stock_request_stocks;
 pub mod stock_requests;
 pub mod stock_snapshots;
 pub mod stock_tables;
+pub mod stock_trading_calendar;
 pub mod stock_watchlist;
 
 pub use he_luo_lookup::HeLuoLookup;
+pub use ai_analysis_messages::{AiAnalysisMessage, NewAiAnalysisMessage};
+pub use ai_batch::{
+    AiBatchItem, AiBatchRun, NewAiBatchItem, NewAiBatchRun, UpdateAiBatchItem, UpdateAiBatchRun,
+};
+pub use ai_market_reports::{AiMarketReport, NewAiMarketReport};
+pub use ai_prediction_evaluations::{AiPredictionEvaluation, NewAiPredictionEvaluation};
 pub use ai_trend_analysis::{AiTrendAnalysis, NewAiTrendAnalysis, UpdateAiTrendAnalysis};
+pub use auction_snapshots::{AuctionSnapshot, NewAuctionSnapshot};
+pub use convertible_bonds::{
+    ConvertibleBond, ConvertibleBondDaily, NewConvertibleBond, NewConvertibleBondDaily,
+};
 pub use daily_klines::{DailyKline, NewDailyKline};
 pub use export_button_configs::{ExportButtonConfig, NewExportButtonConfig, UpdateExportButtonConfig};
 pub use job_execution_history::{NewJobExecutionHistory, UpdateJobExecutionHistory};
+pub use job_locks::JobLock;
+pub use job_pipeline_runs::{JobPipelineRun, NewJobPipelineRun, UpdateJobPipelineRun};
+pub use job_schedules::{JobSchedule, NewJobSchedule, UpdateJobSchedule};
+pub use kline_audit::{KlineAuditFinding, NewKlineAuditFinding};
+pub use kline_backfill::{
+    KlineBackfillItem, KlineBackfillRun, NewKlineBackfillItem, NewKlineBackfillRun,
+    UpdateKlineBackfillItem, UpdateKlineBackfillRun,
+};
+pub use llm_model_prices::{LlmModelPrice, NewLlmModelPrice};
 pub use profit_analysis::{NewProfitAnalysis, ProfitAnalysis};
+pub use prompt_templates::{NewPromptTemplate, PromptTemplate, UpdatePromptTemplate};
+pub use stock_name_history::{NewStockNameHistory, StockNameHistory};
 #[allow(unused_imports)]
 pub use stock_plate_stock_tables::{NewStockPlateStockTable, StockPlateStockTable};
 pub use stock_plates::{NewStockPlate, StockPlate, UpdateStockPlate};
 pub use stock_request_stocks::{NewStockRequestStock, StockRequestStock};
 pub use stock_requests::{NewStockRequest, StockRequest};
 pub use stock_snapshots::{NewStockSnapshot, StockSnapshot};
-pub use stock_tables::{NewStockTable, StockTable, UpdateStockTable};
+pub use stock_tables::{NewStockTable, StockMasterData, StockTable, UpdateStockTable};
+pub use stock_trading_calendar::{
+    NewStockTradingCalendar, StockTradingCalendar, UpdateStockTradingCalendar,
+};
 pub use stock_watchlist::{NewStockWatchlist, StockWatchlist, UpdateStockWatchlist};
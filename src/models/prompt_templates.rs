@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+use crate::schema::prompt_templates;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = prompt_templates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PromptTemplate {
+    pub id: i32,
+    pub template_key: String,
+    pub version: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub user_template: String,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = prompt_templates)]
+pub struct NewPromptTemplate {
+    pub template_key: String,
+    pub version: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub user_template: String,
+    pub is_active: bool,
+}
+
+/// 只允许修改说明性字段，模板内容创建后不可变
+#[derive(AsChangeset, Debug, Default, Clone)]
+#[diesel(table_name = prompt_templates)]
+pub struct UpdatePromptTemplate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub updated_at: Option<NaiveDateTime>,
+}
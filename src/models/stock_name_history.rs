@@ -0,0 +1,29 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+use crate::schema::stock_name_history;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = stock_name_history)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockNameHistory {
+    pub id: i32,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub is_st: bool,
+    pub valid_from: NaiveDate,
+    pub valid_to: Option<NaiveDate>,
+    pub source: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = stock_name_history)]
+pub struct NewStockNameHistory {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub is_st: bool,
+    pub valid_from: NaiveDate,
+    pub valid_to: Option<NaiveDate>,
+    pub source: String,
+}
@@ -18,6 +18,7 @@ pub struct StockSnapshot {
     pub bid_ask_ratio: BigDecimal,
     pub main_force_inflow: BigDecimal,
     pub created_at: DateTime<Utc>,
+    pub session: Option<String>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -32,4 +33,5 @@ pub struct NewStockSnapshot {
     pub turnover_rate: BigDecimal,
     pub bid_ask_ratio: BigDecimal,
     pub main_force_inflow: BigDecimal,
+    pub session: Option<String>,
 }
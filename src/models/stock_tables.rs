@@ -1,4 +1,5 @@
-use chrono::NaiveDateTime;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 
 use crate::schema::stock_table;
@@ -12,6 +13,18 @@ pub struct StockTable {
     pub stock_name: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub exchange: Option<String>,
+    pub board: Option<String>,
+    pub listing_date: Option<NaiveDate>,
+    pub industry: Option<String>,
+    pub total_shares: Option<i64>,
+    pub float_shares: Option<i64>,
+    pub total_market_cap: Option<BigDecimal>,
+    pub float_market_cap: Option<BigDecimal>,
+    pub is_st: bool,
+    pub list_status: String,
+    pub delisted_at: Option<NaiveDateTime>,
+    pub master_synced_at: Option<NaiveDateTime>,
 }
 
 #[derive(Insertable, Debug, Clone)]
@@ -28,3 +41,25 @@ pub struct UpdateStockTable {
     pub stock_name: Option<String>,
     pub updated_at: Option<NaiveDateTime>,
 }
+
+/// A 股列表同步的主数据（新增与更新共用）
+#[derive(Insertable, AsChangeset, Debug, Clone)]
+#[diesel(table_name = stock_table)]
+#[diesel(treat_none_as_null = true)]
+pub struct StockMasterData {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub exchange: Option<String>,
+    pub board: Option<String>,
+    pub listing_date: Option<NaiveDate>,
+    pub industry: Option<String>,
+    pub total_shares: Option<i64>,
+    pub float_shares: Option<i64>,
+    pub total_market_cap: Option<BigDecimal>,
+    pub float_market_cap: Option<BigDecimal>,
+    pub is_st: bool,
+    pub list_status: String,
+    pub delisted_at: Option<NaiveDateTime>,
+    pub master_synced_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,32 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+use crate::schema::stock_trading_calendar;
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = stock_trading_calendar)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockTradingCalendar {
+    pub id: i32,
+    pub trade_date: NaiveDate,
+    pub is_holiday: bool,
+    pub holiday_name: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = stock_trading_calendar)]
+pub struct NewStockTradingCalendar {
+    pub trade_date: NaiveDate,
+    pub is_holiday: bool,
+    pub holiday_name: Option<String>,
+}
+
+#[derive(AsChangeset, Debug, Default, Clone)]
+#[diesel(table_name = stock_trading_calendar)]
+pub struct UpdateStockTradingCalendar {
+    pub is_holiday: Option<bool>,
+    pub holiday_name: Option<Option<String>>,
+    pub updated_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,51 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{AiAnalysisMessage, NewAiAnalysisMessage};
+use crate::schema::ai_analysis_messages;
+
+/// 分析记录下的全部追问消息（按写入顺序）
+pub fn list_by_analysis(
+    conn: &mut PgConnection,
+    analysis_id: i32,
+) -> Result<Vec<AiAnalysisMessage>, DieselError> {
+    use ai_analysis_messages::dsl;
+
+    dsl::ai_analysis_messages
+        .filter(dsl::analysis_id.eq(analysis_id))
+        .order(dsl::id.asc())
+        .select(AiAnalysisMessage::as_select())
+        .load(conn)
+}
+
+/// 在同一事务中写入一轮追问与回答
+pub fn append_turn(
+    conn: &mut PgConnection,
+    question: &NewAiAnalysisMessage,
+    answer: &NewAiAnalysisMessage,
+) -> Result<(AiAnalysisMessage, AiAnalysisMessage), DieselError> {
+    use ai_analysis_messages::dsl;
+
+    conn.transaction(|conn| {
+        let q = diesel::insert_into(dsl::ai_analysis_messages)
+            .values(question)
+            .returning(AiAnalysisMessage::as_returning())
+            .get_result(conn)?;
+        let a = diesel::insert_into(dsl::ai_analysis_messages)
+            .values(answer)
+            .returning(AiAnalysisMessage::as_returning())
+            .get_result(conn)?;
+        Ok((q, a))
+    })
+}
+
+/// 使用该提示词模板的追问回答数
+pub fn count_template_usage(conn: &mut PgConnection, template_id: i32) -> Result<i64, DieselError> {
+    use ai_analysis_messages::dsl;
+
+    dsl::ai_analysis_messages
+        .filter(dsl::prompt_template_id.eq(template_id))
+        .count()
+        .get_result(conn)
+}
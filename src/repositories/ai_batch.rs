@@ -0,0 +1,85 @@
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{
+    AiBatchItem, AiBatchRun, NewAiBatchItem, NewAiBatchRun, UpdateAiBatchItem, UpdateAiBatchRun,
+};
+use crate::schema::{ai_batch_items, ai_batch_runs};
+
+/// 创建批量分析任务及其逐股进度记录
+pub fn create_run(
+    conn: &mut PgConnection,
+    data: &NewAiBatchRun,
+    stock_codes: &[String],
+) -> Result<AiBatchRun, DieselError> {
+    conn.transaction(|conn| {
+        let run: AiBatchRun = diesel::insert_into(ai_batch_runs::table)
+            .values(data)
+            .get_result(conn)?;
+        let items: Vec<NewAiBatchItem> = stock_codes
+            .iter()
+            .map(|code| NewAiBatchItem {
+                run_id: run.id,
+                stock_code: code.clone(),
+            })
+            .collect();
+        diesel::insert_into(ai_batch_items::table)
+            .values(&items)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        Ok(run)
+    })
+}
+
+pub fn find_run(conn: &mut PgConnection, run_id: i32) -> Result<Option<AiBatchRun>, DieselError> {
+    ai_batch_runs::table.find(run_id).first(conn).optional()
+}
+
+pub fn update_run(
+    conn: &mut PgConnection,
+    run_id: i32,
+    data: &UpdateAiBatchRun,
+) -> Result<AiBatchRun, DieselError> {
+    diesel::update(ai_batch_runs::table.find(run_id))
+        .set(data)
+        .get_result(conn)
+}
+
+/// 分页查询批量分析任务（按创建时间倒序）
+pub fn paginate_runs(
+    conn: &mut PgConnection,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<AiBatchRun>, i64), DieselError> {
+    let offset = (page - 1) * page_size;
+    let total = ai_batch_runs::table.count().get_result(conn)?;
+    let items = ai_batch_runs::table
+        .order(ai_batch_runs::created_at.desc())
+        .limit(page_size)
+        .offset(offset)
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 批量分析任务的全部逐股记录（按股票代码排序）
+pub fn list_items(conn: &mut PgConnection, run_id: i32) -> Result<Vec<AiBatchItem>, DieselError> {
+    ai_batch_items::table
+        .filter(ai_batch_items::run_id.eq(run_id))
+        .order(ai_batch_items::stock_code.asc())
+        .load(conn)
+}
+
+pub fn update_item(
+    conn: &mut PgConnection,
+    run_id: i32,
+    code: &str,
+    data: &UpdateAiBatchItem,
+) -> Result<usize, DieselError> {
+    diesel::update(
+        ai_batch_items::table
+            .filter(ai_batch_items::run_id.eq(run_id))
+            .filter(ai_batch_items::stock_code.eq(code)),
+    )
+    .set(data)
+    .execute(conn)
+}
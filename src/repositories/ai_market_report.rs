@@ -0,0 +1,200 @@
+//! AI 每日复盘记录，以及复盘用到的信号统计（日期均按 Asia/Shanghai 时区归属）
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Date, Nullable, Numeric, Text};
+use serde_json::Value as JsonValue;
+
+use crate::models::{AiMarketReport, NewAiMarketReport};
+use crate::schema::ai_market_reports;
+
+/// 写入某日的复盘，已有记录时整行覆盖
+pub fn upsert(
+    conn: &mut PgConnection,
+    item: &NewAiMarketReport,
+) -> Result<AiMarketReport, DieselError> {
+    use ai_market_reports::dsl;
+
+    diesel::insert_into(dsl::ai_market_reports)
+        .values(item)
+        .on_conflict(dsl::report_date)
+        .do_update()
+        .set(item)
+        .returning(AiMarketReport::as_returning())
+        .get_result(conn)
+}
+
+/// 只刷新某日复盘的统计与失败原因，保留已生成的复盘内容
+pub fn update_statistics(
+    conn: &mut PgConnection,
+    date: NaiveDate,
+    statistics: &JsonValue,
+    error_message: Option<&str>,
+    updated_at: DateTime<Utc>,
+) -> Result<AiMarketReport, DieselError> {
+    use ai_market_reports::dsl;
+
+    diesel::update(dsl::ai_market_reports.filter(dsl::report_date.eq(date)))
+        .set((
+            dsl::statistics.eq(statistics),
+            dsl::error_message.eq(error_message),
+            dsl::updated_at.eq(updated_at),
+        ))
+        .returning(AiMarketReport::as_returning())
+        .get_result(conn)
+}
+
+pub fn find_by_date(
+    conn: &mut PgConnection,
+    date: NaiveDate,
+) -> Result<Option<AiMarketReport>, DieselError> {
+    use ai_market_reports::dsl;
+
+    dsl::ai_market_reports
+        .filter(dsl::report_date.eq(date))
+        .select(AiMarketReport::as_select())
+        .first(conn)
+        .optional()
+}
+
+/// 按日期区间分页，新的在前
+pub fn paginate(
+    conn: &mut PgConnection,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<AiMarketReport>, i64), DieselError> {
+    use ai_market_reports::dsl;
+
+    let build = || {
+        let mut query = dsl::ai_market_reports.into_boxed();
+        if let Some(v) = start {
+            query = query.filter(dsl::report_date.ge(v));
+        }
+        if let Some(v) = end {
+            query = query.filter(dsl::report_date.le(v));
+        }
+        query
+    };
+
+    let total: i64 = build().count().get_result(conn)?;
+    let items = build()
+        .order(dsl::report_date.desc())
+        .limit(page_size)
+        .offset((page - 1) * page_size)
+        .select(AiMarketReport::as_select())
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 区间内多天出现信号的股票
+#[derive(Debug, Clone, QueryableByName)]
+pub struct RepeatedSignalRow {
+    #[diesel(sql_type = Text)]
+    pub stock_code: String,
+    #[diesel(sql_type = Text)]
+    pub stock_name: String,
+    #[diesel(sql_type = BigInt)]
+    pub signal_days: i64,
+    #[diesel(sql_type = Date)]
+    pub first_date: NaiveDate,
+}
+
+/// `start ~ end` 内至少两天出现信号、且 `end` 当天也出现的股票，按出现天数降序
+pub fn list_repeated_signals(
+    conn: &mut PgConnection,
+    start: NaiveDate,
+    end: NaiveDate,
+    limit: i64,
+) -> Result<Vec<RepeatedSignalRow>, DieselError> {
+    diesel::sql_query(
+        r#"
+        WITH day_stocks AS (
+            SELECT stock_code,
+                   stock_name,
+                   created_at,
+                   (created_at AT TIME ZONE 'Asia/Shanghai')::date AS signal_date
+            FROM stock_snapshots
+            WHERE (created_at AT TIME ZONE 'Asia/Shanghai')::date BETWEEN $1::date AND $2::date
+        )
+        SELECT stock_code,
+               (array_agg(stock_name ORDER BY created_at DESC))[1]::TEXT AS stock_name,
+               COUNT(DISTINCT signal_date) AS signal_days,
+               MIN(signal_date) AS first_date
+        FROM day_stocks
+        GROUP BY stock_code
+        HAVING COUNT(DISTINCT signal_date) >= 2 AND bool_or(signal_date = $2::date)
+        ORDER BY signal_days DESC, first_date ASC, stock_code ASC
+        LIMIT $3
+        "#,
+    )
+    .bind::<Date, _>(start)
+    .bind::<Date, _>(end)
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+}
+
+/// 某日出现信号的股票在次一交易日的表现（盈利分析结果与当日K线）
+#[derive(Debug, Clone, QueryableByName)]
+pub struct SignalOutcomeRow {
+    #[diesel(sql_type = Text)]
+    pub stock_code: String,
+    #[diesel(sql_type = Text)]
+    pub stock_name: String,
+    /// 信号出现时的价格
+    #[diesel(sql_type = Numeric)]
+    pub entry_price: BigDecimal,
+    /// 盈利分析（OHLC）评级：2 冲高 10% 且收盘 ≥ 5%，1 冲高 5% 未站稳，0 未达 5%
+    #[diesel(sql_type = Nullable<BigInt>)]
+    pub profit_rate: Option<i64>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub close_price: Option<BigDecimal>,
+}
+
+/// `signal_date` 出现信号的股票（每只取当天第一条快照）及其在 `trade_date` 的表现
+pub fn list_signal_outcomes(
+    conn: &mut PgConnection,
+    signal_date: NaiveDate,
+    trade_date: NaiveDate,
+) -> Result<Vec<SignalOutcomeRow>, DieselError> {
+    diesel::sql_query(
+        r#"
+        WITH first_snap AS (
+            SELECT DISTINCT ON (stock_code)
+                id, stock_code, stock_name, latest_price
+            FROM stock_snapshots
+            WHERE (created_at AT TIME ZONE 'Asia/Shanghai')::date = $1::date
+            ORDER BY stock_code, created_at ASC
+        )
+        SELECT s.stock_code::TEXT AS stock_code,
+               s.stock_name::TEXT AS stock_name,
+               s.latest_price AS entry_price,
+               (SELECT MAX(pa.profit_rate)::BIGINT
+                FROM profit_analysis pa
+                WHERE pa.snapshot_id = s.id AND pa.strategy_name = 'OHLC') AS profit_rate,
+               k.close_price
+        FROM first_snap s
+        LEFT JOIN daily_klines k
+               ON k.stock_code = regexp_replace(s.stock_code, '^(SH|SZ)', '')
+              AND k.trade_date = $2::date
+        ORDER BY s.stock_code
+        "#,
+    )
+    .bind::<Date, _>(signal_date)
+    .bind::<Date, _>(trade_date)
+    .load(conn)
+}
+
+/// 使用该提示词模板的复盘数
+pub fn count_template_usage(conn: &mut PgConnection, template_id: i32) -> Result<i64, DieselError> {
+    use ai_market_reports::dsl;
+
+    dsl::ai_market_reports
+        .filter(dsl::prompt_template_id.eq(template_id))
+        .count()
+        .get_result(conn)
+}
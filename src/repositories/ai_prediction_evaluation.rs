@@ -0,0 +1,203 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::dsl::{exists, not};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Date, Int4, Nullable, Numeric, Text};
+
+use crate::models::{AiPredictionEvaluation, NewAiPredictionEvaluation};
+use crate::schema::{ai_prediction_evaluations, ai_trend_analysis};
+
+/// 尚未在指定周期评估的分析记录（只取评估需要的列）
+#[derive(Queryable, Debug, Clone)]
+pub struct PendingAnalysis {
+    pub id: i32,
+    pub stock_code: String,
+    pub model_name: String,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+    pub support_level: Option<BigDecimal>,
+    pub resistance_level: Option<BigDecimal>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `since` 之后通过校验、且尚未在 `horizon` 周期评估的分析记录（按创建时间升序）
+pub fn list_pending(
+    conn: &mut PgConnection,
+    horizon: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<PendingAnalysis>, DieselError> {
+    use ai_prediction_evaluations::dsl as e;
+    use ai_trend_analysis::dsl as a;
+
+    a::ai_trend_analysis
+        .filter(a::status.eq("completed"))
+        .filter(a::action.is_not_null())
+        .filter(a::created_at.ge(since))
+        .filter(not(exists(
+            e::ai_prediction_evaluations
+                .filter(e::analysis_id.eq(a::id))
+                .filter(e::horizon_days.eq(horizon)),
+        )))
+        .select((
+            a::id,
+            a::stock_code,
+            a::model_name,
+            a::prompt_version,
+            a::rating,
+            a::action,
+            a::support_level,
+            a::resistance_level,
+            a::created_at,
+        ))
+        .order(a::created_at.asc())
+        .load(conn)
+}
+
+/// 写入评估结果；同一分析记录同一周期已评估时忽略，返回写入条数
+pub fn insert(
+    conn: &mut PgConnection,
+    item: &NewAiPredictionEvaluation,
+) -> Result<usize, DieselError> {
+    use ai_prediction_evaluations::dsl;
+
+    diesel::insert_into(dsl::ai_prediction_evaluations)
+        .values(item)
+        .on_conflict((dsl::analysis_id, dsl::horizon_days))
+        .do_nothing()
+        .execute(conn)
+}
+
+/// 评估记录筛选条件
+#[derive(Debug, Default)]
+pub struct EvaluationFilter {
+    pub analysis_id: Option<i32>,
+    pub stock_code: Option<String>,
+    pub model_name: Option<String>,
+    pub prompt_version: Option<i32>,
+    pub horizon_days: Option<i32>,
+}
+
+/// 按基准日倒序分页查询评估记录
+pub fn paginate(
+    conn: &mut PgConnection,
+    filter: &EvaluationFilter,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<AiPredictionEvaluation>, i64), DieselError> {
+    use ai_prediction_evaluations::dsl;
+
+    let build = || {
+        let mut query = dsl::ai_prediction_evaluations.into_boxed();
+        if let Some(v) = filter.analysis_id {
+            query = query.filter(dsl::analysis_id.eq(v));
+        }
+        if let Some(v) = &filter.stock_code {
+            query = query.filter(dsl::stock_code.eq(v.clone()));
+        }
+        if let Some(v) = &filter.model_name {
+            query = query.filter(dsl::model_name.eq(v.clone()));
+        }
+        if let Some(v) = filter.prompt_version {
+            query = query.filter(dsl::prompt_version.eq(v));
+        }
+        if let Some(v) = filter.horizon_days {
+            query = query.filter(dsl::horizon_days.eq(v));
+        }
+        query
+    };
+
+    let total: i64 = build().count().get_result(conn)?;
+    let items = build()
+        .order((dsl::base_date.desc(), dsl::id.desc()))
+        .limit(page_size)
+        .offset((page - 1) * page_size)
+        .select(AiPredictionEvaluation::as_select())
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 按模型与提示词版本汇总的准确率统计
+#[derive(Debug, Clone, QueryableByName)]
+pub struct AccuracyRow {
+    #[diesel(sql_type = Text)]
+    pub model_name: String,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub prompt_version: Option<i32>,
+    #[diesel(sql_type = BigInt)]
+    pub evaluated: i64,
+    #[diesel(sql_type = BigInt)]
+    pub direction_hits: i64,
+    #[diesel(sql_type = BigInt)]
+    pub rating_evaluated: i64,
+    #[diesel(sql_type = BigInt)]
+    pub rating_hits: i64,
+    #[diesel(sql_type = BigInt)]
+    pub bullish: i64,
+    #[diesel(sql_type = BigInt)]
+    pub bullish_hits: i64,
+    #[diesel(sql_type = BigInt)]
+    pub bearish: i64,
+    #[diesel(sql_type = BigInt)]
+    pub bearish_hits: i64,
+    #[diesel(sql_type = BigInt)]
+    pub with_resistance: i64,
+    #[diesel(sql_type = BigInt)]
+    pub resistance_reached: i64,
+    #[diesel(sql_type = BigInt)]
+    pub with_support: i64,
+    #[diesel(sql_type = BigInt)]
+    pub support_broken: i64,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub avg_return_pct: Option<BigDecimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub avg_bullish_return_pct: Option<BigDecimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub avg_bearish_return_pct: Option<BigDecimal>,
+}
+
+/// 统计 `horizon` 周期、基准日在 `[start, end]` 内的评估结果
+pub fn accuracy_stats(
+    conn: &mut PgConnection,
+    horizon: i32,
+    start: NaiveDate,
+    end: NaiveDate,
+    model: Option<&str>,
+) -> Result<Vec<AccuracyRow>, DieselError> {
+    diesel::sql_query(
+        r#"
+        SELECT model_name::TEXT AS model_name,
+               prompt_version,
+               COUNT(*) AS evaluated,
+               COUNT(*) FILTER (WHERE direction_hit) AS direction_hits,
+               COUNT(rating_hit) AS rating_evaluated,
+               COUNT(*) FILTER (WHERE rating_hit) AS rating_hits,
+               COUNT(*) FILTER (WHERE expected_direction = 'up') AS bullish,
+               COUNT(*) FILTER (WHERE expected_direction = 'up' AND direction_hit) AS bullish_hits,
+               COUNT(*) FILTER (WHERE expected_direction = 'down') AS bearish,
+               COUNT(*) FILTER (WHERE expected_direction = 'down' AND direction_hit) AS bearish_hits,
+               COUNT(resistance_reached) AS with_resistance,
+               COUNT(*) FILTER (WHERE resistance_reached) AS resistance_reached,
+               COUNT(support_broken) AS with_support,
+               COUNT(*) FILTER (WHERE support_broken) AS support_broken,
+               ROUND(AVG(realized_return_pct), 4) AS avg_return_pct,
+               ROUND(AVG(realized_return_pct) FILTER (WHERE expected_direction = 'up'), 4)
+                   AS avg_bullish_return_pct,
+               ROUND(AVG(realized_return_pct) FILTER (WHERE expected_direction = 'down'), 4)
+                   AS avg_bearish_return_pct
+        FROM ai_prediction_evaluations
+        WHERE horizon_days = $1
+          AND base_date BETWEEN $2 AND $3
+          AND ($4::TEXT IS NULL OR model_name = $4)
+        GROUP BY model_name, prompt_version
+        ORDER BY model_name, prompt_version
+        "#,
+    )
+    .bind::<Int4, _>(horizon)
+    .bind::<Date, _>(start)
+    .bind::<Date, _>(end)
+    .bind::<Nullable<Text>, _>(model)
+    .load(conn)
+}
@@ -42,26 +42,58 @@ pub fn find_by_id(
         .optional()
 }
 
+/// 相同输入最近一次成功的分析记录
+pub fn find_cached(
+    conn: &mut PgPoolConn,
+    hash: &str,
+) -> Result<Option<AiTrendAnalysis>, diesel::result::Error> {
+    use diesel::OptionalExtension;
+    ai_trend_analysis
+        .filter(input_hash.eq(hash))
+        .filter(status.eq("completed"))
+        .order(created_at.desc())
+        .first::<AiTrendAnalysis>(conn)
+        .optional()
+}
+
+/// 历史记录筛选条件
+#[derive(Debug, Default)]
+pub struct AnalysisHistoryFilter {
+    pub stock_code: Option<String>,
+    pub prompt_version: Option<i32>,
+    pub rating: Option<String>,
+    pub action: Option<String>,
+}
+
 /// 查询历史记录（分页）
 pub fn list_history(
     conn: &mut PgPoolConn,
-    filter_stock_code: Option<&str>,
+    filter: &AnalysisHistoryFilter,
     page_size: i64,
     page: i64,
 ) -> Result<(Vec<AiTrendAnalysis>, i64), diesel::result::Error> {
     let offset = (page - 1) * page_size;
 
-    let mut query = ai_trend_analysis.into_boxed();
-    let mut count_query = ai_trend_analysis.into_boxed();
-
-    if let Some(code) = filter_stock_code {
-        query = query.filter(stock_code.eq(code));
-        count_query = count_query.filter(stock_code.eq(code));
-    }
+    let build = || {
+        let mut query = ai_trend_analysis.into_boxed();
+        if let Some(code) = &filter.stock_code {
+            query = query.filter(stock_code.eq(code.clone()));
+        }
+        if let Some(version) = filter.prompt_version {
+            query = query.filter(prompt_version.eq(version));
+        }
+        if let Some(r) = &filter.rating {
+            query = query.filter(rating.eq(r.clone()));
+        }
+        if let Some(a) = &filter.action {
+            query = query.filter(action.eq(a.clone()));
+        }
+        query
+    };
 
-    let total: i64 = count_query.count().get_result(conn)?;
+    let total: i64 = build().count().get_result(conn)?;
 
-    let results = query
+    let results = build()
         .order(created_at.desc())
         .limit(page_size)
         .offset(offset)
@@ -0,0 +1,120 @@
+//! AI 分析用量汇总（按北京时间的自然日、自然月统计 `ai_trend_analysis`、
+//! `ai_analysis_messages` 中追问的回答与 `ai_market_reports`）
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::{BigInt, Date, Nullable, Numeric, Text};
+
+use crate::repositories::daily_kline::PgPoolConn;
+
+/// 全部模型调用：趋势分析记录、追问回答（status 记为 followup）与每日复盘（report，
+/// 重新生成时覆盖，只计最后一次）
+const CALLS_CTE: &str = r#"
+    WITH calls AS (
+        SELECT created_at, model_name::TEXT AS model_name, status::TEXT AS status,
+               prompt_tokens, completion_tokens, total_tokens, cost
+        FROM ai_trend_analysis
+        UNION ALL
+        SELECT created_at, COALESCE(model_name, '')::TEXT, 'followup',
+               prompt_tokens, completion_tokens, total_tokens, cost
+        FROM ai_analysis_messages
+        WHERE role = 'assistant'
+        UNION ALL
+        SELECT updated_at, COALESCE(model_name, '')::TEXT, 'report',
+               prompt_tokens, completion_tokens, total_tokens, cost
+        FROM ai_market_reports
+        WHERE model_name IS NOT NULL
+    )
+"#;
+
+/// 一段时间内的用量合计
+#[derive(Debug, Clone, Default, QueryableByName)]
+pub struct UsageTotals {
+    #[diesel(sql_type = BigInt)]
+    pub calls: i64,
+    #[diesel(sql_type = BigInt)]
+    pub prompt_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub completion_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub total_tokens: i64,
+    #[diesel(sql_type = Numeric)]
+    pub cost: BigDecimal,
+}
+
+/// 用量报表的一行：周期（`YYYY-MM-DD` 或 `YYYY-MM`）× 模型
+#[derive(Debug, Clone, QueryableByName)]
+pub struct UsageReportRow {
+    #[diesel(sql_type = Text)]
+    pub period: String,
+    #[diesel(sql_type = Text)]
+    pub model_name: String,
+    #[diesel(sql_type = BigInt)]
+    pub calls: i64,
+    #[diesel(sql_type = BigInt)]
+    pub completed: i64,
+    /// 其中追问的次数
+    #[diesel(sql_type = BigInt)]
+    pub followups: i64,
+    #[diesel(sql_type = BigInt)]
+    pub prompt_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub completion_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub total_tokens: i64,
+    #[diesel(sql_type = Numeric)]
+    pub cost: BigDecimal,
+}
+
+/// 自某日（北京时间）起的用量合计
+pub fn usage_since(conn: &mut PgPoolConn, start: NaiveDate) -> Result<UsageTotals, DieselError> {
+    diesel::sql_query(format!(
+        r#"{CALLS_CTE}
+        SELECT COUNT(*) AS calls,
+               COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+               COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+               COALESCE(SUM(total_tokens), 0)::BIGINT AS total_tokens,
+               COALESCE(SUM(cost), 0) AS cost
+        FROM calls
+        WHERE created_at >= ($1::date::timestamp AT TIME ZONE 'Asia/Shanghai')
+        "#
+    ))
+    .bind::<Date, _>(start)
+    .get_result(conn)
+}
+
+/// 按日或按月（北京时间）、模型汇总用量；`period_format` 为 Postgres `to_char` 格式
+pub fn usage_report(
+    conn: &mut PgPoolConn,
+    period_format: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    model: Option<&str>,
+) -> Result<Vec<UsageReportRow>, DieselError> {
+    diesel::sql_query(format!(
+        r#"{CALLS_CTE}
+        SELECT to_char(created_at AT TIME ZONE 'Asia/Shanghai', $1) AS period,
+               model_name,
+               COUNT(*) AS calls,
+               COUNT(*) FILTER (WHERE status = 'completed') AS completed,
+               COUNT(*) FILTER (WHERE status = 'followup') AS followups,
+               COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+               COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+               COALESCE(SUM(total_tokens), 0)::BIGINT AS total_tokens,
+               COALESCE(SUM(cost), 0) AS cost
+        FROM calls
+        WHERE created_at >= ($2::date::timestamp AT TIME ZONE 'Asia/Shanghai')
+          AND created_at < (($3::date + 1)::timestamp AT TIME ZONE 'Asia/Shanghai')
+          AND ($4::TEXT IS NULL OR model_name = $4)
+        GROUP BY 1, 2
+        ORDER BY 1, 2
+        "#
+    ))
+    .bind::<Text, _>(period_format)
+    .bind::<Date, _>(start)
+    .bind::<Date, _>(end)
+    .bind::<Nullable<Text>, _>(model)
+    .load(conn)
+}
@@ -0,0 +1,203 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::sql_types::{
+    BigInt, Bool, Date, Double, Integer, Nullable, Numeric, Text, Time, Timestamptz,
+};
+use diesel::upsert::excluded;
+use diesel::OptionalExtension;
+
+use crate::models::{AuctionSnapshot, NewAuctionSnapshot};
+use crate::schema::auction_snapshots::dsl as auction;
+
+pub type PgPoolConn = PooledConnection<ConnectionManager<PgConnection>>;
+
+/// 批量写入竞价快照，同一交易日 + 采样时刻 + 股票已存在时覆盖，返回影响的行数
+pub fn upsert_many(
+    conn: &mut PgPoolConn,
+    rows: &[NewAuctionSnapshot],
+) -> Result<usize, diesel::result::Error> {
+    let mut affected = 0;
+    // 全市场约 5000+ 行，分批避免单条语句参数过多
+    for chunk in rows.chunks(1000) {
+        affected += diesel::insert_into(auction::auction_snapshots)
+            .values(chunk)
+            .on_conflict((
+                auction::trade_date,
+                auction::sample_time,
+                auction::stock_code,
+            ))
+            .do_update()
+            .set((
+                auction::stock_name.eq(excluded(auction::stock_name)),
+                auction::matched_price.eq(excluded(auction::matched_price)),
+                auction::prev_close.eq(excluded(auction::prev_close)),
+                auction::change_pct.eq(excluded(auction::change_pct)),
+                auction::matched_volume.eq(excluded(auction::matched_volume)),
+                auction::matched_amount.eq(excluded(auction::matched_amount)),
+                auction::unmatched_volume.eq(excluded(auction::unmatched_volume)),
+                auction::universe.eq(excluded(auction::universe)),
+                auction::created_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+    }
+    Ok(affected)
+}
+
+pub fn find_by_id(
+    conn: &mut PgPoolConn,
+    item_id: i32,
+) -> Result<Option<AuctionSnapshot>, diesel::result::Error> {
+    auction::auction_snapshots
+        .filter(auction::id.eq(item_id))
+        .first::<AuctionSnapshot>(conn)
+        .optional()
+}
+
+/// 某交易日已采样的时刻（升序）
+pub fn list_sample_times(
+    conn: &mut PgPoolConn,
+    date: NaiveDate,
+) -> Result<Vec<NaiveTime>, diesel::result::Error> {
+    auction::auction_snapshots
+        .filter(auction::trade_date.eq(date))
+        .select(auction::sample_time)
+        .distinct()
+        .order(auction::sample_time.asc())
+        .load(conn)
+}
+
+/// 竞价快照查询条件（数值阈值与 `AuctionFilterParams` 对应）
+#[derive(Debug, Clone)]
+pub struct AuctionSnapshotFilter {
+    pub trade_date: NaiveDate,
+    pub sample_time: NaiveTime,
+    pub pct_min: f64,
+    pub pct_max: f64,
+    pub amount_min: f64,
+    pub volume_min: i64,
+    pub unmatched_min: Option<i64>,
+    /// 仅返回当天盘中被筛选入库（出现在 stock_snapshots）的股票
+    pub linked_only: bool,
+}
+
+/// 竞价快照 + 当日盘中快照关联信息
+#[derive(Debug, QueryableByName)]
+pub struct AuctionSnapshotLinked {
+    #[diesel(sql_type = Integer)]
+    pub id: i32,
+    #[diesel(sql_type = Date)]
+    pub trade_date: NaiveDate,
+    #[diesel(sql_type = Time)]
+    pub sample_time: NaiveTime,
+    #[diesel(sql_type = Text)]
+    pub stock_code: String,
+    #[diesel(sql_type = Text)]
+    pub stock_name: String,
+    #[diesel(sql_type = Numeric)]
+    pub matched_price: BigDecimal,
+    #[diesel(sql_type = Numeric)]
+    pub prev_close: BigDecimal,
+    #[diesel(sql_type = Numeric)]
+    pub change_pct: BigDecimal,
+    #[diesel(sql_type = BigInt)]
+    pub matched_volume: i64,
+    #[diesel(sql_type = Numeric)]
+    pub matched_amount: BigDecimal,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    pub unmatched_volume: Option<i64>,
+    #[diesel(sql_type = Text)]
+    pub universe: String,
+    /// 当日盘中快照条数
+    #[diesel(sql_type = BigInt)]
+    pub snapshot_count: i64,
+    /// 当日首次入库时间
+    #[diesel(sql_type = Nullable<Timestamptz>)]
+    pub first_snapshot_at: Option<DateTime<Utc>>,
+    /// 当日首次入库价格
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub first_snapshot_price: Option<BigDecimal>,
+    /// 当日首次入库所处交易时段
+    #[diesel(sql_type = Nullable<Text>)]
+    pub first_snapshot_session: Option<String>,
+}
+
+#[derive(Debug, QueryableByName)]
+struct CountRow {
+    #[diesel(sql_type = BigInt)]
+    total: i64,
+}
+
+const LINKED_FROM_WHERE: &str = r#"
+    FROM auction_snapshots a
+    LEFT JOIN LATERAL (
+        SELECT
+            COUNT(*) AS snapshot_count,
+            MIN(ss.created_at) AS first_snapshot_at,
+            (array_agg(ss.latest_price ORDER BY ss.created_at))[1] AS first_snapshot_price,
+            (array_agg(ss.session ORDER BY ss.created_at))[1] AS first_snapshot_session
+        FROM stock_snapshots ss
+        WHERE ss.stock_code = a.stock_code
+          AND ss.created_at >= (a.trade_date::timestamp AT TIME ZONE 'Asia/Shanghai')
+          AND ss.created_at < ((a.trade_date + 1)::timestamp AT TIME ZONE 'Asia/Shanghai')
+    ) s ON TRUE
+    WHERE a.trade_date = $1
+      AND a.sample_time = $2
+      AND a.change_pct BETWEEN $3 AND $4
+      AND a.matched_amount >= $5
+      AND a.matched_volume >= $6
+      AND ($7::bigint IS NULL OR a.unmatched_volume >= $7::bigint)
+      AND (NOT $8 OR s.snapshot_count > 0)
+"#;
+
+/// 分页查询竞价快照，并关联同一股票当日的盘中快照（stock_snapshots），按匹配金额倒序
+pub fn list_with_intraday_link(
+    conn: &mut PgPoolConn,
+    filter: &AuctionSnapshotFilter,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<AuctionSnapshotLinked>, i64), diesel::result::Error> {
+    let count_query = format!("SELECT COUNT(*) AS total {LINKED_FROM_WHERE}");
+    let total = diesel::sql_query(count_query)
+        .bind::<Date, _>(filter.trade_date)
+        .bind::<Time, _>(filter.sample_time)
+        .bind::<Double, _>(filter.pct_min)
+        .bind::<Double, _>(filter.pct_max)
+        .bind::<Double, _>(filter.amount_min)
+        .bind::<BigInt, _>(filter.volume_min)
+        .bind::<Nullable<BigInt>, _>(filter.unmatched_min)
+        .bind::<Bool, _>(filter.linked_only)
+        .get_result::<CountRow>(conn)?
+        .total;
+
+    let list_query = format!(
+        r#"
+        SELECT
+            a.id, a.trade_date, a.sample_time, a.stock_code, a.stock_name,
+            a.matched_price, a.prev_close, a.change_pct, a.matched_volume,
+            a.matched_amount, a.unmatched_volume, a.universe,
+            COALESCE(s.snapshot_count, 0) AS snapshot_count,
+            s.first_snapshot_at, s.first_snapshot_price, s.first_snapshot_session
+        {LINKED_FROM_WHERE}
+        ORDER BY a.matched_amount DESC, a.stock_code
+        LIMIT $9 OFFSET $10
+        "#
+    );
+    let offset = (page - 1).max(0) * page_size;
+    let items = diesel::sql_query(list_query)
+        .bind::<Date, _>(filter.trade_date)
+        .bind::<Time, _>(filter.sample_time)
+        .bind::<Double, _>(filter.pct_min)
+        .bind::<Double, _>(filter.pct_max)
+        .bind::<Double, _>(filter.amount_min)
+        .bind::<BigInt, _>(filter.volume_min)
+        .bind::<Nullable<BigInt>, _>(filter.unmatched_min)
+        .bind::<Bool, _>(filter.linked_only)
+        .bind::<BigInt, _>(page_size)
+        .bind::<BigInt, _>(offset)
+        .load::<AuctionSnapshotLinked>(conn)?;
+
+    Ok((items, total))
+}
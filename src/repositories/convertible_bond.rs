@@ -0,0 +1,181 @@
+//! 可转债主数据与每日行情
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use diesel::dsl::max;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+
+use crate::models::{
+    ConvertibleBond, ConvertibleBondDaily, NewConvertibleBond, NewConvertibleBondDaily,
+};
+use crate::schema::{convertible_bond_daily, convertible_bonds};
+
+/// 写入或更新主数据，为 None 的字段保留原值；返回处理的条数
+pub fn upsert_bonds(
+    conn: &mut PgConnection,
+    items: &[NewConvertibleBond],
+) -> Result<usize, DieselError> {
+    use convertible_bonds::dsl;
+
+    conn.transaction(|conn| {
+        let mut affected = 0;
+        for item in items {
+            affected += diesel::insert_into(dsl::convertible_bonds)
+                .values(item)
+                .on_conflict(dsl::bond_code)
+                .do_update()
+                .set(item)
+                .execute(conn)?;
+        }
+        Ok(affected)
+    })
+}
+
+/// 写入某日行情，同一债券同一交易日重复同步时覆盖
+pub fn upsert_daily(
+    conn: &mut PgConnection,
+    rows: &[NewConvertibleBondDaily],
+) -> Result<usize, DieselError> {
+    use convertible_bond_daily::dsl;
+
+    let mut affected = 0;
+    for chunk in rows.chunks(1000) {
+        affected += diesel::insert_into(dsl::convertible_bond_daily)
+            .values(chunk)
+            .on_conflict((dsl::bond_code, dsl::trade_date))
+            .do_update()
+            .set((
+                dsl::bond_price.eq(excluded(dsl::bond_price)),
+                dsl::stock_price.eq(excluded(dsl::stock_price)),
+                dsl::transfer_price.eq(excluded(dsl::transfer_price)),
+                dsl::transfer_value.eq(excluded(dsl::transfer_value)),
+                dsl::transfer_premium_ratio.eq(excluded(dsl::transfer_premium_ratio)),
+                dsl::resale_trig_price.eq(excluded(dsl::resale_trig_price)),
+                dsl::redeem_trig_price.eq(excluded(dsl::redeem_trig_price)),
+                dsl::pbv_ratio.eq(excluded(dsl::pbv_ratio)),
+                dsl::rating.eq(excluded(dsl::rating)),
+                dsl::remaining_scale.eq(excluded(dsl::remaining_scale)),
+            ))
+            .execute(conn)?;
+    }
+    Ok(affected)
+}
+
+/// 已查到最后交易日的债券
+pub fn list_last_trading_dates(
+    conn: &mut PgConnection,
+) -> Result<HashMap<String, NaiveDate>, DieselError> {
+    use convertible_bonds::dsl;
+
+    let rows: Vec<(String, Option<NaiveDate>)> = dsl::convertible_bonds
+        .filter(dsl::last_trading_date.is_not_null())
+        .select((dsl::bond_code, dsl::last_trading_date))
+        .load(conn)?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(code, date)| date.map(|d| (code, d)))
+        .collect())
+}
+
+pub fn find_bond(
+    conn: &mut PgConnection,
+    code: &str,
+) -> Result<Option<ConvertibleBond>, DieselError> {
+    use convertible_bonds::dsl;
+
+    dsl::convertible_bonds
+        .filter(dsl::bond_code.eq(code))
+        .select(ConvertibleBond::as_select())
+        .first(conn)
+        .optional()
+}
+
+/// 按代码/简称/正股关键字分页；`active_on` 有值时只保留该日尚未摘牌的债券
+pub fn paginate(
+    conn: &mut PgConnection,
+    keyword: Option<&str>,
+    active_on: Option<NaiveDate>,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<ConvertibleBond>, i64), DieselError> {
+    use convertible_bonds::dsl;
+
+    let build = || {
+        let mut query = dsl::convertible_bonds.into_boxed();
+        if let Some(kw) = keyword {
+            let pattern = format!("%{kw}%");
+            query = query.filter(
+                dsl::bond_code
+                    .like(pattern.clone())
+                    .or(dsl::bond_short_name.like(pattern.clone()))
+                    .or(dsl::stock_code.like(pattern.clone()))
+                    .or(dsl::stock_name.like(pattern)),
+            );
+        }
+        if let Some(date) = active_on {
+            query = query.filter(dsl::delist_date.is_null().or(dsl::delist_date.ge(date)));
+        }
+        query
+    };
+
+    let total: i64 = build().count().get_result(conn)?;
+    let items = build()
+        .order(dsl::bond_code.asc())
+        .limit(page_size)
+        .offset((page - 1) * page_size)
+        .select(ConvertibleBond::as_select())
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 已同步行情的最近交易日
+pub fn latest_trade_date(conn: &mut PgConnection) -> Result<Option<NaiveDate>, DieselError> {
+    use convertible_bond_daily::dsl;
+
+    dsl::convertible_bond_daily
+        .select(max(dsl::trade_date))
+        .first(conn)
+}
+
+/// 一组债券在某日的行情
+pub fn list_daily_on(
+    conn: &mut PgConnection,
+    codes: &[String],
+    trade_date: NaiveDate,
+) -> Result<Vec<ConvertibleBondDaily>, DieselError> {
+    use convertible_bond_daily::dsl;
+
+    dsl::convertible_bond_daily
+        .filter(dsl::trade_date.eq(trade_date))
+        .filter(dsl::bond_code.eq_any(codes))
+        .select(ConvertibleBondDaily::as_select())
+        .load(conn)
+}
+
+/// 单只债券在日期区间内的行情，按日期升序
+pub fn list_daily(
+    conn: &mut PgConnection,
+    code: &str,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> Result<Vec<ConvertibleBondDaily>, DieselError> {
+    use convertible_bond_daily::dsl;
+
+    let mut query = dsl::convertible_bond_daily
+        .filter(dsl::bond_code.eq(code))
+        .into_boxed();
+    if let Some(v) = start {
+        query = query.filter(dsl::trade_date.ge(v));
+    }
+    if let Some(v) = end {
+        query = query.filter(dsl::trade_date.le(v));
+    }
+    query
+        .order(dsl::trade_date.asc())
+        .select(ConvertibleBondDaily::as_select())
+        .load(conn)
+}
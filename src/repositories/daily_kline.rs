@@ -58,6 +58,21 @@ pub fn exists(
     .get_result(conn)
 }
 
+/// 查询指定股票在闭区间内已有K线的交易日期（升序）
+pub fn list_trade_dates(
+    conn: &mut PgPoolConn,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<NaiveDate>, diesel::result::Error> {
+    daily_klines
+        .select(trade_date)
+        .filter(stock_code.eq(code))
+        .filter(trade_date.between(start, end))
+        .order(trade_date.asc())
+        .load(conn)
+}
+
 /// 查询指定日期之前最近的交易日期（处理节假日）
 pub fn find_previous_trade_date(
     conn: &mut PgPoolConn,
@@ -70,3 +85,74 @@ pub fn find_previous_trade_date(
         .first::<NaiveDate>(conn)
         .optional()
 }
+
+/// 指定股票最早的K线日期（近似上市日）
+pub fn first_trade_date(
+    conn: &mut PgPoolConn,
+    code: &str,
+) -> Result<Option<NaiveDate>, diesel::result::Error> {
+    use diesel::dsl::min;
+
+    daily_klines
+        .select(min(trade_date))
+        .filter(stock_code.eq(code))
+        .first::<Option<NaiveDate>>(conn)
+}
+
+/// 查询指定股票在闭区间内的K线（按日期升序）
+pub fn list_range(
+    conn: &mut PgPoolConn,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<DailyKline>, diesel::result::Error> {
+    daily_klines
+        .filter(stock_code.eq(code))
+        .filter(trade_date.between(start, end))
+        .order(trade_date.asc())
+        .load(conn)
+}
+
+/// 在同一事务中删除指定股票闭区间内的K线并写入新数据，返回（删除数, 写入数）
+/// 区间内不在 `rows` 中的已有K线都会被删除，调用方需保证 `rows` 完整
+pub fn replace_range(
+    conn: &mut PgPoolConn,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    rows: &[NewDailyKline],
+) -> Result<(usize, usize), diesel::result::Error> {
+    conn.transaction(|conn| {
+        let deleted = diesel::delete(
+            daily_klines
+                .filter(stock_code.eq(code))
+                .filter(trade_date.between(start, end)),
+        )
+        .execute(conn)?;
+        let rows: Vec<&NewDailyKline> = rows
+            .iter()
+            .filter(|r| r.stock_code == code && r.trade_date >= start && r.trade_date <= end)
+            .collect();
+        let mut inserted = 0;
+        // 分批写入，避免超出单条语句的参数上限
+        for chunk in rows.chunks(1000) {
+            inserted += diesel::insert_into(daily_klines)
+                .values(chunk.iter().map(|r| (*r).clone()).collect::<Vec<_>>())
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+        Ok((deleted, inserted))
+    })
+}
+
+/// 指定日期有K线的全部股票代码
+pub fn list_stock_codes_on(
+    conn: &mut PgPoolConn,
+    date: NaiveDate,
+) -> Result<Vec<String>, diesel::result::Error> {
+    daily_klines
+        .select(stock_code)
+        .filter(trade_date.eq(date))
+        .order(stock_code.asc())
+        .load(conn)
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::result::Error as DieselError;
 
@@ -35,6 +36,58 @@ pub fn update(
         .get_result(conn)
 }
 
+/// 对执行中的记录请求取消，返回是否置位成功（记录不存在或已结束时为 false）
+pub fn request_cancel(conn: &mut PgConnection, history_id: i32) -> Result<bool, DieselError> {
+    let affected = diesel::update(
+        job_execution_history
+            .find(history_id)
+            .filter(status.eq("running")),
+    )
+    .set(cancel_requested.eq(true))
+    .execute(conn)?;
+    Ok(affected > 0)
+}
+
+/// 是否已请求取消
+pub fn is_cancel_requested(conn: &mut PgConnection, history_id: i32) -> Result<bool, DieselError> {
+    job_execution_history
+        .find(history_id)
+        .select(cancel_requested)
+        .first(conn)
+}
+
+/// `before` 之前开始、仍处于 running 的记录 ID（上次进程退出时未结束的执行候选）
+pub fn list_running_before(
+    conn: &mut PgConnection,
+    before: NaiveDateTime,
+) -> Result<Vec<i32>, DieselError> {
+    job_execution_history
+        .filter(status.eq("running"))
+        .filter(started_at.lt(before))
+        .select(id)
+        .load(conn)
+}
+
+/// 将指定的 running 记录标记为 aborted
+pub fn mark_aborted(
+    conn: &mut PgConnection,
+    ids: &[i32],
+    now: NaiveDateTime,
+    message: &str,
+) -> Result<usize, DieselError> {
+    diesel::update(
+        job_execution_history
+            .filter(id.eq_any(ids))
+            .filter(status.eq("running")),
+    )
+    .set((
+        status.eq("aborted"),
+        completed_at.eq(now),
+        error_message.eq(message),
+    ))
+    .execute(conn)
+}
+
 /// 根据任务名查询最新的执行记录
 pub fn find_latest_by_job_name(
     conn: &mut PgConnection,
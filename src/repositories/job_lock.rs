@@ -0,0 +1,125 @@
+//! 任务锁的租约读写。时间一律取数据库的 NOW()，避免多实例之间的时钟偏差
+
+use chrono::{DateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable, Timestamptz, Varchar};
+
+use crate::models::JobLock;
+use crate::schema::job_locks::dsl::*;
+
+/// 尝试获取锁：无人持有或租约已过期时写入本实例，返回是否获取成功
+pub fn try_acquire(
+    conn: &mut PgConnection,
+    name: &str,
+    holder_id: &str,
+    lease_secs: i32,
+) -> Result<bool, diesel::result::Error> {
+    let affected = diesel::sql_query(
+        "INSERT INTO job_locks (job_name, holder, execution_id, acquired_at, lease_until) \
+         VALUES ($1, $2, NULL, NOW(), NOW() + $3 * INTERVAL '1 second') \
+         ON CONFLICT (job_name) DO UPDATE SET \
+             holder = EXCLUDED.holder, \
+             execution_id = NULL, \
+             acquired_at = EXCLUDED.acquired_at, \
+             lease_until = EXCLUDED.lease_until \
+         WHERE job_locks.lease_until < NOW()",
+    )
+    .bind::<Varchar, _>(name)
+    .bind::<Varchar, _>(holder_id)
+    .bind::<Integer, _>(lease_secs)
+    .execute(conn)?;
+    Ok(affected == 1)
+}
+
+/// 认领一次定时触发：(任务, 计划触发时刻) 尚无记录时写入本实例，返回是否认领成功。
+/// 顺带清理该任务 `retain_days` 天前的认领记录
+pub fn try_claim_fire(
+    conn: &mut PgConnection,
+    name: &str,
+    scheduled_at: DateTime<Utc>,
+    holder_id: &str,
+    retain_days: i32,
+) -> Result<bool, diesel::result::Error> {
+    let affected = diesel::sql_query(
+        "INSERT INTO job_fire_claims (job_name, scheduled_at, holder, claimed_at) \
+         VALUES ($1, $2, $3, NOW()) \
+         ON CONFLICT (job_name, scheduled_at) DO NOTHING",
+    )
+    .bind::<Varchar, _>(name)
+    .bind::<Timestamptz, _>(scheduled_at)
+    .bind::<Varchar, _>(holder_id)
+    .execute(conn)?;
+    if affected == 1 {
+        diesel::sql_query(
+            "DELETE FROM job_fire_claims \
+             WHERE job_name = $1 AND scheduled_at < NOW() - $2 * INTERVAL '1 day'",
+        )
+        .bind::<Varchar, _>(name)
+        .bind::<Integer, _>(retain_days)
+        .execute(conn)?;
+    }
+    Ok(affected == 1)
+}
+
+/// 续期，返回 false 表示锁已不属于本实例
+pub fn renew(
+    conn: &mut PgConnection,
+    name: &str,
+    holder_id: &str,
+    lease_secs: i32,
+) -> Result<bool, diesel::result::Error> {
+    let affected = diesel::sql_query(
+        "UPDATE job_locks SET lease_until = NOW() + $3 * INTERVAL '1 second' \
+         WHERE job_name = $1 AND holder = $2",
+    )
+    .bind::<Varchar, _>(name)
+    .bind::<Varchar, _>(holder_id)
+    .bind::<Integer, _>(lease_secs)
+    .execute(conn)?;
+    Ok(affected == 1)
+}
+
+pub fn set_execution_id(
+    conn: &mut PgConnection,
+    name: &str,
+    holder_id: &str,
+    id: Option<i32>,
+) -> Result<usize, diesel::result::Error> {
+    diesel::sql_query("UPDATE job_locks SET execution_id = $3 WHERE job_name = $1 AND holder = $2")
+        .bind::<Varchar, _>(name)
+        .bind::<Varchar, _>(holder_id)
+        .bind::<Nullable<Integer>, _>(id)
+        .execute(conn)
+}
+
+pub fn release(
+    conn: &mut PgConnection,
+    name: &str,
+    holder_id: &str,
+) -> Result<usize, diesel::result::Error> {
+    diesel::delete(
+        job_locks
+            .filter(job_name.eq(name))
+            .filter(holder.eq(holder_id)),
+    )
+    .execute(conn)
+}
+
+/// 租约未过期的锁
+pub fn list_active(conn: &mut PgConnection) -> Result<Vec<JobLock>, diesel::result::Error> {
+    job_locks
+        .filter(lease_until.ge(diesel::dsl::now))
+        .load::<JobLock>(conn)
+}
+
+pub fn find_active(
+    conn: &mut PgConnection,
+    name: &str,
+) -> Result<Option<JobLock>, diesel::result::Error> {
+    job_locks
+        .filter(job_name.eq(name))
+        .filter(lease_until.ge(diesel::dsl::now))
+        .first::<JobLock>(conn)
+        .optional()
+}
@@ -0,0 +1,57 @@
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{JobPipelineRun, NewJobPipelineRun, UpdateJobPipelineRun};
+use crate::schema::job_pipeline_runs::dsl::*;
+
+pub fn create(
+    conn: &mut PgConnection,
+    data: &NewJobPipelineRun,
+) -> Result<JobPipelineRun, DieselError> {
+    diesel::insert_into(job_pipeline_runs)
+        .values(data)
+        .get_result(conn)
+}
+
+pub fn update(
+    conn: &mut PgConnection,
+    run_id: i32,
+    data: &UpdateJobPipelineRun,
+) -> Result<JobPipelineRun, DieselError> {
+    diesel::update(job_pipeline_runs.find(run_id))
+        .set(data)
+        .get_result(conn)
+}
+
+pub fn find_by_id(
+    conn: &mut PgConnection,
+    run_id: i32,
+) -> Result<Option<JobPipelineRun>, DieselError> {
+    job_pipeline_runs.find(run_id).first(conn).optional()
+}
+
+/// 分页查询流水线执行记录
+pub fn paginate(
+    conn: &mut PgConnection,
+    root_job_filter: Option<String>,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<JobPipelineRun>, i64), DieselError> {
+    let offset = (page - 1) * page_size;
+
+    let mut count_query = job_pipeline_runs.into_boxed();
+    let mut items_query = job_pipeline_runs.into_boxed();
+    if let Some(ref root) = root_job_filter {
+        count_query = count_query.filter(root_job.eq(root));
+        items_query = items_query.filter(root_job.eq(root));
+    }
+
+    let total = count_query.count().get_result(conn)?;
+    let items = items_query
+        .order(started_at.desc())
+        .limit(page_size)
+        .offset(offset)
+        .load(conn)?;
+
+    Ok((items, total))
+}
@@ -0,0 +1,45 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::OptionalExtension;
+
+use crate::models::{JobSchedule, NewJobSchedule, UpdateJobSchedule};
+use crate::schema::job_schedules::dsl::*;
+
+pub type PgPoolConn = PooledConnection<ConnectionManager<PgConnection>>;
+
+pub fn list_all(conn: &mut PgPoolConn) -> Result<Vec<JobSchedule>, diesel::result::Error> {
+    job_schedules.order(id.asc()).load::<JobSchedule>(conn)
+}
+
+pub fn find_by_job_name(
+    conn: &mut PgPoolConn,
+    name: &str,
+) -> Result<Option<JobSchedule>, diesel::result::Error> {
+    job_schedules
+        .filter(job_name.eq(name))
+        .first::<JobSchedule>(conn)
+        .optional()
+}
+
+/// 插入尚不存在的任务配置（已存在的保持不变），返回新插入的行数
+pub fn insert_missing(
+    conn: &mut PgPoolConn,
+    rows: &[NewJobSchedule],
+) -> Result<usize, diesel::result::Error> {
+    diesel::insert_into(job_schedules)
+        .values(rows)
+        .on_conflict(job_name)
+        .do_nothing()
+        .execute(conn)
+}
+
+pub fn update_by_job_name(
+    conn: &mut PgPoolConn,
+    name: &str,
+    data: &UpdateJobSchedule,
+) -> Result<JobSchedule, diesel::result::Error> {
+    diesel::update(job_schedules.filter(job_name.eq(name)))
+        .set(data)
+        .get_result::<JobSchedule>(conn)
+}
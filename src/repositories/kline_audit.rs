@@ -0,0 +1,170 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+
+use crate::models::{KlineAuditFinding, NewKlineAuditFinding};
+use crate::schema::kline_audit_findings;
+
+/// 问题列表的筛选条件
+#[derive(Debug, Default)]
+pub struct FindingFilter {
+    pub stock_code: Option<String>,
+    pub check_type: Option<String>,
+    pub severity: Option<String>,
+    pub status: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// 同步一只股票在 `[start, end]` 内的审计结果：写入或刷新本次发现的问题（已忽略的保持忽略），
+/// 区间内本次未再发现的 open 问题标记为 resolved。`now` 须与本次问题的 last_detected_at 一致，返回 resolved 的数量
+pub fn sync_stock(
+    conn: &mut PgConnection,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    findings: &[NewKlineAuditFinding],
+    now: NaiveDateTime,
+) -> Result<usize, DieselError> {
+    use kline_audit_findings::dsl;
+
+    conn.transaction(|conn| {
+        if !findings.is_empty() {
+            diesel::insert_into(dsl::kline_audit_findings)
+                .values(findings)
+                .on_conflict((dsl::stock_code, dsl::check_type, dsl::trade_date))
+                .do_update()
+                .set((
+                    dsl::severity.eq(excluded(dsl::severity)),
+                    dsl::end_date.eq(excluded(dsl::end_date)),
+                    dsl::detail.eq(excluded(dsl::detail)),
+                    dsl::context.eq(excluded(dsl::context)),
+                    dsl::last_detected_at.eq(excluded(dsl::last_detected_at)),
+                ))
+                .execute(conn)?;
+            // 之前已解决但再次出现的问题重新打开
+            diesel::update(
+                dsl::kline_audit_findings
+                    .filter(dsl::stock_code.eq(code))
+                    .filter(dsl::status.eq("resolved"))
+                    .filter(dsl::last_detected_at.eq(now)),
+            )
+            .set((
+                dsl::status.eq("open"),
+                dsl::resolved_at.eq(None::<NaiveDateTime>),
+            ))
+            .execute(conn)?;
+        }
+        diesel::update(
+            dsl::kline_audit_findings
+                .filter(dsl::stock_code.eq(code))
+                .filter(dsl::status.eq("open"))
+                .filter(dsl::trade_date.between(start, end))
+                .filter(dsl::last_detected_at.lt(now)),
+        )
+        .set((dsl::status.eq("resolved"), dsl::resolved_at.eq(Some(now))))
+        .execute(conn)
+    })
+}
+
+pub fn find_by_id(
+    conn: &mut PgConnection,
+    id: i32,
+) -> Result<Option<KlineAuditFinding>, DieselError> {
+    kline_audit_findings::table.find(id).first(conn).optional()
+}
+
+pub fn update_status(
+    conn: &mut PgConnection,
+    id: i32,
+    status: &str,
+    resolved_at: Option<NaiveDateTime>,
+) -> Result<KlineAuditFinding, DieselError> {
+    use kline_audit_findings::dsl;
+
+    diesel::update(dsl::kline_audit_findings.find(id))
+        .set((dsl::status.eq(status), dsl::resolved_at.eq(resolved_at)))
+        .get_result(conn)
+}
+
+/// 分页查询问题（按问题日期倒序）
+pub fn paginate(
+    conn: &mut PgConnection,
+    filter: &FindingFilter,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<KlineAuditFinding>, i64), DieselError> {
+    use kline_audit_findings::dsl;
+
+    let build = || {
+        let mut query = dsl::kline_audit_findings.into_boxed();
+        if let Some(code) = &filter.stock_code {
+            query = query.filter(dsl::stock_code.eq(code.clone()));
+        }
+        if let Some(check) = &filter.check_type {
+            query = query.filter(dsl::check_type.eq(check.clone()));
+        }
+        if let Some(severity) = &filter.severity {
+            query = query.filter(dsl::severity.eq(severity.clone()));
+        }
+        if let Some(status) = &filter.status {
+            query = query.filter(dsl::status.eq(status.clone()));
+        }
+        if let Some(start) = filter.start_date {
+            query = query.filter(dsl::end_date.ge(start));
+        }
+        if let Some(end) = filter.end_date {
+            query = query.filter(dsl::trade_date.le(end));
+        }
+        query
+    };
+
+    let offset = (page - 1) * page_size;
+    let total = build().count().get_result(conn)?;
+    let items = build()
+        .order((dsl::trade_date.desc(), dsl::stock_code.asc()))
+        .limit(page_size)
+        .offset(offset)
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 一只股票全部未处理（open）的问题
+pub fn list_open_by_stock(
+    conn: &mut PgConnection,
+    code: &str,
+) -> Result<Vec<KlineAuditFinding>, DieselError> {
+    use kline_audit_findings::dsl;
+
+    dsl::kline_audit_findings
+        .filter(dsl::stock_code.eq(code))
+        .filter(dsl::status.eq("open"))
+        .order(dsl::trade_date.asc())
+        .load(conn)
+}
+
+/// 按检查项、严重程度与状态统计问题数
+pub fn count_by_type(
+    conn: &mut PgConnection,
+) -> Result<Vec<(String, String, String, i64)>, DieselError> {
+    use diesel::dsl::count_star;
+    use kline_audit_findings::dsl;
+
+    dsl::kline_audit_findings
+        .group_by((dsl::check_type, dsl::severity, dsl::status))
+        .select((dsl::check_type, dsl::severity, dsl::status, count_star()))
+        .order(dsl::check_type.asc())
+        .load(conn)
+}
+
+/// 有未处理问题的股票数
+pub fn count_open_stocks(conn: &mut PgConnection) -> Result<i64, DieselError> {
+    use diesel::dsl::count;
+    use kline_audit_findings::dsl;
+
+    dsl::kline_audit_findings
+        .filter(dsl::status.eq("open"))
+        .select(count(dsl::stock_code).aggregate_distinct())
+        .first(conn)
+}
@@ -0,0 +1,95 @@
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{
+    KlineBackfillItem, KlineBackfillRun, NewKlineBackfillItem, NewKlineBackfillRun,
+    UpdateKlineBackfillItem, UpdateKlineBackfillRun,
+};
+use crate::schema::{kline_backfill_items, kline_backfill_runs};
+
+/// 创建回补任务及其逐股进度记录
+pub fn create_run(
+    conn: &mut PgConnection,
+    data: &NewKlineBackfillRun,
+    stock_codes: &[String],
+) -> Result<KlineBackfillRun, DieselError> {
+    conn.transaction(|conn| {
+        let run: KlineBackfillRun = diesel::insert_into(kline_backfill_runs::table)
+            .values(data)
+            .get_result(conn)?;
+        let items: Vec<NewKlineBackfillItem> = stock_codes
+            .iter()
+            .map(|code| NewKlineBackfillItem {
+                run_id: run.id,
+                stock_code: code.clone(),
+            })
+            .collect();
+        diesel::insert_into(kline_backfill_items::table)
+            .values(&items)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        Ok(run)
+    })
+}
+
+pub fn find_run(
+    conn: &mut PgConnection,
+    run_id: i32,
+) -> Result<Option<KlineBackfillRun>, DieselError> {
+    kline_backfill_runs::table
+        .find(run_id)
+        .first(conn)
+        .optional()
+}
+
+pub fn update_run(
+    conn: &mut PgConnection,
+    run_id: i32,
+    data: &UpdateKlineBackfillRun,
+) -> Result<KlineBackfillRun, DieselError> {
+    diesel::update(kline_backfill_runs::table.find(run_id))
+        .set(data)
+        .get_result(conn)
+}
+
+/// 分页查询回补任务（按创建时间倒序）
+pub fn paginate_runs(
+    conn: &mut PgConnection,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<KlineBackfillRun>, i64), DieselError> {
+    let offset = (page - 1) * page_size;
+    let total = kline_backfill_runs::table.count().get_result(conn)?;
+    let items = kline_backfill_runs::table
+        .order(kline_backfill_runs::created_at.desc())
+        .limit(page_size)
+        .offset(offset)
+        .load(conn)?;
+    Ok((items, total))
+}
+
+/// 回补任务的全部逐股记录（按股票代码排序）
+pub fn list_items(
+    conn: &mut PgConnection,
+    run_id: i32,
+) -> Result<Vec<KlineBackfillItem>, DieselError> {
+    kline_backfill_items::table
+        .filter(kline_backfill_items::run_id.eq(run_id))
+        .order(kline_backfill_items::stock_code.asc())
+        .load(conn)
+}
+
+pub fn update_item(
+    conn: &mut PgConnection,
+    run_id: i32,
+    code: &str,
+    data: &UpdateKlineBackfillItem,
+) -> Result<usize, DieselError> {
+    diesel::update(
+        kline_backfill_items::table
+            .filter(kline_backfill_items::run_id.eq(run_id))
+            .filter(kline_backfill_items::stock_code.eq(code)),
+    )
+    .set(data)
+    .execute(conn)
+}
@@ -0,0 +1,50 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{LlmModelPrice, NewLlmModelPrice};
+use crate::schema::llm_model_prices;
+
+pub fn list(conn: &mut PgConnection) -> Result<Vec<LlmModelPrice>, DieselError> {
+    use llm_model_prices::dsl;
+
+    dsl::llm_model_prices
+        .order(dsl::model_name.asc())
+        .select(LlmModelPrice::as_select())
+        .load(conn)
+}
+
+/// 按模型名查询单价（用于精确匹配与 `provider/*` 兜底）
+pub fn find_by_names(
+    conn: &mut PgConnection,
+    names: &[String],
+) -> Result<Vec<LlmModelPrice>, DieselError> {
+    use llm_model_prices::dsl;
+
+    dsl::llm_model_prices
+        .filter(dsl::model_name.eq_any(names))
+        .select(LlmModelPrice::as_select())
+        .load(conn)
+}
+
+/// 新增或按 model_name 覆盖单价
+pub fn upsert(
+    conn: &mut PgConnection,
+    item: &NewLlmModelPrice,
+) -> Result<LlmModelPrice, DieselError> {
+    use llm_model_prices::dsl;
+
+    diesel::insert_into(dsl::llm_model_prices)
+        .values(item)
+        .on_conflict(dsl::model_name)
+        .do_update()
+        .set(item)
+        .returning(LlmModelPrice::as_returning())
+        .get_result(conn)
+}
+
+pub fn delete_by_id(conn: &mut PgConnection, item_id: i32) -> Result<usize, DieselError> {
+    use llm_model_prices::dsl;
+
+    diesel::delete(dsl::llm_model_prices.find(item_id)).execute(conn)
+}
@@ -1,12 +1,27 @@
+pub mod ai_analysis_message;
+pub mod ai_batch;
+pub mod ai_market_report;
+pub mod ai_prediction_evaluation;
 pub mod ai_trend_analysis;
+pub mod ai_usage;
+pub mod auction_snapshot;
 pub mod he_luo_lookup;
 pub mod basic_data_analysis;
+pub mod convertible_bond;
 pub mod daily_kline;
 pub mod dynamic_backtrack;
 pub mod export_button_config;
 pub mod job_execution_history;
+pub mod job_lock;
+pub mod job_pipeline_run;
+pub mod job_schedule;
+pub mod kline_audit;
+pub mod kline_backfill;
+pub mod llm_model_price;
 pub mod profit_analysis;
+pub mod prompt_template;
 pub mod stock_appearance_query;
+pub mod stock_name_history;
 pub mod stock_plate;
 pub mod stock_plate_stock_table;
 pub mod stock_price_compare;
@@ -16,5 +31,6 @@ pub mod stock_snapshot;
 pub mod stock_table;
 pub mod stock_track_query;
 pub mod stock_trade_date_query;
+pub mod stock_trading_calendar;
 pub mod stock_watchlist;
 pub mod stock_watchlist_query;
@@ -0,0 +1,203 @@
+use chrono::NaiveDateTime;
+use diesel::dsl::max;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel::sql_types::Text;
+
+use crate::models::{NewPromptTemplate, PromptTemplate, UpdatePromptTemplate};
+use crate::repositories::{ai_analysis_message, ai_market_report};
+use crate::schema::{ai_trend_analysis, prompt_templates};
+
+pub fn find_by_id(
+    conn: &mut PgConnection,
+    item_id: i32,
+) -> Result<Option<PromptTemplate>, DieselError> {
+    use prompt_templates::dsl;
+
+    dsl::prompt_templates
+        .find(item_id)
+        .select(PromptTemplate::as_select())
+        .first(conn)
+        .optional()
+}
+
+pub fn find_by_version(
+    conn: &mut PgConnection,
+    key: &str,
+    version: i32,
+) -> Result<Option<PromptTemplate>, DieselError> {
+    use prompt_templates::dsl;
+
+    dsl::prompt_templates
+        .filter(dsl::template_key.eq(key))
+        .filter(dsl::version.eq(version))
+        .select(PromptTemplate::as_select())
+        .first(conn)
+        .optional()
+}
+
+pub fn find_active(
+    conn: &mut PgConnection,
+    key: &str,
+) -> Result<Option<PromptTemplate>, DieselError> {
+    use prompt_templates::dsl;
+
+    dsl::prompt_templates
+        .filter(dsl::template_key.eq(key))
+        .filter(dsl::is_active.eq(true))
+        .select(PromptTemplate::as_select())
+        .first(conn)
+        .optional()
+}
+
+/// 按用途、版本倒序列出模板
+pub fn list(
+    conn: &mut PgConnection,
+    key: Option<&str>,
+) -> Result<Vec<PromptTemplate>, DieselError> {
+    use prompt_templates::dsl;
+
+    let mut query = dsl::prompt_templates.into_boxed();
+    if let Some(key) = key {
+        query = query.filter(dsl::template_key.eq(key));
+    }
+    query
+        .order((dsl::template_key.asc(), dsl::version.desc()))
+        .select(PromptTemplate::as_select())
+        .load(conn)
+}
+
+fn deactivate_others(
+    conn: &mut PgConnection,
+    key: &str,
+    keep_id: i32,
+    now: NaiveDateTime,
+) -> Result<usize, DieselError> {
+    use prompt_templates::dsl;
+
+    diesel::update(
+        dsl::prompt_templates
+            .filter(dsl::template_key.eq(key))
+            .filter(dsl::id.ne(keep_id))
+            .filter(dsl::is_active.eq(true)),
+    )
+    .set((dsl::is_active.eq(false), dsl::updated_at.eq(now)))
+    .execute(conn)
+}
+
+/// 事务级咨询锁：同一用途的建版本操作串行执行，避免并发时读到相同的最大版本号
+fn lock_key(conn: &mut PgConnection, key: &str) -> Result<(), DieselError> {
+    diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext('prompt_templates:' || $1))")
+        .bind::<Text, _>(key)
+        .execute(conn)?;
+    Ok(())
+}
+
+fn insert_next_version(
+    conn: &mut PgConnection,
+    item: &NewPromptTemplate,
+    now: NaiveDateTime,
+) -> Result<PromptTemplate, DieselError> {
+    use prompt_templates::dsl;
+
+    let latest: Option<i32> = dsl::prompt_templates
+        .filter(dsl::template_key.eq(&item.template_key))
+        .select(max(dsl::version))
+        .first(conn)?;
+    let row = NewPromptTemplate {
+        version: latest.unwrap_or(0) + 1,
+        ..item.clone()
+    };
+    if row.is_active {
+        // 先停用旧版本，避免违反「每个用途至多一个启用版本」的唯一索引
+        deactivate_others(conn, &row.template_key, 0, now)?;
+    }
+    diesel::insert_into(dsl::prompt_templates)
+        .values(&row)
+        .returning(PromptTemplate::as_returning())
+        .get_result(conn)
+}
+
+/// 新建一个版本（版本号为该用途已有最大版本 + 1，忽略传入的 version）；
+/// `is_active` 为 true 时同时停用同用途的其他版本
+pub fn create_version(
+    conn: &mut PgConnection,
+    item: &NewPromptTemplate,
+    now: NaiveDateTime,
+) -> Result<PromptTemplate, DieselError> {
+    conn.transaction(|conn| {
+        lock_key(conn, &item.template_key)?;
+        insert_next_version(conn, item, now)
+    })
+}
+
+/// 该用途还没有任何版本时写入 `item` 作为版本 1，已有版本时返回 None
+pub fn create_first_version(
+    conn: &mut PgConnection,
+    item: &NewPromptTemplate,
+    now: NaiveDateTime,
+) -> Result<Option<PromptTemplate>, DieselError> {
+    use prompt_templates::dsl;
+
+    conn.transaction(|conn| {
+        lock_key(conn, &item.template_key)?;
+        let existing: i64 = dsl::prompt_templates
+            .filter(dsl::template_key.eq(&item.template_key))
+            .count()
+            .get_result(conn)?;
+        if existing > 0 {
+            return Ok(None);
+        }
+        insert_next_version(conn, item, now).map(Some)
+    })
+}
+
+/// 启用指定版本并停用同用途的其他版本
+pub fn activate(
+    conn: &mut PgConnection,
+    template: &PromptTemplate,
+    now: NaiveDateTime,
+) -> Result<PromptTemplate, DieselError> {
+    use prompt_templates::dsl;
+
+    conn.transaction(|conn| {
+        deactivate_others(conn, &template.template_key, template.id, now)?;
+        diesel::update(dsl::prompt_templates.find(template.id))
+            .set((dsl::is_active.eq(true), dsl::updated_at.eq(now)))
+            .returning(PromptTemplate::as_returning())
+            .get_result(conn)
+    })
+}
+
+pub fn update_by_id(
+    conn: &mut PgConnection,
+    item_id: i32,
+    update_data: &UpdatePromptTemplate,
+) -> Result<PromptTemplate, DieselError> {
+    use prompt_templates::dsl;
+
+    diesel::update(dsl::prompt_templates.find(item_id))
+        .set(update_data)
+        .returning(PromptTemplate::as_returning())
+        .get_result(conn)
+}
+
+pub fn delete_by_id(conn: &mut PgConnection, item_id: i32) -> Result<usize, DieselError> {
+    use prompt_templates::dsl;
+
+    diesel::delete(dsl::prompt_templates.find(item_id)).execute(conn)
+}
+
+/// 使用该模板的分析记录、追问回答与复盘数
+pub fn count_usage(conn: &mut PgConnection, item_id: i32) -> Result<i64, DieselError> {
+    use ai_trend_analysis::dsl;
+
+    let analyses: i64 = dsl::ai_trend_analysis
+        .filter(dsl::prompt_template_id.eq(item_id))
+        .count()
+        .get_result(conn)?;
+    let messages = ai_analysis_message::count_template_usage(conn, item_id)?;
+    let reports = ai_market_report::count_template_usage(conn, item_id)?;
+    Ok(analyses + messages + reports)
+}
@@ -0,0 +1,110 @@
+use chrono::{Days, NaiveDate};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+
+use crate::models::{NewStockNameHistory, StockNameHistory};
+use crate::schema::stock_name_history;
+
+pub fn count(conn: &mut PgConnection) -> Result<i64, DieselError> {
+    use stock_name_history::dsl;
+
+    dsl::stock_name_history.count().get_result(conn)
+}
+
+/// 批量写入（回填用），同一代码同一生效日已存在时跳过
+pub fn insert_batch(
+    conn: &mut PgConnection,
+    rows: &[NewStockNameHistory],
+) -> Result<usize, DieselError> {
+    use stock_name_history::dsl;
+
+    let mut inserted = 0;
+    for chunk in rows.chunks(1000) {
+        inserted += diesel::insert_into(dsl::stock_name_history)
+            .values(chunk)
+            .on_conflict((dsl::stock_code, dsl::valid_from))
+            .do_nothing()
+            .execute(conn)?;
+    }
+    Ok(inserted)
+}
+
+pub fn insert(
+    conn: &mut PgConnection,
+    row: &NewStockNameHistory,
+) -> Result<StockNameHistory, DieselError> {
+    use stock_name_history::dsl;
+
+    diesel::insert_into(dsl::stock_name_history)
+        .values(row)
+        .returning(StockNameHistory::as_returning())
+        .get_result(conn)
+}
+
+/// 全部股票当前有效的名称
+pub fn list_open(conn: &mut PgConnection) -> Result<Vec<StockNameHistory>, DieselError> {
+    use stock_name_history::dsl;
+
+    dsl::stock_name_history
+        .filter(dsl::valid_to.is_null())
+        .select(StockNameHistory::as_select())
+        .load(conn)
+}
+
+/// 一只股票的名称时间线，按生效日升序
+pub fn list_by_code(
+    conn: &mut PgConnection,
+    code: &str,
+) -> Result<Vec<StockNameHistory>, DieselError> {
+    use stock_name_history::dsl;
+
+    dsl::stock_name_history
+        .filter(dsl::stock_code.eq(code))
+        .order(dsl::valid_from.asc())
+        .select(StockNameHistory::as_select())
+        .load(conn)
+}
+
+/// 在 `date` 当天使用 ST 类简称的股票
+pub fn list_st_on(
+    conn: &mut PgConnection,
+    date: NaiveDate,
+) -> Result<Vec<StockNameHistory>, DieselError> {
+    use stock_name_history::dsl;
+
+    dsl::stock_name_history
+        .filter(dsl::is_st.eq(true))
+        .filter(dsl::valid_from.le(date))
+        .filter(dsl::valid_to.is_null().or(dsl::valid_to.ge(date)))
+        .order(dsl::stock_code.asc())
+        .select(StockNameHistory::as_select())
+        .load(conn)
+}
+
+/// 记录改名：关闭当前记录（最后有效日为新名称生效日前一天）并写入新记录；
+/// 当前记录与新名称同一天生效时直接改写当前记录
+pub fn rename(
+    conn: &mut PgConnection,
+    open: &StockNameHistory,
+    row: &NewStockNameHistory,
+) -> Result<StockNameHistory, DieselError> {
+    use stock_name_history::dsl;
+
+    conn.transaction(|conn| {
+        if open.valid_from >= row.valid_from {
+            return diesel::update(dsl::stock_name_history.find(open.id))
+                .set((
+                    dsl::stock_name.eq(&row.stock_name),
+                    dsl::is_st.eq(row.is_st),
+                    dsl::source.eq(&row.source),
+                ))
+                .returning(StockNameHistory::as_returning())
+                .get_result(conn);
+        }
+        let valid_to = row.valid_from.checked_sub_days(Days::new(1));
+        diesel::update(dsl::stock_name_history.find(open.id))
+            .set(dsl::valid_to.eq(valid_to))
+            .execute(conn)?;
+        insert(conn, row)
+    })
+}
@@ -48,6 +48,22 @@ pub fn exists_by_ids(
     Ok(existing.is_some())
 }
 
+/// 按板块代码查询板块内全部股票代码
+pub fn list_stock_codes_by_plate_code(
+    conn: &mut PgPoolConn,
+    code: &str,
+) -> Result<Vec<String>, diesel::result::Error> {
+    use crate::schema::{stock_plate, stock_table};
+
+    stock_plate_stock_table
+        .inner_join(stock_plate::table)
+        .inner_join(stock_table::table)
+        .filter(stock_plate::plate_code.eq(code))
+        .select(stock_table::stock_code)
+        .order(stock_table::stock_code.asc())
+        .load(conn)
+}
+
 #[derive(Debug, QueryableByName)]
 pub struct StockPlateStockQueryResult {
     #[diesel(sql_type = Int4)]
@@ -1,14 +1,16 @@
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Asia::Shanghai;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, PooledConnection};
-use diesel::sql_types::{BigInt, Date as SqlDate, Jsonb, Numeric, Text};
+use diesel::sql_types::{BigInt, Date as SqlDate, Jsonb, Nullable, Numeric, Text};
 use serde_json::Value;
 
 use crate::models::{NewStockSnapshot, StockSnapshot};
 use crate::schema::stock_snapshots::dsl::{
-    created_at, id, request_id, stock_code as stock_code_col, stock_snapshots,
+    created_at, id, request_id, session as session_col, stock_code as stock_code_col,
+    stock_snapshots,
 };
 use crate::utils::stock_name_filter;
 
@@ -48,6 +50,27 @@ pub fn find_by_request_id(
         .load::<StockSnapshot>(conn)
 }
 
+/// 获取某只股票在某交易日（Asia/Shanghai）的全部快照，按时间升序
+pub fn find_by_code_on_date(
+    conn: &mut PgPoolConn,
+    code: &str,
+    date: NaiveDate,
+) -> Result<Vec<StockSnapshot>, diesel::result::Error> {
+    let day_start = Shanghai
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default();
+    let day_end = day_start + chrono::Duration::days(1);
+
+    stock_snapshots
+        .filter(stock_code_col.eq(code))
+        .filter(created_at.ge(day_start))
+        .filter(created_at.lt(day_end))
+        .order(created_at.asc())
+        .load::<StockSnapshot>(conn)
+}
+
 /// 获取昨日（UTC+8 时区）创建的快照，根据 request_ids 过滤
 #[allow(dead_code)]
 pub fn find_yesterday_snapshots(
@@ -83,9 +106,10 @@ pub fn find_yesterday_snapshots(
         .load::<StockSnapshot>(conn)
 }
 
-/// 查询当天（UTC+8 时区）创建的所有不重复股票代码
+/// 查询当天（UTC+8 时区）创建的所有不重复股票代码，`session` 非空时只取该交易时段
 pub fn get_distinct_codes_today(
     conn: &mut PgPoolConn,
+    session: Option<&str>,
 ) -> Result<Vec<String>, diesel::result::Error> {
     // 使用 UTC+8 时区（东八区，北京时间）
     let utc_plus_8 = FixedOffset::east_opt(8 * 3600).unwrap();
@@ -107,12 +131,16 @@ pub fn get_distinct_codes_today(
         Utc,
     );
 
-    stock_snapshots
+    let mut query = stock_snapshots
         .select(stock_code_col)
         .filter(created_at.ge(today_start_utc))
         .filter(created_at.lt(tomorrow_start_utc))
         .distinct()
-        .load::<String>(conn)
+        .into_boxed();
+    if let Some(s) = session {
+        query = query.filter(session_col.eq(s));
+    }
+    query.load::<String>(conn)
 }
 
 #[derive(Debug, QueryableByName)]
@@ -132,17 +160,19 @@ pub struct DailyStockCount {
     pub stock_count: i64,
 }
 
-/// 统计日期范围内每天抓取到的去重股票支数
+/// 统计日期范围内每天抓取到的去重股票支数，`session` 非空时只统计该交易时段
 pub fn count_distinct_codes_by_date_range(
     conn: &mut PgPoolConn,
     start: NaiveDate,
     end: NaiveDate,
+    session: Option<&str>,
 ) -> Result<Vec<DailyStockCount>, diesel::result::Error> {
     let query = r#"
         SELECT (created_at AT TIME ZONE 'Asia/Shanghai')::date AS stat_date,
                COUNT(DISTINCT stock_code) AS stock_count
         FROM stock_snapshots
         WHERE (created_at AT TIME ZONE 'Asia/Shanghai')::date BETWEEN $1::date AND $2::date
+          AND ($3::text IS NULL OR session = $3::text)
         GROUP BY stat_date
         ORDER BY stat_date;
     "#;
@@ -150,9 +180,43 @@ pub fn count_distinct_codes_by_date_range(
     diesel::sql_query(query)
         .bind::<SqlDate, _>(start)
         .bind::<SqlDate, _>(end)
+        .bind::<Nullable<Text>, _>(session)
         .load::<DailyStockCount>(conn)
 }
 
+/// 某天某交易时段的去重股票支数
+#[derive(Debug, QueryableByName)]
+pub struct SessionStockCount {
+    #[diesel(sql_type = SqlDate)]
+    pub stat_date: NaiveDate,
+    #[diesel(sql_type = Text)]
+    pub session: String,
+    #[diesel(sql_type = BigInt)]
+    pub stock_count: i64,
+}
+
+/// 统计日期范围内每天各交易时段抓取到的去重股票支数（未打标的历史数据归为 closed）
+pub fn count_distinct_codes_by_session(
+    conn: &mut PgPoolConn,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<SessionStockCount>, diesel::result::Error> {
+    let query = r#"
+        SELECT (created_at AT TIME ZONE 'Asia/Shanghai')::date AS stat_date,
+               COALESCE(session, 'closed') AS session,
+               COUNT(DISTINCT stock_code) AS stock_count
+        FROM stock_snapshots
+        WHERE (created_at AT TIME ZONE 'Asia/Shanghai')::date BETWEEN $1::date AND $2::date
+        GROUP BY stat_date, COALESCE(session, 'closed')
+        ORDER BY stat_date, session;
+    "#;
+
+    diesel::sql_query(query)
+        .bind::<SqlDate, _>(start)
+        .bind::<SqlDate, _>(end)
+        .load::<SessionStockCount>(conn)
+}
+
 /// 获取去重后的 stock_code + stock_name（按 created_at 倒序取最新）
 pub fn list_distinct_codes_with_name(
     conn: &mut PgPoolConn,
@@ -168,6 +232,33 @@ pub fn list_distinct_codes_with_name(
     diesel::sql_query(query).load::<StockCodeName>(conn)
 }
 
+/// 某只股票在某个交易日快照中使用的名称
+#[derive(Debug, QueryableByName)]
+pub struct StockDailyName {
+    #[diesel(sql_type = Text)]
+    pub stock_code: String,
+    #[diesel(sql_type = Text)]
+    pub stock_name: String,
+    #[diesel(sql_type = SqlDate)]
+    pub trade_date: NaiveDate,
+}
+
+/// 每只股票每天最后一条快照的名称（日期按 Asia/Shanghai 时区归属），按代码、日期升序，用于回填名称历史
+pub fn list_daily_names(
+    conn: &mut PgConnection,
+) -> Result<Vec<StockDailyName>, diesel::result::Error> {
+    let query = r#"
+        SELECT DISTINCT ON (stock_code, (created_at AT TIME ZONE 'Asia/Shanghai')::date)
+            stock_code,
+            stock_name,
+            (created_at AT TIME ZONE 'Asia/Shanghai')::date AS trade_date
+        FROM stock_snapshots
+        ORDER BY stock_code, (created_at AT TIME ZONE 'Asia/Shanghai')::date, created_at DESC
+    "#;
+
+    diesel::sql_query(query).load::<StockDailyName>(conn)
+}
+
 /// 每股取 `created_at` 最新的一条快照（代码 / 名称 / 最新价 / 板块 JSON，与交易日查询聚合方式一致）
 #[derive(Debug, QueryableByName)]
 pub struct LatestSnapshotFields {
@@ -182,8 +273,11 @@ pub struct LatestSnapshotFields {
 }
 
 /// 每只股取最新一条快照（代码 / 名称 / 价 / 板块），**不包含** ST、*ST、S*ST、SST 等特殊处理简称。
+///
+/// 名称取 `as_of` 当天有效的简称（`stock_name_history`），无历史记录时沿用快照中的名称。
 pub fn list_latest_snapshot_fields_per_stock(
     conn: &mut PgPoolConn,
+    as_of: NaiveDate,
 ) -> Result<Vec<LatestSnapshotFields>, diesel::result::Error> {
     let query = r#"
         WITH latest_snap AS (
@@ -196,7 +290,7 @@ pub fn list_latest_snapshot_fields_per_stock(
         )
         SELECT
             ls.stock_code,
-            ls.stock_name,
+            COALESCE(nh.stock_name, ls.stock_name) AS stock_name,
             ls.latest_price,
             COALESCE(
                 jsonb_agg(DISTINCT jsonb_build_object('plate_code', sp.plate_code, 'name', sp.name))
@@ -204,14 +298,20 @@ pub fn list_latest_snapshot_fields_per_stock(
                 '[]'::jsonb
             ) AS plates
         FROM latest_snap ls
+        LEFT JOIN stock_name_history nh
+            ON nh.stock_code = ls.stock_code
+            AND nh.valid_from <= $1
+            AND (nh.valid_to IS NULL OR nh.valid_to >= $1)
         LEFT JOIN stock_table st ON ls.stock_code = st.stock_code
         LEFT JOIN stock_plate_stock_table sps ON st.id = sps.stock_table_id
         LEFT JOIN stock_plate sp ON sps.plate_id = sp.id
-        GROUP BY ls.stock_code, ls.stock_name, ls.latest_price
+        GROUP BY ls.stock_code, nh.stock_name, ls.stock_name, ls.latest_price
         ORDER BY ls.stock_code
     "#;
 
-    let mut rows = diesel::sql_query(query).load::<LatestSnapshotFields>(conn)?;
+    let mut rows = diesel::sql_query(query)
+        .bind::<SqlDate, _>(as_of)
+        .load::<LatestSnapshotFields>(conn)?;
     rows.retain(|r| !stock_name_filter::is_st_special_stock_name(&r.stock_name));
     Ok(rows)
 }
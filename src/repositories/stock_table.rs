@@ -3,7 +3,10 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, PooledConnection};
 use diesel::OptionalExtension;
 
-use crate::models::{NewStockTable, StockTable, UpdateStockTable};
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+
+use crate::models::{NewStockTable, StockMasterData, StockTable, UpdateStockTable};
 use crate::schema::stock_table::dsl::*;
 
 pub type PgPoolConn = PooledConnection<ConnectionManager<PgConnection>>;
@@ -60,3 +63,111 @@ pub fn exists_by_code(conn: &mut PgPoolConn, code: &str) -> Result<bool, diesel:
         .optional()?;
     Ok(existing.is_some())
 }
+
+/// 未退市的股票（遍历全部股票的任务使用，避免反复请求已退市的代码）
+pub fn list_active(conn: &mut PgPoolConn) -> Result<Vec<StockTable>, diesel::result::Error> {
+    stock_table
+        .filter(list_status.ne("delisted"))
+        .order(id.asc())
+        .load(conn)
+}
+
+pub fn create_master(
+    conn: &mut PgPoolConn,
+    data: &StockMasterData,
+) -> Result<StockTable, diesel::result::Error> {
+    diesel::insert_into(stock_table)
+        .values(data)
+        .get_result(conn)
+}
+
+pub fn update_master(
+    conn: &mut PgPoolConn,
+    stock_id: i32,
+    data: &StockMasterData,
+) -> Result<StockTable, diesel::result::Error> {
+    diesel::update(stock_table.find(stock_id))
+        .set(data)
+        .get_result(conn)
+}
+
+/// 将指定股票标记为 delisted，返回更新的行数
+pub fn mark_delisted(
+    conn: &mut PgPoolConn,
+    ids: &[i32],
+    now: NaiveDateTime,
+) -> Result<usize, diesel::result::Error> {
+    diesel::update(stock_table.filter(id.eq_any(ids)))
+        .set((
+            list_status.eq("delisted"),
+            delisted_at.eq(Some(now)),
+            updated_at.eq(now),
+        ))
+        .execute(conn)
+}
+
+/// 主数据查询条件
+#[derive(Debug, Default)]
+pub struct StockMasterFilter {
+    /// 代码或名称包含
+    pub keyword: Option<String>,
+    pub exchange: Option<String>,
+    pub board: Option<String>,
+    pub industry: Option<String>,
+    pub is_st: Option<bool>,
+    pub list_status: Option<String>,
+    /// 总市值范围（元）
+    pub market_cap_min: Option<BigDecimal>,
+    pub market_cap_max: Option<BigDecimal>,
+}
+
+/// 分页查询主数据（按代码排序）
+pub fn paginate_master(
+    conn: &mut PgPoolConn,
+    filter: &StockMasterFilter,
+    page: i64,
+    page_size: i64,
+) -> Result<(Vec<StockTable>, i64), diesel::result::Error> {
+    let build = || {
+        let mut query = stock_table.into_boxed();
+        if let Some(kw) = &filter.keyword {
+            let pattern = format!("%{kw}%");
+            query = query.filter(
+                stock_code
+                    .like(pattern.clone())
+                    .or(stock_name.like(pattern)),
+            );
+        }
+        if let Some(v) = &filter.exchange {
+            query = query.filter(exchange.eq(v.clone()));
+        }
+        if let Some(v) = &filter.board {
+            query = query.filter(board.eq(v.clone()));
+        }
+        if let Some(v) = &filter.industry {
+            query = query.filter(industry.eq(v.clone()));
+        }
+        if let Some(v) = filter.is_st {
+            query = query.filter(is_st.eq(v));
+        }
+        if let Some(v) = &filter.list_status {
+            query = query.filter(list_status.eq(v.clone()));
+        }
+        if let Some(v) = &filter.market_cap_min {
+            query = query.filter(total_market_cap.ge(v.clone()));
+        }
+        if let Some(v) = &filter.market_cap_max {
+            query = query.filter(total_market_cap.le(v.clone()));
+        }
+        query
+    };
+
+    let offset = (page - 1) * page_size;
+    let total = build().count().get_result(conn)?;
+    let items = build()
+        .order(stock_code.asc())
+        .limit(page_size)
+        .offset(offset)
+        .load(conn)?;
+    Ok((items, total))
+}
@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::sql_types::Date;
+use diesel::upsert::excluded;
+use diesel::OptionalExtension;
+
+use crate::models::{NewStockTradingCalendar, StockTradingCalendar, UpdateStockTradingCalendar};
+use crate::schema::stock_trading_calendar::dsl::*;
+
+pub type PgPoolConn = PooledConnection<ConnectionManager<PgConnection>>;
+
+/// 加载全部日历记录（按日期升序），用于构建内存缓存
+pub fn list_all(conn: &mut PgPoolConn) -> Result<Vec<StockTradingCalendar>, diesel::result::Error> {
+    stock_trading_calendar
+        .order(trade_date.asc())
+        .load::<StockTradingCalendar>(conn)
+}
+
+/// 为日期区间内尚未登记的日期补齐记录：周六、周日记为休市，其余记为交易日。
+/// 已存在的日期（包括手工维护的法定节假日）保持不变，返回新插入的行数。
+pub fn seed_missing_days(
+    conn: &mut PgPoolConn,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<usize, diesel::result::Error> {
+    let query = r#"
+        INSERT INTO stock_trading_calendar (trade_date, is_holiday, holiday_name, created_at, updated_at)
+        SELECT
+            d::date,
+            EXTRACT(ISODOW FROM d) IN (6, 7),
+            CASE WHEN EXTRACT(ISODOW FROM d) IN (6, 7) THEN '周末' END,
+            NOW(),
+            NOW()
+        FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d
+        WHERE NOT EXISTS (
+            SELECT 1 FROM stock_trading_calendar c WHERE c.trade_date = d::date
+        )
+    "#;
+
+    diesel::sql_query(query)
+        .bind::<Date, _>(start)
+        .bind::<Date, _>(end)
+        .execute(conn)
+}
+
+/// 为日期区间内尚未登记的日期按 `daily_klines` 推断：当日存在任意 K 线即视为交易日，
+/// 周末记为「周末」，其余无 K 线的工作日记为「K线推断休市」。
+/// 只推断到 `daily_klines` 中最新的交易日为止（K线尚未导入的日期无法判断），
+/// 没有任何 K 线时不写入。已存在的日期保持不变，返回新插入的行数。
+pub fn derive_from_daily_klines(
+    conn: &mut PgPoolConn,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<usize, diesel::result::Error> {
+    let query = r#"
+        WITH kline_days AS (
+            SELECT DISTINCT trade_date
+            FROM daily_klines
+            WHERE trade_date BETWEEN $1::date AND $2::date
+        )
+        INSERT INTO stock_trading_calendar (trade_date, is_holiday, holiday_name, created_at, updated_at)
+        SELECT
+            d::date,
+            k.trade_date IS NULL,
+            CASE
+                WHEN k.trade_date IS NOT NULL THEN NULL
+                WHEN EXTRACT(ISODOW FROM d) IN (6, 7) THEN '周末'
+                ELSE 'K线推断休市'
+            END,
+            NOW(),
+            NOW()
+        FROM generate_series($1::date, $2::date, INTERVAL '1 day') AS d
+        LEFT JOIN kline_days k ON k.trade_date = d::date
+        WHERE d::date <= (SELECT MAX(trade_date) FROM daily_klines)
+          AND NOT EXISTS (
+            SELECT 1 FROM stock_trading_calendar c WHERE c.trade_date = d::date
+        )
+    "#;
+
+    diesel::sql_query(query)
+        .bind::<Date, _>(start)
+        .bind::<Date, _>(end)
+        .execute(conn)
+}
+
+/// 批量写入日历记录，日期已存在时覆盖休市标记与名称，返回影响的行数
+pub fn upsert_days(
+    conn: &mut PgPoolConn,
+    rows: &[NewStockTradingCalendar],
+) -> Result<usize, diesel::result::Error> {
+    let mut affected = 0;
+    // 一年约 366 行，分批避免单条语句参数过多
+    for chunk in rows.chunks(500) {
+        affected += diesel::insert_into(stock_trading_calendar)
+            .values(chunk)
+            .on_conflict(trade_date)
+            .do_update()
+            .set((
+                is_holiday.eq(excluded(is_holiday)),
+                holiday_name.eq(excluded(holiday_name)),
+                updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)?;
+    }
+    Ok(affected)
+}
+
+pub fn list_range(
+    conn: &mut PgPoolConn,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<StockTradingCalendar>, diesel::result::Error> {
+    stock_trading_calendar
+        .filter(trade_date.between(start, end))
+        .order(trade_date.asc())
+        .load(conn)
+}
+
+pub fn find_by_id(
+    conn: &mut PgPoolConn,
+    item_id: i32,
+) -> Result<Option<StockTradingCalendar>, diesel::result::Error> {
+    stock_trading_calendar
+        .filter(id.eq(item_id))
+        .first::<StockTradingCalendar>(conn)
+        .optional()
+}
+
+pub fn create(
+    conn: &mut PgPoolConn,
+    new_item: &NewStockTradingCalendar,
+) -> Result<StockTradingCalendar, diesel::result::Error> {
+    diesel::insert_into(stock_trading_calendar)
+        .values(new_item)
+        .get_result(conn)
+}
+
+pub fn update_by_id(
+    conn: &mut PgPoolConn,
+    item_id: i32,
+    update_data: &UpdateStockTradingCalendar,
+) -> Result<StockTradingCalendar, diesel::result::Error> {
+    diesel::update(stock_trading_calendar.filter(id.eq(item_id)))
+        .set(update_data)
+        .get_result(conn)
+}
+
+pub fn delete_by_id(conn: &mut PgPoolConn, item_id: i32) -> Result<usize, diesel::result::Error> {
+    diesel::delete(stock_trading_calendar.filter(id.eq(item_id))).execute(conn)
+}
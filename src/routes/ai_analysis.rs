@@ -1,14 +1,60 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 
 use crate::app::AppState;
-use crate::handler::ai_analysis::{trend_detail, trend_history, trend_prediction};
+use crate::handler::ai_analysis::{
+    follow_up_analysis, list_analysis_messages, trend_detail, trend_history, trend_prediction,
+    trend_prediction_stream,
+};
+use crate::handler::ai_batch::{create_ai_batch, get_ai_batch, list_ai_batches, resume_ai_batch};
+use crate::handler::ai_evaluation::{ai_accuracy, list_ai_evaluations};
+use crate::handler::ai_market_report::{get_market_report, list_market_reports};
+use crate::handler::ai_usage::{
+    ai_usage_budget, ai_usage_report, delete_model_price, list_model_prices, upsert_model_price,
+};
+use crate::handler::prompt_template::{
+    activate_prompt_template, create_prompt_template, delete_prompt_template, get_prompt_template,
+    list_prompt_templates, update_prompt_template,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/trend-prediction", post(trend_prediction))
+        .route("/trend-prediction/stream", get(trend_prediction_stream))
         .route("/trend-prediction/history", get(trend_history))
         .route("/trend-prediction/:id", get(trend_detail))
+        .route(
+            "/trend-prediction/:id/messages",
+            get(list_analysis_messages).post(follow_up_analysis),
+        )
+        .route("/batch", post(create_ai_batch).get(list_ai_batches))
+        .route("/batch/:id", get(get_ai_batch))
+        .route("/batch/:id/resume", post(resume_ai_batch))
+        .route("/accuracy", get(ai_accuracy))
+        .route("/evaluations", get(list_ai_evaluations))
+        .route("/market-reports", get(list_market_reports))
+        .route("/market-reports/:date", get(get_market_report))
+        .route("/usage", get(ai_usage_report))
+        .route("/usage/budget", get(ai_usage_budget))
+        .route(
+            "/model-prices",
+            post(upsert_model_price).get(list_model_prices),
+        )
+        .route("/model-prices/:id", delete(delete_model_price))
+        .route(
+            "/prompt-templates",
+            post(create_prompt_template).get(list_prompt_templates),
+        )
+        .route(
+            "/prompt-templates/:id",
+            get(get_prompt_template)
+                .put(update_prompt_template)
+                .delete(delete_prompt_template),
+        )
+        .route(
+            "/prompt-templates/:id/activate",
+            post(activate_prompt_template),
+        )
 }
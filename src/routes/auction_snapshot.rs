@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::app::AppState;
+use crate::handler::auction_snapshot::{get_auction_intraday_snapshots, list_auction_snapshots};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_auction_snapshots))
+        .route("/:id/intraday", get(get_auction_intraday_snapshots))
+}
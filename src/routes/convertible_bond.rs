@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::app::AppState;
+use crate::handler::convertible_bond::{get_convertible_bond_daily, list_convertible_bonds};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_convertible_bonds))
+        .route("/:code/daily", get(get_convertible_bond_daily))
+}
@@ -0,0 +1,18 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::app::AppState;
+use crate::handler::kline_audit::{
+    get_summary, list_findings, repair_stock, run_audit, update_finding,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/findings", get(list_findings))
+        .route("/findings/:id", put(update_finding))
+        .route("/summary", get(get_summary))
+        .route("/run", post(run_audit))
+        .route("/stocks/:code/repair", post(repair_stock))
+}
@@ -0,0 +1,18 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::app::AppState;
+use crate::handler::kline_backfill::{
+    create_kline_backfill, get_kline_backfill, get_kline_coverage, list_kline_backfills,
+    resume_kline_backfill,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_kline_backfills).post(create_kline_backfill))
+        .route("/coverage", get(get_kline_coverage))
+        .route("/:id", get(get_kline_backfill))
+        .route("/:id/resume", post(resume_kline_backfill))
+}
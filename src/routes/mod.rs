@@ -3,12 +3,16 @@ use axum::Router;
 use crate::app::AppState;
 
 mod ai_analysis;
+mod auction_snapshot;
 mod bagua;
 mod basic_data_analysis;
+mod convertible_bond;
 mod convertible_bond_query;
 mod daily_kline;
 mod dynamic_backtrack;
 mod export_button_config;
+mod kline_audit;
+mod kline_backfill;
 mod monthly_kline;
 mod multi_level_filter;
 mod profit_analysis;
@@ -27,14 +31,18 @@ mod stock_track_query;
 mod stock_trade_date_query;
 mod stock_watchlist;
 mod stock_watchlist_query;
+mod trading_calendar;
 
 pub fn build_routes() -> Router<AppState> {
     let api_router = Router::new()
         .merge(stock::router())
         .nest("/stock-request-stocks", stock_request_stock::router())
         .nest("/stock-snapshots", stock_snapshot::router())
+        .nest("/auction-snapshots", auction_snapshot::router())
         .nest("/profit-analyses", profit_analysis::router())
         .nest("/daily-klines", daily_kline::router())
+        .nest("/kline-backfill", kline_backfill::router())
+        .nest("/kline-audit", kline_audit::router())
         .nest("/monthly-klines", monthly_kline::router())
         .nest("/multi-level-filter", multi_level_filter::router())
         .nest("/scheduler", scheduler::router())
@@ -50,9 +58,11 @@ pub fn build_routes() -> Router<AppState> {
         .nest("/stock-watchlist-query", stock_watchlist_query::router())
         .nest("/ai-analysis", ai_analysis::router())
         .merge(convertible_bond_query::router())
+        .nest("/convertible-bonds", convertible_bond::router())
         .nest("/basic-data-analysis", basic_data_analysis::router())
         .nest("/export-button-config", export_button_config::router())
-        .nest("/bagua", bagua::router());
+        .nest("/bagua", bagua::router())
+        .nest("/trading-calendar", trading_calendar::router());
 
     Router::new()
         // 根路径与健康检查
@@ -1,33 +1,74 @@
 use axum::{
-    routing::{get, post},
-    Router,
+    extract::State,
+    routing::{get, post, MethodRouter},
+    Json, Router,
 };
+use serde_json::Value;
 
 use crate::app::AppState;
 use crate::handler::scheduler::{
-    get_execution_detail, get_execution_history, get_job_list, get_latest_execution,
-    trigger_kline_import, trigger_profit_analysis, trigger_stock_filter, trigger_stock_plate_sync,
-    trigger_stock_table_sync, trigger_watchlist_kline_import,
+    cancel_execution, get_execution_detail, get_execution_history, get_job_list, get_job_schedule,
+    get_latest_execution, get_pipeline_run, get_pipeline_runs, reset_job_schedule, retry_execution,
+    trigger_job, trigger_legacy, update_job_schedule, LegacyTrigger,
 };
 use crate::handler::ws_handler;
 
+/// 旧版按任务区分的触发路径：经注册表执行，响应保持各接口原有的格式
+fn trigger_alias(legacy: LegacyTrigger) -> MethodRouter<AppState> {
+    post(
+        move |State(state): State<AppState>, payload: Option<Json<Value>>| {
+            trigger_legacy(state, legacy, payload)
+        },
+    )
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         // 手动触发接口
-        .route("/trigger-kline-import", post(trigger_kline_import))
-        .route("/trigger-profit-analysis", post(trigger_profit_analysis))
-        .route("/trigger-stock-filter", post(trigger_stock_filter))
-        .route("/trigger-stock-table-sync", post(trigger_stock_table_sync))
-        .route("/trigger-stock-plate-sync", post(trigger_stock_plate_sync))
+        .route("/jobs/:job_name/trigger", post(trigger_job))
+        .route(
+            "/trigger-kline-import",
+            trigger_alias(LegacyTrigger::KlineImport),
+        )
+        .route(
+            "/trigger-profit-analysis",
+            trigger_alias(LegacyTrigger::ProfitAnalysis),
+        )
+        .route(
+            "/trigger-stock-filter",
+            trigger_alias(LegacyTrigger::StockFilter),
+        )
+        .route(
+            "/trigger-stock-table-sync",
+            trigger_alias(LegacyTrigger::StockTableSync),
+        )
+        .route(
+            "/trigger-stock-plate-sync",
+            trigger_alias(LegacyTrigger::StockPlateSync),
+        )
         .route(
             "/trigger-watchlist-kline-import",
-            post(trigger_watchlist_kline_import),
+            trigger_alias(LegacyTrigger::WatchlistKlineImport),
+        )
+        .route(
+            "/trigger-auction-capture",
+            trigger_alias(LegacyTrigger::AuctionCapture),
+        )
+        // 调度配置
+        .route(
+            "/jobs/:job_name/schedule",
+            get(get_job_schedule).put(update_job_schedule),
         )
+        .route("/jobs/:job_name/schedule/reset", post(reset_job_schedule))
         // 查询接口
         .route("/jobs", get(get_job_list))
         .route("/history", get(get_execution_history))
         .route("/history/:id", get(get_execution_detail))
+        .route("/history/:id/retry", post(retry_execution))
+        .route("/executions/:id/cancel", post(cancel_execution))
         .route("/latest/:job_name", get(get_latest_execution))
+        .route("/pipelines", get(get_pipeline_runs))
+        .route("/pipelines/:id", get(get_pipeline_run))
         // WebSocket 路由
         .route("/ws", get(ws_handler::ws_handler))
 }
@@ -5,12 +5,16 @@ use axum::{
 
 use crate::app::AppState;
 use crate::handler::stock_table::{
-    create_stock_table, delete_stock_table, get_stock_table, list_stock_tables, update_stock_table,
+    create_stock_table, delete_stock_table, get_stock_table, list_name_history, list_st_stocks,
+    list_stock_master, list_stock_tables, update_stock_table,
 };
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_stock_table).get(list_stock_tables))
+        .route("/master", get(list_stock_master))
+        .route("/name-history", get(list_name_history))
+        .route("/st", get(list_st_stocks))
         .route(
             "/:id",
             get(get_stock_table)
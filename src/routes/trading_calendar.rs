@@ -0,0 +1,36 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::app::AppState;
+use crate::handler::trading_calendar::{
+    count_trading_days, create_trading_calendar_day, delete_trading_calendar_day,
+    derive_trading_calendar_from_klines, get_trading_calendar_day, get_trading_day_info,
+    import_embedded_holidays, import_holiday_csv, import_holiday_json, list_trading_calendar_days,
+    sync_trading_calendar, update_trading_calendar_day,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/sync", post(sync_trading_calendar))
+        .route(
+            "/derive-from-klines",
+            post(derive_trading_calendar_from_klines),
+        )
+        .route("/import/json", post(import_holiday_json))
+        .route("/import/csv", post(import_holiday_csv))
+        .route("/import/embedded", post(import_embedded_holidays))
+        .route(
+            "/days",
+            get(list_trading_calendar_days).post(create_trading_calendar_day),
+        )
+        .route(
+            "/days/:id",
+            get(get_trading_calendar_day)
+                .put(update_trading_calendar_day)
+                .delete(delete_trading_calendar_day),
+        )
+        .route("/info", get(get_trading_day_info))
+        .route("/count", get(count_trading_days))
+}
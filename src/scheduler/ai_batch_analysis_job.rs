@@ -0,0 +1,378 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::job::{Job, JobContext, JobOutcome, JobStatus};
+use crate::app::DbPool;
+use crate::models::{AiBatchRun, PromptTemplate, UpdateAiBatchItem, UpdateAiBatchRun};
+use crate::repositories::ai_batch;
+use crate::services::ai_batch::{
+    self as batch, ITEM_COMPLETED, ITEM_FAILED, ITEM_INVALID, ITEM_SKIPPED,
+};
+use crate::services::llm_provider::{self, LlmProvider};
+use crate::services::prompt_template::{self, TEMPLATE_TREND_PREDICTION};
+use crate::services::trading_calendar::{shanghai_now, TradingCalendar};
+use crate::services::trend_analysis::{self, StockAnalysis, TrendAnalysisError};
+
+/// 任务参数：`{"run_id": 1}`，批量任务由 `POST /api/ai-analysis/batch` 创建
+#[derive(Debug, Deserialize)]
+pub struct AiBatchParams {
+    pub run_id: i32,
+}
+
+impl AiBatchParams {
+    fn parse(params: &Value) -> Result<Self, String> {
+        serde_json::from_value(params.clone()).map_err(|e| format!("run_id is required: {e}"))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StockAnalysisDetail {
+    stock_code: String,
+    status: String,
+    analysis_id: Option<i32>,
+    rating: Option<String>,
+    action: Option<String>,
+    tokens_used: i64,
+    /// 复用了相同输入的已有结果
+    cache_hit: bool,
+    error: Option<String>,
+}
+
+/// 批量 AI 趋势分析：按批量任务逐只分析并写入 ai_trend_analysis，仅手动触发
+pub struct AiBatchAnalysisJob;
+
+impl Job for AiBatchAnalysisJob {
+    fn name(&self) -> &'static str {
+        "ai_batch_analysis"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "批量 AI 趋势分析"
+    }
+
+    fn description(&self) -> &'static str {
+        "对批量任务中的股票逐只调用大模型做趋势分析，并发与 token 预算受控，可中断后继续"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "手动触发"
+    }
+
+    fn detach_on_trigger(&self) -> bool {
+        true
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        AiBatchParams::parse(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_ai_batch_task(ctx))
+    }
+}
+
+async fn run_ai_batch_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let params = AiBatchParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+    let run = {
+        let mut conn = db_pool.get()?;
+        ai_batch::find_run(&mut conn, params.run_id)?
+            .ok_or_else(|| anyhow::anyhow!("批量分析任务 #{} 不存在", params.run_id))?
+    };
+
+    let result = analyze_run(ctx, &run).await;
+    if let Err(e) = &result {
+        update_run(
+            db_pool,
+            run.id,
+            UpdateAiBatchRun {
+                status: Some(JobStatus::Failed.as_str().to_string()),
+                error_message: Some(Some(e.to_string())),
+                completed_at: Some(Some(shanghai_now())),
+                ..Default::default()
+            },
+        );
+    }
+    result
+}
+
+async fn analyze_run(ctx: &JobContext, run: &AiBatchRun) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let (pending, template) = {
+        let mut conn = db_pool.get()?;
+        let pending: Vec<String> = ai_batch::list_items(&mut conn, run.id)?
+            .into_iter()
+            .filter(|item| batch::needs_analysis(&item.status))
+            .map(|item| item.stock_code)
+            .collect();
+        let template = prompt_template::resolve(
+            &mut conn,
+            TEMPLATE_TREND_PREDICTION,
+            Some(run.prompt_version),
+            chrono::Utc::now().naive_utc(),
+        )?;
+        (pending, template)
+    };
+    if pending.is_empty() {
+        return Ok(JobOutcome::empty(format!(
+            "批量分析任务 #{} 没有未完成的股票",
+            run.id
+        )));
+    }
+    let provider: Arc<dyn LlmProvider> =
+        Arc::from(llm_provider::resolve_provider(Some(&run.model_name))?);
+    tracing::info!(
+        "批量分析任务 #{}: 模型 {}，提示词 v{}，待处理 {} / {} 只股票，并发 {}",
+        run.id,
+        run.model_name,
+        run.prompt_version,
+        pending.len(),
+        run.total_stocks,
+        run.concurrency
+    );
+    update_run(
+        db_pool,
+        run.id,
+        UpdateAiBatchRun {
+            status: Some(JobStatus::Running.as_str().to_string()),
+            execution_id: ctx.execution_id,
+            error_message: Some(None),
+            completed_at: Some(None),
+            ..Default::default()
+        },
+    );
+
+    let template = Arc::new(template);
+    let tokens_used = Arc::new(AtomicI64::new(run.tokens_used));
+    let semaphore = Arc::new(Semaphore::new(run.concurrency.max(1) as usize));
+    let mut join_set = JoinSet::new();
+    for code in pending.iter().cloned() {
+        let pool = db_pool.clone();
+        let calendar = ctx.trading_calendar.clone();
+        let provider = provider.clone();
+        let template = template.clone();
+        let tokens_used = tokens_used.clone();
+        let sem = semaphore.clone();
+        let budget = run.token_budget;
+        join_set.spawn(async move {
+            let _permit = sem.acquire_owned().await;
+            let result = analyze_within_budget(
+                &pool,
+                &calendar,
+                provider.as_ref(),
+                &template,
+                &code,
+                budget,
+                &tokens_used,
+            )
+            .await;
+            (code, result)
+        });
+    }
+
+    let mut details = Vec::with_capacity(pending.len());
+    while let Some(res) = join_set.join_next().await {
+        match res {
+            Ok((code, result)) => {
+                let detail = record_item(db_pool, run.id, code, result);
+                details.push(detail);
+                update_run(
+                    db_pool,
+                    run.id,
+                    UpdateAiBatchRun {
+                        tokens_used: Some(tokens_used.load(Ordering::SeqCst)),
+                        ..Default::default()
+                    },
+                );
+            }
+            Err(join_err) => tracing::error!("批量分析任务并发执行失败: {}", join_err),
+        }
+        ctx.progress(details.len(), pending.len());
+        if ctx.is_cancelled() {
+            tracing::warn!(
+                "批量分析任务 #{} 已取消，剩余 {} 只股票可稍后继续",
+                run.id,
+                pending.len() - details.len()
+            );
+            join_set.abort_all();
+            break;
+        }
+    }
+
+    // 汇总全部逐股记录（含此前执行已完成的部分）
+    let items = {
+        let mut conn = db_pool.get()?;
+        ai_batch::list_items(&mut conn, run.id)?
+    };
+    let completed = items.iter().filter(|i| i.status == ITEM_COMPLETED).count();
+    let skipped = items.iter().filter(|i| i.status == ITEM_SKIPPED).count();
+    let failed = items
+        .iter()
+        .filter(|i| i.status == ITEM_FAILED || i.status == ITEM_INVALID)
+        .count();
+    let tokens = tokens_used.load(Ordering::SeqCst);
+    let status = if ctx.is_cancelled() {
+        JobStatus::Cancelled
+    } else if completed == items.len() {
+        JobStatus::Success
+    } else if completed > 0 {
+        JobStatus::Partial
+    } else {
+        JobStatus::Failed
+    };
+    update_run(
+        db_pool,
+        run.id,
+        UpdateAiBatchRun {
+            status: Some(status.as_str().to_string()),
+            tokens_used: Some(tokens),
+            completed_stocks: Some(completed as i32),
+            failed_stocks: Some(failed as i32),
+            skipped_stocks: Some(skipped as i32),
+            completed_at: Some(Some(shanghai_now())),
+            ..Default::default()
+        },
+    );
+
+    let success_count = details
+        .iter()
+        .filter(|d| d.status == ITEM_COMPLETED)
+        .count();
+    let skipped_count = details.iter().filter(|d| d.status == ITEM_SKIPPED).count();
+    Ok(JobOutcome {
+        total_count: details.len(),
+        success_count,
+        failed_count: details.len() - success_count - skipped_count,
+        skipped_count,
+        message: Some(format!(
+            "批量分析任务 #{}：已完成 {}/{} 只股票，累计消耗 {} tokens",
+            run.id,
+            completed,
+            items.len(),
+            tokens
+        )),
+        ..Default::default()
+    }
+    .with_details(&details))
+}
+
+/// 预算未用尽时分析一只股票并累计 token 用量；预算已用尽时返回 None，不发起分析
+async fn analyze_within_budget(
+    db_pool: &DbPool,
+    calendar: &TradingCalendar,
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    code: &str,
+    token_budget: Option<i64>,
+    tokens_used: &AtomicI64,
+) -> Option<Result<StockAnalysis, TrendAnalysisError>> {
+    if batch::budget_exhausted(token_budget, tokens_used.load(Ordering::SeqCst)) {
+        return None;
+    }
+    let result =
+        trend_analysis::analyze_stock(db_pool, calendar, provider, template, code, false, None)
+            .await;
+    if let Ok(analysis) = &result {
+        tokens_used.fetch_add(analysis.usage.total(), Ordering::SeqCst);
+    }
+    Some(result)
+}
+
+/// 写入单只股票的分析进度，返回执行明细
+fn record_item(
+    db_pool: &DbPool,
+    run_id: i32,
+    code: String,
+    result: Option<Result<StockAnalysis, TrendAnalysisError>>,
+) -> StockAnalysisDetail {
+    let detail = match result {
+        Some(Ok(StockAnalysis {
+            record,
+            usage,
+            cache_hit,
+        })) => StockAnalysisDetail {
+            stock_code: code.clone(),
+            status: batch::item_status(&record.status).to_string(),
+            analysis_id: Some(record.id),
+            rating: record.rating,
+            action: record.action,
+            tokens_used: usage.total(),
+            cache_hit,
+            error: record.error_message,
+        },
+        Some(Err(e)) => {
+            let status = match e {
+                TrendAnalysisError::NoSignals(_) | TrendAnalysisError::BudgetExceeded(_) => {
+                    ITEM_SKIPPED
+                }
+                _ => {
+                    tracing::error!("股票 {} 批量分析失败: {}", code, e);
+                    ITEM_FAILED
+                }
+            };
+            StockAnalysisDetail {
+                stock_code: code.clone(),
+                status: status.to_string(),
+                analysis_id: None,
+                rating: None,
+                action: None,
+                tokens_used: 0,
+                cache_hit: false,
+                error: Some(e.to_string()),
+            }
+        }
+        None => StockAnalysisDetail {
+            stock_code: code.clone(),
+            status: ITEM_SKIPPED.to_string(),
+            analysis_id: None,
+            rating: None,
+            action: None,
+            tokens_used: 0,
+            cache_hit: false,
+            error: Some("token 预算已用尽".to_string()),
+        },
+    };
+
+    let update = UpdateAiBatchItem {
+        status: Some(detail.status.clone()),
+        analysis_id: detail.analysis_id,
+        rating: Some(detail.rating.clone()),
+        action: Some(detail.action.clone()),
+        tokens_used: Some(detail.tokens_used),
+        error_message: Some(detail.error.clone()),
+        updated_at: Some(shanghai_now()),
+    };
+    let updated = db_pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            ai_batch::update_item(&mut conn, run_id, &code, &update).map_err(|e| e.to_string())
+        });
+    if let Err(e) = updated {
+        tracing::warn!("更新批量分析任务 #{} 中 {} 的进度失败: {}", run_id, code, e);
+    }
+    detail
+}
+
+fn update_run(db_pool: &DbPool, run_id: i32, mut data: UpdateAiBatchRun) {
+    data.updated_at = Some(shanghai_now());
+    let updated = db_pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            ai_batch::update_run(&mut conn, run_id, &data).map_err(|e| e.to_string())
+        });
+    if let Err(e) = updated {
+        tracing::warn!("更新批量分析任务 #{} 失败: {}", run_id, e);
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::job::{Job, JobContext, JobOutcome, JobStatus};
+use crate::services::market_report::{self, REPORT_COMPLETED};
+use crate::services::trading_calendar::shanghai_now;
+
+/// 任务参数（可省略）：`{"date": "2026-10-19", "model": "qwen"}`，
+/// 日期缺省为最近一个交易日，模型缺省为 `AI_MARKET_REPORT_MODEL`
+#[derive(Debug, Default, Deserialize)]
+pub struct AiMarketReportParams {
+    pub date: Option<NaiveDate>,
+    pub model: Option<String>,
+}
+
+impl AiMarketReportParams {
+    fn parse(params: &Value) -> Result<Self, String> {
+        if params.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(params.clone()).map_err(|e| e.to_string())
+    }
+}
+
+/// AI 每日复盘：盈利分析完成后汇总当日信号与板块统计并生成复盘
+pub struct AiMarketReportJob;
+
+impl Job for AiMarketReportJob {
+    fn name(&self) -> &'static str {
+        "ai_market_report"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "AI 每日复盘"
+    }
+
+    fn description(&self) -> &'static str {
+        "汇总当日信号支数、热门板块、连续信号个股与上一交易日信号表现，由大模型生成复盘，写入 ai_market_reports"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "盈利分析完成后"
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["profit_analysis"]
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        AiMarketReportParams::parse(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_ai_market_report_task(ctx))
+    }
+}
+
+async fn run_ai_market_report_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let params = AiMarketReportParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+    let days = ctx.trading_calendar.days(&ctx.db_pool).await;
+    let date = params
+        .date
+        .unwrap_or_else(|| days.trading_day_on_or_before(shanghai_now().date()));
+    let model = params.model.or_else(market_report::default_model);
+
+    let report = market_report::generate(&ctx.db_pool, &days, date, model.as_deref()).await?;
+    let completed = report.status == REPORT_COMPLETED;
+    let message = match &report.error_message {
+        Some(reason) if !completed => format!("{date} 复盘未生成，仅保存统计：{reason}"),
+        Some(reason) => format!("{date} 复盘未重新生成，保留已有复盘并更新统计：{reason}"),
+        _ => format!(
+            "{date} 复盘已生成（{}，{} tokens）",
+            report.model_name.as_deref().unwrap_or("-"),
+            report.total_tokens.unwrap_or(0)
+        ),
+    };
+    Ok(JobOutcome {
+        total_count: 1,
+        success_count: usize::from(completed),
+        skipped_count: usize::from(!completed),
+        message: Some(message),
+        status: Some(if completed {
+            JobStatus::Success
+        } else {
+            JobStatus::Partial
+        }),
+        ..Default::default()
+    }
+    .with_details(&[json!({
+        "report_id": report.id,
+        "report_date": report.report_date,
+        "status": report.status,
+    })]))
+}
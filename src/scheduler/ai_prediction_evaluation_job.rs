@@ -0,0 +1,168 @@
+use chrono::{Duration, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::job::{Job, JobContext, JobOutcome};
+use crate::repositories::{ai_prediction_evaluation, daily_kline};
+use crate::services::ai_evaluation::{self, EvaluationResult, LOOKBACK_DAYS};
+use crate::services::trading_calendar::shanghai_now;
+
+/// 任务参数（可省略）：`{"horizon_days": 5}`，缺省为 `AI_EVAL_HORIZON_DAYS`
+#[derive(Debug, Default, Deserialize)]
+pub struct AiEvaluationParams {
+    pub horizon_days: Option<i32>,
+}
+
+impl AiEvaluationParams {
+    fn parse(params: &Value) -> Result<i32, String> {
+        let p: AiEvaluationParams = if params.is_null() {
+            AiEvaluationParams::default()
+        } else {
+            serde_json::from_value(params.clone()).map_err(|e| e.to_string())?
+        };
+        match p.horizon_days {
+            Some(h) => ai_evaluation::check_horizon(h),
+            None => Ok(ai_evaluation::default_horizon()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EvaluationDetail {
+    analysis_id: i32,
+    stock_code: String,
+    success: bool,
+    direction_hit: Option<bool>,
+    realized_return_pct: Option<String>,
+    error: Option<String>,
+}
+
+/// AI 预测评估：分析后第 N 个交易日用实际K线检验预测，K线导入完成后执行
+pub struct AiPredictionEvaluationJob;
+
+impl Job for AiPredictionEvaluationJob {
+    fn name(&self) -> &'static str {
+        "ai_prediction_evaluation"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "AI 预测评估"
+    }
+
+    fn description(&self) -> &'static str {
+        "按实际K线检验 AI 趋势分析的方向、评级与支撑压力位，写入 ai_prediction_evaluations"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "K线导入完成后"
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["kline_import"]
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        AiEvaluationParams::parse(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_ai_evaluation_task(ctx))
+    }
+}
+
+async fn run_ai_evaluation_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let horizon = AiEvaluationParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+    let days = ctx.trading_calendar.days(db_pool).await;
+
+    let mut conn = db_pool.get()?;
+    let tomorrow = shanghai_now().date() + Duration::days(1);
+    let Some(latest) = daily_kline::find_previous_trade_date(&mut conn, tomorrow)? else {
+        return Ok(JobOutcome::empty("daily_klines 中没有K线数据"));
+    };
+    let since = Utc::now() - Duration::days(LOOKBACK_DAYS);
+    let pending = ai_prediction_evaluation::list_pending(&mut conn, horizon, since)?;
+    if pending.is_empty() {
+        return Ok(JobOutcome::empty(format!(
+            "没有待评估的分析记录（周期 {horizon} 个交易日）"
+        )));
+    }
+
+    let mut details = Vec::new();
+    let (mut not_ready, mut skipped, mut failed, mut hits) = (0, 0, 0, 0);
+    for (i, analysis) in pending.iter().enumerate() {
+        let result = ai_evaluation::evaluate_analysis(&mut conn, &days, analysis, horizon, latest)
+            .and_then(|result| match result {
+                EvaluationResult::Evaluated(item) => {
+                    ai_prediction_evaluation::insert(&mut conn, &item)?;
+                    Ok(EvaluationResult::Evaluated(item))
+                }
+                other => Ok(other),
+            });
+        let detail = match result {
+            Ok(EvaluationResult::NotReady) => {
+                not_ready += 1;
+                None
+            }
+            Ok(EvaluationResult::Evaluated(item)) => {
+                if item.direction_hit {
+                    hits += 1;
+                }
+                Some(EvaluationDetail {
+                    analysis_id: analysis.id,
+                    stock_code: analysis.stock_code.clone(),
+                    success: true,
+                    direction_hit: Some(item.direction_hit),
+                    realized_return_pct: Some(item.realized_return_pct.to_string()),
+                    error: None,
+                })
+            }
+            Ok(EvaluationResult::NoData(reason)) => {
+                skipped += 1;
+                Some(EvaluationDetail {
+                    analysis_id: analysis.id,
+                    stock_code: analysis.stock_code.clone(),
+                    success: false,
+                    direction_hit: None,
+                    realized_return_pct: None,
+                    error: Some(reason),
+                })
+            }
+            Err(e) => {
+                failed += 1;
+                tracing::error!("评估分析记录 #{} 失败: {}", analysis.id, e);
+                Some(EvaluationDetail {
+                    analysis_id: analysis.id,
+                    stock_code: analysis.stock_code.clone(),
+                    success: false,
+                    direction_hit: None,
+                    realized_return_pct: None,
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+        details.extend(detail);
+        ctx.progress(i + 1, pending.len());
+        if ctx.is_cancelled() {
+            break;
+        }
+    }
+
+    let evaluated = details.iter().filter(|d| d.success).count();
+    Ok(JobOutcome {
+        total_count: details.len(),
+        success_count: evaluated,
+        failed_count: failed,
+        skipped_count: skipped,
+        message: Some(format!(
+            "周期 {horizon} 个交易日：评估 {evaluated} 条，方向命中 {hits} 条，{not_ready} 条未到评估日，{skipped} 条暂无法评估"
+        )),
+        ..Default::default()
+    }
+    .with_details(&details))
+}
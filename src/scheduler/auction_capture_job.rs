@@ -0,0 +1,158 @@
+use super::job::{Job, JobContext, JobOutcome};
+use crate::api_models::auction_snapshot::AuctionCaptureRequest;
+use crate::models::NewAuctionSnapshot;
+use crate::repositories::{auction_snapshot, stock_watchlist};
+use crate::services::auction_capture::{
+    capture_auction_quotes, AuctionFilterParams, AuctionUniverse,
+};
+use crate::services::trading_calendar::shanghai_now;
+use crate::utils::bigdecimal_parser::parse_bigdecimal;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveTime, Timelike};
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+fn decimal(v: f64) -> BigDecimal {
+    parse_bigdecimal(Some(&Value::from(v)))
+}
+
+/// 定时任务的采样范围：环境变量 `AUCTION_CAPTURE_UNIVERSE`（market / watchlist），缺省为全市场
+fn scheduled_universe() -> AuctionUniverse {
+    match std::env::var("AUCTION_CAPTURE_UNIVERSE").as_deref() {
+        Ok("watchlist") => AuctionUniverse::Watchlist,
+        _ => AuctionUniverse::Market,
+    }
+}
+
+/// 手动触发参数：`{"universe": "market" | "watchlist" | "codes", "codes": [...]}`
+fn parse_universe(params: &Value) -> Result<AuctionUniverse, String> {
+    if params.is_null() {
+        return Ok(AuctionUniverse::Market);
+    }
+    let req: AuctionCaptureRequest =
+        serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+    match req.universe.as_deref().unwrap_or("market") {
+        "market" => Ok(AuctionUniverse::Market),
+        "watchlist" => Ok(AuctionUniverse::Watchlist),
+        "codes" if !req.codes.is_empty() => Ok(AuctionUniverse::Codes(req.codes)),
+        "codes" => Err("codes must not be empty when universe = codes".to_string()),
+        other => Err(format!("unknown universe: {other}")),
+    }
+}
+
+/// 集合竞价采样：9:20（撤单截止）与 9:25 撮合完成后各采样一次
+pub struct AuctionCaptureJob;
+
+impl Job for AuctionCaptureJob {
+    fn name(&self) -> &'static str {
+        "auction_capture"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "集合竞价采样"
+    }
+
+    fn description(&self) -> &'static str {
+        "采样开盘集合竞价的匹配价、匹配量与未匹配量并入库"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[
+            "0 20 9 * * 1-5",  // 9:20:00
+            "30 25 9 * * 1-5", // 9:25:30，等待撮合结果发布
+        ]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "交易日 9:20、9:25"
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        parse_universe(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(async move {
+            // 定时触发且未在 job_schedules 配置参数时，沿用环境变量
+            let universe = if ctx.is_manual() || !ctx.params.is_null() {
+                parse_universe(&ctx.params).map_err(anyhow::Error::msg)?
+            } else {
+                scheduled_universe()
+            };
+            run_auction_capture_task(ctx, universe).await
+        })
+    }
+}
+
+async fn run_auction_capture_task(
+    ctx: &JobContext,
+    universe: AuctionUniverse,
+) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let now = shanghai_now();
+    // 采样时刻按分钟归属，与交易时段划分一致
+    let sample_time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default();
+    let trade_date = ctx
+        .trading_calendar
+        .days(db_pool)
+        .await
+        .trading_day_on_or_before(now.date());
+    tracing::info!(
+        "集合竞价采样 [{}] - {} {}",
+        universe.as_str(),
+        trade_date,
+        sample_time
+    );
+
+    let codes = match &universe {
+        AuctionUniverse::Market => None,
+        AuctionUniverse::Watchlist => {
+            let mut conn = db_pool.get()?;
+            Some(
+                stock_watchlist::list_all(&mut conn)?
+                    .into_iter()
+                    .map(|w| w.stock_code)
+                    .collect(),
+            )
+        }
+        AuctionUniverse::Codes(codes) => Some(codes.clone()),
+    };
+
+    let quotes = capture_auction_quotes(codes, &AuctionFilterParams::default()).await?;
+    let rows: Vec<NewAuctionSnapshot> = quotes
+        .iter()
+        .map(|q| NewAuctionSnapshot {
+            trade_date,
+            sample_time,
+            stock_code: q.stock_code.clone(),
+            stock_name: q.stock_name.clone(),
+            matched_price: decimal(q.matched_price),
+            prev_close: decimal(q.prev_close),
+            change_pct: decimal(q.change_pct),
+            matched_volume: q.matched_volume,
+            matched_amount: decimal(q.matched_amount),
+            unmatched_volume: q.unmatched_volume,
+            universe: universe.as_str().to_string(),
+        })
+        .collect();
+    let detail_count = quotes
+        .iter()
+        .filter(|q| q.unmatched_volume.is_some())
+        .count();
+    let saved_count = {
+        let mut conn = db_pool.get()?;
+        auction_snapshot::upsert_many(&mut conn, &rows)?
+    };
+
+    Ok(JobOutcome {
+        total_count: quotes.len(),
+        success_count: saved_count,
+        details: Some(serde_json::json!({
+            "universe": universe.as_str(),
+            "trade_date": trade_date.to_string(),
+            "sample_time": sample_time.format("%H:%M").to_string(),
+            "detail_count": detail_count,
+        })),
+        ..Default::default()
+    })
+}
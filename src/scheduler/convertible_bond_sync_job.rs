@@ -0,0 +1,78 @@
+use futures::future::BoxFuture;
+use serde_json::json;
+
+use super::job::{Job, JobContext, JobOutcome, JobStatus};
+use crate::services::convertible_bond_sync;
+use crate::services::trading_calendar::shanghai_now;
+
+/// 可转债同步：收盘后全量更新可转债主数据并记录当日行情（每天 15:40）
+pub struct ConvertibleBondSyncJob;
+
+impl Job for ConvertibleBondSyncJob {
+    fn name(&self) -> &'static str {
+        "convertible_bond_sync"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "可转债同步"
+    }
+
+    fn description(&self) -> &'static str {
+        "从东财数据中心同步可转债主数据（评级、规模、触发价、摘牌与最后交易日），并写入当日价格、转股价值与溢价率"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &["0 40 15 * * *"]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "每天 15:40"
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_convertible_bond_sync_task(ctx))
+    }
+}
+
+async fn run_convertible_bond_sync_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let trade_date = ctx
+        .trading_calendar
+        .days(&ctx.db_pool)
+        .await
+        .trading_day_on_or_before(shanghai_now().date());
+    let summary = convertible_bond_sync::sync(&ctx.db_pool, trade_date).await?;
+    if summary.fetched == 0 {
+        anyhow::bail!("可转债列表为空");
+    }
+
+    let missing = summary.missing_last_trading_dates.len();
+    Ok(JobOutcome {
+        total_count: summary.bonds,
+        success_count: summary.bonds,
+        message: Some(format!(
+            "{trade_date} 同步可转债 {} 支，行情 {} 条，剩余规模 {} 支，新查到最后交易日 {} 支{}",
+            summary.bonds,
+            summary.quotes,
+            summary.remaining_scales,
+            summary.last_trading_dates,
+            if missing > 0 {
+                format!("，{missing} 支未查到")
+            } else {
+                String::new()
+            }
+        )),
+        status: Some(if missing > 0 {
+            JobStatus::Partial
+        } else {
+            JobStatus::Success
+        }),
+        ..Default::default()
+    }
+    .with_details(
+        &summary
+            .missing_last_trading_dates
+            .iter()
+            .map(|code| json!({ "bond_code": code, "error": "未查到最后交易日" }))
+            .collect::<Vec<_>>(),
+    ))
+}
@@ -0,0 +1,247 @@
+//! 定时任务抽象：每个任务只需实现 [`Job`]（名称、cron、执行逻辑），
+//! 执行记录、WebSocket 广播、计时、panic 捕获与防重入统一由 [`super::registry::JobRegistry`] 处理。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use crate::app::DbPool;
+use crate::services::trading_calendar::TradingCalendar;
+use crate::utils::ws_broadcast::{broadcast_task_progress, TaskStatusSender};
+
+/// 触发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobTrigger {
+    /// cron 定时触发
+    Scheduled,
+    /// 通过接口手动触发
+    Manual,
+}
+
+impl JobTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobTrigger::Scheduled => "scheduled",
+            JobTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// 执行状态（写入 job_execution_history.status 并广播）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Success,
+    Partial,
+    Failed,
+    /// 执行中被接口取消
+    Cancelled,
+    /// 进程退出时仍在执行，重启后标记
+    Aborted,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Success => "success",
+            JobStatus::Partial => "partial",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+            JobStatus::Aborted => "aborted",
+        }
+    }
+}
+
+/// 单次执行的统计结果
+#[derive(Debug, Default)]
+pub struct JobOutcome {
+    pub total_count: usize,
+    pub success_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    /// 明细，原样写入 job_execution_history.details
+    pub details: Option<Value>,
+    /// 附加说明（如「今日没有股票代码入库」），写入 error_message
+    pub message: Option<String>,
+    /// 显式指定的状态；缺省按成功 / 失败数推断
+    pub status: Option<JobStatus>,
+}
+
+impl JobOutcome {
+    /// 没有可处理的数据时的结果
+    pub fn empty(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_details<T: Serialize>(mut self, details: &T) -> Self {
+        self.details = serde_json::to_value(details).ok();
+        self
+    }
+
+    /// 无失败为 success，有成功也有失败为 partial，否则 failed
+    pub fn status(&self) -> JobStatus {
+        if let Some(status) = self.status {
+            return status;
+        }
+        if self.failed_count == 0 {
+            JobStatus::Success
+        } else if self.success_count > 0 {
+            JobStatus::Partial
+        } else {
+            JobStatus::Failed
+        }
+    }
+}
+
+/// 任务执行上下文
+pub struct JobContext {
+    pub db_pool: DbPool,
+    pub trading_calendar: TradingCalendar,
+    pub trigger: JobTrigger,
+    /// 手动触发时为请求体，定时触发为 job_schedules.params（未配置时为 `Value::Null`）
+    pub params: Value,
+    /// 对应的 job_execution_history 记录 ID（写入失败时为空）
+    pub execution_id: Option<i32>,
+    /// 第几次执行（首次为 1，每次重试加 1）
+    pub attempt: i32,
+    /// 被重试的执行记录 ID
+    pub retry_of: Option<i32>,
+    /// 取消令牌，任务在批次之间通过 [`JobContext::is_cancelled`] 检查
+    pub cancel: CancellationToken,
+    job_name: &'static str,
+    ws_sender: TaskStatusSender,
+    last_progress_step: AtomicUsize,
+}
+
+/// 进度广播的粒度（百分比），避免大批量任务刷屏导致 WebSocket 接收端滞后断开
+const PROGRESS_STEP_PERCENT: usize = 5;
+
+impl JobContext {
+    pub(crate) fn new(
+        job_name: &'static str,
+        db_pool: DbPool,
+        trading_calendar: TradingCalendar,
+        ws_sender: TaskStatusSender,
+        trigger: JobTrigger,
+        params: Value,
+    ) -> Self {
+        Self {
+            db_pool,
+            trading_calendar,
+            trigger,
+            params,
+            execution_id: None,
+            attempt: 1,
+            retry_of: None,
+            cancel: CancellationToken::new(),
+            job_name,
+            ws_sender,
+            last_progress_step: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn job_name(&self) -> &'static str {
+        self.job_name
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.trigger == JobTrigger::Manual
+    }
+
+    /// 是否已请求取消。任务应在批次之间检查，取消后停止派发新的工作并返回已完成部分的结果
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// 上报进度，按百分比档位节流后广播
+    pub fn progress(&self, done: usize, total: usize) {
+        if total == 0 {
+            return;
+        }
+        let step = done.min(total) * 100 / total / PROGRESS_STEP_PERCENT;
+        if self.last_progress_step.fetch_max(step, Ordering::Relaxed) < step {
+            broadcast_task_progress(&self.ws_sender, self.job_name.to_string(), done, total);
+        }
+    }
+}
+
+/// 定时任务
+pub trait Job: Send + Sync {
+    /// 唯一名称，同时用作执行记录的 job_name 与广播名称
+    fn name(&self) -> &'static str;
+
+    fn display_name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// 默认 cron 表达式（六段含秒，Asia/Shanghai 时区），首次启动时写入 job_schedules
+    fn schedules(&self) -> &'static [&'static str];
+
+    /// 默认执行时间的说明
+    fn schedule_desc(&self) -> &'static str;
+
+    /// 上游任务：上游定时执行完成（success / partial）后按依赖顺序执行本任务，
+    /// 上游失败时本任务记为 blocked。有上游的任务通常不再配置自己的 cron
+    fn depends_on(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// 手动触发时是否后台执行（耗时较长的任务立即返回）
+    fn detach_on_trigger(&self) -> bool {
+        false
+    }
+
+    /// 校验任务参数（手动触发的请求体与 job_schedules.params）
+    fn validate_params(&self, _params: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 是否支持「只重试失败项」。支持的任务需接受 [`super::retry::StockRetryParams`] 参数
+    fn retryable(&self) -> bool {
+        false
+    }
+
+    /// 从执行明细（job_execution_history.details）中取出失败项的股票代码
+    fn failed_items(&self, _details: &Value) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 定时触发前的额外判断（交易日判断由注册表统一处理），返回 false 时跳过且不记录
+    fn should_run<'a>(&'a self, _ctx: &'a JobContext) -> BoxFuture<'a, bool> {
+        Box::pin(async { true })
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_status_from_counts() {
+        let outcome = |success_count, failed_count| JobOutcome {
+            success_count,
+            failed_count,
+            ..Default::default()
+        };
+        assert_eq!(outcome(0, 0).status(), JobStatus::Success);
+        assert_eq!(outcome(3, 1).status(), JobStatus::Partial);
+        assert_eq!(outcome(0, 2).status(), JobStatus::Failed);
+
+        let forced = JobOutcome {
+            status: Some(JobStatus::Failed),
+            ..outcome(0, 0)
+        };
+        assert_eq!(forced.status(), JobStatus::Failed);
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::job::{Job, JobContext, JobOutcome};
+use crate::repositories::daily_kline;
+use crate::services::kline_audit;
+use crate::services::kline_backfill::{self as backfill, StockSet};
+use crate::services::trading_calendar::shanghai_now;
+
+/// 未指定区间时审计最近的交易日数
+const DEFAULT_AUDIT_DAYS: usize = 20;
+
+/// 任务参数（均可省略）：`{"stock_set": "all" | "plate" | "watchlist" | "codes", "plate_code": "BK0477", "codes": [...], "start_date": "YYYY-MM-DD", "end_date": "YYYY-MM-DD"}`。
+/// 省略 stock_set 时审计截止日有K线的股票，省略区间时为最近 [`DEFAULT_AUDIT_DAYS`] 个交易日
+#[derive(Debug, Default, Deserialize)]
+pub struct KlineAuditParams {
+    pub stock_set: Option<String>,
+    pub plate_code: Option<String>,
+    pub codes: Option<Vec<String>>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+impl KlineAuditParams {
+    fn parse(params: &Value) -> Result<(Self, Option<StockSet>), String> {
+        let mut p: KlineAuditParams = if params.is_null() {
+            KlineAuditParams::default()
+        } else {
+            serde_json::from_value(params.clone()).map_err(|e| e.to_string())?
+        };
+        let set = match p.stock_set.take() {
+            Some(s) => Some(StockSet::parse(&s, p.plate_code.take(), p.codes.take())?),
+            None => None,
+        };
+        if let (Some(start), Some(end)) = (p.start_date, p.end_date) {
+            backfill::validate_range(start, end, shanghai_now().date())?;
+        }
+        Ok((p, set))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StockAuditDetail {
+    stock_code: String,
+    success: bool,
+    bars: usize,
+    findings: usize,
+    resolved: usize,
+    error: Option<String>,
+}
+
+/// K线数据质量审计：检查 daily_klines 的 OHLC、成交量、重复、缺口与异常跳空，K线导入完成后执行
+pub struct KlineAuditJob;
+
+impl Job for KlineAuditJob {
+    fn name(&self) -> &'static str {
+        "kline_audit"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "K线数据审计"
+    }
+
+    fn description(&self) -> &'static str {
+        "检查日K线的数据质量问题并写入 kline_audit_findings"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "K线导入完成后"
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["kline_import"]
+    }
+
+    fn detach_on_trigger(&self) -> bool {
+        true
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        KlineAuditParams::parse(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_kline_audit_task(ctx))
+    }
+}
+
+async fn run_kline_audit_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let (params, set) = KlineAuditParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+    let days = ctx.trading_calendar.days(db_pool).await;
+    let end = params
+        .end_date
+        .unwrap_or_else(|| days.trading_day_on_or_before(shanghai_now().date()));
+    let start = params
+        .start_date
+        .unwrap_or_else(|| days.nth_trading_day_before(end, DEFAULT_AUDIT_DAYS - 1));
+
+    let mut conn = db_pool.get()?;
+    let codes = match &set {
+        Some(set) => backfill::resolve_codes(&mut conn, set)?,
+        None => daily_kline::list_stock_codes_on(&mut conn, end)?,
+    };
+    if codes.is_empty() {
+        return Ok(JobOutcome::empty(format!("{end} 没有需要审计的股票")));
+    }
+    tracing::info!("K线审计: {} ~ {}，共 {} 只股票", start, end, codes.len());
+
+    let mut details = Vec::with_capacity(codes.len());
+    for (i, code) in codes.iter().enumerate() {
+        let detail = match kline_audit::audit_stock(&mut conn, &days, code, start, end) {
+            Ok(audit) => StockAuditDetail {
+                stock_code: code.clone(),
+                success: true,
+                bars: audit.bars,
+                findings: audit.findings,
+                resolved: audit.resolved,
+                error: None,
+            },
+            Err(e) => {
+                tracing::error!("股票 {} 审计失败: {}", code, e);
+                StockAuditDetail {
+                    stock_code: code.clone(),
+                    success: false,
+                    bars: 0,
+                    findings: 0,
+                    resolved: 0,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        details.push(detail);
+        ctx.progress(i + 1, codes.len());
+        if ctx.is_cancelled() {
+            break;
+        }
+    }
+
+    let processed = details.len();
+    let success_count = details.iter().filter(|d| d.success).count();
+    let findings: usize = details.iter().map(|d| d.findings).sum();
+    let resolved: usize = details.iter().map(|d| d.resolved).sum();
+    let with_findings = details.iter().filter(|d| d.findings > 0).count();
+    // 明细只保留有问题或失败的股票
+    let details: Vec<_> = details
+        .into_iter()
+        .filter(|d| !d.success || d.findings > 0 || d.resolved > 0)
+        .collect();
+    Ok(JobOutcome {
+        total_count: processed,
+        success_count,
+        failed_count: details.iter().filter(|d| !d.success).count(),
+        message: Some(format!(
+            "{start} ~ {end}：{with_findings} 只股票共 {findings} 个问题，{resolved} 个已不再出现"
+        )),
+        ..Default::default()
+    }
+    .with_details(&details))
+}
@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::job::{Job, JobContext, JobOutcome, JobStatus};
+use crate::app::DbPool;
+use crate::models::{KlineBackfillRun, UpdateKlineBackfillItem, UpdateKlineBackfillRun};
+use crate::repositories::kline_backfill;
+use crate::services::kline_backfill::{self as backfill, StockBackfill, StockSet};
+use crate::services::trading_calendar::{shanghai_now, TradingDays};
+use crate::utils::http_client;
+
+/// 同时回补的股票数（每只股票按缺口区间串行请求）
+const BACKFILL_CONCURRENCY: usize = 8;
+
+/// 任务参数：`{"run_id": 1}` 继续已创建的回补任务，
+/// 或 `{"stock_set": "all" | "plate" | "watchlist" | "codes", "plate_code": "BK0477", "codes": [...], "start_date": "YYYY-MM-DD", "end_date": "YYYY-MM-DD"}` 新建
+#[derive(Debug, Default, Deserialize)]
+pub struct KlineBackfillParams {
+    pub run_id: Option<i32>,
+    pub stock_set: Option<String>,
+    pub plate_code: Option<String>,
+    pub codes: Option<Vec<String>>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+enum BackfillTarget {
+    Resume(i32),
+    Create(StockSet, NaiveDate, NaiveDate),
+}
+
+impl KlineBackfillParams {
+    fn parse(params: &Value) -> Result<BackfillTarget, String> {
+        let p: KlineBackfillParams =
+            serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+        if let Some(run_id) = p.run_id {
+            return Ok(BackfillTarget::Resume(run_id));
+        }
+        let (Some(stock_set), Some(start), Some(end)) = (p.stock_set, p.start_date, p.end_date)
+        else {
+            return Err(
+                "run_id, or stock_set with start_date and end_date, is required".to_string(),
+            );
+        };
+        let set = StockSet::parse(&stock_set, p.plate_code, p.codes)?;
+        backfill::validate_range(start, end, shanghai_now().date())?;
+        Ok(BackfillTarget::Create(set, start, end))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StockBackfillDetail {
+    stock_code: String,
+    success: bool,
+    inserted_count: usize,
+    expected_days: usize,
+    present_days: usize,
+    missing_days: usize,
+    error: Option<String>,
+}
+
+/// 历史K线回补：按股票集合与日期区间补齐缺失的日K线，仅手动触发
+pub struct KlineBackfillJob;
+
+impl Job for KlineBackfillJob {
+    fn name(&self) -> &'static str {
+        "kline_backfill"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "历史K线回补"
+    }
+
+    fn description(&self) -> &'static str {
+        "按股票集合与日期区间检测并补齐 daily_klines 的缺口，可中断后继续"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "手动触发"
+    }
+
+    fn detach_on_trigger(&self) -> bool {
+        true
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        KlineBackfillParams::parse(params).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_kline_backfill_task(ctx))
+    }
+}
+
+async fn run_kline_backfill_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let run = {
+        let mut conn = db_pool.get()?;
+        match KlineBackfillParams::parse(&ctx.params).map_err(anyhow::Error::msg)? {
+            BackfillTarget::Resume(run_id) => kline_backfill::find_run(&mut conn, run_id)?
+                .ok_or_else(|| anyhow::anyhow!("回补任务 #{run_id} 不存在"))?,
+            BackfillTarget::Create(set, start, end) => {
+                let codes = backfill::resolve_codes(&mut conn, &set)?;
+                if codes.is_empty() {
+                    return Ok(JobOutcome::empty(format!("股票集合 {} 为空", set.as_str())));
+                }
+                backfill::create_run(&mut conn, &set, &codes, start, end)?
+            }
+        }
+    };
+
+    let result = backfill_run(ctx, &run).await;
+    if let Err(e) = &result {
+        update_run(
+            db_pool,
+            run.id,
+            UpdateKlineBackfillRun {
+                status: Some(JobStatus::Failed.as_str().to_string()),
+                error_message: Some(Some(e.to_string())),
+                completed_at: Some(Some(shanghai_now())),
+                ..Default::default()
+            },
+        );
+    }
+    result
+}
+
+async fn backfill_run(ctx: &JobContext, run: &KlineBackfillRun) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let pending: Vec<String> = {
+        let mut conn = db_pool.get()?;
+        kline_backfill::list_items(&mut conn, run.id)?
+            .into_iter()
+            .filter(|item| item.status != "success")
+            .map(|item| item.stock_code)
+            .collect()
+    };
+    if pending.is_empty() {
+        return Ok(JobOutcome::empty(format!(
+            "回补任务 #{} 没有未完成的股票",
+            run.id
+        )));
+    }
+    tracing::info!(
+        "回补任务 #{}: {} ~ {}，待处理 {} / {} 只股票",
+        run.id,
+        run.start_date,
+        run.end_date,
+        pending.len(),
+        run.total_stocks
+    );
+    update_run(
+        db_pool,
+        run.id,
+        UpdateKlineBackfillRun {
+            status: Some(JobStatus::Running.as_str().to_string()),
+            execution_id: ctx.execution_id,
+            error_message: Some(None),
+            completed_at: Some(None),
+            ..Default::default()
+        },
+    );
+
+    let days: Arc<TradingDays> = ctx.trading_calendar.days(db_pool).await;
+    let client = http_client::create_em_client()?;
+    let semaphore = Arc::new(Semaphore::new(BACKFILL_CONCURRENCY));
+    let mut join_set = JoinSet::new();
+    for code in pending.iter().cloned() {
+        let pool = db_pool.clone();
+        let client = client.clone();
+        let days = days.clone();
+        let sem = semaphore.clone();
+        let (start, end) = (run.start_date, run.end_date);
+        join_set.spawn(async move {
+            let _permit = sem.acquire_owned().await;
+            let result = backfill::backfill_stock(&client, &pool, &days, &code, start, end).await;
+            (code, result)
+        });
+    }
+
+    let mut details = Vec::with_capacity(pending.len());
+    while let Some(res) = join_set.join_next().await {
+        match res {
+            Ok((code, result)) => {
+                let detail = record_item(db_pool, run.id, code, result);
+                details.push(detail);
+            }
+            Err(join_err) => tracing::error!("K线回补任务并发执行失败: {}", join_err),
+        }
+        ctx.progress(details.len(), pending.len());
+        if ctx.is_cancelled() {
+            tracing::warn!(
+                "回补任务 #{} 已取消，剩余 {} 只股票可稍后继续",
+                run.id,
+                pending.len() - details.len()
+            );
+            join_set.abort_all();
+            break;
+        }
+    }
+
+    // 汇总全部逐股记录（含此前执行已完成的部分）
+    let items = {
+        let mut conn = db_pool.get()?;
+        kline_backfill::list_items(&mut conn, run.id)?
+    };
+    let completed = items.iter().filter(|i| i.status == "success").count();
+    let failed = items.iter().filter(|i| i.status == "failed").count();
+    let inserted: i32 = items.iter().map(|i| i.inserted_count).sum();
+    let status = if ctx.is_cancelled() {
+        JobStatus::Cancelled
+    } else if completed == items.len() {
+        JobStatus::Success
+    } else if completed > 0 {
+        JobStatus::Partial
+    } else {
+        JobStatus::Failed
+    };
+    update_run(
+        db_pool,
+        run.id,
+        UpdateKlineBackfillRun {
+            status: Some(status.as_str().to_string()),
+            completed_stocks: Some(completed as i32),
+            failed_stocks: Some(failed as i32),
+            inserted_count: Some(inserted),
+            completed_at: Some(Some(shanghai_now())),
+            ..Default::default()
+        },
+    );
+
+    let success_count = details.iter().filter(|d| d.success).count();
+    Ok(JobOutcome {
+        total_count: details.len(),
+        success_count,
+        failed_count: details.len() - success_count,
+        message: Some(format!(
+            "回补任务 #{}：已完成 {}/{} 只股票，累计写入 {} 条K线",
+            run.id,
+            completed,
+            items.len(),
+            inserted
+        )),
+        ..Default::default()
+    }
+    .with_details(&details))
+}
+
+/// 写入单只股票的回补进度，返回执行明细
+fn record_item(
+    db_pool: &DbPool,
+    run_id: i32,
+    code: String,
+    result: anyhow::Result<StockBackfill>,
+) -> StockBackfillDetail {
+    let (update, detail) = match result {
+        Ok(b) => {
+            let error = (!b.errors.is_empty()).then(|| b.errors.join("; "));
+            let status = if error.is_some() { "failed" } else { "success" };
+            let missing_days = b.after.expected_days - b.after.present_days;
+            (
+                UpdateKlineBackfillItem {
+                    status: Some(status.to_string()),
+                    expected_days: Some(b.before.expected_days as i32),
+                    present_before: Some(b.before.present_days as i32),
+                    present_after: Some(b.after.present_days as i32),
+                    inserted_count: Some(b.inserted as i32),
+                    missing_ranges: serde_json::to_value(&b.after.missing_ranges).ok(),
+                    error_message: Some(error.clone()),
+                    updated_at: Some(shanghai_now()),
+                },
+                StockBackfillDetail {
+                    stock_code: code.clone(),
+                    success: error.is_none(),
+                    inserted_count: b.inserted,
+                    expected_days: b.after.expected_days,
+                    present_days: b.after.present_days,
+                    missing_days,
+                    error,
+                },
+            )
+        }
+        Err(e) => {
+            tracing::error!("股票 {} 回补失败: {}", code, e);
+            (
+                UpdateKlineBackfillItem {
+                    status: Some("failed".to_string()),
+                    error_message: Some(Some(e.to_string())),
+                    updated_at: Some(shanghai_now()),
+                    ..Default::default()
+                },
+                StockBackfillDetail {
+                    stock_code: code.clone(),
+                    success: false,
+                    inserted_count: 0,
+                    expected_days: 0,
+                    present_days: 0,
+                    missing_days: 0,
+                    error: Some(e.to_string()),
+                },
+            )
+        }
+    };
+
+    let updated = db_pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            kline_backfill::update_item(&mut conn, run_id, &code, &update)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = updated {
+        tracing::warn!("更新回补任务 #{} 中 {} 的进度失败: {}", run_id, code, e);
+    }
+    detail
+}
+
+fn update_run(db_pool: &DbPool, run_id: i32, mut data: UpdateKlineBackfillRun) {
+    data.updated_at = Some(shanghai_now());
+    let updated = db_pool
+        .get()
+        .map_err(|e| e.to_string())
+        .and_then(|mut conn| {
+            kline_backfill::update_run(&mut conn, run_id, &data).map_err(|e| e.to_string())
+        });
+    if let Err(e) = updated {
+        tracing::warn!("更新回补任务 #{} 失败: {}", run_id, e);
+    }
+}
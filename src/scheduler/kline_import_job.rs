@@ -1,22 +1,15 @@
+use super::job::{Job, JobContext, JobOutcome};
+use super::retry::{self, StockRetryParams};
 use crate::app::DbPool;
 use crate::repositories::stock_snapshot;
 use crate::services::kline_service;
+use crate::services::trading_calendar::shanghai_now;
 use crate::utils::http_client;
-use chrono::Local;
-use chrono_tz::Asia::Shanghai;
+use futures::future::BoxFuture;
+use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
-
-/// K线导入任务执行结果
-#[derive(Debug)]
-pub struct KlineImportResult {
-    pub total_stocks: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub stock_details: Vec<StockImportDetail>,
-}
 
 #[derive(Debug, serde::Serialize)]
 pub struct StockImportDetail {
@@ -26,164 +19,95 @@ pub struct StockImportDetail {
     pub error: Option<String>,
 }
 
-const KLINE_HTTP_CONCURRENCY: usize = 200;
-const KLINE_DB_CONCURRENCY: usize = 200;
+pub(super) const KLINE_HTTP_CONCURRENCY: usize = 200;
+pub(super) const KLINE_DB_CONCURRENCY: usize = 200;
 
+/// 单只股票的导入结果（观察表K线导入共用）
 #[derive(Debug)]
-enum StockImportOutcome {
+pub(super) enum StockImportOutcome {
     Success(StockImportDetail),
     Skipped(StockImportDetail),
     Failed(StockImportDetail),
 }
 
-pub async fn create_kline_import_job(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: crate::utils::ws_broadcast::TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // 创建每天15:01执行的任务（北京时间 UTC+8）
-    // 使用 JobBuilder 设置上海时区（UTC+8）
-    let job = JobBuilder::new()
-        .with_timezone(Shanghai)
-        .with_cron_job_type()
-        .with_schedule("0 1 15 * * *")?
-        .with_run_async(Box::new(move |_uuid, _l| {
-            let pool = db_pool.clone();
-            let sender = ws_sender.clone();
-            Box::pin(async move {
-                // 广播任务开始
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "kline_import".to_string(),
-                    "running".to_string(),
-                );
-
-                match run_kline_import_task(pool).await {
-                    Ok(result) => {
-                        // 广播任务完成
-                        let status = if result.failed_count == 0 {
-                            "success"
-                        } else if result.success_count > 0 {
-                            "partial"
-                        } else {
-                            "failed"
-                        };
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "kline_import".to_string(),
-                            status.to_string(),
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("K线导入任务失败: {}", e);
-                        // 广播任务失败
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "kline_import".to_string(),
-                            "failed".to_string(),
-                        );
-                    }
-                }
-            })
-        }))
-        .build()?;
+/// K线导入：获取当天入库的股票代码并批量导入当日K线（每天 15:01）
+pub struct KlineImportJob;
 
-    scheduler.add(job).await?;
-    tracing::info!("K线导入定时任务已注册（每天北京时间 15:01 执行，使用 Asia/Shanghai 时区）");
-    Ok(())
-}
+impl Job for KlineImportJob {
+    fn name(&self) -> &'static str {
+        "kline_import"
+    }
 
-/// 执行K线导入任务（可以被定时任务或手动触发调用）
-pub async fn run_kline_import_task(db_pool: DbPool) -> anyhow::Result<KlineImportResult> {
-    tracing::info!("开始执行K线导入定时任务");
-
-    let start_time = chrono::Local::now().naive_local();
-    let mut history_id: Option<i32> = None;
-
-    // 记录任务开始
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = crate::models::NewJobExecutionHistory {
-            job_name: "kline_import".to_string(),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
+    fn display_name(&self) -> &'static str {
+        "K线数据导入"
+    }
 
-        match crate::repositories::job_execution_history::create(&mut conn, &new_history) {
-            Ok(history) => {
-                history_id = Some(history.id);
-                tracing::info!("创建任务执行记录，ID: {}", history.id);
-            }
-            Err(e) => {
-                tracing::warn!("创建任务执行记录失败: {}", e);
-            }
-        }
+    fn description(&self) -> &'static str {
+        "自动导入当天的K线数据到数据库"
     }
 
-    // 1. 获取数据库连接
-    let mut conn = db_pool.get()?;
+    fn schedules(&self) -> &'static [&'static str] {
+        &["0 1 15 * * *"]
+    }
 
-    // 2. 获取当天入库的股票代码
-    let stock_codes = stock_snapshot::get_distinct_codes_today(&mut conn)?;
-    tracing::info!("获取到 {} 个股票代码", stock_codes.len());
+    fn schedule_desc(&self) -> &'static str {
+        "每天 15:01"
+    }
 
-    if stock_codes.is_empty() {
-        tracing::info!("今日没有股票代码入库，跳过K线导入");
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        StockRetryParams::parse(params).map(|_| ())
+    }
 
-        // 更新任务完成状态
-        if let Some(id) = history_id {
-            let end_time = chrono::Local::now().naive_local();
-            let duration = (end_time - start_time).num_milliseconds();
-            let mut conn = db_pool.get().ok();
-            if let Some(ref mut c) = conn {
-                let update = crate::models::UpdateJobExecutionHistory {
-                    status: Some("success".to_string()),
-                    completed_at: Some(end_time),
-                    total_count: Some(0),
-                    success_count: Some(0),
-                    failed_count: Some(0),
-                    skipped_count: Some(0),
-                    details: None,
-                    error_message: Some("今日没有股票代码入库".to_string()),
-                    duration_ms: Some(duration),
-                };
-                let _ = crate::repositories::job_execution_history::update(c, id, &update);
-            }
+    fn retryable(&self) -> bool {
+        true
+    }
+
+    fn failed_items(&self, details: &Value) -> Vec<String> {
+        retry::failed_codes(details, |d| d["success"] == Value::Bool(false))
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_kline_import_task(ctx))
+    }
+}
+
+async fn run_kline_import_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let params = StockRetryParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+
+    // 1. 获取当天入库的股票代码（重试时为指定的失败代码）
+    let stock_codes = match params.codes {
+        Some(codes) => codes,
+        None => {
+            let mut conn = db_pool.get()?;
+            stock_snapshot::get_distinct_codes_today(&mut conn, None)?
         }
+    };
+    tracing::info!("获取到 {} 个股票代码", stock_codes.len());
 
-        return Ok(KlineImportResult {
-            total_stocks: 0,
-            success_count: 0,
-            failed_count: 0,
-            stock_details: Vec::new(),
-        });
+    if stock_codes.is_empty() {
+        tracing::info!("今日没有股票代码入库，跳过K线导入");
+        return Ok(JobOutcome::empty("今日没有股票代码入库"));
     }
 
-    // 3. 创建HTTP客户端
+    // 2. 创建HTTP客户端
     let client = http_client::create_em_client()?;
 
-    // 4. 获取当天日期（格式：YYYYMMDD）
-    // 如果是周末，回溯到上一个交易日（周五）
-    let today = get_trading_date();
+    // 3. 获取交易日期（格式：YYYYMMDD）
+    // 非交易日（周末、法定节假日）回溯到上一个交易日
+    let trade_date = ctx
+        .trading_calendar
+        .days(db_pool)
+        .await
+        .trading_day_on_or_before(params.trade_date.unwrap_or_else(|| shanghai_now().date()));
+    let today = trade_date.format("%Y%m%d").to_string();
 
-    // 5. 并发导入K线数据
+    // 4. 并发导入K线数据
     let mut success_count = 0;
     let mut failed_count = 0;
     let mut skipped_count = 0;
     let mut stock_details = Vec::new();
 
-    // 解析交易日期用于检查
-    let trade_date = chrono::NaiveDate::parse_from_str(&today, "%Y%m%d")
-        .map_err(|e| anyhow::anyhow!("日期解析失败: {e}"))?;
-
     let http_semaphore = Arc::new(Semaphore::new(KLINE_HTTP_CONCURRENCY));
     let db_semaphore = Arc::new(Semaphore::new(KLINE_DB_CONCURRENCY));
     let mut join_set = JoinSet::new();
@@ -232,57 +156,28 @@ pub async fn run_kline_import_task(db_pool: DbPool) -> anyhow::Result<KlineImpor
                 tracing::error!("K线导入任务并发执行失败: {}", join_err);
             }
         }
-    }
-
-    tracing::info!(
-        "K线导入任务完成，总计: {}, 成功: {}, 失败: {}, 跳过: {}",
-        stock_codes.len(),
-        success_count,
-        failed_count,
-        skipped_count
-    );
-
-    // 更新任务完成状态
-    if let Some(id) = history_id {
-        let end_time = chrono::Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-        let mut conn = db_pool.get().ok();
-        if let Some(ref mut c) = conn {
-            let status = if failed_count == 0 {
-                "success"
-            } else if success_count > 0 {
-                "partial"
-            } else {
-                "failed"
-            };
-
-            let details_json = serde_json::to_value(&stock_details).ok();
-
-            let update = crate::models::UpdateJobExecutionHistory {
-                status: Some(status.to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(stock_codes.len() as i32),
-                success_count: Some(success_count as i32),
-                failed_count: Some(failed_count as i32),
-                skipped_count: Some(skipped_count),
-                details: details_json,
-                error_message: None,
-                duration_ms: Some(duration),
-            };
-
-            match crate::repositories::job_execution_history::update(c, id, &update) {
-                Ok(_) => tracing::info!("任务执行记录已更新"),
-                Err(e) => tracing::warn!("更新任务执行记录失败: {}", e),
-            }
+        ctx.progress(
+            success_count + failed_count + skipped_count,
+            stock_codes.len(),
+        );
+        if ctx.is_cancelled() {
+            tracing::warn!(
+                "K线导入已取消，放弃剩余 {} 只股票",
+                stock_codes.len() - success_count - failed_count - skipped_count
+            );
+            join_set.abort_all();
+            break;
         }
     }
 
-    Ok(KlineImportResult {
-        total_stocks: stock_codes.len(),
+    Ok(JobOutcome {
+        total_count: stock_codes.len(),
         success_count,
         failed_count,
-        stock_details,
-    })
+        skipped_count,
+        ..Default::default()
+    }
+    .with_details(&stock_details))
 }
 
 async fn import_single_stock_kline(
@@ -325,7 +220,7 @@ async fn import_single_stock_kline(
     Ok(imported_count)
 }
 
-async fn process_single_stock_kline(
+pub(super) async fn process_single_stock_kline(
     client: reqwest::Client,
     stock_code: String,
     today: String,
@@ -408,19 +303,3 @@ async fn process_single_stock_kline(
         }),
     }
 }
-
-/// 获取交易日期：如果是周末则返回上周五，否则返回当天
-fn get_trading_date() -> String {
-    use chrono::{Datelike, Duration, Weekday};
-
-    let now = Local::now();
-    let weekday = now.weekday();
-
-    let trading_date = match weekday {
-        Weekday::Sat => now - Duration::days(1), // 周六 -> 上周五
-        Weekday::Sun => now - Duration::days(2), // 周日 -> 上周五
-        _ => now,                                // 工作日使用当天
-    };
-
-    trading_date.format("%Y%m%d").to_string()
-}
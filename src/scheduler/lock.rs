@@ -0,0 +1,258 @@
+//! 跨实例任务锁：基于 job_locks 表的租约，执行期间后台续期，结束时释放。
+//! 实例崩溃时租约到期自动失效，其他实例可重新获取。
+//! 定时触发另按计划触发时刻在 job_fire_claims 中认领，执行很快的任务也不会被多个实例各跑一次。
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use chrono_tz::Tz;
+use tokio::task::JoinHandle;
+
+use crate::app::DbPool;
+use crate::models::JobLock;
+use crate::repositories::job_lock;
+
+/// 租约时长缺省值（秒），可通过 JOB_LOCK_LEASE_SECS 调整
+const DEFAULT_LEASE_SECS: i32 = 120;
+const MIN_LEASE_SECS: i32 = 15;
+
+/// 向前查找计划触发时刻的窗口（调度器触发延迟的上限）
+const FIRE_LOOKBACK_SECS: i64 = 300;
+/// 触发认领记录保留天数
+const FIRE_CLAIM_RETAIN_DAYS: i32 = 7;
+
+/// 任务已在执行（本实例或其他实例）时的处理策略，对应 job_schedules.conflict_policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// 跳过本次触发（手动触发返回 400）
+    #[default]
+    Skip,
+    /// 等待锁释放后执行
+    Queue,
+    /// 记录一条失败的执行记录
+    Fail,
+}
+
+impl ConflictPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictPolicy::Skip => "skip",
+            ConflictPolicy::Queue => "queue",
+            ConflictPolicy::Fail => "fail",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "skip" => Ok(ConflictPolicy::Skip),
+            "queue" => Ok(ConflictPolicy::Queue),
+            "fail" => Ok(ConflictPolicy::Fail),
+            other => Err(format!(
+                "invalid conflict policy: {other} (expected skip / queue / fail)"
+            )),
+        }
+    }
+}
+
+/// 本实例标识：JOB_INSTANCE_ID，缺省为「主机名-进程号-随机后缀」
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| {
+        std::env::var("JOB_INSTANCE_ID")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| {
+                let host = std::env::var("HOSTNAME")
+                    .or_else(|_| std::env::var("COMPUTERNAME"))
+                    .unwrap_or_else(|_| "local".to_string());
+                let suffix = uuid::Uuid::new_v4().simple().to_string();
+                format!("{}-{}-{}", host, std::process::id(), &suffix[..8])
+            })
+    })
+}
+
+pub(crate) fn lease_secs() -> i32 {
+    std::env::var("JOB_LOCK_LEASE_SECS")
+        .ok()
+        .and_then(|s| s.parse::<i32>().ok())
+        .unwrap_or(DEFAULT_LEASE_SECS)
+        .max(MIN_LEASE_SECS)
+}
+
+/// 锁的描述（用于冲突提示）
+pub fn describe_holder(lock: &JobLock) -> String {
+    let holder = if lock.holder == instance_id() {
+        "当前实例".to_string()
+    } else {
+        format!("实例 {}", lock.holder)
+    };
+    match lock.execution_id {
+        Some(id) => format!("{holder}，执行记录 #{id}，开始于 {}", lock.acquired_at),
+        None => format!("{holder}，开始于 {}", lock.acquired_at),
+    }
+}
+
+/// 查询任务当前的有效锁
+pub fn current_holder(db_pool: &DbPool, job_name: &str) -> Option<JobLock> {
+    let mut conn = db_pool.get().ok()?;
+    job_lock::find_active(&mut conn, job_name).ok().flatten()
+}
+
+/// 本次定时触发对应的计划触发时刻：`now` 之前（含）最近一次 cron 触发时刻。
+/// 调度器触发略有延迟，各实例据此得到相同的时刻；找不到时退回精确到秒的 `now`
+pub fn scheduled_fire_time(cron_expr: &str, tz: Tz, now: DateTime<Utc>) -> DateTime<Utc> {
+    let fallback = now
+        .duration_trunc(ChronoDuration::seconds(1))
+        .unwrap_or(now);
+    let Ok(schedule) = cron::Schedule::from_str(cron_expr) else {
+        return fallback;
+    };
+    let since = (now - ChronoDuration::seconds(FIRE_LOOKBACK_SECS)).with_timezone(&tz);
+    schedule
+        .after(&since)
+        .map(|tick| tick.with_timezone(&Utc))
+        .take_while(|tick| *tick <= now)
+        .last()
+        .unwrap_or(fallback)
+}
+
+/// 认领一次定时触发；同一计划触发时刻已被其他实例认领时返回 `Ok(false)`
+pub fn claim_fire(
+    db_pool: &DbPool,
+    job_name: &str,
+    scheduled_at: DateTime<Utc>,
+) -> anyhow::Result<bool> {
+    let mut conn = db_pool.get()?;
+    Ok(job_lock::try_claim_fire(
+        &mut conn,
+        job_name,
+        scheduled_at,
+        instance_id(),
+        FIRE_CLAIM_RETAIN_DAYS,
+    )?)
+}
+
+/// 持有中的租约，Drop 时停止续期并释放
+pub struct LockLease {
+    db_pool: DbPool,
+    job_name: &'static str,
+    heartbeat: JoinHandle<()>,
+}
+
+impl LockLease {
+    /// 尝试获取锁；已被其他持有者占用时返回 `Ok(None)`
+    pub fn try_acquire(db_pool: &DbPool, job_name: &'static str) -> anyhow::Result<Option<Self>> {
+        let lease = lease_secs();
+        let mut conn = db_pool.get()?;
+        if !job_lock::try_acquire(&mut conn, job_name, instance_id(), lease)? {
+            return Ok(None);
+        }
+
+        let pool = db_pool.clone();
+        let heartbeat = tokio::spawn(async move {
+            let interval = Duration::from_secs((lease / 3) as u64);
+            loop {
+                tokio::time::sleep(interval).await;
+                let renewed = pool.get().map_err(|e| e.to_string()).and_then(|mut conn| {
+                    job_lock::renew(&mut conn, job_name, instance_id(), lease)
+                        .map_err(|e| e.to_string())
+                });
+                match renewed {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::warn!("任务 {} 的锁已失效（租约过期后被其他实例获取）", job_name);
+                        break;
+                    }
+                    Err(e) => tracing::warn!("任务 {} 锁续期失败: {}", job_name, e),
+                }
+            }
+        });
+
+        Ok(Some(Self {
+            db_pool: db_pool.clone(),
+            job_name,
+            heartbeat,
+        }))
+    }
+
+    /// 记录持有者对应的执行记录，便于其他实例提示
+    pub fn attach_execution(&self, execution_id: Option<i32>) {
+        if let Ok(mut conn) = self.db_pool.get() {
+            if let Err(e) =
+                job_lock::set_execution_id(&mut conn, self.job_name, instance_id(), execution_id)
+            {
+                tracing::warn!("更新任务 {} 的锁信息失败: {}", self.job_name, e);
+            }
+        }
+    }
+}
+
+impl Drop for LockLease {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+        let released = self
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_lock::release(&mut conn, self.job_name, instance_id())
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = released {
+            tracing::warn!(
+                "释放任务 {} 的锁失败（将于租约到期后失效）: {}",
+                self.job_name,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conflict_policy() {
+        for policy in [
+            ConflictPolicy::Skip,
+            ConflictPolicy::Queue,
+            ConflictPolicy::Fail,
+        ] {
+            assert_eq!(ConflictPolicy::parse(policy.as_str()), Ok(policy));
+        }
+        assert!(ConflictPolicy::parse("wait").is_err());
+    }
+
+    #[test]
+    fn resolves_scheduled_fire_time_despite_delay() {
+        use chrono::TimeZone;
+
+        let tz = chrono_tz::Asia::Shanghai;
+        let at = |h, m, s| {
+            tz.with_ymd_and_hms(2026, 10, 19, h, m, s)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        // 每分钟执行的任务，两个实例分别在 0.4 秒与 2 秒后触发，得到同一时刻
+        let every_minute = "0 * 9-15 * * Mon-Fri";
+        let early = at(9, 31, 0) + ChronoDuration::milliseconds(400);
+        assert_eq!(scheduled_fire_time(every_minute, tz, early), at(9, 31, 0));
+        assert_eq!(
+            scheduled_fire_time(every_minute, tz, at(9, 31, 2)),
+            at(9, 31, 0)
+        );
+        // 下一分钟的触发是不同的时刻
+        assert_eq!(
+            scheduled_fire_time(every_minute, tz, at(9, 32, 1)),
+            at(9, 32, 0)
+        );
+        // 窗口内没有计划时刻时退回当前秒
+        assert_eq!(
+            scheduled_fire_time("0 0 15 * * Mon-Fri", tz, early),
+            at(9, 31, 0)
+        );
+    }
+}
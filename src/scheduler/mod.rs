@@ -1,6 +1,43 @@
+pub mod ai_batch_analysis_job;
+pub mod ai_market_report_job;
+pub mod ai_prediction_evaluation_job;
+pub mod auction_capture_job;
+pub mod convertible_bond_sync_job;
+pub mod job;
+pub mod kline_audit_job;
+pub mod kline_backfill_job;
 pub mod kline_import_job;
+pub mod lock;
+pub mod pipeline;
 pub mod profit_analysis_job;
+pub mod registry;
+pub mod retry;
 pub mod stock_filter_job;
+pub mod stock_master_sync_job;
 pub mod stock_plate_sync_job;
 pub mod stock_table_sync_job;
 pub mod watchlist_kline_job;
+
+use std::sync::Arc;
+
+use job::Job;
+
+/// 全部定时任务（顺序即任务列表的展示顺序）
+pub fn all_jobs() -> Vec<Arc<dyn Job>> {
+    vec![
+        Arc::new(kline_import_job::KlineImportJob),
+        Arc::new(stock_master_sync_job::StockMasterSyncJob),
+        Arc::new(stock_table_sync_job::StockTableSyncJob),
+        Arc::new(stock_plate_sync_job::StockPlateSyncJob),
+        Arc::new(convertible_bond_sync_job::ConvertibleBondSyncJob),
+        Arc::new(profit_analysis_job::ProfitAnalysisJob),
+        Arc::new(auction_capture_job::AuctionCaptureJob),
+        Arc::new(stock_filter_job::StockFilterJob),
+        Arc::new(watchlist_kline_job::WatchlistKlineJob),
+        Arc::new(kline_backfill_job::KlineBackfillJob),
+        Arc::new(ai_batch_analysis_job::AiBatchAnalysisJob),
+        Arc::new(kline_audit_job::KlineAuditJob),
+        Arc::new(ai_prediction_evaluation_job::AiPredictionEvaluationJob),
+        Arc::new(ai_market_report_job::AiMarketReportJob),
+    ]
+}
@@ -0,0 +1,203 @@
+//! 任务依赖（DAG）：下游任务通过 [`super::job::Job::depends_on`] 声明上游，
+//! 上游执行完成后由注册表按拓扑顺序依次执行下游，整条链路记为一次流水线执行（job_pipeline_runs）。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::job::{Job, JobStatus};
+
+/// 流水线中单个步骤的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Success,
+    Partial,
+    Failed,
+    /// 上游失败，未执行
+    Blocked,
+    /// 任务已停用、上游被跳过或锁冲突，未执行
+    Skipped,
+    /// 执行中被取消
+    Cancelled,
+}
+
+impl StepStatus {
+    /// 下游是否可以继续执行
+    pub fn allows_dependents(&self) -> bool {
+        matches!(self, StepStatus::Success | StepStatus::Partial)
+    }
+}
+
+impl From<JobStatus> for StepStatus {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Running => StepStatus::Running,
+            JobStatus::Success => StepStatus::Success,
+            JobStatus::Partial => StepStatus::Partial,
+            JobStatus::Failed | JobStatus::Aborted => StepStatus::Failed,
+            JobStatus::Cancelled => StepStatus::Cancelled,
+        }
+    }
+}
+
+/// 流水线步骤，原样写入 job_pipeline_runs.steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStep {
+    pub job_name: String,
+    pub status: StepStatus,
+    /// 对应的 job_execution_history 记录 ID（未执行时为空）
+    pub execution_id: Option<i32>,
+    pub duration_ms: Option<i64>,
+    /// 未执行或失败的原因
+    pub reason: Option<String>,
+}
+
+impl PipelineStep {
+    pub fn pending(job_name: &str) -> Self {
+        Self {
+            job_name: job_name.to_string(),
+            status: StepStatus::Pending,
+            execution_id: None,
+            duration_ms: None,
+            reason: None,
+        }
+    }
+}
+
+/// 是否有任务直接依赖 `name`
+pub fn has_dependents(jobs: &[Arc<dyn Job>], name: &str) -> bool {
+    jobs.iter().any(|j| j.depends_on().contains(&name))
+}
+
+/// 以 `root` 为起点的流水线执行顺序（含 root），只包含从 root 可达的下游任务。
+/// 下游任务在其全部（位于流水线内的）上游之后执行，同层按任务注册顺序。
+pub fn execution_order(jobs: &[Arc<dyn Job>], root: &'static str) -> Vec<&'static str> {
+    // 收集可达的下游
+    let mut reachable: HashSet<&'static str> = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(current) = queue.pop_front() {
+        for job in jobs {
+            if job.depends_on().contains(&current) && reachable.insert(job.name()) {
+                queue.push_back(job.name());
+            }
+        }
+    }
+
+    // 在可达子图上做拓扑排序
+    let mut indegree: HashMap<&'static str, usize> = jobs
+        .iter()
+        .filter(|j| reachable.contains(j.name()))
+        .map(|j| {
+            let n = j
+                .depends_on()
+                .iter()
+                .filter(|d| reachable.contains(*d) && **d != j.name())
+                .count();
+            (j.name(), n)
+        })
+        .collect();
+    let mut order = Vec::with_capacity(indegree.len());
+    let mut ready: VecDeque<&'static str> = VecDeque::from([root]);
+    indegree.insert(root, 0);
+    while let Some(current) = ready.pop_front() {
+        order.push(current);
+        for job in jobs {
+            if !job.depends_on().contains(&current) {
+                continue;
+            }
+            if let Some(n) = indegree.get_mut(job.name()) {
+                if *n > 0 {
+                    *n -= 1;
+                    if *n == 0 {
+                        ready.push_back(job.name());
+                    }
+                }
+            }
+        }
+    }
+    order
+}
+
+/// 校验依赖声明：上游必须存在且不能成环
+pub fn validate_dependencies(jobs: &[Arc<dyn Job>]) -> Result<(), String> {
+    let names: HashSet<&'static str> = jobs.iter().map(|j| j.name()).collect();
+    for job in jobs {
+        for dep in job.depends_on() {
+            if !names.contains(dep) {
+                return Err(format!("{} depends on unknown job {}", job.name(), dep));
+            }
+        }
+    }
+    // 每个任务都应出现在以自己为起点的顺序中一次，且其上游不应再出现在其下游里
+    for job in jobs {
+        let order = execution_order(jobs, job.name());
+        let reachable: HashSet<&str> = order.iter().copied().collect();
+        if job.depends_on().iter().any(|d| reachable.contains(d)) {
+            return Err(format!("dependency cycle involving {}", job.name()));
+        }
+    }
+    Ok(())
+}
+
+/// 按已执行步骤判断某个下游能否执行：返回 `Err` 时为未执行的状态与原因
+pub fn check_upstream(job: &dyn Job, steps: &[PipelineStep]) -> Result<(), (StepStatus, String)> {
+    for dep in job.depends_on() {
+        let Some(step) = steps.iter().find(|s| s.job_name == *dep) else {
+            continue;
+        };
+        match step.status {
+            s if s.allows_dependents() => {}
+            StepStatus::Failed | StepStatus::Blocked => {
+                return Err((StepStatus::Blocked, format!("上游任务 {dep} 未成功")));
+            }
+            StepStatus::Cancelled => {
+                return Err((StepStatus::Skipped, format!("上游任务 {dep} 已取消")));
+            }
+            _ => return Err((StepStatus::Skipped, format!("上游任务 {dep} 未执行"))),
+        }
+    }
+    Ok(())
+}
+
+/// 汇总流水线状态：全部成功为 success，有步骤被取消为 cancelled，首个步骤失败为 failed，其余为 partial
+pub fn overall_status(steps: &[PipelineStep]) -> JobStatus {
+    if steps.iter().all(|s| s.status == StepStatus::Success) {
+        JobStatus::Success
+    } else if steps.iter().any(|s| s.status == StepStatus::Cancelled) {
+        JobStatus::Cancelled
+    } else if steps.first().is_some_and(|s| !s.status.allows_dependents()) {
+        JobStatus::Failed
+    } else {
+        JobStatus::Partial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_end_of_day_order() {
+        let jobs = super::super::all_jobs();
+        validate_dependencies(&jobs).unwrap();
+        assert_eq!(
+            execution_order(&jobs, "kline_import"),
+            vec![
+                "kline_import",
+                "profit_analysis",
+                "kline_audit",
+                "ai_prediction_evaluation",
+                "ai_market_report"
+            ]
+        );
+        assert_eq!(
+            execution_order(&jobs, "stock_table_sync"),
+            vec!["stock_table_sync", "stock_plate_sync"]
+        );
+        assert_eq!(execution_order(&jobs, "stock_filter"), vec!["stock_filter"]);
+    }
+}
@@ -1,23 +1,13 @@
 use bigdecimal::BigDecimal;
-use chrono::{Datelike, Local, NaiveDate, Weekday};
-use chrono_tz::Asia::Shanghai;
+use chrono::{Local, NaiveDate};
+use futures::future::BoxFuture;
 use std::str::FromStr;
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
+use super::job::{Job, JobContext, JobOutcome, JobStatus};
 use crate::app::DbPool;
 use crate::models::{NewProfitAnalysis, StockSnapshot};
 use crate::repositories::{daily_kline, profit_analysis, stock_request, stock_snapshot};
 
-/// 盈利分析任务执行结果
-#[derive(Debug)]
-pub struct ProfitAnalysisResult {
-    pub total_snapshots: usize,
-    pub analyzed_count: usize,
-    pub skipped_count: usize,
-    pub no_kline_count: usize,
-    pub snapshot_details: Vec<SnapshotAnalysisDetail>,
-}
-
 #[derive(Debug, serde::Serialize)]
 pub struct SnapshotAnalysisDetail {
     pub stock_code: String,
@@ -27,146 +17,73 @@ pub struct SnapshotAnalysisDetail {
     pub error: Option<String>,
 }
 
-/// 创建盈利分析定时任务（每天15:40执行，北京时间 UTC+8）
-pub async fn create_profit_analysis_job(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: crate::utils::ws_broadcast::TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // 创建每天15:40执行的任务（北京时间 UTC+8）
-    // 使用 JobBuilder 设置上海时区（UTC+8）
-    let job = JobBuilder::new()
-        .with_timezone(Shanghai)
-        .with_cron_job_type()
-        .with_schedule("0 40 15 * * *")?
-        .with_run_async(Box::new(move |_uuid, _l| {
-            let pool = db_pool.clone();
-            let sender = ws_sender.clone();
-            Box::pin(async move {
-                // 广播任务开始
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "profit_analysis".to_string(),
-                    "running".to_string(),
-                );
-
-                match run_profit_analysis_task(pool).await {
-                    Ok(result) => {
-                        // 广播任务完成
-                        let status = if result.analyzed_count > 0 || result.skipped_count > 0 {
-                            "success"
-                        } else {
-                            "failed"
-                        };
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "profit_analysis".to_string(),
-                            status.to_string(),
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("盈利分析任务失败: {}", e);
-                        // 广播任务失败
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "profit_analysis".to_string(),
-                            "failed".to_string(),
-                        );
-                    }
-                }
-            })
-        }))
-        .build()?;
+/// 盈利分析：分析昨日快照与今日K线的盈利情况（K线导入完成后执行）
+pub struct ProfitAnalysisJob;
 
-    scheduler.add(job).await?;
-    tracing::info!("盈利分析定时任务已注册（每天北京时间 15:40 执行，使用 Asia/Shanghai 时区）");
-    Ok(())
-}
+impl Job for ProfitAnalysisJob {
+    fn name(&self) -> &'static str {
+        "profit_analysis"
+    }
 
-/// 执行盈利分析任务（可以被定时任务或手动触发调用）
-pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitAnalysisResult> {
-    tracing::info!("开始执行盈利分析任务");
+    fn display_name(&self) -> &'static str {
+        "盈利分析"
+    }
 
-    let start_time = chrono::Local::now().naive_local();
-    let mut history_id: Option<i32> = None;
+    fn description(&self) -> &'static str {
+        "分析股票快照的盈利情况"
+    }
 
-    // 记录任务开始
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = crate::models::NewJobExecutionHistory {
-            job_name: "profit_analysis".to_string(),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
+    }
 
-        match crate::repositories::job_execution_history::create(&mut conn, &new_history) {
-            Ok(history) => {
-                history_id = Some(history.id);
-                tracing::info!("创建任务执行记录，ID: {}", history.id);
-            }
-            Err(e) => {
-                tracing::warn!("创建任务执行记录失败: {}", e);
-            }
-        }
+    fn schedule_desc(&self) -> &'static str {
+        "K线导入完成后"
     }
 
-    // 1. 获取数据库连接
-    let mut conn = db_pool.get()?;
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["kline_import"]
+    }
 
-    // 2. 查找所有 time_range_end 为空的请求（待处理）
-    let pending_requests = stock_request::find_pending_requests(&mut conn)?;
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_profit_analysis_task(ctx))
+    }
+}
+
+async fn run_profit_analysis_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+
+    // 1. 查找所有 time_range_end 为空的请求（待处理）
+    let pending_requests = {
+        let mut conn = db_pool.get()?;
+        stock_request::find_pending_requests(&mut conn)?
+    };
     tracing::info!("找到 {} 个待处理的请求", pending_requests.len());
 
     if pending_requests.is_empty() {
         tracing::info!("没有待处理的请求，跳过盈利分析");
-
-        // 更新任务完成状态
-        if let Some(id) = history_id {
-            let end_time = chrono::Local::now().naive_local();
-            let duration = (end_time - start_time).num_milliseconds();
-            let mut conn = db_pool.get().ok();
-            if let Some(ref mut c) = conn {
-                let update = crate::models::UpdateJobExecutionHistory {
-                    status: Some("success".to_string()),
-                    completed_at: Some(end_time),
-                    total_count: Some(0),
-                    success_count: Some(0),
-                    failed_count: Some(0),
-                    skipped_count: Some(0),
-                    details: None,
-                    error_message: Some("没有待处理的请求".to_string()),
-                    duration_ms: Some(duration),
-                };
-                let _ = crate::repositories::job_execution_history::update(c, id, &update);
-            }
-        }
-
-        return Ok(ProfitAnalysisResult {
-            total_snapshots: 0,
-            analyzed_count: 0,
-            skipped_count: 0,
-            no_kline_count: 0,
-            snapshot_details: Vec::new(),
-        });
+        return Ok(JobOutcome::empty("没有待处理的请求"));
     }
 
-    // 3. 遍历每个请求，处理其下的快照
+    // 2. 遍历每个请求，处理其下的快照
+    let trading_days = ctx.trading_calendar.days(db_pool).await;
     let mut total_snapshots = 0;
     let mut analyzed_count = 0;
     let mut skipped_count = 0;
     let mut no_kline_count = 0;
     let mut snapshot_details = Vec::new();
 
-    for request in pending_requests.iter() {
-        // 3.1 检查 time_range_start 是否存在
+    for (request_idx, request) in pending_requests.iter().enumerate() {
+        ctx.progress(request_idx, pending_requests.len());
+        if ctx.is_cancelled() {
+            tracing::warn!(
+                "盈利分析已取消，剩余 {} 个请求未处理",
+                pending_requests.len() - request_idx
+            );
+            break;
+        }
+
+        // 2.1 检查 time_range_start 是否存在
         let time_range_start = match request.time_range_start {
             Some(start) => start,
             None => {
@@ -175,8 +92,8 @@ pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitA
             }
         };
 
-        // 3.2 计算 K线日期 = time_range_start + 1 天（智能处理周末）
-        let kline_date = get_next_trading_date(time_range_start);
+        // 2.2 计算 K线日期 = time_range_start 之后的下一个交易日（跳过周末与法定节假日）
+        let kline_date = trading_days.next_trading_day(time_range_start);
         tracing::info!(
             "请求 {}: time_range_start={}, K线日期={}",
             request.id,
@@ -184,7 +101,7 @@ pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitA
             kline_date
         );
 
-        // 3.3 获取该请求下的所有快照
+        // 2.3 获取该请求下的所有快照
         let mut conn = db_pool.get()?;
         let snapshots = stock_snapshot::find_by_request_id(&mut conn, request.id)?;
         tracing::info!("请求 {} 下有 {} 个快照", request.id, snapshots.len());
@@ -196,9 +113,9 @@ pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitA
 
         total_snapshots += snapshots.len();
 
-        // 3.4 遍历快照，计算盈利指标
+        // 2.4 遍历快照，计算盈利指标
         for snapshot in snapshots.iter() {
-            let result = analyze_single_snapshot(&db_pool, snapshot, kline_date).await;
+            let result = analyze_single_snapshot(db_pool, snapshot, kline_date).await;
 
             match result {
                 Ok(detail) => {
@@ -230,7 +147,7 @@ pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitA
             }
         }
 
-        // 3.5 更新该请求的 time_range_end
+        // 2.5 更新该请求的 time_range_end
         let today = Local::now().date_naive();
         let mut conn = db_pool.get()?;
         if let Err(e) = stock_request::update_time_range_end(&mut conn, request.id, today) {
@@ -240,54 +157,24 @@ pub async fn run_profit_analysis_task(db_pool: DbPool) -> anyhow::Result<ProfitA
         }
     }
 
-    tracing::info!(
-        "盈利分析任务完成，总计: {}, 分析: {}, 跳过: {}, 无K线: {}",
-        total_snapshots,
-        analyzed_count,
-        skipped_count,
-        no_kline_count
-    );
-
-    // 更新任务完成状态
-    if let Some(id) = history_id {
-        let end_time = chrono::Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-        let mut conn = db_pool.get().ok();
-        if let Some(ref mut c) = conn {
-            let status = if analyzed_count > 0 || skipped_count > 0 {
-                "success"
-            } else {
-                "failed"
-            };
-
-            let details_json = serde_json::to_value(&snapshot_details).ok();
-
-            let update = crate::models::UpdateJobExecutionHistory {
-                status: Some(status.to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(total_snapshots as i32),
-                success_count: Some(analyzed_count as i32),
-                failed_count: Some(0),
-                skipped_count: Some(skipped_count as i32),
-                details: details_json,
-                error_message: None,
-                duration_ms: Some(duration),
-            };
-
-            match crate::repositories::job_execution_history::update(c, id, &update) {
-                Ok(_) => tracing::info!("任务执行记录已更新"),
-                Err(e) => tracing::warn!("更新任务执行记录失败: {}", e),
-            }
-        }
-    }
+    // 无K线的快照既不算成功也不算失败：有分析或跳过即视为成功
+    let status = if analyzed_count > 0 || skipped_count > 0 || total_snapshots == 0 {
+        JobStatus::Success
+    } else {
+        JobStatus::Failed
+    };
+    let failed_count = snapshot_details.iter().filter(|d| !d.success).count();
 
-    Ok(ProfitAnalysisResult {
-        total_snapshots,
-        analyzed_count,
+    Ok(JobOutcome {
+        total_count: total_snapshots,
+        success_count: analyzed_count,
+        failed_count,
         skipped_count,
-        no_kline_count,
-        snapshot_details,
-    })
+        message: (no_kline_count > 0).then(|| format!("{no_kline_count} 个快照无K线数据")),
+        status: Some(status),
+        ..Default::default()
+    }
+    .with_details(&snapshot_details))
 }
 
 /// 分析单个快照的盈利情况
@@ -382,15 +269,3 @@ async fn analyze_single_snapshot(
         error: None,
     })
 }
-
-/// 获取下一个交易日期：基于给定日期 + 1 天，如果是周末则顺延到周一
-fn get_next_trading_date(base_date: NaiveDate) -> NaiveDate {
-    let next_day = base_date + chrono::Days::new(1);
-    let weekday = next_day.weekday();
-
-    match weekday {
-        Weekday::Sat => next_day + chrono::Days::new(2), // 周六 -> 周一
-        Weekday::Sun => next_day + chrono::Days::new(1), // 周日 -> 周一
-        _ => next_day,                                   // 工作日直接使用
-    }
-}
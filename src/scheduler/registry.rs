@@ -0,0 +1,1337 @@
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use futures::FutureExt;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tokio_cron_scheduler::{JobBuilder, JobScheduler};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::job::{Job, JobContext, JobOutcome, JobStatus, JobTrigger};
+use super::lock::{self, ConflictPolicy, LockLease};
+use super::pipeline::{self, PipelineStep, StepStatus};
+use super::retry::{self, Backoff, StockRetryParams};
+use crate::app::DbPool;
+use crate::models::{
+    JobLock, JobSchedule, NewJobExecutionHistory, NewJobPipelineRun, NewJobSchedule,
+    UpdateJobExecutionHistory, UpdateJobPipelineRun, UpdateJobSchedule,
+};
+use crate::repositories::{job_execution_history, job_lock, job_pipeline_run, job_schedule};
+use crate::services::trading_calendar::TradingCalendar;
+use crate::utils::ws_broadcast::{broadcast_task_status, TaskStatusSender};
+
+/// job_schedules 缺省时区
+pub const DEFAULT_TIMEZONE: &str = "Asia/Shanghai";
+
+/// 校验 cron 表达式与时区，返回解析后的时区
+pub fn validate_schedule(cron_expressions: &[String], timezone: &str) -> Result<Tz, String> {
+    let tz: Tz = timezone
+        .parse()
+        .map_err(|_| format!("invalid timezone: {timezone}"))?;
+    for cron_expr in cron_expressions {
+        JobBuilder::new()
+            .with_cron_job_type()
+            .with_schedule(cron_expr.as_str())
+            .map_err(|_| format!("invalid cron expression: {cron_expr}"))?;
+    }
+    Ok(tz)
+}
+
+/// 按给定时区计算多条 cron 表达式在 `after` 之后最早的触发时间，无法解析的表达式忽略
+pub fn next_fire_time(
+    cron_expressions: &[String],
+    tz: Tz,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let after = after.with_timezone(&tz);
+    cron_expressions
+        .iter()
+        .filter_map(|expr| cron::Schedule::from_str(expr).ok())
+        .filter_map(|schedule| schedule.after(&after).next())
+        .map(|tick| tick.with_timezone(&Utc))
+        .min()
+}
+
+/// 遗留执行中需要标记为 aborted 的部分：仍被有效锁引用的记录属于其他存活实例，排除在外
+fn stale_execution_ids(running: &[i32], active_locks: &[JobLock]) -> Vec<i32> {
+    let held: HashSet<i32> = active_locks.iter().filter_map(|l| l.execution_id).collect();
+    running
+        .iter()
+        .copied()
+        .filter(|id| !held.contains(id))
+        .collect()
+}
+
+#[derive(Debug, Error)]
+pub enum TriggerError {
+    #[error("unknown job: {0}")]
+    NotFound(String),
+    #[error("job {0} is already running ({1})")]
+    AlreadyRunning(&'static str, String),
+    #[error("invalid params: {0}")]
+    InvalidParams(String),
+    #[error("execution {0} not found")]
+    ExecutionNotFound(i32),
+    #[error("cannot retry: {0}")]
+    NotRetryable(String),
+    #[error("execution {0} is not running")]
+    NotRunning(i32),
+}
+
+/// 单次执行的结果
+#[derive(Debug)]
+pub struct JobExecution {
+    pub execution_id: Option<i32>,
+    pub status: JobStatus,
+    pub duration_ms: i64,
+    /// 任务返回错误或 panic 时为空
+    pub outcome: Option<JobOutcome>,
+    pub error: Option<String>,
+}
+
+/// 手动触发的结果
+#[derive(Debug)]
+pub enum TriggerResult {
+    /// 同步执行完成
+    Finished(JobExecution),
+    /// 已在后台执行
+    Detached,
+    /// 任务正在执行，按 queue 策略排队等待
+    Queued,
+}
+
+/// 自动重试的缺省退避秒数（对应 job_schedules.retry_backoff_secs 的默认值）
+const DEFAULT_RETRY_BACKOFF_SECS: i32 = 300;
+/// 自动重试退避的翻倍上限（2^6 倍）
+const AUTO_RETRY_MAX_EXP: u32 = 6;
+
+/// 排队等待锁释放时的轮询间隔
+const QUEUE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// 排队等待的最长时间缺省值（秒），可通过 JOB_LOCK_QUEUE_TIMEOUT_SECS 调整
+const DEFAULT_QUEUE_TIMEOUT_SECS: u64 = 3600;
+
+/// 执行中轮询 job_execution_history.cancel_requested 的间隔（其他实例发起的取消经此生效）
+const CANCEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 已注册到调度器的 cron（按任务名），热加载时整体替换
+#[derive(Default)]
+struct CronState {
+    scheduler: Option<JobScheduler>,
+    cron_ids: HashMap<&'static str, Vec<Uuid>>,
+    /// 已注册的 cron 表达式及时区，用于计算下次触发时间
+    schedules: HashMap<&'static str, (Vec<String>, Tz)>,
+}
+
+struct RegistryInner {
+    jobs: Vec<Arc<dyn Job>>,
+    running: Mutex<HashSet<&'static str>>,
+    crons: tokio::sync::Mutex<CronState>,
+    /// 本实例执行中的任务的取消令牌（按执行记录 ID）
+    cancels: Mutex<HashMap<i32, CancellationToken>>,
+    /// 本进程的启动时间，早于此时间仍为 running 的记录来自已退出的进程
+    booted_at: NaiveDateTime,
+    db_pool: DbPool,
+    ws_sender: TaskStatusSender,
+    trading_calendar: TradingCalendar,
+}
+
+/// 任务注册表：定时调度、手动触发与任务列表共用
+#[derive(Clone)]
+pub struct JobRegistry {
+    inner: Arc<RegistryInner>,
+}
+
+/// 运行标记，释放时清除，保证同一任务不会重叠执行。
+/// 本实例内用内存标记，跨实例用 job_locks 租约（数据库不可用时仅保留内存标记）
+struct RunningGuard {
+    inner: Arc<RegistryInner>,
+    name: &'static str,
+    lease: Option<LockLease>,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        // 先释放分布式锁，再清除内存标记
+        drop(self.lease.take());
+        if let Ok(mut running) = self.inner.running.lock() {
+            running.remove(self.name);
+        }
+    }
+}
+
+impl JobRegistry {
+    pub fn new(
+        db_pool: DbPool,
+        ws_sender: TaskStatusSender,
+        trading_calendar: TradingCalendar,
+        jobs: Vec<Arc<dyn Job>>,
+    ) -> Self {
+        if let Err(e) = pipeline::validate_dependencies(&jobs) {
+            tracing::error!("任务依赖声明有误: {}", e);
+        }
+        Self {
+            inner: Arc::new(RegistryInner {
+                jobs,
+                running: Mutex::new(HashSet::new()),
+                crons: tokio::sync::Mutex::new(CronState::default()),
+                cancels: Mutex::new(HashMap::new()),
+                booted_at: Local::now().naive_local(),
+                db_pool,
+                ws_sender,
+                trading_calendar,
+            }),
+        }
+    }
+
+    pub fn jobs(&self) -> &[Arc<dyn Job>] {
+        &self.inner.jobs
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Job>> {
+        self.inner.jobs.iter().find(|j| j.name() == name).cloned()
+    }
+
+    pub fn is_running(&self, name: &str) -> bool {
+        self.inner
+            .running
+            .lock()
+            .map(|running| running.contains(name))
+            .unwrap_or(false)
+    }
+
+    /// 获取运行标记；冲突时返回当前持有者的描述
+    fn try_acquire(&self, name: &'static str) -> Result<RunningGuard, String> {
+        let inserted = self
+            .inner
+            .running
+            .lock()
+            .map(|mut running| running.insert(name))
+            .unwrap_or(false);
+        if !inserted {
+            return Err(lock::current_holder(&self.inner.db_pool, name)
+                .map(|l| lock::describe_holder(&l))
+                .unwrap_or_else(|| "当前实例".to_string()));
+        }
+        let mut guard = RunningGuard {
+            inner: self.inner.clone(),
+            name,
+            lease: None,
+        };
+
+        match LockLease::try_acquire(&self.inner.db_pool, name) {
+            Ok(Some(lease)) => guard.lease = Some(lease),
+            Ok(None) => {
+                return Err(lock::current_holder(&self.inner.db_pool, name)
+                    .map(|l| lock::describe_holder(&l))
+                    .unwrap_or_else(|| "其他实例".to_string()));
+            }
+            Err(e) => tracing::warn!("获取任务 {} 的分布式锁失败，仅使用本实例互斥: {}", name, e),
+        }
+        Ok(guard)
+    }
+
+    /// 排队等待锁释放，超时返回 None
+    async fn acquire_queued(&self, name: &'static str) -> Option<RunningGuard> {
+        let timeout = std::env::var("JOB_LOCK_QUEUE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_QUEUE_TIMEOUT_SECS);
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(timeout);
+        loop {
+            if let Ok(guard) = self.try_acquire(name) {
+                return Some(guard);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+        }
+    }
+
+    fn stored_schedule(&self, name: &str) -> Option<JobSchedule> {
+        let mut conn = self.inner.db_pool.get().ok()?;
+        job_schedule::find_by_job_name(&mut conn, name)
+            .ok()
+            .flatten()
+    }
+
+    fn conflict_policy(&self, name: &str) -> ConflictPolicy {
+        self.stored_schedule(name)
+            .and_then(|s| ConflictPolicy::parse(&s.conflict_policy).ok())
+            .unwrap_or_default()
+    }
+
+    /// 按冲突策略获取运行标记；skip / fail 时返回 None（fail 会写入一条失败记录）
+    async fn acquire_with_policy(&self, job: &dyn Job, holder: String) -> Option<RunningGuard> {
+        let name = job.name();
+        match self.conflict_policy(name) {
+            ConflictPolicy::Skip => {
+                tracing::warn!("{} 正在执行（{}），跳过本次触发", name, holder);
+                None
+            }
+            ConflictPolicy::Fail => {
+                tracing::warn!("{} 正在执行（{}），本次触发记为失败", name, holder);
+                self.record_conflict(name, &holder);
+                None
+            }
+            ConflictPolicy::Queue => {
+                tracing::info!("{} 正在执行（{}），排队等待", name, holder);
+                let guard = self.acquire_queued(name).await;
+                if guard.is_none() {
+                    tracing::warn!("{} 排队等待超时，放弃本次触发", name);
+                    self.record_conflict(name, &format!("排队等待超时，{holder}"));
+                }
+                guard
+            }
+        }
+    }
+
+    fn context(&self, job: &dyn Job, trigger: JobTrigger, params: Value) -> JobContext {
+        JobContext::new(
+            job.name(),
+            self.inner.db_pool.clone(),
+            self.inner.trading_calendar.clone(),
+            self.inner.ws_sender.clone(),
+            trigger,
+            params,
+        )
+    }
+
+    /// 代码中的默认调度配置
+    pub fn default_schedule(job: &dyn Job) -> NewJobSchedule {
+        NewJobSchedule {
+            job_name: job.name().to_string(),
+            enabled: true,
+            cron_expressions: job.schedules().iter().map(|c| c.to_string()).collect(),
+            timezone: DEFAULT_TIMEZONE.to_string(),
+            params: None,
+            conflict_policy: ConflictPolicy::default().as_str().to_string(),
+            retry_max_attempts: 0,
+            retry_backoff_secs: DEFAULT_RETRY_BACKOFF_SECS,
+        }
+    }
+
+    /// 恢复默认配置时写入 job_schedules 的变更（全部字段覆盖为默认值）
+    pub fn reset_to_default(job: &dyn Job, now: NaiveDateTime) -> UpdateJobSchedule {
+        let default = Self::default_schedule(job);
+        UpdateJobSchedule {
+            enabled: Some(default.enabled),
+            cron_expressions: Some(default.cron_expressions),
+            timezone: Some(default.timezone),
+            params: Some(default.params),
+            updated_at: Some(now),
+            conflict_policy: Some(default.conflict_policy),
+            retry_max_attempts: Some(default.retry_max_attempts),
+            retry_backoff_secs: Some(default.retry_backoff_secs),
+        }
+    }
+
+    /// 按 job_schedules 将全部任务注册到调度器：缺失的任务先按默认配置写入，
+    /// 数据库不可用时退回默认配置。单个任务注册失败只记录日志。
+    /// 同时将上次进程遗留的 running 记录标记为 aborted
+    pub async fn start(&self, scheduler: JobScheduler) {
+        self.abort_stale_executions();
+        // 上次进程持有的租约尚未到期时对应记录会被跳过，租约过期后再清理一次
+        let registry = self.clone();
+        tokio::spawn(async move {
+            let wait = std::time::Duration::from_secs(lock::lease_secs() as u64 + 5);
+            tokio::time::sleep(wait).await;
+            registry.abort_stale_executions();
+        });
+
+        let defaults: Vec<NewJobSchedule> = self
+            .jobs()
+            .iter()
+            .map(|j| Self::default_schedule(j.as_ref()))
+            .collect();
+        let stored = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_schedule::insert_missing(&mut conn, &defaults).map_err(|e| e.to_string())?;
+                job_schedule::list_all(&mut conn).map_err(|e| e.to_string())
+            });
+        let stored = stored.unwrap_or_else(|e| {
+            tracing::warn!("读取任务调度配置失败，使用默认配置: {}", e);
+            Vec::new()
+        });
+
+        let mut crons = self.inner.crons.lock().await;
+        crons.scheduler = Some(scheduler);
+        for (job, default) in self.jobs().iter().zip(defaults) {
+            let (enabled, cron_expressions, timezone, params) =
+                match stored.iter().find(|s| s.job_name == job.name()) {
+                    Some(s) => (
+                        s.enabled,
+                        s.cron_expressions.clone(),
+                        s.timezone.clone(),
+                        s.params.clone(),
+                    ),
+                    None => (
+                        default.enabled,
+                        default.cron_expressions,
+                        default.timezone,
+                        default.params,
+                    ),
+                };
+            if let Err(e) = self
+                .apply_schedule(
+                    &mut crons,
+                    job.clone(),
+                    enabled,
+                    &cron_expressions,
+                    &timezone,
+                    params,
+                )
+                .await
+            {
+                tracing::error!("创建{}定时任务失败: {}", job.display_name(), e);
+            }
+        }
+    }
+
+    /// 重新读取某个任务的 job_schedules 配置并替换调度器中的 cron
+    pub async fn reload(&self, name: &str) -> anyhow::Result<()> {
+        let job = self
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown job: {name}"))?;
+        let schedule: JobSchedule = {
+            let mut conn = self.inner.db_pool.get()?;
+            job_schedule::find_by_job_name(&mut conn, name)?
+                .ok_or_else(|| anyhow::anyhow!("job_schedules 中没有任务 {name}"))?
+        };
+        let mut crons = self.inner.crons.lock().await;
+        self.apply_schedule(
+            &mut crons,
+            job,
+            schedule.enabled,
+            &schedule.cron_expressions,
+            &schedule.timezone,
+            schedule.params,
+        )
+        .await
+    }
+
+    async fn apply_schedule(
+        &self,
+        crons: &mut CronState,
+        job: Arc<dyn Job>,
+        enabled: bool,
+        cron_expressions: &[String],
+        timezone: &str,
+        params: Option<Value>,
+    ) -> anyhow::Result<()> {
+        let name = job.name();
+        let tz = validate_schedule(cron_expressions, timezone).map_err(anyhow::Error::msg)?;
+        let scheduler = crons
+            .scheduler
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("调度器尚未启动"))?;
+
+        crons.schedules.remove(name);
+        for uuid in crons.cron_ids.remove(name).unwrap_or_default() {
+            if let Err(e) = scheduler.remove(&uuid).await {
+                tracing::warn!("移除 {} 的旧 cron 失败: {}", name, e);
+            }
+        }
+        if !enabled {
+            tracing::info!("{}定时任务已停用", job.display_name());
+            return Ok(());
+        }
+
+        let params = params.unwrap_or(Value::Null);
+        let mut ids = Vec::with_capacity(cron_expressions.len());
+        for cron_expr in cron_expressions {
+            let registry = self.clone();
+            let run_job = job.clone();
+            let params = params.clone();
+            let expr = cron_expr.clone();
+            let cron_job = JobBuilder::new()
+                .with_timezone(tz)
+                .with_cron_job_type()
+                .with_schedule(cron_expr.as_str())?
+                .with_run_async(Box::new(move |_uuid, _l| {
+                    let registry = registry.clone();
+                    let job = run_job.clone();
+                    let params = params.clone();
+                    let scheduled_at = lock::scheduled_fire_time(&expr, tz, Utc::now());
+                    Box::pin(async move { registry.run_scheduled(job, params, scheduled_at).await })
+                }))
+                .build()?;
+            ids.push(scheduler.add(cron_job).await?);
+            tracing::info!(
+                "{}定时任务已注册: {} ({})",
+                job.display_name(),
+                cron_expr,
+                timezone
+            );
+        }
+        crons.cron_ids.insert(name, ids);
+        crons
+            .schedules
+            .insert(name, (cron_expressions.to_vec(), tz));
+        Ok(())
+    }
+
+    /// 将本进程启动前开始、仍为 running 且不被有效锁引用的执行记录标记为 aborted
+    fn abort_stale_executions(&self) {
+        match self.mark_stale_aborted() {
+            Ok(0) => {}
+            Ok(n) => tracing::warn!("{} 条上次进程遗留的执行记录已标记为 aborted", n),
+            Err(e) => tracing::warn!("清理遗留的执行记录失败: {}", e),
+        }
+    }
+
+    fn mark_stale_aborted(&self) -> anyhow::Result<usize> {
+        let mut conn = self.inner.db_pool.get()?;
+        let running = job_execution_history::list_running_before(&mut conn, self.inner.booted_at)?;
+        if running.is_empty() {
+            return Ok(0);
+        }
+        let locks = job_lock::list_active(&mut conn)?;
+        Ok(job_execution_history::mark_aborted(
+            &mut conn,
+            &stale_execution_ids(&running, &locks),
+            Local::now().naive_local(),
+            "服务重启，执行中断",
+        )?)
+    }
+
+    /// 取消一次执行：置位 cancel_requested，执行所在实例在批次之间停止任务。
+    /// 返回任务是否在本实例执行（其他实例最迟在一个轮询间隔后生效）
+    pub fn cancel_execution(&self, execution_id: i32) -> Result<bool, TriggerError> {
+        let mut conn = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|_| TriggerError::ExecutionNotFound(execution_id))?;
+        let history = job_execution_history::find_by_id(&mut conn, execution_id)
+            .map_err(|_| TriggerError::ExecutionNotFound(execution_id))?;
+        if history.status != JobStatus::Running.as_str() {
+            return Err(TriggerError::NotRunning(execution_id));
+        }
+        match job_execution_history::request_cancel(&mut conn, execution_id) {
+            Ok(true) => {}
+            Ok(false) => return Err(TriggerError::NotRunning(execution_id)),
+            Err(e) => tracing::warn!("记录执行 #{} 的取消请求失败: {}", execution_id, e),
+        }
+
+        tracing::info!("请求取消{}任务的执行 #{}", history.job_name, execution_id);
+        Ok(self.signal_cancel(execution_id))
+    }
+
+    /// 触发本实例登记的取消令牌，返回该执行是否在本实例进行
+    fn signal_cancel(&self, execution_id: i32) -> bool {
+        let token = self
+            .inner
+            .cancels
+            .lock()
+            .ok()
+            .and_then(|cancels| cancels.get(&execution_id).cloned());
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 登记取消令牌并轮询数据库中的取消标记，返回轮询任务的句柄
+    fn watch_cancel(&self, execution_id: i32, token: CancellationToken) -> JoinHandle<()> {
+        if let Ok(mut cancels) = self.inner.cancels.lock() {
+            cancels.insert(execution_id, token.clone());
+        }
+        let pool = self.inner.db_pool.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(CANCEL_POLL_INTERVAL) => {}
+                }
+                let requested = pool.get().ok().and_then(|mut conn| {
+                    job_execution_history::is_cancel_requested(&mut conn, execution_id).ok()
+                });
+                if requested == Some(true) {
+                    token.cancel();
+                    break;
+                }
+            }
+        })
+    }
+
+    fn unwatch_cancel(&self, execution_id: i32, watcher: JoinHandle<()>) {
+        watcher.abort();
+        if let Ok(mut cancels) = self.inner.cancels.lock() {
+            cancels.remove(&execution_id);
+        }
+    }
+
+    /// 调度器中该任务最近一次的触发时间（停用或未注册时为空）
+    pub async fn next_run(&self, name: &str) -> Option<DateTime<Utc>> {
+        let crons = self.inner.crons.lock().await;
+        let (cron_expressions, tz) = crons.schedules.get(name)?;
+        next_fire_time(cron_expressions, *tz, Utc::now())
+    }
+
+    async fn run_scheduled(&self, job: Arc<dyn Job>, params: Value, scheduled_at: DateTime<Utc>) {
+        let name = job.name();
+        // 多实例各自的调度器都会触发，每个计划触发时刻只由认领成功的实例执行
+        match lock::claim_fire(&self.inner.db_pool, name, scheduled_at) {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!("{} 的触发 {} 已由其他实例认领，跳过", name, scheduled_at);
+                return;
+            }
+            Err(e) => {
+                tracing::error!("认领{}任务的触发失败，跳过本次: {}", job.display_name(), e);
+                return;
+            }
+        }
+        if !self
+            .inner
+            .trading_calendar
+            .is_trading_day_today(&self.inner.db_pool)
+            .await
+        {
+            tracing::info!("今日非交易日，跳过{}任务", job.display_name());
+            return;
+        }
+
+        let ctx = self.context(job.as_ref(), JobTrigger::Scheduled, params);
+        if !job.should_run(&ctx).await {
+            tracing::debug!("{} 本次触发条件不满足，跳过", name);
+            return;
+        }
+
+        let guard = match self.try_acquire(name) {
+            Ok(guard) => guard,
+            Err(holder) => match self.acquire_with_policy(job.as_ref(), holder).await {
+                Some(guard) => guard,
+                None => return,
+            },
+        };
+        if pipeline::has_dependents(self.jobs(), name) {
+            self.run_pipeline(job, ctx, guard).await;
+        } else {
+            self.execute(job, ctx, guard).await;
+        }
+    }
+
+    /// 手动触发。`detach` 为 true 时后台执行并立即返回；`cascade` 为 true 时连同下游任务
+    /// 按流水线在后台执行。任务正在执行时按冲突策略处理（queue 策略在后台排队，不阻塞请求）
+    pub async fn trigger(
+        &self,
+        name: &str,
+        params: Value,
+        detach: bool,
+        cascade: bool,
+    ) -> Result<TriggerResult, TriggerError> {
+        let job = self
+            .get(name)
+            .ok_or_else(|| TriggerError::NotFound(name.to_string()))?;
+        job.validate_params(&params)
+            .map_err(TriggerError::InvalidParams)?;
+        let ctx = self.context(job.as_ref(), JobTrigger::Manual, params);
+        self.dispatch(job, ctx, detach, cascade).await
+    }
+
+    /// 重试一次历史执行：支持按项重试的任务只处理其中的失败项，
+    /// 整体失败（任务报错）的执行按原参数重新执行。有下游的任务按流水线在后台重试，
+    /// 使原先因上游失败而 blocked 的下游随之执行
+    pub async fn retry_execution(
+        &self,
+        execution_id: i32,
+        detach: bool,
+    ) -> Result<TriggerResult, TriggerError> {
+        let history = self
+            .inner
+            .db_pool
+            .get()
+            .ok()
+            .and_then(|mut conn| job_execution_history::find_by_id(&mut conn, execution_id).ok())
+            .ok_or(TriggerError::ExecutionNotFound(execution_id))?;
+        let job = self
+            .get(&history.job_name)
+            .ok_or_else(|| TriggerError::NotFound(history.job_name.clone()))?;
+        let original = history.params.clone().unwrap_or(Value::Null);
+        let params = build_retry_params(
+            job.as_ref(),
+            &history.status,
+            &original,
+            history.details.as_ref(),
+            history.started_at,
+        )
+        .map_err(TriggerError::NotRetryable)?;
+
+        tracing::info!("重试{}任务的执行记录 #{}", job.display_name(), execution_id);
+        let mut ctx = self.context(job.as_ref(), JobTrigger::Manual, params);
+        ctx.attempt = history.attempt + 1;
+        ctx.retry_of = Some(execution_id);
+        self.dispatch(job, ctx, detach, true).await
+    }
+
+    async fn dispatch(
+        &self,
+        job: Arc<dyn Job>,
+        ctx: JobContext,
+        detach: bool,
+        cascade: bool,
+    ) -> Result<TriggerResult, TriggerError> {
+        let cascade = cascade && pipeline::has_dependents(self.jobs(), job.name());
+
+        let guard = match self.try_acquire(job.name()) {
+            Ok(guard) => guard,
+            Err(holder) => match self.conflict_policy(job.name()) {
+                ConflictPolicy::Queue => {
+                    let registry = self.clone();
+                    tokio::spawn(async move {
+                        if let Some(guard) =
+                            registry.acquire_with_policy(job.as_ref(), holder).await
+                        {
+                            if cascade {
+                                registry.run_pipeline(job, ctx, guard).await;
+                            } else {
+                                registry.execute(job, ctx, guard).await;
+                            }
+                        }
+                    });
+                    return Ok(TriggerResult::Queued);
+                }
+                ConflictPolicy::Fail => {
+                    self.record_conflict(job.name(), &holder);
+                    return Err(TriggerError::AlreadyRunning(job.name(), holder));
+                }
+                ConflictPolicy::Skip => {
+                    return Err(TriggerError::AlreadyRunning(job.name(), holder))
+                }
+            },
+        };
+
+        if cascade {
+            let registry = self.clone();
+            tokio::spawn(async move {
+                registry.run_pipeline(job, ctx, guard).await;
+            });
+            return Ok(TriggerResult::Detached);
+        }
+        if detach {
+            let registry = self.clone();
+            tokio::spawn(async move {
+                registry.execute(job, ctx, guard).await;
+            });
+            return Ok(TriggerResult::Detached);
+        }
+        Ok(TriggerResult::Finished(self.execute(job, ctx, guard).await))
+    }
+
+    /// 执行 root 及其全部下游任务：按依赖顺序逐个执行，上游失败的下游记为 blocked，
+    /// 停用或锁冲突的下游记为 skipped；各步骤状态写入 job_pipeline_runs。返回 root 的执行结果
+    async fn run_pipeline(
+        &self,
+        root: Arc<dyn Job>,
+        ctx: JobContext,
+        guard: RunningGuard,
+    ) -> JobExecution {
+        let trigger = ctx.trigger;
+        let order = pipeline::execution_order(self.jobs(), root.name());
+        let mut steps: Vec<PipelineStep> = order
+            .iter()
+            .map(|name| PipelineStep::pending(name))
+            .collect();
+        let start_time = Local::now().naive_local();
+        let run_id = self.create_pipeline_run(root.name(), trigger, start_time, &steps);
+        tracing::info!("开始执行任务流水线: {}", order.join(" -> "));
+
+        steps[0].status = StepStatus::Running;
+        self.update_pipeline_run(run_id, &steps, None);
+        let root_execution = self.execute(root, ctx, guard).await;
+        finish_step(&mut steps[0], &root_execution);
+        self.update_pipeline_run(run_id, &steps, None);
+
+        for i in 1..order.len() {
+            let Some(job) = self.get(order[i]) else {
+                continue;
+            };
+            if let Err((status, reason)) = pipeline::check_upstream(job.as_ref(), &steps[..i]) {
+                tracing::warn!("流水线步骤 {} 未执行: {}", job.name(), reason);
+                steps[i].status = status;
+                steps[i].reason = Some(reason);
+                self.update_pipeline_run(run_id, &steps, None);
+                continue;
+            }
+
+            let schedule = self.stored_schedule(job.name());
+            if schedule.as_ref().is_some_and(|s| !s.enabled) {
+                tracing::info!("流水线步骤 {} 已停用，跳过", job.name());
+                steps[i].status = StepStatus::Skipped;
+                steps[i].reason = Some("任务已停用".to_string());
+                self.update_pipeline_run(run_id, &steps, None);
+                continue;
+            }
+            let params = schedule.and_then(|s| s.params).unwrap_or(Value::Null);
+            let ctx = self.context(job.as_ref(), trigger, params);
+
+            let guard = match self.try_acquire(job.name()) {
+                Ok(guard) => guard,
+                Err(holder) => match self.acquire_with_policy(job.as_ref(), holder.clone()).await {
+                    Some(guard) => guard,
+                    None => {
+                        steps[i].status = StepStatus::Skipped;
+                        steps[i].reason = Some(format!("任务正在执行（{holder}）"));
+                        self.update_pipeline_run(run_id, &steps, None);
+                        continue;
+                    }
+                },
+            };
+            steps[i].status = StepStatus::Running;
+            self.update_pipeline_run(run_id, &steps, None);
+            let execution = self.execute(job, ctx, guard).await;
+            finish_step(&mut steps[i], &execution);
+            self.update_pipeline_run(run_id, &steps, None);
+        }
+
+        let status = pipeline::overall_status(&steps);
+        let end_time = Local::now().naive_local();
+        self.update_pipeline_run(
+            run_id,
+            &steps,
+            Some((status, end_time, (end_time - start_time).num_milliseconds())),
+        );
+        tracing::info!(
+            "任务流水线 {} 完成 [{}]",
+            order.join(" -> "),
+            status.as_str()
+        );
+        root_execution
+    }
+
+    fn create_pipeline_run(
+        &self,
+        root: &str,
+        trigger: JobTrigger,
+        started_at: chrono::NaiveDateTime,
+        steps: &[PipelineStep],
+    ) -> Option<i32> {
+        let data = NewJobPipelineRun {
+            root_job: root.to_string(),
+            trigger_type: trigger.as_str().to_string(),
+            status: JobStatus::Running.as_str().to_string(),
+            steps: serde_json::to_value(steps).unwrap_or_default(),
+            started_at,
+        };
+        let created = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_pipeline_run::create(&mut conn, &data).map_err(|e| e.to_string())
+            });
+        match created {
+            Ok(run) => Some(run.id),
+            Err(e) => {
+                tracing::warn!("创建流水线执行记录失败: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 更新流水线步骤；`finished` 为最终状态、结束时间与耗时
+    fn update_pipeline_run(
+        &self,
+        run_id: Option<i32>,
+        steps: &[PipelineStep],
+        finished: Option<(JobStatus, chrono::NaiveDateTime, i64)>,
+    ) {
+        let Some(id) = run_id else {
+            return;
+        };
+        let data = UpdateJobPipelineRun {
+            status: finished.map(|(status, _, _)| status.as_str().to_string()),
+            steps: serde_json::to_value(steps).ok(),
+            completed_at: finished.map(|(_, at, _)| at),
+            duration_ms: finished.map(|(_, _, ms)| ms),
+        };
+        let updated = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_pipeline_run::update(&mut conn, id, &data).map_err(|e| e.to_string())
+            });
+        if let Err(e) = updated {
+            tracing::warn!("更新流水线执行记录失败: {}", e);
+        }
+    }
+
+    async fn execute(
+        &self,
+        job: Arc<dyn Job>,
+        mut ctx: JobContext,
+        guard: RunningGuard,
+    ) -> JobExecution {
+        let name = job.name();
+        tracing::info!("开始执行{}任务（{:?}）", job.display_name(), ctx.trigger);
+        broadcast_task_status(
+            &self.inner.ws_sender,
+            name.to_string(),
+            JobStatus::Running.as_str().to_string(),
+        );
+
+        let start_time = Local::now().naive_local();
+        ctx.execution_id = self.create_history(&ctx, start_time);
+        if let Some(lease) = &guard.lease {
+            lease.attach_execution(ctx.execution_id);
+        }
+        let watcher = ctx
+            .execution_id
+            .map(|id| (id, self.watch_cancel(id, ctx.cancel.clone())));
+
+        let result = AssertUnwindSafe(job.run(&ctx)).catch_unwind().await;
+        let end_time = Local::now().naive_local();
+        let duration_ms = (end_time - start_time).num_milliseconds();
+        if let Some((id, watcher)) = watcher {
+            self.unwatch_cancel(id, watcher);
+        }
+
+        let mut execution = match result {
+            Ok(Ok(outcome)) => JobExecution {
+                execution_id: ctx.execution_id,
+                status: outcome.status(),
+                duration_ms,
+                outcome: Some(outcome),
+                error: None,
+            },
+            Ok(Err(e)) => JobExecution {
+                execution_id: ctx.execution_id,
+                status: JobStatus::Failed,
+                duration_ms,
+                outcome: None,
+                error: Some(e.to_string()),
+            },
+            Err(panic) => {
+                let msg = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                JobExecution {
+                    execution_id: ctx.execution_id,
+                    status: JobStatus::Failed,
+                    duration_ms,
+                    outcome: None,
+                    error: Some(format!("任务 panic: {msg}")),
+                }
+            }
+        };
+        if ctx.is_cancelled() {
+            execution.status = JobStatus::Cancelled;
+            if execution.error.is_none() {
+                execution.error = Some("执行已取消".to_string());
+            }
+        }
+
+        match (&execution.outcome, &execution.error) {
+            (Some(o), _) => tracing::info!(
+                "{}任务完成 [{}]，总计: {}, 成功: {}, 失败: {}, 跳过: {}, 耗时 {} ms",
+                job.display_name(),
+                execution.status.as_str(),
+                o.total_count,
+                o.success_count,
+                o.failed_count,
+                o.skipped_count,
+                duration_ms
+            ),
+            (None, Some(e)) => tracing::error!("{}任务失败: {}", job.display_name(), e),
+            (None, None) => {}
+        }
+
+        if let Some(id) = ctx.execution_id {
+            self.finish_history(id, end_time, &execution);
+        }
+        drop(guard);
+        broadcast_task_status(
+            &self.inner.ws_sender,
+            name.to_string(),
+            execution.status.as_str().to_string(),
+        );
+        self.schedule_auto_retry(&job, &ctx, &execution, start_time);
+        execution
+    }
+
+    /// 按 job_schedules.retry_* 在退避后自动重试失败（或部分失败）的执行；
+    /// 有下游的任务按流水线重试，下游随之重新执行
+    fn schedule_auto_retry(
+        &self,
+        job: &Arc<dyn Job>,
+        ctx: &JobContext,
+        execution: &JobExecution,
+        started_at: chrono::NaiveDateTime,
+    ) {
+        if matches!(execution.status, JobStatus::Success | JobStatus::Cancelled) {
+            return;
+        }
+        let Some(schedule) = self.stored_schedule(job.name()) else {
+            return;
+        };
+        if ctx.attempt > schedule.retry_max_attempts {
+            return;
+        }
+        let details = execution.outcome.as_ref().and_then(|o| o.details.as_ref());
+        let params = match build_retry_params(
+            job.as_ref(),
+            execution.status.as_str(),
+            &ctx.params,
+            details,
+            started_at,
+        ) {
+            Ok(params) => params,
+            Err(reason) => {
+                tracing::debug!("{} 不自动重试: {}", job.name(), reason);
+                return;
+            }
+        };
+
+        let backoff = Backoff {
+            base: std::time::Duration::from_secs(schedule.retry_backoff_secs.max(1) as u64),
+            max_exp: AUTO_RETRY_MAX_EXP,
+            jitter_ms: 0,
+        };
+        let delay = backoff.delay(ctx.attempt as u32);
+        tracing::info!(
+            "{}任务将在 {} 秒后自动重试（第 {}/{} 次）",
+            job.display_name(),
+            delay.as_secs(),
+            ctx.attempt,
+            schedule.retry_max_attempts
+        );
+
+        let registry = self.clone();
+        let job = job.clone();
+        let trigger = ctx.trigger;
+        let attempt = ctx.attempt + 1;
+        let retry_of = ctx.execution_id;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let mut ctx = registry.context(job.as_ref(), trigger, params);
+            ctx.attempt = attempt;
+            ctx.retry_of = retry_of;
+            let guard = match registry.try_acquire(job.name()) {
+                Ok(guard) => guard,
+                Err(holder) => match registry.acquire_with_policy(job.as_ref(), holder).await {
+                    Some(guard) => guard,
+                    None => return,
+                },
+            };
+            if pipeline::has_dependents(registry.jobs(), job.name()) {
+                registry.run_pipeline(job, ctx, guard).await;
+            } else {
+                registry.execute(job, ctx, guard).await;
+            }
+        });
+    }
+
+    /// fail 策略下记录一条因锁冲突而失败的执行记录
+    fn record_conflict(&self, name: &str, holder: &str) {
+        let now = Local::now().naive_local();
+        let new_history = NewJobExecutionHistory {
+            job_name: name.to_string(),
+            status: JobStatus::Failed.as_str().to_string(),
+            started_at: now,
+            completed_at: Some(now),
+            total_count: 0,
+            success_count: 0,
+            failed_count: 0,
+            skipped_count: 0,
+            details: None,
+            error_message: Some(format!("任务正在执行，未启动本次执行: {holder}")),
+            duration_ms: Some(0),
+            attempt: 1,
+            retry_of: None,
+            params: None,
+        };
+        let created = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_execution_history::create(&mut conn, &new_history).map_err(|e| e.to_string())
+            });
+        if let Err(e) = created {
+            tracing::warn!("记录任务 {} 的冲突失败: {}", name, e);
+        }
+    }
+
+    fn create_history(&self, ctx: &JobContext, started_at: chrono::NaiveDateTime) -> Option<i32> {
+        let new_history = NewJobExecutionHistory {
+            job_name: ctx.job_name().to_string(),
+            status: JobStatus::Running.as_str().to_string(),
+            started_at,
+            completed_at: None,
+            total_count: 0,
+            success_count: 0,
+            failed_count: 0,
+            skipped_count: 0,
+            details: None,
+            error_message: None,
+            duration_ms: None,
+            attempt: ctx.attempt,
+            retry_of: ctx.retry_of,
+            params: Some(ctx.params.clone()).filter(|p| !p.is_null()),
+        };
+        let created = self
+            .inner
+            .db_pool
+            .get()
+            .map_err(|e| e.to_string())
+            .and_then(|mut conn| {
+                job_execution_history::create(&mut conn, &new_history).map_err(|e| e.to_string())
+            });
+        match created {
+            Ok(history) => {
+                tracing::debug!("创建任务执行记录，ID: {}", history.id);
+                Some(history.id)
+            }
+            Err(e) => {
+                tracing::warn!("创建任务执行记录失败: {}", e);
+                None
+            }
+        }
+    }
+
+    fn finish_history(
+        &self,
+        id: i32,
+        completed_at: chrono::NaiveDateTime,
+        execution: &JobExecution,
+    ) {
+        let outcome = execution.outcome.as_ref();
+        let count = |f: fn(&JobOutcome) -> usize| Some(outcome.map(f).unwrap_or(0) as i32);
+        let update = UpdateJobExecutionHistory {
+            status: Some(execution.status.as_str().to_string()),
+            completed_at: Some(completed_at),
+            total_count: count(|o| o.total_count),
+            success_count: count(|o| o.success_count),
+            failed_count: match outcome {
+                Some(o) => Some(o.failed_count as i32),
+                None => Some(1),
+            },
+            skipped_count: count(|o| o.skipped_count),
+            details: outcome.and_then(|o| o.details.clone()),
+            error_message: execution
+                .error
+                .clone()
+                .or_else(|| outcome.and_then(|o| o.message.clone())),
+            duration_ms: Some(execution.duration_ms),
+        };
+        let Ok(mut conn) = self.inner.db_pool.get() else {
+            tracing::warn!("更新任务执行记录失败: 无法获取数据库连接");
+            return;
+        };
+        if let Err(e) = job_execution_history::update(&mut conn, id, &update) {
+            tracing::warn!("更新任务执行记录失败: {}", e);
+        }
+    }
+}
+
+fn finish_step(step: &mut PipelineStep, execution: &JobExecution) {
+    step.status = execution.status.into();
+    step.execution_id = execution.execution_id;
+    step.duration_ms = Some(execution.duration_ms);
+    step.reason = execution
+        .error
+        .clone()
+        .or_else(|| execution.outcome.as_ref().and_then(|o| o.message.clone()));
+}
+
+/// 生成重试参数：支持按项重试的任务只处理失败项（沿用原执行的交易日），
+/// 任务整体报错时按原参数重新执行
+fn build_retry_params(
+    job: &dyn Job,
+    status: &str,
+    original: &Value,
+    details: Option<&Value>,
+    started_at: chrono::NaiveDateTime,
+) -> Result<Value, String> {
+    if status == JobStatus::Success.as_str() {
+        return Err("执行成功，没有需要重试的失败项".to_string());
+    }
+    if status == JobStatus::Running.as_str() {
+        return Err("执行尚未结束".to_string());
+    }
+    // 被取消或中断的执行未处理完全部数据，按原参数重新执行
+    if status == JobStatus::Cancelled.as_str() || status == JobStatus::Aborted.as_str() {
+        return Ok(original.clone());
+    }
+    if job.retryable() {
+        if let Some(details) = details {
+            let trade_date = StockRetryParams::parse(original)
+                .ok()
+                .and_then(|p| p.trade_date)
+                .unwrap_or(started_at.date());
+            if let Some(params) = retry::stock_retry_params(job.failed_items(details), trade_date) {
+                return Ok(params);
+            }
+        }
+    }
+    if status == JobStatus::Failed.as_str() && details.is_none() {
+        return Ok(original.clone());
+    }
+    Err("没有可重试的失败项".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+    use futures::future::BoxFuture;
+
+    use super::*;
+
+    struct DummyJob;
+
+    impl Job for DummyJob {
+        fn name(&self) -> &'static str {
+            "dummy"
+        }
+
+        fn display_name(&self) -> &'static str {
+            "测试任务"
+        }
+
+        fn description(&self) -> &'static str {
+            ""
+        }
+
+        fn schedules(&self) -> &'static [&'static str] {
+            &["0 30 9 * * Mon-Fri", "0 0 15 * * Mon-Fri"]
+        }
+
+        fn schedule_desc(&self) -> &'static str {
+            ""
+        }
+
+        fn run<'a>(&'a self, _ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+            Box::pin(async { Ok(JobOutcome::default()) })
+        }
+    }
+
+    fn shanghai(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        chrono_tz::Asia::Shanghai
+            .with_ymd_and_hms(y, m, d, h, min, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn next_fire_time_picks_earliest_in_timezone() {
+        let crons: Vec<String> = DummyJob.schedules().iter().map(|c| c.to_string()).collect();
+        let tz = chrono_tz::Asia::Shanghai;
+
+        // 周五 10:00（北京时间）之后最早的是当天 15:00
+        let next = next_fire_time(&crons, tz, shanghai(2026, 10, 16, 10, 0));
+        assert_eq!(next, Some(shanghai(2026, 10, 16, 15, 0)));
+        // 周五收盘后跳过周末到周一 09:30
+        let next = next_fire_time(&crons, tz, shanghai(2026, 10, 16, 15, 0));
+        assert_eq!(next, Some(shanghai(2026, 10, 19, 9, 30)));
+        // 同一表达式按 UTC 解释时触发时刻不同
+        let next = next_fire_time(&crons, chrono_tz::UTC, shanghai(2026, 10, 16, 10, 0));
+        assert_eq!(
+            next,
+            Some(Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap())
+        );
+        assert_eq!(next_fire_time(&["bogus".to_string()], tz, Utc::now()), None);
+    }
+
+    fn test_registry() -> JobRegistry {
+        use diesel::r2d2::{ConnectionManager, Pool};
+
+        // 不建立连接：下面的用例只涉及内存中的取消令牌
+        let db_pool =
+            Pool::builder().build_unchecked(ConnectionManager::new("postgres://localhost/unused"));
+        let (ws_sender, _) = tokio::sync::broadcast::channel(1);
+        JobRegistry::new(db_pool, ws_sender, TradingCalendar::default(), Vec::new())
+    }
+
+    #[test]
+    fn stale_executions_exclude_active_locks() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        let lock = |job_name: &str, execution_id| JobLock {
+            job_name: job_name.to_string(),
+            holder: "other-instance".to_string(),
+            execution_id,
+            acquired_at: now,
+            lease_until: now,
+        };
+        let locks = vec![lock("kline_import", Some(2)), lock("stock_filter", None)];
+
+        assert_eq!(stale_execution_ids(&[1, 2, 3], &locks), vec![1, 3]);
+        assert_eq!(stale_execution_ids(&[2], &locks), Vec::<i32>::new());
+        assert_eq!(stale_execution_ids(&[1, 3], &[]), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn cancel_reaches_watched_execution_only() {
+        let registry = test_registry();
+        let token = CancellationToken::new();
+        let watcher = registry.watch_cancel(7, token.clone());
+
+        // 未在本实例登记的执行不受影响
+        assert!(!registry.signal_cancel(8));
+        assert!(!token.is_cancelled());
+
+        assert!(registry.signal_cancel(7));
+        assert!(token.is_cancelled());
+        // 令牌取消后轮询任务随之结束
+        tokio::time::timeout(std::time::Duration::from_secs(1), watcher)
+            .await
+            .expect("watcher should stop after cancel")
+            .unwrap();
+
+        let watcher = registry.watch_cancel(9, CancellationToken::new());
+        registry.unwatch_cancel(9, watcher);
+        assert!(!registry.signal_cancel(9));
+    }
+
+    #[test]
+    fn reset_to_default_overwrites_every_field() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let data = JobRegistry::reset_to_default(&DummyJob, now);
+
+        assert_eq!(data.enabled, Some(true));
+        assert_eq!(
+            data.cron_expressions,
+            Some(vec![
+                "0 30 9 * * Mon-Fri".to_string(),
+                "0 0 15 * * Mon-Fri".to_string()
+            ])
+        );
+        assert_eq!(data.timezone.as_deref(), Some(DEFAULT_TIMEZONE));
+        // params 显式置空而非保持不变
+        assert_eq!(data.params, Some(None));
+        assert_eq!(
+            data.conflict_policy.as_deref(),
+            Some(ConflictPolicy::default().as_str())
+        );
+        assert_eq!(data.retry_max_attempts, Some(0));
+        assert_eq!(data.retry_backoff_secs, Some(DEFAULT_RETRY_BACKOFF_SECS));
+        assert_eq!(data.updated_at, Some(now));
+        assert!(validate_schedule(&data.cron_expressions.unwrap(), DEFAULT_TIMEZONE).is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+//! 失败重试：指数退避，以及从执行明细中提取失败项（用于「只重试失败项」与自动重试）
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 指数退避：第 n 次重试等待 `base * 2^(n-1)`（指数上限 `max_exp`），另加随机抖动
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max_exp: u32,
+    pub jitter_ms: u64,
+}
+
+impl Backoff {
+    /// `retry` 从 1 开始
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = retry.saturating_sub(1).min(self.max_exp);
+        let jitter = if self.jitter_ms > 0 {
+            rand::thread_rng().gen_range(0..=self.jitter_ms)
+        } else {
+            0
+        };
+        self.base.saturating_mul(1 << exp) + Duration::from_millis(jitter)
+    }
+}
+
+/// 按股票处理的任务的参数：指定股票代码（缺省为任务自身的股票范围）与交易日（缺省为当天）。
+/// 「重试失败项」即以失败的代码和原执行的交易日构造该参数
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StockRetryParams {
+    pub codes: Option<Vec<String>>,
+    pub trade_date: Option<NaiveDate>,
+}
+
+impl StockRetryParams {
+    pub fn parse(params: &Value) -> Result<Self, String> {
+        if params.is_null() {
+            return Ok(Self::default());
+        }
+        let parsed: Self = serde_json::from_value(params.clone()).map_err(|e| e.to_string())?;
+        if parsed.codes.as_ref().is_some_and(|c| c.is_empty()) {
+            return Err("codes must not be empty".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+/// 从明细数组中取出失败项的 stock_code
+pub fn failed_codes(details: &Value, is_failed: impl Fn(&Value) -> bool) -> Vec<String> {
+    details
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| is_failed(item))
+                .filter_map(|item| item.get("stock_code").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 生成只处理失败项的参数，没有失败项时返回 None
+pub fn stock_retry_params(codes: Vec<String>, trade_date: NaiveDate) -> Option<Value> {
+    if codes.is_empty() {
+        return None;
+    }
+    serde_json::to_value(StockRetryParams {
+        codes: Some(codes),
+        trade_date: Some(trade_date),
+    })
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_failed_codes() {
+        let details = json!([
+            {"stock_code": "600519", "success": true},
+            {"stock_code": "000001", "success": false},
+            {"stock_code": "300750", "success": false},
+        ]);
+        let codes = failed_codes(&details, |d| d["success"] == json!(false));
+        assert_eq!(codes, vec!["000001", "300750"]);
+        assert!(failed_codes(&Value::Null, |_| true).is_empty());
+    }
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let backoff = Backoff {
+            base: Duration::from_secs(10),
+            max_exp: 2,
+            jitter_ms: 0,
+        };
+        assert_eq!(backoff.delay(1), Duration::from_secs(10));
+        assert_eq!(backoff.delay(2), Duration::from_secs(20));
+        assert_eq!(backoff.delay(5), Duration::from_secs(40));
+    }
+}
@@ -1,236 +1,133 @@
+use super::job::{Job, JobContext, JobOutcome};
 use crate::app::DbPool;
-use crate::models::{
-    NewJobExecutionHistory, NewStockRequest, NewStockSnapshot, UpdateJobExecutionHistory,
+use crate::models::{NewStockRequest, NewStockSnapshot};
+use crate::repositories::{stock_request, stock_snapshot};
+use crate::services::market_session::MarketSession;
+use crate::services::stock_filter::{
+    get_filtered_stocks_param_with_proxy, session_filter_strategies, SessionFilterStrategy,
 };
-use crate::repositories::{job_execution_history, stock_request, stock_snapshot};
-use crate::services::stock_filter::{get_filtered_stocks_param_with_proxy, FilterParams};
 use crate::utils::bigdecimal_parser::parse_bigdecimal;
-use chrono_tz::Asia::Shanghai;
+use futures::future::BoxFuture;
 use serde_json::Value;
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
-/// 股票筛选任务执行结果
-#[derive(Debug)]
-pub struct StockFilterResult {
-    pub items_count: usize,
-    pub success: bool,
-}
-
-/// 创建股票筛选定时任务（上午和下午两个时段）
-pub async fn create_stock_filter_jobs(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: crate::utils::ws_broadcast::TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // 上午时段 cron 表达式（工作日 1-5）
-    // 9:30 - 9:59
-    // 10:00 - 10:59
-    // 11:00 - 11:30
-    let morning_crons = vec![
-        "0 30-59 9 * * 1-5", // 9:30-9:59
-        "0 0-59 10 * * 1-5", // 10:00-10:59
-        "0 0-30 11 * * 1-5", // 11:00-11:30
-    ];
-
-    // 下午时段 cron 表达式（工作日 1-5）
-    let afternoon_crons = vec![
-        "0 * 13-14 * * 1-5", // 13:00-14:59
-        "0 0 15 * * 1-5",    // 15:00
-    ];
-
-    // 注册上午时段任务
-    for cron_expr in morning_crons {
-        let pool = db_pool.clone();
-        let sender = ws_sender.clone();
+/// 股票筛选：交易时段每分钟按当前时段适用的策略筛选并入库
+///
+/// cron 只区分工作日，法定节假日由注册表跳过；当前时段（连续竞价 / 收盘集合竞价等）
+/// 没有对应策略时同样跳过。
+pub struct StockFilterJob;
 
-        let job = JobBuilder::new()
-            .with_timezone(Shanghai)
-            .with_cron_job_type()
-            .with_schedule(cron_expr)?
-            .with_run_async(Box::new(move |_uuid, _l| {
-                let pool = pool.clone();
-                let sender = sender.clone();
-                Box::pin(async move {
-                    execute_stock_filter_task(pool, sender, "morning").await;
-                })
-            }))
-            .build()?;
-
-        scheduler.add(job).await?;
-        tracing::info!("股票筛选上午任务已注册: {} (Asia/Shanghai)", cron_expr);
+impl Job for StockFilterJob {
+    fn name(&self) -> &'static str {
+        "stock_filter"
     }
 
-    // 注册下午时段任务
-    for cron_expr in afternoon_crons {
-        let pool = db_pool.clone();
-        let sender = ws_sender.clone();
+    fn display_name(&self) -> &'static str {
+        "股票筛选"
+    }
 
-        let job = JobBuilder::new()
-            .with_timezone(Shanghai)
-            .with_cron_job_type()
-            .with_schedule(cron_expr)?
-            .with_run_async(Box::new(move |_uuid, _l| {
-                let pool = pool.clone();
-                let sender = sender.clone();
-                Box::pin(async move {
-                    execute_stock_filter_task(pool, sender, "afternoon").await;
-                })
-            }))
-            .build()?;
+    fn description(&self) -> &'static str {
+        "交易时段自动筛选符合条件的股票并入库"
+    }
 
-        scheduler.add(job).await?;
-        tracing::info!("股票筛选下午任务已注册: {} (Asia/Shanghai)", cron_expr);
+    fn schedules(&self) -> &'static [&'static str] {
+        &[
+            "0 30-59 9 * * 1-5", // 9:30-9:59
+            "0 0-59 10 * * 1-5", // 10:00-10:59
+            "0 0-30 11 * * 1-5", // 11:00-11:30
+            "0 * 13-14 * * 1-5", // 13:00-14:59
+            "0 0 15 * * 1-5",    // 15:00
+        ]
     }
 
-    tracing::info!("股票筛选定时任务已全部注册（上午 9:30-11:30，下午 13:00-15:00，每分钟执行）");
-    Ok(())
-}
+    fn schedule_desc(&self) -> &'static str {
+        "工作日 9:30-11:30、13:00-15:00 每分钟"
+    }
 
-/// 执行股票筛选任务的包装函数
-async fn execute_stock_filter_task(
-    db_pool: DbPool,
-    ws_sender: crate::utils::ws_broadcast::TaskStatusSender,
-    session: &str,
-) {
-    // 广播任务开始
-    crate::utils::ws_broadcast::broadcast_task_status(
-        &ws_sender,
-        "stock_filter".to_string(),
-        "running".to_string(),
-    );
+    fn should_run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, bool> {
+        Box::pin(async move {
+            let market_session = ctx.trading_calendar.session_now(&ctx.db_pool).await;
+            session_filter_strategies()
+                .iter()
+                .any(|s| s.applies_to(market_session))
+        })
+    }
 
-    match run_stock_filter_task(db_pool, session).await {
-        Ok(result) => {
-            let status = if result.success { "success" } else { "failed" };
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &ws_sender,
-                "stock_filter".to_string(),
-                status.to_string(),
-            );
-        }
-        Err(e) => {
-            tracing::error!("股票筛选任务失败: {}", e);
-            crate::utils::ws_broadcast::broadcast_task_status(
-                &ws_sender,
-                "stock_filter".to_string(),
-                "failed".to_string(),
-            );
-        }
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_stock_filter_task(ctx))
     }
 }
 
-/// 执行股票筛选任务（可被定时任务或手动触发调用）
-pub async fn run_stock_filter_task(
-    db_pool: DbPool,
-    session: &str,
-) -> anyhow::Result<StockFilterResult> {
-    let now = chrono::Local::now();
+/// 按触发时刻所处的交易时段选择策略；手动触发不受时段限制，执行全部策略。
+/// 快照以触发时刻的时段打标，避免抓取耗时跨分钟导致归属漂移。
+async fn run_stock_filter_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let market_session = ctx.trading_calendar.session_now(db_pool).await;
     tracing::info!(
-        "开始执行股票筛选定时任务 [{}] - {}",
-        session,
-        now.format("%Y-%m-%d %H:%M:%S")
+        "股票筛选 - {}（{}）",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        market_session.display_name()
     );
 
-    let start_time = now.naive_local();
-    let mut history_id: Option<i32> = None;
-
-    // 记录任务开始
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = NewJobExecutionHistory {
-            job_name: format!("stock_filter_{session}"),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
-
-        match job_execution_history::create(&mut conn, &new_history) {
-            Ok(history) => {
-                history_id = Some(history.id);
-                tracing::debug!("创建任务执行记录，ID: {}", history.id);
-            }
-            Err(e) => {
-                tracing::warn!("创建任务执行记录失败: {}", e);
-            }
-        }
+    let mut strategies: Vec<SessionFilterStrategy> = session_filter_strategies();
+    if !ctx.is_manual() {
+        strategies.retain(|s| s.applies_to(market_session));
     }
 
-    // 使用默认参数
-    let params = FilterParams::default();
+    let mut items_count = 0;
+    let mut failed_count = 0;
+    let mut errors: Vec<String> = Vec::new();
 
-    // 调用筛选服务（内部使用代理）
-    let result = get_filtered_stocks_param_with_proxy(params).await;
+    for strategy in &strategies {
+        if ctx.is_cancelled() {
+            tracing::warn!("股票筛选已取消，跳过剩余策略");
+            break;
+        }
+        // 调用筛选服务（内部使用代理）
+        let result = get_filtered_stocks_param_with_proxy(strategy.params.clone()).await;
 
-    let (items_count, success, error_msg) = match result {
-        Ok(ref json_result) => {
-            let items = json_result.get("items").and_then(|v| v.as_array());
+        match result {
+            Ok(ref json_result) => {
+                let items = json_result.get("items").and_then(|v| v.as_array());
 
-            if let Some(items_arr) = items {
-                if !items_arr.is_empty() {
+                if let Some(items_arr) = items.filter(|arr| !arr.is_empty()) {
+                    items_count += items_arr.len();
                     // 持久化到数据库
-                    if let Err(e) = persist_to_db(&db_pool, items_arr).await {
-                        tracing::warn!("持久化股票数据失败: {}", e);
-                        (
-                            items_arr.len(),
-                            true,
-                            Some(format!("数据获取成功但持久化失败: {e}")),
-                        )
+                    if let Err(e) =
+                        persist_to_db(db_pool, items_arr, strategy.name, market_session).await
+                    {
+                        tracing::warn!("持久化股票数据失败 [{}]: {}", strategy.name, e);
+                        errors.push(format!("{}: 数据获取成功但持久化失败: {e}", strategy.name));
                     } else {
-                        tracing::info!("成功筛选并持久化 {} 条股票数据", items_arr.len());
-                        (items_arr.len(), true, None)
+                        tracing::info!(
+                            "策略 {} 成功筛选并持久化 {} 条股票数据",
+                            strategy.name,
+                            items_arr.len()
+                        );
                     }
                 } else {
-                    tracing::info!("本次筛选没有符合条件的股票");
-                    (0, true, None)
+                    tracing::info!("策略 {} 本次筛选没有符合条件的股票", strategy.name);
                 }
-            } else {
-                (0, true, None)
             }
-        }
-        Err(e) => {
-            let error_str = e.to_string();
-            tracing::error!("股票筛选失败: {}", error_str);
-            (0, false, Some(error_str))
-        }
-    };
-
-    // 更新任务完成状态
-    if let Some(id) = history_id {
-        let end_time = chrono::Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-
-        if let Ok(mut conn) = db_pool.get() {
-            let status = if success { "success" } else { "failed" };
-
-            let update = UpdateJobExecutionHistory {
-                status: Some(status.to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(items_count as i32),
-                success_count: Some(if success { items_count as i32 } else { 0 }),
-                failed_count: Some(if success { 0 } else { 1 }),
-                skipped_count: Some(0),
-                details: None,
-                error_message: error_msg.clone(),
-                duration_ms: Some(duration),
-            };
-
-            match job_execution_history::update(&mut conn, id, &update) {
-                Ok(_) => tracing::debug!("任务执行记录已更新"),
-                Err(e) => tracing::warn!("更新任务执行记录失败: {}", e),
+            Err(e) => {
+                let error_str = e.to_string();
+                tracing::error!("股票筛选失败 [{}]: {}", strategy.name, error_str);
+                failed_count += 1;
+                errors.push(format!("{}: {error_str}", strategy.name));
             }
         }
     }
 
-    Ok(StockFilterResult {
-        items_count,
-        success,
+    Ok(JobOutcome {
+        total_count: items_count,
+        success_count: items_count,
+        failed_count,
+        details: Some(serde_json::json!({
+            "market_session": market_session.as_str(),
+            "trigger": ctx.trigger,
+            "strategies": strategies.iter().map(|s| s.name).collect::<Vec<_>>(),
+        })),
+        message: (!errors.is_empty()).then(|| errors.join("; ")),
+        ..Default::default()
     })
 }
 
@@ -238,13 +135,15 @@ pub async fn run_stock_filter_task(
 async fn persist_to_db(
     db_pool: &DbPool,
     items: &[Value],
+    strategy_name: &str,
+    market_session: MarketSession,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let mut conn = db_pool.get()?;
 
     // 1. 插入 stock_requests 记录
     let now_date = chrono::Utc::now().date_naive();
     let new_request = NewStockRequest {
-        strategy_name: Some("filtered_param".to_string()),
+        strategy_name: Some(strategy_name.to_string()),
         time_range_start: Some(now_date),
         time_range_end: None,
     };
@@ -280,6 +179,7 @@ async fn persist_to_db(
             turnover_rate,
             bid_ask_ratio,
             main_force_inflow,
+            session: Some(market_session.as_str().to_string()),
         };
 
         if let Err(e) = stock_snapshot::create(&mut conn, &new_snapshot) {
@@ -0,0 +1,170 @@
+use futures::future::BoxFuture;
+use serde::Serialize;
+
+use super::job::{Job, JobContext, JobOutcome};
+use crate::repositories::stock_name_history as name_history_repo;
+use crate::repositories::stock_table;
+use crate::services::stock_master::{self, STATUS_DELISTED, STATUS_SUSPENDED};
+use crate::services::stock_name_history;
+use crate::services::trading_calendar::shanghai_now;
+
+#[derive(Debug, Serialize)]
+struct StockMasterSyncDetail {
+    stock_code: String,
+    stock_name: String,
+    action: String,
+    error: Option<String>,
+}
+
+/// 证券主数据同步：从东财 A 股列表全量更新 stock_table（每天 17:10）
+pub struct StockMasterSyncJob;
+
+impl Job for StockMasterSyncJob {
+    fn name(&self) -> &'static str {
+        "stock_master_sync"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "证券主数据同步"
+    }
+
+    fn description(&self) -> &'static str {
+        "从东财 A 股列表同步交易所、板块、上市日期、行业、股本、市值、ST 与上市状态，并记录简称变更历史"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &["0 10 17 * * *"]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "每天 17:10"
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_stock_master_sync_task(ctx))
+    }
+}
+
+async fn run_stock_master_sync_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let list = stock_master::fetch_master_list().await?;
+    if list.records.is_empty() {
+        anyhow::bail!("A 股列表为空");
+    }
+    tracing::info!(
+        "A 股列表共 {} 只股票（{}）",
+        list.records.len(),
+        if list.complete {
+            "完整"
+        } else {
+            "不完整，跳过退市判断"
+        }
+    );
+
+    let mut conn = ctx.db_pool.get()?;
+    let existing = stock_table::list_all(&mut conn)?;
+    let by_code = stock_master::index_by_code(&existing);
+    let now = shanghai_now();
+
+    let seeded = stock_name_history::seed_from_snapshots(&mut conn)?;
+    if seeded > 0 {
+        tracing::info!("由历史快照回填简称历史 {} 条", seeded);
+    }
+    let open_names = stock_name_history::index_open(name_history_repo::list_open(&mut conn)?);
+
+    let total = list.records.len();
+    let (mut inserted, mut updated, mut failed, mut suspended, mut renamed) = (0, 0, 0, 0, 0);
+    let mut details = Vec::new();
+    for (idx, record) in list.records.iter().enumerate() {
+        ctx.progress(idx, total);
+        if ctx.is_cancelled() {
+            tracing::warn!("证券主数据同步已取消，剩余 {} 只股票未处理", total - idx);
+            break;
+        }
+        let found = by_code.get(&record.code).copied();
+        let data = stock_master::to_master_data(record, found, now);
+        if data.list_status == STATUS_SUSPENDED {
+            suspended += 1;
+        }
+        let result = match found {
+            Some(stock) => stock_table::update_master(&mut conn, stock.id, &data).map(|_| {
+                updated += 1;
+                // 只记录状态变化，避免明细过大
+                (stock.list_status != data.list_status)
+                    .then(|| format!("{} -> {}", stock.list_status, data.list_status))
+            }),
+            None => stock_table::create_master(&mut conn, &data).map(|_| {
+                inserted += 1;
+                Some("inserted".to_string())
+            }),
+        };
+        // 名称变化写入简称历史；失败只记日志，不影响主数据同步结果
+        match stock_name_history::record_name(
+            &mut conn,
+            &open_names,
+            &record.code,
+            &record.name,
+            now.date(),
+        ) {
+            Ok(Some(change)) => {
+                if let Some(old_name) = change.old_name {
+                    renamed += 1;
+                    details.push(StockMasterSyncDetail {
+                        stock_code: record.code.clone(),
+                        stock_name: record.name.clone(),
+                        action: format!("renamed: {old_name} -> {}", change.new_name),
+                        error: None,
+                    });
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!("记录股票 {} 简称历史失败: {}", record.code, e),
+        }
+        match result {
+            Ok(Some(action)) => details.push(StockMasterSyncDetail {
+                stock_code: record.code.clone(),
+                stock_name: record.name.clone(),
+                action,
+                error: None,
+            }),
+            Ok(None) => {}
+            Err(e) => {
+                failed += 1;
+                tracing::error!("同步股票 {} 主数据失败: {}", record.code, e);
+                details.push(StockMasterSyncDetail {
+                    stock_code: record.code.clone(),
+                    stock_name: record.name.clone(),
+                    action: "failed".to_string(),
+                    error: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    // 列表完整且未取消时，不在列表中的代码标记为退市
+    let mut delisted = 0;
+    if list.complete && !ctx.is_cancelled() {
+        let missing = stock_master::missing_ids(&existing, &list.records);
+        if !missing.is_empty() {
+            delisted = stock_table::mark_delisted(&mut conn, &missing, now)?;
+            for stock in existing.iter().filter(|s| missing.contains(&s.id)) {
+                details.push(StockMasterSyncDetail {
+                    stock_code: stock.stock_code.clone(),
+                    stock_name: stock.stock_name.clone(),
+                    action: format!("{} -> {}", stock.list_status, STATUS_DELISTED),
+                    error: None,
+                });
+            }
+        }
+    }
+
+    Ok(JobOutcome {
+        total_count: inserted + updated + failed,
+        success_count: inserted + updated,
+        failed_count: failed,
+        message: Some(format!(
+            "新增 {inserted}，更新 {updated}（停牌 {suspended}），改名 {renamed}，新标记退市 {delisted}"
+        )),
+        ..Default::default()
+    }
+    .with_details(&details))
+}
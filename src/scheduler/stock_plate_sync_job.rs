@@ -1,30 +1,26 @@
 use anyhow::anyhow;
 use chrono::Local;
-use chrono_tz::Asia::Shanghai;
+use futures::future::BoxFuture;
 use rand::Rng;
 use reqwest::Client;
 use serde::Serialize;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{Mutex, Semaphore};
 use tokio::task::JoinSet;
 use tokio::time::{sleep, Duration};
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
+use super::job::{Job, JobContext, JobOutcome};
+use super::retry::{self, Backoff, StockRetryParams};
 use crate::app::DbPool;
 use crate::models::stock_tables::StockTable;
-use crate::models::{
-    NewJobExecutionHistory, NewStockPlate, NewStockPlateStockTable, UpdateJobExecutionHistory,
-    UpdateStockPlate,
-};
-use crate::repositories::{
-    job_execution_history, stock_plate, stock_plate_stock_table, stock_table,
-};
+use crate::models::{NewStockPlate, NewStockPlateStockTable, UpdateStockPlate};
+use crate::repositories::{stock_plate, stock_plate_stock_table, stock_table};
 use crate::services::stock_plate_em::fetch_em_plate_list_with_proxy_client;
 use crate::utils::http_client::create_em_client;
 use crate::utils::proxy::{shared_proxy_client, ProxyClient};
-use crate::utils::ws_broadcast::TaskStatusSender;
 
 #[derive(Debug, Serialize)]
 pub struct StockPlateSyncDetail {
@@ -39,20 +35,14 @@ pub struct StockPlateSyncDetail {
     pub error: Option<String>,
 }
 
-#[derive(Debug)]
-pub struct StockPlateSyncResult {
-    #[allow(dead_code)]
-    pub total_count: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    #[allow(dead_code)]
-    pub skipped_count: usize,
-    #[allow(dead_code)]
-    pub details: Vec<StockPlateSyncDetail>,
-}
-
 const PLATE_SYNC_CONCURRENCY: usize = 100;
 const PLATE_SYNC_RETRY_ROUNDS: usize = 3;
+/// 轮次之间的退避
+const PLATE_SYNC_ROUND_BACKOFF: Backoff = Backoff {
+    base: Duration::from_millis(300),
+    max_exp: 4,
+    jitter_ms: 200,
+};
 
 fn db_write_concurrency() -> usize {
     std::env::var("DB_WRITE_CONCURRENCY")
@@ -64,123 +54,80 @@ fn db_write_concurrency() -> usize {
 #[derive(Debug)]
 enum StockProcessOutcome {
     Completed(StockPlateSyncDetail),
-    RequestFailed { stock: StockTable, error: String },
+    RequestFailed {
+        stock: Box<StockTable>,
+        error: String,
+    },
 }
 
-/// 创建 stock_plate 同步任务（每天 UTC+8 18:00 执行）
-pub async fn create_stock_plate_sync_job(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let job = JobBuilder::new()
-        .with_timezone(Shanghai)
-        .with_cron_job_type()
-        .with_schedule("0 0 18 * * *")?
-        .with_run_async(Box::new(move |_uuid, _l| {
-            let pool = db_pool.clone();
-            let sender = ws_sender.clone();
-            Box::pin(async move {
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "stock_plate_sync".to_string(),
-                    "running".to_string(),
-                );
-                match run_stock_plate_sync_task(pool).await {
-                    Ok(result) => {
-                        let status = if result.failed_count == 0 {
-                            "success"
-                        } else if result.success_count > 0 {
-                            "partial"
-                        } else {
-                            "failed"
-                        };
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "stock_plate_sync".to_string(),
-                            status.to_string(),
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("stock_plate 同步任务失败: {}", e);
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "stock_plate_sync".to_string(),
-                            "failed".to_string(),
-                        );
-                    }
-                }
-            })
-        }))
-        .build()?;
+/// stock_plate 同步：根据 stock_table 同步板块及关系（stock_table 同步完成后执行）
+pub struct StockPlateSyncJob;
 
-    scheduler.add(job).await?;
-    tracing::info!(
-        "stock_plate 同步定时任务已注册（每天北京时间 18:00 执行，使用 Asia/Shanghai 时区）"
-    );
-    Ok(())
-}
+impl Job for StockPlateSyncJob {
+    fn name(&self) -> &'static str {
+        "stock_plate_sync"
+    }
 
-pub async fn run_stock_plate_sync_task(db_pool: DbPool) -> anyhow::Result<StockPlateSyncResult> {
-    tracing::info!("开始执行 stock_plate 同步任务");
-    let start_time = Local::now().naive_local();
-    let mut history_id: Option<i32> = None;
+    fn display_name(&self) -> &'static str {
+        "stock_plate 同步"
+    }
 
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = NewJobExecutionHistory {
-            job_name: "stock_plate_sync".to_string(),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
-        if let Ok(history) = job_execution_history::create(&mut conn, &new_history) {
-            history_id = Some(history.id);
-            tracing::debug!("创建任务执行记录，ID: {}", history.id);
-        }
+    fn description(&self) -> &'static str {
+        "根据 stock_table 同步板块及关系"
+    }
+
+    fn schedules(&self) -> &'static [&'static str] {
+        &[]
     }
 
-    let stocks = {
+    fn schedule_desc(&self) -> &'static str {
+        "stock_table 同步完成后"
+    }
+
+    fn depends_on(&self) -> &'static [&'static str] {
+        &["stock_table_sync"]
+    }
+
+    fn detach_on_trigger(&self) -> bool {
+        true
+    }
+
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        StockRetryParams::parse(params).map(|_| ())
+    }
+
+    fn retryable(&self) -> bool {
+        true
+    }
+
+    fn failed_items(&self, details: &Value) -> Vec<String> {
+        retry::failed_codes(details, |d| d["action"] == "failed")
+    }
+
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_stock_plate_sync_task(ctx))
+    }
+}
+
+async fn run_stock_plate_sync_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let params = StockRetryParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+    let mut stocks = {
         let mut conn = db_pool.get()?;
-        stock_table::list_all(&mut conn)?
+        stock_table::list_active(&mut conn)?
     };
+    // 重试时只处理指定的失败代码
+    if let Some(codes) = params.codes {
+        let codes: HashSet<String> = codes.into_iter().collect();
+        stocks.retain(|s| codes.contains(&s.stock_code));
+    }
 
     if stocks.is_empty() {
         tracing::info!("stock_table 为空，跳过同步");
-        if let Some(id) = history_id {
-            let end_time = Local::now().naive_local();
-            let duration = (end_time - start_time).num_milliseconds();
-            let update = UpdateJobExecutionHistory {
-                status: Some("success".to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(0),
-                success_count: Some(0),
-                failed_count: Some(0),
-                skipped_count: Some(0),
-                details: None,
-                error_message: Some("stock_table 为空".to_string()),
-                duration_ms: Some(duration),
-            };
-            if let Ok(mut c) = db_pool.get() {
-                let _ = job_execution_history::update(&mut c, id, &update);
-            }
-        }
-        return Ok(StockPlateSyncResult {
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: Vec::new(),
-        });
+        return Ok(JobOutcome::empty("stock_table 为空"));
     }
 
+    let total_stocks = stocks.len();
     let client = create_em_client()?;
     let mut success_count = 0;
     let mut failed_count = 0;
@@ -254,7 +201,7 @@ pub async fn run_stock_plate_sync_task(db_pool: DbPool) -> anyhow::Result<StockP
                     StockProcessOutcome::RequestFailed { stock, error } => {
                         if attempt < max_attempts {
                             round_retrying += 1;
-                            queue.push(stock);
+                            queue.push(*stock);
                         } else {
                             failed_count += 1;
                             round_failed_final += 1;
@@ -279,6 +226,13 @@ pub async fn run_stock_plate_sync_task(db_pool: DbPool) -> anyhow::Result<StockP
                     fatal_error = Some(anyhow!("板块任务执行失败: {join_err}"));
                 }
             }
+            ctx.progress(details.len(), total_stocks);
+            if ctx.is_cancelled() {
+                tracing::warn!("板块同步已取消，放弃本轮剩余 {} 只股票", join_set.len());
+                join_set.abort_all();
+                queue.clear();
+                break;
+            }
         }
 
         tracing::info!(
@@ -297,64 +251,26 @@ pub async fn run_stock_plate_sync_task(db_pool: DbPool) -> anyhow::Result<StockP
         }
 
         if !queue.is_empty() && attempt < max_attempts {
-            let exp = attempt.saturating_sub(1).min(4) as u32;
-            let base_backoff = 300_u64.saturating_mul(1_u64 << exp);
-            let jitter = rand::thread_rng().gen_range(0..=200);
-            let backoff_ms = base_backoff + jitter;
+            let backoff = PLATE_SYNC_ROUND_BACKOFF.delay(attempt as u32);
             tracing::warn!(
                 "仍有失败任务，准备重试: attempt={}/{}, queue={}, backoff_ms={}",
                 attempt + 1,
                 max_attempts,
                 queue.len(),
-                backoff_ms
+                backoff.as_millis()
             );
-            sleep(Duration::from_millis(backoff_ms)).await;
-        }
-    }
-
-    let total_count = success_count + failed_count + skipped_count;
-    tracing::info!(
-        "stock_plate 同步完成，总计: {}, 成功: {}, 失败: {}, 跳过: {}",
-        total_count,
-        success_count,
-        failed_count,
-        skipped_count
-    );
-
-    if let Some(id) = history_id {
-        let end_time = Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-        let status = if failed_count == 0 {
-            "success"
-        } else if success_count > 0 {
-            "partial"
-        } else {
-            "failed"
-        };
-        let details_json = serde_json::to_value(&details).ok();
-        let update = UpdateJobExecutionHistory {
-            status: Some(status.to_string()),
-            completed_at: Some(end_time),
-            total_count: Some(total_count as i32),
-            success_count: Some(success_count as i32),
-            failed_count: Some(failed_count as i32),
-            skipped_count: Some(skipped_count as i32),
-            details: details_json,
-            error_message: None,
-            duration_ms: Some(duration),
-        };
-        if let Ok(mut c) = db_pool.get() {
-            let _ = job_execution_history::update(&mut c, id, &update);
+            sleep(backoff).await;
         }
     }
 
-    Ok(StockPlateSyncResult {
-        total_count,
+    Ok(JobOutcome {
+        total_count: success_count + failed_count + skipped_count,
         success_count,
         failed_count,
         skipped_count,
-        details,
-    })
+        ..Default::default()
+    }
+    .with_details(&details))
 }
 
 async fn process_stock_once(
@@ -532,7 +448,7 @@ async fn process_stock_once(
                 e
             );
             Ok(StockProcessOutcome::RequestFailed {
-                stock,
+                stock: Box::new(stock),
                 error: e.to_string(),
             })
         }
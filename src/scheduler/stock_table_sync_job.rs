@@ -1,12 +1,9 @@
-use chrono::Local;
-use chrono_tz::Asia::Shanghai;
+use futures::future::BoxFuture;
 use serde::Serialize;
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
-use crate::app::DbPool;
-use crate::models::{NewJobExecutionHistory, NewStockTable, UpdateJobExecutionHistory};
-use crate::repositories::{job_execution_history, stock_snapshot, stock_table};
-use crate::utils::ws_broadcast::TaskStatusSender;
+use super::job::{Job, JobContext, JobOutcome};
+use crate::models::NewStockTable;
+use crate::repositories::{stock_snapshot, stock_table};
 
 #[derive(Debug, Serialize)]
 pub struct StockTableSyncDetail {
@@ -16,132 +13,55 @@ pub struct StockTableSyncDetail {
     pub error: Option<String>,
 }
 
-#[derive(Debug)]
-pub struct StockTableSyncResult {
-    pub total_count: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub skipped_count: usize,
-    pub details: Vec<StockTableSyncDetail>,
-}
+/// stock_table 同步：从快照去重写入 stock_table（每天 17:30）
+pub struct StockTableSyncJob;
 
-/// 创建 stock_table 同步任务（每天 UTC+8 17:30 执行）
-pub async fn create_stock_table_sync_job(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let job = JobBuilder::new()
-        .with_timezone(Shanghai)
-        .with_cron_job_type()
-        .with_schedule("0 30 17 * * *")?
-        .with_run_async(Box::new(move |_uuid, _l| {
-            let pool = db_pool.clone();
-            let sender = ws_sender.clone();
-            Box::pin(async move {
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "stock_table_sync".to_string(),
-                    "running".to_string(),
-                );
-                match run_stock_table_sync_task(pool).await {
-                    Ok(result) => {
-                        let status = if result.failed_count == 0 {
-                            "success"
-                        } else if result.success_count > 0 {
-                            "partial"
-                        } else {
-                            "failed"
-                        };
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "stock_table_sync".to_string(),
-                            status.to_string(),
-                        );
-                    }
-                    Err(e) => {
-                        tracing::error!("stock_table 同步任务失败: {}", e);
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "stock_table_sync".to_string(),
-                            "failed".to_string(),
-                        );
-                    }
-                }
-            })
-        }))
-        .build()?;
+impl Job for StockTableSyncJob {
+    fn name(&self) -> &'static str {
+        "stock_table_sync"
+    }
 
-    scheduler.add(job).await?;
-    tracing::info!(
-        "stock_table 同步定时任务已注册（每天北京时间 04:00 执行，使用 Asia/Shanghai 时区）"
-    );
-    Ok(())
-}
+    fn display_name(&self) -> &'static str {
+        "stock_table 同步"
+    }
 
-pub async fn run_stock_table_sync_task(db_pool: DbPool) -> anyhow::Result<StockTableSyncResult> {
-    tracing::info!("开始执行 stock_table 同步任务");
-    let start_time = Local::now().naive_local();
-    let mut history_id: Option<i32> = None;
+    fn description(&self) -> &'static str {
+        "从快照去重写入 stock_table"
+    }
 
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = NewJobExecutionHistory {
-            job_name: "stock_table_sync".to_string(),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
-        if let Ok(history) = job_execution_history::create(&mut conn, &new_history) {
-            history_id = Some(history.id);
-            tracing::debug!("创建任务执行记录，ID: {}", history.id);
-        }
+    fn schedules(&self) -> &'static [&'static str] {
+        &["0 30 17 * * *"]
+    }
+
+    fn schedule_desc(&self) -> &'static str {
+        "每天 17:30"
     }
 
-    let mut conn = db_pool.get()?;
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_stock_table_sync_task(ctx))
+    }
+}
+
+async fn run_stock_table_sync_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let mut conn = ctx.db_pool.get()?;
     let distinct = stock_snapshot::list_distinct_codes_with_name(&mut conn)?;
     if distinct.is_empty() {
         tracing::info!("没有快照数据，跳过同步");
-        if let Some(id) = history_id {
-            let end_time = Local::now().naive_local();
-            let duration = (end_time - start_time).num_milliseconds();
-            let update = UpdateJobExecutionHistory {
-                status: Some("success".to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(0),
-                success_count: Some(0),
-                failed_count: Some(0),
-                skipped_count: Some(0),
-                details: None,
-                error_message: Some("没有快照数据".to_string()),
-                duration_ms: Some(duration),
-            };
-            if let Ok(mut c) = db_pool.get() {
-                let _ = job_execution_history::update(&mut c, id, &update);
-            }
-        }
-        return Ok(StockTableSyncResult {
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: Vec::new(),
-        });
+        return Ok(JobOutcome::empty("没有快照数据"));
     }
 
+    let total = distinct.len();
     let mut success_count = 0;
     let mut failed_count = 0;
     let mut skipped_count = 0;
-    let mut details = Vec::with_capacity(distinct.len());
+    let mut details = Vec::with_capacity(total);
 
-    for item in distinct {
+    for (idx, item) in distinct.into_iter().enumerate() {
+        ctx.progress(idx, total);
+        if ctx.is_cancelled() {
+            tracing::warn!("stock_table 同步已取消，剩余 {} 个代码未处理", total - idx);
+            break;
+        }
         if stock_table::exists_by_code(&mut conn, &item.code)? {
             skipped_count += 1;
             details.push(StockTableSyncDetail {
@@ -179,47 +99,12 @@ pub async fn run_stock_table_sync_task(db_pool: DbPool) -> anyhow::Result<StockT
         }
     }
 
-    let total_count = success_count + failed_count + skipped_count;
-    tracing::info!(
-        "stock_table 同步完成，总计: {}, 成功: {}, 失败: {}, 跳过: {}",
-        total_count,
-        success_count,
-        failed_count,
-        skipped_count
-    );
-
-    if let Some(id) = history_id {
-        let end_time = Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-        let status = if failed_count == 0 {
-            "success"
-        } else if success_count > 0 {
-            "partial"
-        } else {
-            "failed"
-        };
-        let details_json = serde_json::to_value(&details).ok();
-        let update = UpdateJobExecutionHistory {
-            status: Some(status.to_string()),
-            completed_at: Some(end_time),
-            total_count: Some(total_count as i32),
-            success_count: Some(success_count as i32),
-            failed_count: Some(failed_count as i32),
-            skipped_count: Some(skipped_count as i32),
-            details: details_json,
-            error_message: None,
-            duration_ms: Some(duration),
-        };
-        if let Ok(mut c) = db_pool.get() {
-            let _ = job_execution_history::update(&mut c, id, &update);
-        }
-    }
-
-    Ok(StockTableSyncResult {
-        total_count,
+    Ok(JobOutcome {
+        total_count: success_count + failed_count + skipped_count,
         success_count,
         failed_count,
         skipped_count,
-        details,
-    })
+        ..Default::default()
+    }
+    .with_details(&details))
 }
@@ -1,211 +1,98 @@
-use crate::app::DbPool;
+use super::job::{Job, JobContext, JobOutcome};
+use super::kline_import_job::{
+    process_single_stock_kline, StockImportOutcome, KLINE_DB_CONCURRENCY, KLINE_HTTP_CONCURRENCY,
+};
+use super::retry::{self, StockRetryParams};
 use crate::repositories::stock_watchlist;
-use crate::services::kline_service;
+use crate::services::trading_calendar::shanghai_now;
 use crate::utils::http_client;
-use chrono::Local;
-use chrono_tz::Asia::Shanghai;
+use futures::future::BoxFuture;
+use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
-use tokio_cron_scheduler::{JobBuilder, JobScheduler};
 
-/// 观察表K线导入任务执行结果
-#[derive(Debug)]
-pub struct WatchlistKlineImportResult {
-    pub total_stocks: usize,
-    pub success_count: usize,
-    pub failed_count: usize,
-    pub skipped_count: usize,
-    pub stock_details: Vec<StockImportDetail>,
-}
-
-#[derive(Debug, serde::Serialize)]
-pub struct StockImportDetail {
-    pub stock_code: String,
-    pub imported_count: usize,
-    pub success: bool,
-    pub error: Option<String>,
-}
+/// 观察表K线导入：对观察表中的股票检查并导入当日K线（每天 16:00）
+pub struct WatchlistKlineJob;
 
-const KLINE_HTTP_CONCURRENCY: usize = 200;
-const KLINE_DB_CONCURRENCY: usize = 200;
-
-#[derive(Debug)]
-enum StockImportOutcome {
-    Success(StockImportDetail),
-    Skipped(StockImportDetail),
-    Failed(StockImportDetail),
-}
-
-pub async fn create_watchlist_kline_job(
-    scheduler: &JobScheduler,
-    db_pool: DbPool,
-    ws_sender: crate::utils::ws_broadcast::TaskStatusSender,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // 创建每天16:00执行的任务（北京时间 UTC+8）
-    // 使用 JobBuilder 设置上海时区（UTC+8）
-    let job = JobBuilder::new()
-        .with_timezone(Shanghai)
-        .with_cron_job_type()
-        .with_schedule("0 0 16 * * *")?
-        .with_run_async(Box::new(move |_uuid, _l| {
-            let pool = db_pool.clone();
-            let sender = ws_sender.clone();
-            Box::pin(async move {
-                // 广播任务开始
-                crate::utils::ws_broadcast::broadcast_task_status(
-                    &sender,
-                    "watchlist_kline_import".to_string(),
-                    "running".to_string(),
-                );
+impl Job for WatchlistKlineJob {
+    fn name(&self) -> &'static str {
+        "watchlist_kline_import"
+    }
 
-                match run_watchlist_kline_task(pool).await {
-                    Ok(result) => {
-                        // 广播任务完成
-                        let status = if result.failed_count == 0 {
-                            "success"
-                        } else if result.success_count > 0 {
-                            "partial"
-                        } else {
-                            "failed"
-                        };
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "watchlist_kline_import".to_string(),
-                            status.to_string(),
-                        );
-                        // 使用所有结果字段输出详细日志
-                        tracing::info!(
-                            "观察表K线导入任务完成，总计: {} 只股票，成功: {} 只，失败: {} 只，跳过: {} 只",
-                            result.total_stocks,
-                            result.success_count,
-                            result.failed_count,
-                            result.skipped_count
-                        );
-                        if !result.stock_details.is_empty() {
-                            tracing::debug!("股票详情: {:?}", result.stock_details);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("观察表K线导入任务失败: {}", e);
-                        // 广播任务失败
-                        crate::utils::ws_broadcast::broadcast_task_status(
-                            &sender,
-                            "watchlist_kline_import".to_string(),
-                            "failed".to_string(),
-                        );
-                    }
-                }
-            })
-        }))
-        .build()?;
+    fn display_name(&self) -> &'static str {
+        "观察表K线导入"
+    }
 
-    scheduler.add(job).await?;
-    tracing::info!(
-        "观察表K线导入定时任务已注册（每天北京时间 16:00 执行，使用 Asia/Shanghai 时区）"
-    );
-    Ok(())
-}
+    fn description(&self) -> &'static str {
+        "对观察表中的股票进行K线数据检查和导入"
+    }
 
-/// 执行观察表K线导入任务（可以被定时任务或手动触发调用）
-pub async fn run_watchlist_kline_task(
-    db_pool: DbPool,
-) -> anyhow::Result<WatchlistKlineImportResult> {
-    tracing::info!("开始执行观察表K线导入定时任务");
+    fn schedules(&self) -> &'static [&'static str] {
+        &["0 0 16 * * *"]
+    }
 
-    let start_time = chrono::Local::now().naive_local();
-    let mut history_id: Option<i32> = None;
+    fn schedule_desc(&self) -> &'static str {
+        "每天 16:00"
+    }
 
-    // 记录任务开始
-    {
-        let mut conn = db_pool.get()?;
-        let new_history = crate::models::NewJobExecutionHistory {
-            job_name: "watchlist_kline_import".to_string(),
-            status: "running".to_string(),
-            started_at: start_time,
-            completed_at: None,
-            total_count: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            details: None,
-            error_message: None,
-            duration_ms: None,
-        };
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        StockRetryParams::parse(params).map(|_| ())
+    }
 
-        match crate::repositories::job_execution_history::create(&mut conn, &new_history) {
-            Ok(history) => {
-                history_id = Some(history.id);
-                tracing::info!("创建任务执行记录，ID: {}", history.id);
-            }
-            Err(e) => {
-                tracing::warn!("创建任务执行记录失败: {}", e);
-            }
-        }
+    fn retryable(&self) -> bool {
+        true
     }
 
-    // 1. 获取数据库连接
-    let mut conn = db_pool.get()?;
+    fn failed_items(&self, details: &Value) -> Vec<String> {
+        retry::failed_codes(details, |d| d["success"] == Value::Bool(false))
+    }
 
-    // 2. 从观察表获取所有股票代码
-    let watchlist_items = stock_watchlist::list_all(&mut conn)?;
-    let stock_codes: Vec<String> = watchlist_items
-        .into_iter()
-        .map(|item| item.stock_code)
-        .collect();
+    fn run<'a>(&'a self, ctx: &'a JobContext) -> BoxFuture<'a, anyhow::Result<JobOutcome>> {
+        Box::pin(run_watchlist_kline_task(ctx))
+    }
+}
 
+async fn run_watchlist_kline_task(ctx: &JobContext) -> anyhow::Result<JobOutcome> {
+    let db_pool = &ctx.db_pool;
+    let params = StockRetryParams::parse(&ctx.params).map_err(anyhow::Error::msg)?;
+
+    // 1. 从观察表获取所有股票代码（重试时为指定的失败代码）
+    let stock_codes: Vec<String> = match params.codes {
+        Some(codes) => codes,
+        None => {
+            let mut conn = db_pool.get()?;
+            stock_watchlist::list_all(&mut conn)?
+                .into_iter()
+                .map(|item| item.stock_code)
+                .collect()
+        }
+    };
     tracing::info!("从观察表获取到 {} 个股票代码", stock_codes.len());
 
     if stock_codes.is_empty() {
         tracing::info!("观察表中没有股票，跳过K线导入");
-
-        // 更新任务完成状态
-        if let Some(id) = history_id {
-            let end_time = chrono::Local::now().naive_local();
-            let duration = (end_time - start_time).num_milliseconds();
-            let mut conn = db_pool.get().ok();
-            if let Some(ref mut c) = conn {
-                let update = crate::models::UpdateJobExecutionHistory {
-                    status: Some("success".to_string()),
-                    completed_at: Some(end_time),
-                    total_count: Some(0),
-                    success_count: Some(0),
-                    failed_count: Some(0),
-                    skipped_count: Some(0),
-                    details: None,
-                    error_message: Some("观察表中没有股票".to_string()),
-                    duration_ms: Some(duration),
-                };
-                let _ = crate::repositories::job_execution_history::update(c, id, &update);
-            }
-        }
-
-        return Ok(WatchlistKlineImportResult {
-            total_stocks: 0,
-            success_count: 0,
-            failed_count: 0,
-            skipped_count: 0,
-            stock_details: Vec::new(),
-        });
+        return Ok(JobOutcome::empty("观察表中没有股票"));
     }
 
-    // 3. 创建HTTP客户端
+    // 2. 创建HTTP客户端
     let client = http_client::create_em_client()?;
 
-    // 4. 获取当天日期（格式：YYYYMMDD）
-    // 如果是周末，回溯到上一个交易日（周五）
-    let today = get_trading_date();
+    // 3. 获取交易日期（格式：YYYYMMDD）
+    // 非交易日（周末、法定节假日）回溯到上一个交易日
+    let trade_date = ctx
+        .trading_calendar
+        .days(db_pool)
+        .await
+        .trading_day_on_or_before(params.trade_date.unwrap_or_else(|| shanghai_now().date()));
+    let today = trade_date.format("%Y%m%d").to_string();
 
-    // 5. 并发导入K线数据
+    // 4. 并发导入K线数据
     let mut success_count = 0;
     let mut failed_count = 0;
     let mut skipped_count = 0;
     let mut stock_details = Vec::new();
 
-    // 解析交易日期用于检查
-    let trade_date = chrono::NaiveDate::parse_from_str(&today, "%Y%m%d")
-        .map_err(|e| anyhow::anyhow!("日期解析失败: {e}"))?;
-
     let http_semaphore = Arc::new(Semaphore::new(KLINE_HTTP_CONCURRENCY));
     let db_semaphore = Arc::new(Semaphore::new(KLINE_DB_CONCURRENCY));
     let mut join_set = JoinSet::new();
@@ -254,196 +141,26 @@ pub async fn run_watchlist_kline_task(
                 tracing::error!("观察表K线导入任务并发执行失败: {}", join_err);
             }
         }
-    }
-
-    tracing::info!(
-        "观察表K线导入任务完成，总计: {}, 成功: {}, 失败: {}, 跳过: {}",
-        stock_codes.len(),
-        success_count,
-        failed_count,
-        skipped_count
-    );
-
-    // 更新任务完成状态
-    if let Some(id) = history_id {
-        let end_time = chrono::Local::now().naive_local();
-        let duration = (end_time - start_time).num_milliseconds();
-        let mut conn = db_pool.get().ok();
-        if let Some(ref mut c) = conn {
-            let status = if failed_count == 0 {
-                "success"
-            } else if success_count > 0 {
-                "partial"
-            } else {
-                "failed"
-            };
-
-            let details_json = serde_json::to_value(&stock_details).ok();
-
-            let update = crate::models::UpdateJobExecutionHistory {
-                status: Some(status.to_string()),
-                completed_at: Some(end_time),
-                total_count: Some(stock_codes.len() as i32),
-                success_count: Some(success_count as i32),
-                failed_count: Some(failed_count as i32),
-                skipped_count: Some(skipped_count as i32),
-                details: details_json,
-                error_message: None,
-                duration_ms: Some(duration),
-            };
-
-            match crate::repositories::job_execution_history::update(c, id, &update) {
-                Ok(_) => tracing::info!("任务执行记录已更新"),
-                Err(e) => tracing::warn!("更新任务执行记录失败: {}", e),
-            }
+        ctx.progress(
+            success_count + failed_count + skipped_count,
+            stock_codes.len(),
+        );
+        if ctx.is_cancelled() {
+            tracing::warn!(
+                "观察表K线导入已取消，放弃剩余 {} 只股票",
+                stock_codes.len() - success_count - failed_count - skipped_count
+            );
+            join_set.abort_all();
+            break;
         }
     }
 
-    Ok(WatchlistKlineImportResult {
-        total_stocks: stock_codes.len(),
+    Ok(JobOutcome {
+        total_count: stock_codes.len(),
         success_count,
         failed_count,
         skipped_count,
-        stock_details,
-    })
-}
-
-async fn import_single_stock_kline(
-    client: &reqwest::Client,
-    stock_code: &str,
-    date: &str,
-    db_pool: &DbPool,
-    db_semaphore: Arc<Semaphore>,
-) -> anyhow::Result<usize> {
-    // 1. 从东方财富获取并解析K线数据
-    let kline_result =
-        kline_service::fetch_and_parse_kline_data(client, stock_code, date, date).await?;
-
-    // 2. 获取数据库连接
-    let _db_permit = db_semaphore
-        .acquire_owned()
-        .await
-        .map_err(|_| anyhow::anyhow!("DB 并发限流器已关闭"))?;
-    let mut conn = db_pool.get()?;
-
-    // 3. 批量插入数据库
-    let mut imported_count = 0;
-    use crate::repositories::daily_kline;
-
-    for kline_data in kline_result.parsed {
-        match daily_kline::create(&mut conn, &kline_data) {
-            Ok(_) => imported_count += 1,
-            Err(diesel::result::Error::DatabaseError(
-                diesel::result::DatabaseErrorKind::UniqueViolation,
-                _,
-            )) => {
-                // 重复数据，忽略
-            }
-            Err(e) => {
-                tracing::warn!("插入K线数据失败: {}", e);
-            }
-        }
-    }
-
-    Ok(imported_count)
-}
-
-async fn process_single_stock_kline(
-    client: reqwest::Client,
-    stock_code: String,
-    today: String,
-    trade_date: chrono::NaiveDate,
-    db_pool: DbPool,
-    http_semaphore: Arc<Semaphore>,
-    db_semaphore: Arc<Semaphore>,
-) -> StockImportOutcome {
-    let pure_code = stock_code
-        .trim_start_matches("SH")
-        .trim_start_matches("SZ")
-        .to_string();
-
-    let exists = {
-        let _db_permit = match db_semaphore.clone().acquire_owned().await {
-            Ok(permit) => permit,
-            Err(_) => {
-                return StockImportOutcome::Failed(StockImportDetail {
-                    stock_code,
-                    imported_count: 0,
-                    success: false,
-                    error: Some("DB 并发限流器已关闭".to_string()),
-                });
-            }
-        };
-        let mut conn = match db_pool.get() {
-            Ok(conn) => conn,
-            Err(e) => {
-                return StockImportOutcome::Failed(StockImportDetail {
-                    stock_code,
-                    imported_count: 0,
-                    success: false,
-                    error: Some(format!("获取数据库连接失败: {e}")),
-                });
-            }
-        };
-        match crate::repositories::daily_kline::exists(&mut conn, &pure_code, trade_date) {
-            Ok(true) => true,
-            Ok(false) => false,
-            Err(e) => {
-                tracing::warn!("检查股票 {} 数据是否存在时出错: {}", stock_code, e);
-                false
-            }
-        }
-    };
-
-    if exists {
-        return StockImportOutcome::Skipped(StockImportDetail {
-            stock_code,
-            imported_count: 0,
-            success: true,
-            error: Some("数据已存在，跳过导入".to_string()),
-        });
-    }
-
-    let _http_permit = match http_semaphore.acquire_owned().await {
-        Ok(permit) => permit,
-        Err(_) => {
-            return StockImportOutcome::Failed(StockImportDetail {
-                stock_code,
-                imported_count: 0,
-                success: false,
-                error: Some("HTTP 并发限流器已关闭".to_string()),
-            });
-        }
-    };
-
-    match import_single_stock_kline(&client, &pure_code, &today, &db_pool, db_semaphore).await {
-        Ok(imported) => StockImportOutcome::Success(StockImportDetail {
-            stock_code,
-            imported_count: imported,
-            success: true,
-            error: None,
-        }),
-        Err(e) => StockImportOutcome::Failed(StockImportDetail {
-            stock_code,
-            imported_count: 0,
-            success: false,
-            error: Some(e.to_string()),
-        }),
+        ..Default::default()
     }
-}
-
-/// 获取交易日期：如果是周末则返回上周五，否则返回当天
-fn get_trading_date() -> String {
-    use chrono::{Datelike, Duration, Weekday};
-
-    let now = Local::now();
-    let weekday = now.weekday();
-
-    let trading_date = match weekday {
-        Weekday::Sat => now - Duration::days(1), // 周六 -> 上周五
-        Weekday::Sun => now - Duration::days(2), // 周日 -> 上周五
-        _ => now,                                // 工作日使用当天
-    };
-
-    trading_date.format("%Y%m%d").to_string()
+    .with_details(&stock_details))
 }
@@ -34,6 +34,18 @@ diesel::table! {
         stock_name -> Varchar,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        exchange -> Nullable<Varchar>,
+        board -> Nullable<Varchar>,
+        listing_date -> Nullable<Date>,
+        industry -> Nullable<Varchar>,
+        total_shares -> Nullable<Int8>,
+        float_shares -> Nullable<Int8>,
+        total_market_cap -> Nullable<Numeric>,
+        float_market_cap -> Nullable<Numeric>,
+        is_st -> Bool,
+        list_status -> Varchar,
+        delisted_at -> Nullable<Timestamp>,
+        master_synced_at -> Nullable<Timestamp>,
     }
 }
 
@@ -59,6 +71,7 @@ diesel::table! {
         bid_ask_ratio -> Numeric,
         main_force_inflow -> Numeric,
         created_at -> Timestamptz,
+        session -> Nullable<Varchar>,
     }
 }
 
@@ -100,6 +113,134 @@ diesel::table! {
         error_message -> Nullable<Text>,
         duration_ms -> Nullable<Int8>,
         created_at -> Timestamp,
+        attempt -> Int4,
+        retry_of -> Nullable<Int4>,
+        params -> Nullable<Jsonb>,
+        cancel_requested -> Bool,
+    }
+}
+
+diesel::table! {
+    job_fire_claims (job_name, scheduled_at) {
+        job_name -> Varchar,
+        scheduled_at -> Timestamptz,
+        holder -> Varchar,
+        claimed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    job_locks (job_name) {
+        job_name -> Varchar,
+        holder -> Varchar,
+        execution_id -> Nullable<Int4>,
+        acquired_at -> Timestamp,
+        lease_until -> Timestamp,
+    }
+}
+
+diesel::table! {
+    job_pipeline_runs (id) {
+        id -> Int4,
+        root_job -> Varchar,
+        trigger_type -> Varchar,
+        status -> Varchar,
+        steps -> Jsonb,
+        started_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+        duration_ms -> Nullable<Int8>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    job_schedules (id) {
+        id -> Int4,
+        job_name -> Varchar,
+        enabled -> Bool,
+        cron_expressions -> Array<Text>,
+        timezone -> Varchar,
+        params -> Nullable<Jsonb>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        conflict_policy -> Varchar,
+        retry_max_attempts -> Int4,
+        retry_backoff_secs -> Int4,
+    }
+}
+
+diesel::table! {
+    kline_backfill_items (id) {
+        id -> Int4,
+        run_id -> Int4,
+        stock_code -> Varchar,
+        status -> Varchar,
+        expected_days -> Int4,
+        present_before -> Int4,
+        present_after -> Nullable<Int4>,
+        inserted_count -> Int4,
+        missing_ranges -> Nullable<Jsonb>,
+        error_message -> Nullable<Text>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ai_batch_runs (id) {
+        id -> Int4,
+        source -> Varchar,
+        source_key -> Nullable<Varchar>,
+        source_params -> Nullable<Jsonb>,
+        model_name -> Varchar,
+        prompt_version -> Int4,
+        concurrency -> Int4,
+        token_budget -> Nullable<Int8>,
+        tokens_used -> Int8,
+        status -> Varchar,
+        total_stocks -> Int4,
+        completed_stocks -> Int4,
+        failed_stocks -> Int4,
+        skipped_stocks -> Int4,
+        execution_id -> Nullable<Int4>,
+        error_message -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    ai_batch_items (id) {
+        id -> Int4,
+        run_id -> Int4,
+        stock_code -> Varchar,
+        status -> Varchar,
+        analysis_id -> Nullable<Int4>,
+        rating -> Nullable<Varchar>,
+        action -> Nullable<Varchar>,
+        tokens_used -> Int8,
+        error_message -> Nullable<Text>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    kline_backfill_runs (id) {
+        id -> Int4,
+        stock_set -> Varchar,
+        set_key -> Nullable<Varchar>,
+        start_date -> Date,
+        end_date -> Date,
+        status -> Varchar,
+        total_stocks -> Int4,
+        completed_stocks -> Int4,
+        failed_stocks -> Int4,
+        inserted_count -> Int4,
+        execution_id -> Nullable<Int4>,
+        error_message -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
     }
 }
 
@@ -130,6 +271,112 @@ diesel::table! {
         duration_ms -> Nullable<Int8>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        prompt_template_id -> Nullable<Int4>,
+        prompt_version -> Nullable<Int4>,
+        rating -> Nullable<Varchar>,
+        action -> Nullable<Varchar>,
+        trend_stage -> Nullable<Varchar>,
+        support_level -> Nullable<Numeric>,
+        resistance_level -> Nullable<Numeric>,
+        validation_errors -> Nullable<Jsonb>,
+        repair_rounds -> Int4,
+        input_hash -> Nullable<Varchar>,
+        prompt_tokens -> Nullable<Int8>,
+        completion_tokens -> Nullable<Int8>,
+        total_tokens -> Nullable<Int8>,
+        cost -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    ai_analysis_messages (id) {
+        id -> Int4,
+        analysis_id -> Int4,
+        role -> Varchar,
+        content -> Text,
+        model_name -> Nullable<Varchar>,
+        prompt_template_id -> Nullable<Int4>,
+        prompt_version -> Nullable<Int4>,
+        prompt_tokens -> Nullable<Int8>,
+        completion_tokens -> Nullable<Int8>,
+        total_tokens -> Nullable<Int8>,
+        cost -> Nullable<Numeric>,
+        duration_ms -> Nullable<Int8>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    ai_market_reports (id) {
+        id -> Int4,
+        report_date -> Date,
+        status -> Varchar,
+        statistics -> Jsonb,
+        summary -> Nullable<Text>,
+        model_name -> Nullable<Varchar>,
+        prompt_template_id -> Nullable<Int4>,
+        prompt_version -> Nullable<Int4>,
+        prompt_tokens -> Nullable<Int8>,
+        completion_tokens -> Nullable<Int8>,
+        total_tokens -> Nullable<Int8>,
+        cost -> Nullable<Numeric>,
+        duration_ms -> Nullable<Int8>,
+        error_message -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    ai_prediction_evaluations (id) {
+        id -> Int4,
+        analysis_id -> Int4,
+        horizon_days -> Int4,
+        stock_code -> Varchar,
+        model_name -> Varchar,
+        prompt_version -> Nullable<Int4>,
+        rating -> Nullable<Varchar>,
+        action -> Varchar,
+        expected_direction -> Varchar,
+        base_date -> Date,
+        base_close -> Numeric,
+        eval_date -> Date,
+        eval_close -> Numeric,
+        realized_return_pct -> Numeric,
+        max_gain_pct -> Numeric,
+        max_drawdown_pct -> Numeric,
+        direction_hit -> Bool,
+        rating_hit -> Nullable<Bool>,
+        resistance_reached -> Nullable<Bool>,
+        support_broken -> Nullable<Bool>,
+        evaluated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    llm_model_prices (id) {
+        id -> Int4,
+        model_name -> Varchar,
+        prompt_price -> Numeric,
+        completion_price -> Numeric,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    prompt_templates (id) {
+        id -> Int4,
+        template_key -> Varchar,
+        version -> Int4,
+        name -> Varchar,
+        description -> Nullable<Text>,
+        system_prompt -> Text,
+        user_template -> Text,
+        is_active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -167,10 +414,106 @@ diesel::table! {
 }
 
 diesel::joinable!(stock_request_stocks -> stock_requests (request_id));
+diesel::table! {
+    auction_snapshots (id) {
+        id -> Int4,
+        trade_date -> Date,
+        sample_time -> Time,
+        stock_code -> Varchar,
+        stock_name -> Varchar,
+        matched_price -> Numeric,
+        prev_close -> Numeric,
+        change_pct -> Numeric,
+        matched_volume -> Int8,
+        matched_amount -> Numeric,
+        unmatched_volume -> Nullable<Int8>,
+        universe -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::joinable!(stock_snapshots -> stock_requests (request_id));
 diesel::joinable!(profit_analysis -> stock_snapshots (snapshot_id));
+diesel::table! {
+    kline_audit_findings (id) {
+        id -> Int4,
+        stock_code -> Varchar,
+        check_type -> Varchar,
+        severity -> Varchar,
+        trade_date -> Date,
+        end_date -> Date,
+        detail -> Text,
+        context -> Nullable<Jsonb>,
+        status -> Varchar,
+        first_detected_at -> Timestamp,
+        last_detected_at -> Timestamp,
+        resolved_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    stock_name_history (id) {
+        id -> Int4,
+        stock_code -> Varchar,
+        stock_name -> Varchar,
+        is_st -> Bool,
+        valid_from -> Date,
+        valid_to -> Nullable<Date>,
+        source -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::joinable!(stock_plate_stock_table -> stock_plate (plate_id));
 diesel::joinable!(stock_plate_stock_table -> stock_table (stock_table_id));
+diesel::table! {
+    convertible_bonds (bond_code) {
+        bond_code -> Varchar,
+        bond_short_name -> Varchar,
+        stock_code -> Varchar,
+        stock_name -> Varchar,
+        rating -> Nullable<Varchar>,
+        issue_scale -> Nullable<Numeric>,
+        remaining_scale -> Nullable<Numeric>,
+        transfer_price -> Nullable<Numeric>,
+        resale_trig_price -> Nullable<Numeric>,
+        redeem_trig_price -> Nullable<Numeric>,
+        listing_date -> Nullable<Date>,
+        expire_date -> Nullable<Date>,
+        delist_date -> Nullable<Date>,
+        last_trading_date -> Nullable<Date>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    convertible_bond_daily (bond_code, trade_date) {
+        bond_code -> Varchar,
+        trade_date -> Date,
+        bond_price -> Numeric,
+        stock_price -> Nullable<Numeric>,
+        transfer_price -> Nullable<Numeric>,
+        transfer_value -> Nullable<Numeric>,
+        transfer_premium_ratio -> Nullable<Numeric>,
+        resale_trig_price -> Nullable<Numeric>,
+        redeem_trig_price -> Nullable<Numeric>,
+        pbv_ratio -> Nullable<Numeric>,
+        rating -> Nullable<Varchar>,
+        remaining_scale -> Nullable<Numeric>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(kline_backfill_items -> kline_backfill_runs (run_id));
+diesel::joinable!(ai_trend_analysis -> prompt_templates (prompt_template_id));
+diesel::joinable!(ai_batch_items -> ai_batch_runs (run_id));
+diesel::joinable!(ai_analysis_messages -> ai_trend_analysis (analysis_id));
+diesel::joinable!(ai_analysis_messages -> prompt_templates (prompt_template_id));
+diesel::joinable!(ai_batch_items -> ai_trend_analysis (analysis_id));
+diesel::joinable!(ai_prediction_evaluations -> ai_trend_analysis (analysis_id));
+diesel::joinable!(ai_market_reports -> prompt_templates (prompt_template_id));
+diesel::joinable!(convertible_bond_daily -> convertible_bonds (bond_code));
 
 diesel::allow_tables_to_appear_in_same_query!(
     he_luo_lookup,
@@ -183,8 +526,26 @@ diesel::allow_tables_to_appear_in_same_query!(
     daily_klines,
     profit_analysis,
     job_execution_history,
+    job_fire_claims,
+    job_locks,
+    job_pipeline_runs,
+    job_schedules,
+    kline_audit_findings,
+    kline_backfill_items,
+    kline_backfill_runs,
+    stock_name_history,
     stock_watchlist,
     ai_trend_analysis,
+    ai_analysis_messages,
+    ai_batch_items,
+    ai_batch_runs,
+    ai_market_reports,
+    ai_prediction_evaluations,
+    llm_model_prices,
+    prompt_templates,
     stock_trading_calendar,
+    auction_snapshots,
     export_button_config,
+    convertible_bonds,
+    convertible_bond_daily,
 );
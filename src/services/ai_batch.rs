@@ -0,0 +1,143 @@
+//! 批量 AI 趋势分析：对一组股票逐只执行 [`trend_analysis::analyze_stock`]，
+//! 并发数与 token 预算在创建时确定；模型与提示词版本也在创建时固定，整批结果可比。
+//!
+//! 预算按已完成分析的实际用量累计，达到预算后不再发起新的分析，剩余股票标记为 skipped；
+//! 预算检查发生在每只股票开始前，已在进行中的分析仍会完成，因此实际用量可能略超预算。
+//!
+//! [`trend_analysis::analyze_stock`]: crate::services::trend_analysis::analyze_stock
+
+use diesel::result::Error as DieselError;
+use serde_json::Value;
+
+use crate::models::{AiBatchRun, NewAiBatchRun};
+use crate::repositories::ai_batch;
+use crate::repositories::daily_kline::PgPoolConn;
+use crate::services::ai_service;
+
+/// 月线 MA5 上穿 MA20 的筛选结果
+pub const SOURCE_MONTHLY_MA_CROSS: &str = "monthly_ma_cross";
+
+pub const ITEM_COMPLETED: &str = "completed";
+pub const ITEM_INVALID: &str = "invalid";
+pub const ITEM_FAILED: &str = "failed";
+pub const ITEM_SKIPPED: &str = "skipped";
+
+/// 未指定时的并发数
+const DEFAULT_CONCURRENCY: i32 = 2;
+
+fn env_limit(key: &str, default: i64) -> i64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|v: &i64| *v > 0)
+        .unwrap_or(default)
+}
+
+/// 单批股票数上限，可由 `AI_BATCH_MAX_STOCKS` 覆盖
+pub fn max_stocks() -> usize {
+    env_limit("AI_BATCH_MAX_STOCKS", 100) as usize
+}
+
+/// 并发数上限，可由 `AI_BATCH_MAX_CONCURRENCY` 覆盖
+fn max_concurrency() -> i32 {
+    env_limit("AI_BATCH_MAX_CONCURRENCY", 4) as i32
+}
+
+/// 批量任务的执行参数
+#[derive(Debug, Clone)]
+pub struct BatchSettings {
+    pub model_name: String,
+    pub prompt_version: i32,
+    pub concurrency: i32,
+    pub token_budget: Option<i64>,
+}
+
+/// 校验并发数与 token 预算：并发数缺省为 2，不超过上限；预算必须为正数
+pub fn check_limits(
+    concurrency: Option<i32>,
+    token_budget: Option<i64>,
+    max_concurrency: i32,
+) -> Result<(i32, Option<i64>), String> {
+    let concurrency = concurrency.unwrap_or(DEFAULT_CONCURRENCY.min(max_concurrency));
+    if !(1..=max_concurrency).contains(&concurrency) {
+        return Err(format!(
+            "concurrency must be between 1 and {max_concurrency}"
+        ));
+    }
+    if token_budget.is_some_and(|b| b <= 0) {
+        return Err("token_budget must be positive".to_string());
+    }
+    Ok((concurrency, token_budget))
+}
+
+/// 按环境配置的上限校验并发数与 token 预算
+pub fn limits(
+    concurrency: Option<i32>,
+    token_budget: Option<i64>,
+) -> Result<(i32, Option<i64>), String> {
+    check_limits(concurrency, token_budget, max_concurrency())
+}
+
+/// 已用 token 是否达到预算
+pub fn budget_exhausted(token_budget: Option<i64>, tokens_used: i64) -> bool {
+    token_budget.is_some_and(|budget| tokens_used >= budget)
+}
+
+/// 分析记录的状态对应的逐股状态
+pub fn item_status(analysis_status: &str) -> &'static str {
+    match analysis_status {
+        ai_service::STATUS_COMPLETED => ITEM_COMPLETED,
+        ai_service::STATUS_INVALID => ITEM_INVALID,
+        _ => ITEM_FAILED,
+    }
+}
+
+/// 继续执行时需要（重新）分析的股票：已得到分析结果（completed / invalid）的不再重复
+pub fn needs_analysis(item_status: &str) -> bool {
+    item_status != ITEM_COMPLETED && item_status != ITEM_INVALID
+}
+
+/// 创建批量分析任务（逐股进度记录初始为 pending）
+pub fn create_run(
+    conn: &mut PgPoolConn,
+    source: &str,
+    source_key: Option<String>,
+    source_params: Option<Value>,
+    codes: &[String],
+    settings: &BatchSettings,
+) -> Result<AiBatchRun, DieselError> {
+    let data = NewAiBatchRun {
+        source: source.to_string(),
+        source_key,
+        source_params,
+        model_name: settings.model_name.clone(),
+        prompt_version: settings.prompt_version,
+        concurrency: settings.concurrency,
+        token_budget: settings.token_budget,
+        status: "pending".to_string(),
+        total_stocks: codes.len() as i32,
+    };
+    ai_batch::create_run(conn, &data, codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_limits_and_budget() {
+        assert_eq!(check_limits(None, None, 4), Ok((2, None)));
+        assert_eq!(check_limits(None, Some(50_000), 1), Ok((1, Some(50_000))));
+        assert!(check_limits(Some(0), None, 4).is_err());
+        assert!(check_limits(Some(5), None, 4).is_err());
+        assert!(check_limits(Some(2), Some(0), 4).is_err());
+
+        assert!(!budget_exhausted(None, i64::MAX));
+        assert!(!budget_exhausted(Some(1000), 999));
+        assert!(budget_exhausted(Some(1000), 1000));
+
+        assert_eq!(item_status(ai_service::STATUS_INVALID), ITEM_INVALID);
+        assert!(needs_analysis(ITEM_SKIPPED));
+        assert!(!needs_analysis(ITEM_COMPLETED));
+    }
+}
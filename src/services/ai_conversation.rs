@@ -0,0 +1,251 @@
+//! 趋势分析结果的追问对话：消息存于 `ai_analysis_messages`，按分析记录组成一个对话线程。
+//!
+//! 每次追问发送给模型的上下文依次为：追问模板的系统提示词、原分析的输入数据、
+//! 原分析的输出、最近 [`MAX_HISTORY_MESSAGES`] 条追问消息与本次问题。
+//! 追问与趋势分析共用用量预算，token 与费用记在回答消息上。
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono_tz::Asia::Shanghai;
+use diesel::r2d2::PoolError;
+use thiserror::Error;
+
+use crate::app::DbPool;
+use crate::models::{AiAnalysisMessage, AiTrendAnalysis, NewAiAnalysisMessage, PromptTemplate};
+use crate::repositories::ai_analysis_message;
+use crate::services::ai_service::{AiServiceError, STATUS_COMPLETED, STATUS_INVALID};
+use crate::services::ai_usage;
+use crate::services::llm_provider::{ChatMessage, ChatOptions, LlmProvider};
+use crate::services::prompt_template::{self, RenderedPrompt};
+
+pub const ROLE_USER: &str = "user";
+pub const ROLE_ASSISTANT: &str = "assistant";
+
+/// 随追问发送的历史消息上限（条）
+pub const MAX_HISTORY_MESSAGES: usize = 20;
+
+/// 单次追问的字数上限
+pub const MAX_QUESTION_CHARS: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum ConversationError {
+    #[error("分析记录 #{0} 的状态为 {1}，没有可追问的分析结果")]
+    NotAnswerable(i32, String),
+    #[error("{0}，暂停新的 AI 分析")]
+    BudgetExceeded(String),
+    #[error("llm error: {0}")]
+    Llm(#[from] AiServiceError),
+    #[error("connection pool error: {0}")]
+    Pool(#[from] PoolError),
+    #[error("database error: {0}")]
+    Db(#[from] diesel::result::Error),
+}
+
+/// 一轮追问：写入的问题与回答消息
+#[derive(Debug)]
+pub struct FollowUp {
+    pub question: AiAnalysisMessage,
+    pub answer: AiAnalysisMessage,
+}
+
+/// 只有得到模型输出的分析（completed / invalid）可以追问
+pub fn can_follow_up(status: &str) -> bool {
+    status == STATUS_COMPLETED || status == STATUS_INVALID
+}
+
+/// 校验并整理追问内容
+pub fn check_question(content: &str) -> Result<String, String> {
+    let question = content.trim();
+    if question.is_empty() {
+        return Err("content must not be empty".to_string());
+    }
+    if question.chars().count() > MAX_QUESTION_CHARS {
+        return Err(format!(
+            "content must not exceed {MAX_QUESTION_CHARS} characters"
+        ));
+    }
+    Ok(question.to_string())
+}
+
+/// 追问模板的变量
+pub fn template_vars(record: &AiTrendAnalysis) -> HashMap<&'static str, String> {
+    let display_name = format!(
+        "{} {}",
+        record.stock_code,
+        record.stock_name.as_deref().unwrap_or("")
+    );
+    HashMap::from([
+        ("stock_code", record.stock_code.clone()),
+        ("stock_name", display_name.trim().to_string()),
+        (
+            "payload",
+            serde_json::to_string_pretty(&record.request_payload).unwrap_or_default(),
+        ),
+        (
+            "analysis_date",
+            record
+                .created_at
+                .with_timezone(&Shanghai)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+        ),
+        ("model_name", record.model_name.clone()),
+    ])
+}
+
+/// 原分析的输出：优先取模型原文，没有时取解析后的 JSON
+fn previous_answer(record: &AiTrendAnalysis) -> String {
+    record
+        .raw_response
+        .clone()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| {
+            record
+                .response_json
+                .as_ref()
+                .and_then(|v| serde_json::to_string_pretty(v).ok())
+        })
+        .unwrap_or_default()
+}
+
+/// 组装发送给模型的对话；历史消息只保留最近的部分，且从用户消息开始
+pub fn build_messages(
+    prompt: &RenderedPrompt,
+    answer: &str,
+    history: &[AiAnalysisMessage],
+    question: &str,
+) -> Vec<ChatMessage> {
+    let mut recent = &history[history.len().saturating_sub(MAX_HISTORY_MESSAGES)..];
+    while recent.first().is_some_and(|m| m.role != ROLE_USER) {
+        recent = &recent[1..];
+    }
+    let mut messages = Vec::with_capacity(recent.len() + 4);
+    messages.push(ChatMessage::system(prompt.system.clone()));
+    messages.push(ChatMessage::user(prompt.user.clone()));
+    messages.push(ChatMessage::assistant(answer));
+    messages.extend(recent.iter().map(|m| ChatMessage {
+        role: m.role.clone(),
+        content: m.content.clone(),
+    }));
+    messages.push(ChatMessage::user(question));
+    messages
+}
+
+/// 对一条分析记录追问一次，问题与回答写入 `ai_analysis_messages`
+pub async fn ask(
+    db_pool: &DbPool,
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    record: &AiTrendAnalysis,
+    question: &str,
+) -> Result<FollowUp, ConversationError> {
+    if !can_follow_up(&record.status) {
+        return Err(ConversationError::NotAnswerable(
+            record.id,
+            record.status.clone(),
+        ));
+    }
+    if let Some(reason) = ai_usage::check_budget(&mut db_pool.get()?)? {
+        return Err(ConversationError::BudgetExceeded(reason));
+    }
+
+    let history = ai_analysis_message::list_by_analysis(&mut *db_pool.get()?, record.id)?;
+    let prompt = prompt_template::render_template(template, &template_vars(record));
+    let messages = build_messages(&prompt, &previous_answer(record), &history, question);
+
+    let start = Instant::now();
+    let model_name = provider.model_name();
+    tracing::info!(
+        "Calling {} for follow-up on analysis #{} ({} history messages)",
+        model_name,
+        record.id,
+        history.len()
+    );
+    let completion = provider.chat(&messages, &ChatOptions::from_env()).await?;
+    let duration_ms = start.elapsed().as_millis() as i64;
+    let usage = completion.usage;
+    let cost = ai_usage::cost_for(&mut *db_pool.get()?, &model_name, usage)?;
+
+    let user_message = NewAiAnalysisMessage {
+        analysis_id: record.id,
+        role: ROLE_USER.to_string(),
+        content: question.to_string(),
+        ..Default::default()
+    };
+    let assistant_message = NewAiAnalysisMessage {
+        analysis_id: record.id,
+        role: ROLE_ASSISTANT.to_string(),
+        content: completion.content,
+        model_name: Some(model_name),
+        prompt_template_id: Some(prompt.template_id),
+        prompt_version: Some(prompt.version),
+        prompt_tokens: Some(usage.prompt_tokens),
+        completion_tokens: Some(usage.completion_tokens),
+        total_tokens: Some(usage.total()),
+        cost,
+        duration_ms: Some(duration_ms),
+    };
+    let (question, answer) =
+        ai_analysis_message::append_turn(&mut *db_pool.get()?, &user_message, &assistant_message)?;
+    Ok(FollowUp { question, answer })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn message(id: i32, role: &str) -> AiAnalysisMessage {
+        AiAnalysisMessage {
+            id,
+            analysis_id: 1,
+            role: role.to_string(),
+            content: format!("m{id}"),
+            model_name: None,
+            prompt_template_id: None,
+            prompt_version: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+            total_tokens: None,
+            cost: None,
+            duration_ms: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn builds_follow_up_messages() {
+        let prompt = RenderedPrompt {
+            template_id: 1,
+            version: 1,
+            system: "规则".to_string(),
+            user: "数据".to_string(),
+        };
+        let history: Vec<AiAnalysisMessage> = (1..=23)
+            .map(|id| {
+                message(
+                    id,
+                    if id % 2 == 1 {
+                        ROLE_USER
+                    } else {
+                        ROLE_ASSISTANT
+                    },
+                )
+            })
+            .collect();
+        let messages = build_messages(&prompt, "结论", &history, "压力位在哪？");
+        // 最近 20 条为 m4..m23，去掉开头的回答后从 m5 开始
+        assert_eq!(messages.len(), 3 + 19 + 1);
+        assert_eq!(messages[0], ChatMessage::system("规则"));
+        assert_eq!(messages[2], ChatMessage::assistant("结论"));
+        assert_eq!(messages[3], ChatMessage::user("m5"));
+        assert_eq!(messages.last(), Some(&ChatMessage::user("压力位在哪？")));
+
+        assert!(check_question("  ").is_err());
+        assert_eq!(check_question(" 还能买吗 "), Ok("还能买吗".to_string()));
+        assert!(check_question(&"问".repeat(MAX_QUESTION_CHARS + 1)).is_err());
+        assert!(can_follow_up(STATUS_INVALID));
+        assert!(!can_follow_up("processing"));
+    }
+}
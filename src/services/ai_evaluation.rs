@@ -0,0 +1,306 @@
+//! AI 预测的事后评估：分析后第 N 个交易日，用 `daily_klines` 检验操作建议隐含的方向、
+//! 综合评级与支撑 / 压力位，结果写入 `ai_prediction_evaluations` 供按模型、提示词版本统计。
+//!
+//! 基准日为分析时已收盘的最近交易日（交易日 15:00 前的分析以前一交易日为基准），
+//! 评估日为基准日之后第 N 个交易日；评估日的K线导入后才评估，停牌时以评估期内最后一根K线为准。
+
+use bigdecimal::BigDecimal;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono_tz::Asia::Shanghai;
+use diesel::result::Error as DieselError;
+
+use crate::models::{DailyKline, NewAiPredictionEvaluation};
+use crate::repositories::ai_prediction_evaluation::PendingAnalysis;
+use crate::repositories::daily_kline::{self, PgPoolConn};
+use crate::services::ai_result::{Action, Rating};
+use crate::services::trading_calendar::{shanghai_now, TradingDays};
+
+pub const DIRECTION_UP: &str = "up";
+pub const DIRECTION_DOWN: &str = "down";
+pub const DIRECTION_FLAT: &str = "flat";
+
+/// hold / watch 视为判断横盘，涨跌幅在此范围（%）内算命中
+const FLAT_BAND_PCT: i64 = 3;
+
+/// 只评估最近这么多天内的分析记录
+pub const LOOKBACK_DAYS: i64 = 180;
+
+const MAX_HORIZON_DAYS: i32 = 60;
+
+/// 评估周期（交易日数），可由 `AI_EVAL_HORIZON_DAYS` 覆盖，默认 5
+pub fn default_horizon() -> i32 {
+    std::env::var("AI_EVAL_HORIZON_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|v| check_horizon(*v).is_ok())
+        .unwrap_or(5)
+}
+
+pub fn check_horizon(horizon: i32) -> Result<i32, String> {
+    if (1..=MAX_HORIZON_DAYS).contains(&horizon) {
+        Ok(horizon)
+    } else {
+        Err(format!(
+            "horizon_days must be between 1 and {MAX_HORIZON_DAYS}"
+        ))
+    }
+}
+
+/// 操作建议隐含的方向
+pub fn expected_direction(action: Action) -> &'static str {
+    match action {
+        Action::Buy | Action::Add => DIRECTION_UP,
+        Action::Sell | Action::Reduce => DIRECTION_DOWN,
+        Action::Hold | Action::Watch => DIRECTION_FLAT,
+    }
+}
+
+/// 评级隐含的方向：A/B 看涨、D/F 看跌，C 级不参与评级命中统计
+pub fn rating_direction(rating: Rating) -> Option<&'static str> {
+    match rating {
+        Rating::A | Rating::B => Some(DIRECTION_UP),
+        Rating::D | Rating::F => Some(DIRECTION_DOWN),
+        Rating::C => None,
+    }
+}
+
+/// 基准日：分析时（上海时间）已收盘的最近交易日
+pub fn base_date(days: &TradingDays, analyzed_at: NaiveDateTime) -> NaiveDate {
+    let date = analyzed_at.date();
+    let close = NaiveTime::from_hms_opt(15, 0, 0).unwrap_or_default();
+    if days.is_trading_day(date) && analyzed_at.time() < close {
+        days.prev_trading_day(date)
+    } else {
+        days.trading_day_on_or_before(date)
+    }
+}
+
+fn pct(price: &BigDecimal, base: &BigDecimal) -> BigDecimal {
+    ((price - base) * BigDecimal::from(100) / base).round(4)
+}
+
+fn hit(direction: &str, return_pct: &BigDecimal) -> bool {
+    let zero = BigDecimal::from(0);
+    match direction {
+        DIRECTION_UP => *return_pct > zero,
+        DIRECTION_DOWN => *return_pct < zero,
+        _ => return_pct.abs() <= BigDecimal::from(FLAT_BAND_PCT),
+    }
+}
+
+/// 评估期走势与命中情况
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceOutcome {
+    pub eval_date: NaiveDate,
+    pub eval_close: BigDecimal,
+    pub realized_return_pct: BigDecimal,
+    pub max_gain_pct: BigDecimal,
+    pub max_drawdown_pct: BigDecimal,
+    pub direction_hit: bool,
+    pub rating_hit: Option<bool>,
+    pub resistance_reached: Option<bool>,
+    pub support_broken: Option<bool>,
+}
+
+/// 按基准收盘价与评估期K线（基准日之后，升序）检验预测；评估期内没有K线时为 None
+pub fn evaluate(
+    direction: &str,
+    rating: Option<Rating>,
+    support: Option<&BigDecimal>,
+    resistance: Option<&BigDecimal>,
+    base_close: &BigDecimal,
+    window: &[DailyKline],
+) -> Option<PriceOutcome> {
+    let last = window.last()?;
+    let high = window.iter().map(|k| &k.high_price).max()?;
+    let low = window.iter().map(|k| &k.low_price).min()?;
+    let zero = BigDecimal::from(0);
+    let realized = pct(&last.close_price, base_close);
+    Some(PriceOutcome {
+        eval_date: last.trade_date,
+        eval_close: last.close_price.clone(),
+        direction_hit: hit(direction, &realized),
+        rating_hit: rating.and_then(rating_direction).map(|d| hit(d, &realized)),
+        resistance_reached: resistance.map(|r| high >= r),
+        support_broken: support.map(|s| low < s),
+        realized_return_pct: realized,
+        max_gain_pct: pct(high, base_close).max(zero.clone()),
+        max_drawdown_pct: pct(low, base_close).min(zero),
+    })
+}
+
+/// 单条分析记录的评估结果
+#[derive(Debug)]
+pub enum EvaluationResult {
+    Evaluated(Box<NewAiPredictionEvaluation>),
+    /// 评估日的K线尚未导入
+    NotReady,
+    /// 无法评估的原因（缺少基准K线、评估期停牌等），之后的执行会再次尝试
+    NoData(String),
+}
+
+/// 评估一条分析记录；`latest_trade_date` 为K线表中最新的交易日
+pub fn evaluate_analysis(
+    conn: &mut PgPoolConn,
+    days: &TradingDays,
+    analysis: &PendingAnalysis,
+    horizon: i32,
+    latest_trade_date: NaiveDate,
+) -> Result<EvaluationResult, DieselError> {
+    let Some(action) = analysis.action.as_deref().and_then(Action::parse) else {
+        return Ok(EvaluationResult::NoData(format!(
+            "无法识别的操作建议: {:?}",
+            analysis.action
+        )));
+    };
+    let analyzed_at = analysis.created_at.with_timezone(&Shanghai).naive_local();
+    let base = base_date(days, analyzed_at);
+    let eval_date = days.nth_trading_day_after(base, horizon as usize);
+    if eval_date > latest_trade_date {
+        return Ok(EvaluationResult::NotReady);
+    }
+
+    // 基准日停牌时取之前最近一根K线
+    let klines = daily_kline::list_range(
+        conn,
+        &analysis.stock_code,
+        base - Duration::days(30),
+        eval_date,
+    )?;
+    let split = klines.partition_point(|k| k.trade_date <= base);
+    let Some(base_bar) = split.checked_sub(1).map(|i| &klines[i]) else {
+        return Ok(EvaluationResult::NoData(format!(
+            "基准日 {base} 之前没有K线"
+        )));
+    };
+    let direction = expected_direction(action);
+    let rating = analysis.rating.as_deref().and_then(Rating::parse);
+    let Some(outcome) = evaluate(
+        direction,
+        rating,
+        analysis.support_level.as_ref(),
+        analysis.resistance_level.as_ref(),
+        &base_bar.close_price,
+        &klines[split..],
+    ) else {
+        return Ok(EvaluationResult::NoData(format!(
+            "{base} ~ {eval_date} 没有K线（停牌）"
+        )));
+    };
+
+    Ok(EvaluationResult::Evaluated(Box::new(
+        NewAiPredictionEvaluation {
+            analysis_id: analysis.id,
+            horizon_days: horizon,
+            stock_code: analysis.stock_code.clone(),
+            model_name: analysis.model_name.clone(),
+            prompt_version: analysis.prompt_version,
+            rating: analysis.rating.clone(),
+            action: action.as_str().to_string(),
+            expected_direction: direction.to_string(),
+            base_date: base_bar.trade_date,
+            base_close: base_bar.close_price.clone(),
+            eval_date: outcome.eval_date,
+            eval_close: outcome.eval_close,
+            realized_return_pct: outcome.realized_return_pct,
+            max_gain_pct: outcome.max_gain_pct,
+            max_drawdown_pct: outcome.max_drawdown_pct,
+            direction_hit: outcome.direction_hit,
+            rating_hit: outcome.rating_hit,
+            resistance_reached: outcome.resistance_reached,
+            support_broken: outcome.support_broken,
+            evaluated_at: shanghai_now(),
+        },
+    )))
+}
+
+/// 命中率（%，保留两位小数）；样本为 0 时为 None
+pub fn hit_rate(hits: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| (hits as f64 * 10000.0 / total as f64).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    fn bar(day: u32, high: &str, low: &str, close: &str) -> DailyKline {
+        DailyKline {
+            stock_code: "600519".to_string(),
+            trade_date: NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            open_price: dec(close),
+            high_price: dec(high),
+            low_price: dec(low),
+            close_price: dec(close),
+            volume: 100,
+            amount: dec("0"),
+        }
+    }
+
+    #[test]
+    fn base_date_uses_last_closed_session() {
+        let days = TradingDays::default();
+        let at = |day, h| {
+            NaiveDate::from_ymd_opt(2026, 10, day)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+        };
+        let d = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        // 2026-10-16 为周五
+        assert_eq!(base_date(&days, at(16, 10)), d(15));
+        assert_eq!(base_date(&days, at(16, 16)), d(16));
+        assert_eq!(base_date(&days, at(18, 10)), d(16));
+    }
+
+    #[test]
+    fn evaluates_direction_rating_and_levels() {
+        let window = [
+            bar(16, "10.80", "9.70", "10.50"),
+            bar(19, "11.20", "10.30", "11.00"),
+        ];
+        let outcome = evaluate(
+            DIRECTION_UP,
+            Some(Rating::D),
+            Some(&dec("9.80")),
+            Some(&dec("11.50")),
+            &dec("10.00"),
+            &window,
+        )
+        .unwrap();
+        assert_eq!(
+            outcome.eval_date,
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+        );
+        assert_eq!(outcome.realized_return_pct, dec("10"));
+        assert_eq!(outcome.max_gain_pct, dec("12"));
+        assert_eq!(outcome.max_drawdown_pct, dec("-3"));
+        assert!(outcome.direction_hit);
+        assert_eq!(outcome.rating_hit, Some(false));
+        assert_eq!(outcome.resistance_reached, Some(false));
+        assert_eq!(outcome.support_broken, Some(true));
+
+        let flat = evaluate(
+            DIRECTION_FLAT,
+            Some(Rating::C),
+            None,
+            None,
+            &dec("10.00"),
+            &window,
+        )
+        .unwrap();
+        assert!(!flat.direction_hit);
+        assert_eq!(flat.rating_hit, None);
+        assert_eq!(flat.resistance_reached, None);
+        assert!(evaluate(DIRECTION_UP, None, None, None, &dec("10"), &[]).is_none());
+
+        assert_eq!(expected_direction(Action::Reduce), DIRECTION_DOWN);
+        assert_eq!(hit_rate(2, 3), Some(66.67));
+        assert_eq!(hit_rate(0, 0), None);
+        assert!(check_horizon(0).is_err());
+    }
+}
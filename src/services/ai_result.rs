@@ -0,0 +1,301 @@
+//! 趋势分析结果的类型与校验：先按 JSON Schema（`src/asset/schema/trend_analysis.schema.json`）
+//! 校验结构，再反序列化为类型化结果并检查业务约束。校验失败时由 [`repair_request`] 生成修正提示，
+//! 把错误列表连同 Schema 交给模型重新输出。
+
+use std::sync::OnceLock;
+
+use jsonschema::JSONSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+const SCHEMA_TEXT: &str = include_str!("../asset/schema/trend_analysis.schema.json");
+
+/// 评级（与提示词一致：A 最强，F 为无效信号）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rating {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Rating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rating::A => "A",
+            Rating::B => "B",
+            Rating::C => "C",
+            Rating::D => "D",
+            Rating::F => "F",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "A" => Some(Rating::A),
+            "B" => Some(Rating::B),
+            "C" => Some(Rating::C),
+            "D" => Some(Rating::D),
+            "F" => Some(Rating::F),
+            _ => None,
+        }
+    }
+}
+
+/// 操作建议归类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Buy,
+    Add,
+    Hold,
+    Reduce,
+    Sell,
+    Watch,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Buy => "buy",
+            Action::Add => "add",
+            Action::Hold => "hold",
+            Action::Reduce => "reduce",
+            Action::Sell => "sell",
+            Action::Watch => "watch",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "buy" => Some(Action::Buy),
+            "add" => Some(Action::Add),
+            "hold" => Some(Action::Hold),
+            "reduce" => Some(Action::Reduce),
+            "sell" => Some(Action::Sell),
+            "watch" => Some(Action::Watch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataCompleteness {
+    #[serde(default)]
+    pub earliest_signal_date: Option<String>,
+    #[serde(default)]
+    pub history_trading_days: Option<i64>,
+    pub is_sufficient: bool,
+    #[serde(default)]
+    pub trend_coverage_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overview {
+    pub stock_name: String,
+    pub signal_count: i64,
+    #[serde(default)]
+    pub analysis_period: Option<String>,
+    pub data_completeness: DataCompleteness,
+}
+
+/// 单个信号的回溯结论
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalEvaluation {
+    pub signal_index: i64,
+    pub datetime: String,
+    #[serde(default)]
+    pub indicators: Option<JsonValue>,
+    pub trend_phase: String,
+    #[serde(default)]
+    pub time_quality: Option<String>,
+    pub is_valid: bool,
+    #[serde(default)]
+    pub kline_pattern: Option<String>,
+    #[serde(default)]
+    pub actual_change_pct: Option<f64>,
+    #[serde(default)]
+    pub volume_assessment: Option<String>,
+    #[serde(default)]
+    pub key_level: Option<String>,
+    pub rating: Rating,
+    pub rating_reason: String,
+    pub action_suggestion: String,
+}
+
+/// 当前趋势诊断
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentDiagnosis {
+    /// 趋势阶段，如 主升延续 / 高位震荡 / 主跌初期 / 筑底反弹
+    pub status: String,
+    pub rating: Rating,
+    pub action: Action,
+    pub action_suggestion: String,
+    pub support_level: f64,
+    pub resistance_level: f64,
+    #[serde(default)]
+    pub ma5: Option<f64>,
+    #[serde(default)]
+    pub ma20: Option<f64>,
+    #[serde(default)]
+    pub has_new_signal: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePoint {
+    pub date: String,
+    pub close_price: f64,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+    #[serde(default)]
+    pub matched_conditions: Option<i64>,
+    #[serde(default)]
+    pub trend_phase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePair {
+    pub pair_index: i64,
+    pub buy: TradePoint,
+    pub sell: TradePoint,
+    #[serde(default)]
+    pub theoretical_return_pct: Option<f64>,
+    #[serde(default)]
+    pub holding_days: Option<i64>,
+}
+
+/// 类型化的趋势分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendAnalysisResult {
+    pub overview: Overview,
+    pub signal_evaluations: Vec<SignalEvaluation>,
+    pub current_diagnosis: CurrentDiagnosis,
+    #[serde(default)]
+    pub optimal_trade_signals: Vec<TradePair>,
+    pub risk_warnings: Vec<String>,
+}
+
+fn schema_value() -> &'static JsonValue {
+    static SCHEMA: OnceLock<JsonValue> = OnceLock::new();
+    SCHEMA.get_or_init(|| serde_json::from_str(SCHEMA_TEXT).expect("invalid result schema"))
+}
+
+fn compiled_schema() -> &'static JSONSchema {
+    static COMPILED: OnceLock<JSONSchema> = OnceLock::new();
+    COMPILED.get_or_init(|| JSONSchema::compile(schema_value()).expect("invalid result schema"))
+}
+
+/// 校验模型输出：Schema 错误以「路径: 原因」列出，通过后检查业务约束
+pub fn validate(value: &JsonValue) -> Result<TrendAnalysisResult, Vec<String>> {
+    if let Err(errors) = compiled_schema().validate(value) {
+        return Err(errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                let path = if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path
+                };
+                format!("{path}: {e}")
+            })
+            .collect());
+    }
+    let result: TrendAnalysisResult =
+        serde_json::from_value(value.clone()).map_err(|e| vec![e.to_string()])?;
+
+    let mut errors = Vec::new();
+    if !result.overview.data_completeness.is_sufficient {
+        for (i, s) in result.signal_evaluations.iter().enumerate() {
+            if matches!(s.rating, Rating::A | Rating::B) {
+                errors.push(format!(
+                    "/signal_evaluations/{i}/rating: 历史数据不足时不得给予 A/B 级评级"
+                ));
+            }
+        }
+    }
+    let diagnosis = &result.current_diagnosis;
+    if diagnosis.support_level > diagnosis.resistance_level {
+        errors.push(format!(
+            "/current_diagnosis: 支撑位 {} 高于压力位 {}",
+            diagnosis.support_level, diagnosis.resistance_level
+        ));
+    }
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+/// 修正提示：列出错误并附上 Schema，要求只输出修正后的完整 JSON
+pub fn repair_request(errors: &[String]) -> String {
+    format!(
+        "上一次输出的 JSON 未通过校验，错误如下：\n{}\n\n请修正以上问题，严格按照下面的 JSON Schema 重新输出完整的 JSON 对象，\
+         不要包含 markdown 标记或任何额外文字：\n{}",
+        errors
+            .iter()
+            .map(|e| format!("- {e}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        SCHEMA_TEXT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> JsonValue {
+        json!({
+            "overview": {
+                "stock_name": "600519 贵州茅台",
+                "signal_count": 1,
+                "data_completeness": {"is_sufficient": true, "history_trading_days": 35}
+            },
+            "signal_evaluations": [{
+                "signal_index": 1,
+                "datetime": "2026-01-19 09:43:00.827 +0800",
+                "trend_phase": "主升初期",
+                "is_valid": true,
+                "rating": "A",
+                "rating_reason": "放量突破",
+                "action_suggestion": "持有"
+            }],
+            "current_diagnosis": {
+                "status": "主升延续",
+                "rating": "B",
+                "action": "hold",
+                "action_suggestion": "沿 MA5 持有",
+                "support_level": 8.65,
+                "resistance_level": 9.33
+            },
+            "risk_warnings": ["本结论仅用于复盘验证，不构成投资建议"]
+        })
+    }
+
+    #[test]
+    fn validates_schema_and_business_rules() {
+        let result = validate(&sample()).unwrap();
+        assert_eq!(result.current_diagnosis.rating, Rating::B);
+        assert_eq!(result.current_diagnosis.action, Action::Hold);
+        assert!(result.optimal_trade_signals.is_empty());
+
+        let mut bad = sample();
+        bad["current_diagnosis"]["action"] = json!("梭哈");
+        bad["signal_evaluations"][0]["rating"] = json!("S");
+        let errors = validate(&bad).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("/current_diagnosis/action")));
+
+        let mut insufficient = sample();
+        insufficient["overview"]["data_completeness"]["is_sufficient"] = json!(false);
+        insufficient["current_diagnosis"]["support_level"] = json!(10.0);
+        let errors = validate(&insufficient).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(repair_request(&errors).contains("/signal_evaluations/0/rating"));
+    }
+}
@@ -1,9 +1,16 @@
-use anyhow::Result;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::Utc;
 use serde_json::Value as JsonValue;
 use thiserror::Error;
 
+use crate::models::UpdateAiTrendAnalysis;
+
+use crate::services::ai_result::{self, TrendAnalysisResult};
+use crate::services::llm_provider::{ChatMessage, ChatOptions, LlmProvider, TokenUsage};
+use crate::services::prompt_template::RenderedPrompt;
+
 #[derive(Debug, Error)]
 pub enum AiServiceError {
     #[error("http error: {0}")]
@@ -16,145 +23,196 @@ pub enum AiServiceError {
     EnvError(String),
     #[error("parse error: {0}")]
     ParseError(String),
+    #[error("unsupported model: {0}")]
+    UnsupportedModel(String),
 }
 
-/// Qwen API 请求消息
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
-}
-
-/// Qwen API 请求体
-#[derive(Debug, Serialize)]
-struct ChatCompletionRequest {
-    model: String,
-    messages: Vec<ChatMessage>,
-    temperature: f64,
-    max_tokens: u32,
-}
-
-/// Qwen API 响应结构
-#[derive(Debug, Deserialize)]
-struct ChatCompletionResponse {
-    choices: Vec<ChatChoice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChatChoice {
-    message: ChatMessageResponse,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChatMessageResponse {
-    content: String,
-}
+pub const STATUS_COMPLETED: &str = "completed";
+/// 输出在修正后仍未通过校验
+pub const STATUS_INVALID: &str = "invalid";
+pub const STATUS_FAILED: &str = "failed";
 
 /// AI 分析结果
 #[derive(Debug)]
 pub struct AiAnalysisResult {
     /// 解析后的结构化 JSON（如果解析成功）
     pub response_json: Option<JsonValue>,
-    /// 原始文本响应
+    /// 原始文本响应（发生修正时为最后一轮的输出）
     pub raw_response: String,
+    /// 通过校验的类型化结果
+    pub result: Option<TrendAnalysisResult>,
+    /// 最后一轮输出的校验错误，通过校验时为空
+    pub validation_errors: Vec<String>,
+    /// 修正轮数
+    pub repair_rounds: i32,
+    /// 各轮调用累计的 token 用量
+    pub usage: TokenUsage,
 }
 
-/// 创建用于调用 Qwen API 的 HTTP 客户端（不需要代理）
-pub fn create_ai_client() -> Result<Client, reqwest::Error> {
-    Client::builder()
-        .timeout(std::time::Duration::from_secs(180))
-        .build()
+/// 流式分析过程中的事件
+#[derive(Debug, Clone)]
+pub enum AnalysisEvent {
+    /// 已写入 processing 状态的分析记录，开始调用模型
+    Started {
+        analysis_id: i32,
+        model_name: String,
+    },
+    /// 第 round 轮（0 为首轮）输出的增量文本
+    Delta { round: i32, text: String },
+    /// 输出未通过校验，开始第 round 轮修正
+    Repair { round: i32, errors: Vec<String> },
 }
 
-/// 读取系统 prompt
-pub fn load_system_prompt() -> String {
-    include_str!("../asset/prompt/盘中启动信号全息回溯与趋势诊断系统 Prompt.md").to_string()
+/// 接收分析事件的回调
+pub type EventSink<'a> = &'a (dyn Fn(AnalysisEvent) + Send + Sync);
+
+/// 校验失败时最多重新请求的轮数，可由 `LLM_REPAIR_ROUNDS` 覆盖
+fn max_repair_rounds() -> i32 {
+    std::env::var("LLM_REPAIR_ROUNDS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(1)
 }
 
-/// 调用 Qwen API 进行趋势分析
-pub async fn call_qwen_analysis(
-    client: &Client,
-    user_payload: &JsonValue,
+/// 用替换变量后的提示词调用所选模型进行趋势分析；传入 `events` 时以流式调用并推送增量文本
+pub async fn run_trend_analysis(
+    provider: &dyn LlmProvider,
+    prompt: &RenderedPrompt,
+    events: Option<EventSink<'_>>,
 ) -> Result<AiAnalysisResult, AiServiceError> {
-    let api_url = std::env::var("QWEN_API_URL").unwrap_or_else(|_| {
-        "https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions".to_string()
-    });
-    let api_key = std::env::var("QWEN_API_KEY")
-        .map_err(|_| AiServiceError::EnvError("QWEN_API_KEY not set".to_string()))?;
-    let model = std::env::var("QWEN_MODEL").unwrap_or_else(|_| "qwen3-max-2026-01-23".to_string());
-
-    let system_prompt = load_system_prompt();
-
-    let user_content =
-        serde_json::to_string_pretty(user_payload).map_err(AiServiceError::SerdeJson)?;
-
-    let request_body = ChatCompletionRequest {
-        model,
-        messages: vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: system_prompt,
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: user_content,
-            },
-        ],
-        temperature: 0.3,
-        max_tokens: 8192,
-    };
+    let mut messages = vec![
+        ChatMessage::system(prompt.system.clone()),
+        ChatMessage::user(prompt.user.clone()),
+    ];
+    let options = ChatOptions::from_env();
+    let max_rounds = max_repair_rounds();
 
-    tracing::info!("Calling Qwen API for stock analysis...");
-
-    let response = client
-        .post(&api_url)
-        .header("Authorization", format!("Bearer {api_key}"))
-        .header("Content-Type", "application/json")
-        .json(&request_body)
-        .send()
-        .await
-        .map_err(AiServiceError::Http)?;
-
-    let status = response.status();
-    let response_text = response.text().await.map_err(AiServiceError::Http)?;
-
-    if !status.is_success() {
-        tracing::error!(
-            "Qwen API returned error status {}: {}",
-            status,
-            response_text
+    let mut round = 0;
+    let mut usage = TokenUsage::default();
+    loop {
+        tracing::info!(
+            "Calling {} for stock analysis (round {})...",
+            provider.model_name(),
+            round
+        );
+        let completion = match events {
+            Some(sink) => {
+                let on_delta = |text: &str| {
+                    sink(AnalysisEvent::Delta {
+                        round,
+                        text: text.to_string(),
+                    })
+                };
+                provider.chat_stream(&messages, &options, &on_delta).await?
+            }
+            None => provider.chat(&messages, &options).await?,
+        };
+        usage.add(completion.usage);
+        let raw_content = completion.content;
+        tracing::info!(
+            "{} returned {} chars of content",
+            provider.model_name(),
+            raw_content.len()
         );
-        return Err(AiServiceError::ApiError(format!(
-            "API returned status {status}: {response_text}"
-        )));
-    }
 
-    // 解析 API 响应
-    let api_response: ChatCompletionResponse =
-        serde_json::from_str(&response_text).map_err(|e| {
-            tracing::error!(
-                "Failed to parse Qwen API response: {} | raw: {}",
-                e,
-                response_text
+        // 尝试从返回的内容中提取 JSON 并校验
+        let response_json = extract_json_from_response(&raw_content);
+        let checked = match &response_json {
+            Some(value) => ai_result::validate(value),
+            None => Err(vec!["输出中没有可解析的 JSON 对象".to_string()]),
+        };
+        let errors = match checked {
+            Ok(result) => {
+                return Ok(AiAnalysisResult {
+                    response_json,
+                    raw_response: raw_content,
+                    result: Some(result),
+                    validation_errors: Vec::new(),
+                    repair_rounds: round,
+                    usage,
+                })
+            }
+            Err(errors) => errors,
+        };
+        if round >= max_rounds {
+            tracing::warn!(
+                "AI output still invalid after {} repair rounds: {:?}",
+                round,
+                errors
             );
-            AiServiceError::ParseError(format!("Failed to parse API response: {e}"))
-        })?;
-
-    let raw_content = api_response
-        .choices
-        .first()
-        .map(|c| c.message.content.clone())
-        .unwrap_or_default();
-
-    tracing::info!("Qwen API returned {} chars of content", raw_content.len());
+            return Ok(AiAnalysisResult {
+                response_json,
+                raw_response: raw_content,
+                result: None,
+                validation_errors: errors,
+                repair_rounds: round,
+                usage,
+            });
+        }
+        tracing::warn!(
+            "AI output failed validation, asking for repair: {:?}",
+            errors
+        );
+        messages.push(ChatMessage::assistant(raw_content));
+        messages.push(ChatMessage::user(ai_result::repair_request(&errors)));
+        round += 1;
+        if let Some(sink) = events {
+            sink(AnalysisEvent::Repair { round, errors });
+        }
+    }
+}
 
-    // 尝试从返回的内容中提取 JSON
-    let response_json = extract_json_from_response(&raw_content);
+fn decimal(v: f64) -> Option<BigDecimal> {
+    BigDecimal::from_str(&format!("{v:.2}")).ok()
+}
 
-    Ok(AiAnalysisResult {
-        response_json,
-        raw_response: raw_content,
-    })
+/// 由调用结果生成分析记录的更新：通过校验时写入评级、操作建议等可查询字段
+pub fn to_update(
+    outcome: Result<AiAnalysisResult, AiServiceError>,
+    duration_ms: i64,
+) -> UpdateAiTrendAnalysis {
+    let base = UpdateAiTrendAnalysis {
+        duration_ms: Some(duration_ms),
+        updated_at: Some(Utc::now()),
+        ..Default::default()
+    };
+    let result = match outcome {
+        Ok(result) => result,
+        Err(e) => {
+            return UpdateAiTrendAnalysis {
+                status: Some(STATUS_FAILED.to_string()),
+                error_message: Some(e.to_string()),
+                ..base
+            }
+        }
+    };
+    let Some(typed) = result.result else {
+        return UpdateAiTrendAnalysis {
+            status: Some(STATUS_INVALID.to_string()),
+            response_json: result.response_json,
+            raw_response: Some(result.raw_response),
+            error_message: Some(format!(
+                "AI 输出未通过校验：{}",
+                result.validation_errors.join("; ")
+            )),
+            validation_errors: Some(serde_json::json!(result.validation_errors)),
+            repair_rounds: Some(result.repair_rounds),
+            ..base
+        };
+    };
+    let diagnosis = &typed.current_diagnosis;
+    UpdateAiTrendAnalysis {
+        status: Some(STATUS_COMPLETED.to_string()),
+        response_json: result.response_json,
+        raw_response: Some(result.raw_response),
+        rating: Some(diagnosis.rating.as_str().to_string()),
+        action: Some(diagnosis.action.as_str().to_string()),
+        trend_stage: Some(diagnosis.status.chars().take(32).collect()),
+        support_level: decimal(diagnosis.support_level),
+        resistance_level: decimal(diagnosis.resistance_level),
+        repair_rounds: Some(result.repair_rounds),
+        ..base
+    }
 }
 
 /// 从 AI 响应中提取 JSON
@@ -0,0 +1,257 @@
+//! AI 分析的 token 用量、费用与预算。
+//!
+//! 费用按 `llm_model_prices` 中的单价（元 / 百万 token）计算：先找 `provider/model`，
+//! 找不到时使用 `provider/*`，都没有时不计费用（`cost` 为空）。
+//!
+//! 预算由环境变量配置（按北京时间的自然日、自然月累计），任一项达到上限后拒绝新的分析；
+//! 命中结果缓存的请求不调用模型，不受预算限制。
+
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use diesel::result::Error as DieselError;
+use diesel::PgConnection;
+
+use crate::models::LlmModelPrice;
+use crate::repositories::ai_usage::{self as usage_repo, UsageTotals};
+use crate::repositories::daily_kline::PgPoolConn;
+use crate::repositories::llm_model_price;
+use crate::services::llm_provider::TokenUsage;
+use crate::services::trading_calendar::shanghai_now;
+
+/// 单价的计量单位：每百万 token
+const PRICE_UNIT_TOKENS: i64 = 1_000_000;
+
+/// 查找单价时依次尝试的名称：`provider/model`、`provider/*`
+pub fn price_candidates(model_name: &str) -> Vec<String> {
+    let mut names = vec![model_name.to_string()];
+    if let Some((provider, _)) = model_name.split_once('/') {
+        names.push(format!("{provider}/*"));
+    }
+    names
+}
+
+/// 按用量与单价计算费用（元，保留 6 位小数）
+pub fn compute_cost(usage: TokenUsage, price: &LlmModelPrice) -> BigDecimal {
+    let amount = BigDecimal::from(usage.prompt_tokens) * &price.prompt_price
+        + BigDecimal::from(usage.completion_tokens) * &price.completion_price;
+    (amount / BigDecimal::from(PRICE_UNIT_TOKENS)).with_scale(6)
+}
+
+/// 模型适用的单价
+pub fn price_for(
+    conn: &mut PgConnection,
+    model_name: &str,
+) -> Result<Option<LlmModelPrice>, DieselError> {
+    let candidates = price_candidates(model_name);
+    let prices = llm_model_price::find_by_names(conn, &candidates)?;
+    Ok(candidates
+        .iter()
+        .find_map(|name| prices.iter().find(|p| &p.model_name == name))
+        .cloned())
+}
+
+/// 一次分析的费用；未配置单价时为 None
+pub fn cost_for(
+    conn: &mut PgConnection,
+    model_name: &str,
+    usage: TokenUsage,
+) -> Result<Option<BigDecimal>, DieselError> {
+    Ok(price_for(conn, model_name)?.map(|price| compute_cost(usage, &price)))
+}
+
+/// 用量上限，未配置的项不限制
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetLimits {
+    pub daily_tokens: Option<i64>,
+    pub monthly_tokens: Option<i64>,
+    pub daily_cost: Option<BigDecimal>,
+    pub monthly_cost: Option<BigDecimal>,
+}
+
+fn env_value<T: FromStr>(key: &str) -> Option<T> {
+    std::env::var(key).ok().and_then(|v| v.trim().parse().ok())
+}
+
+impl BudgetLimits {
+    /// `AI_DAILY_TOKEN_LIMIT` / `AI_MONTHLY_TOKEN_LIMIT` / `AI_DAILY_COST_LIMIT` / `AI_MONTHLY_COST_LIMIT`
+    pub fn from_env() -> Self {
+        Self {
+            daily_tokens: env_value("AI_DAILY_TOKEN_LIMIT"),
+            monthly_tokens: env_value("AI_MONTHLY_TOKEN_LIMIT"),
+            daily_cost: env_value("AI_DAILY_COST_LIMIT"),
+            monthly_cost: env_value("AI_MONTHLY_COST_LIMIT"),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 已达到的上限说明；未超出时为 None
+    pub fn exceeded(&self, today: &UsageTotals, month: &UsageTotals) -> Option<String> {
+        let tokens = [
+            ("今日", self.daily_tokens, today.total_tokens),
+            ("本月", self.monthly_tokens, month.total_tokens),
+        ];
+        for (label, limit, used) in tokens {
+            if let Some(limit) = limit.filter(|limit| used >= *limit) {
+                return Some(format!(
+                    "{label} AI 分析 token 用量 {used} 已达上限 {limit}"
+                ));
+            }
+        }
+        let costs = [
+            ("今日", &self.daily_cost, &today.cost),
+            ("本月", &self.monthly_cost, &month.cost),
+        ];
+        for (label, limit, used) in costs {
+            if let Some(limit) = limit.as_ref().filter(|limit| used >= *limit) {
+                return Some(format!("{label} AI 分析费用 {used} 元已达上限 {limit} 元"));
+            }
+        }
+        None
+    }
+}
+
+/// 本月第一天
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// 用量报表的 `to_char` 格式与区间：按日缺省为最近 30 天，按月缺省为最近 12 个月
+pub fn report_range(
+    period: &str,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<(&'static str, NaiveDate, NaiveDate), String> {
+    let end = end.unwrap_or(today);
+    let (format, default_start) = match period {
+        "day" => ("YYYY-MM-DD", end - Duration::days(29)),
+        "month" => (
+            "YYYY-MM",
+            month_start(end)
+                .checked_sub_months(Months::new(11))
+                .unwrap_or(end),
+        ),
+        other => return Err(format!("invalid period: {other} (expected day / month)")),
+    };
+    let start = start.unwrap_or(default_start);
+    if start > end {
+        return Err("start_date must not be after end_date".to_string());
+    }
+    Ok((format, start, end))
+}
+
+/// 今日与本月（北京时间）的用量
+pub fn current_usage(conn: &mut PgPoolConn) -> Result<(UsageTotals, UsageTotals), DieselError> {
+    let today = shanghai_now().date();
+    let daily = usage_repo::usage_since(conn, today)?;
+    let monthly = usage_repo::usage_since(conn, month_start(today))?;
+    Ok((daily, monthly))
+}
+
+/// 检查预算，已达上限时返回说明；未配置预算时不查询
+pub fn check_budget(conn: &mut PgPoolConn) -> Result<Option<String>, DieselError> {
+    let limits = BudgetLimits::from_env();
+    if limits.is_unlimited() {
+        return Ok(None);
+    }
+    let (daily, monthly) = current_usage(conn)?;
+    Ok(limits.exceeded(&daily, &monthly))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn price(prompt: &str, completion: &str) -> LlmModelPrice {
+        LlmModelPrice {
+            id: 1,
+            model_name: "qwen/*".to_string(),
+            prompt_price: BigDecimal::from_str(prompt).unwrap(),
+            completion_price: BigDecimal::from_str(completion).unwrap(),
+            description: None,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn totals(total_tokens: i64, cost: &str) -> UsageTotals {
+        UsageTotals {
+            total_tokens,
+            cost: BigDecimal::from_str(cost).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn computes_cost_and_checks_budget() {
+        assert_eq!(
+            price_candidates("qwen/qwen3-max"),
+            vec!["qwen/qwen3-max".to_string(), "qwen/*".to_string()]
+        );
+        assert_eq!(price_candidates("stub"), vec!["stub".to_string()]);
+
+        let usage = TokenUsage {
+            prompt_tokens: 12_000,
+            completion_tokens: 1_500,
+        };
+        // 12000 × 2.4 / 1e6 + 1500 × 9.6 / 1e6 = 0.0288 + 0.0144
+        assert_eq!(
+            compute_cost(usage, &price("2.4", "9.6")),
+            BigDecimal::from_str("0.043200").unwrap()
+        );
+
+        let limits = BudgetLimits {
+            daily_tokens: Some(100_000),
+            monthly_cost: Some(BigDecimal::from(50)),
+            ..Default::default()
+        };
+        assert!(!limits.is_unlimited());
+        assert_eq!(
+            limits.exceeded(&totals(99_999, "1"), &totals(500_000, "49.9")),
+            None
+        );
+        assert!(limits
+            .exceeded(&totals(100_000, "1"), &totals(500_000, "1"))
+            .is_some());
+        assert!(limits
+            .exceeded(&totals(0, "0"), &totals(500_000, "50"))
+            .is_some());
+        assert!(BudgetLimits::default().is_unlimited());
+
+        let date = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert_eq!(
+            month_start(date),
+            NaiveDate::from_ymd_opt(2026, 10, 1).unwrap()
+        );
+        assert_eq!(
+            report_range("day", None, None, date),
+            Ok((
+                "YYYY-MM-DD",
+                NaiveDate::from_ymd_opt(2026, 9, 20).unwrap(),
+                date
+            ))
+        );
+        assert_eq!(
+            report_range("month", None, None, date),
+            Ok((
+                "YYYY-MM",
+                NaiveDate::from_ymd_opt(2025, 11, 1).unwrap(),
+                date
+            ))
+        );
+        assert!(report_range("week", None, None, date).is_err());
+        assert!(report_range(
+            "day",
+            Some(date),
+            NaiveDate::from_ymd_opt(2026, 10, 1),
+            date
+        )
+        .is_err());
+    }
+}
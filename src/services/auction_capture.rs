@@ -0,0 +1,328 @@
+//! 开盘集合竞价（9:15-9:25）行情采样：全市场列表取匹配价 / 匹配量 / 匹配金额 / 昨收，
+//! 对满足 [`AuctionFilterParams`] 的个股再拉取明细计算未匹配量（买一量 - 卖一量）。
+
+use std::sync::Arc;
+
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::sync::{Mutex, Semaphore};
+
+use crate::utils::proxy::{proxy_get_json, shared_proxy_client, ProxyClient, ProxyError};
+use crate::utils::secid::code_to_secid;
+
+const EM_LIST_URL: &str = "https://push2.eastmoney.com/api/qt/clist/get";
+const EM_DETAIL_URL: &str = "https://push2.eastmoney.com/api/qt/stock/get";
+const EM_UT: &str = "bd1d9ddb04089700cf9c27f6f7426281";
+
+#[derive(Debug, Error)]
+pub enum AuctionCaptureError {
+    #[error("proxy error: {0}")]
+    Proxy(#[from] ProxyError),
+    #[error("url parse error: {0}")]
+    Url(String),
+}
+
+/// 竞价筛选参数（与 `FilterParams` 对应，阈值作用于竞价数据）
+#[derive(Debug, Clone)]
+pub struct AuctionFilterParams {
+    /// 竞价涨幅下限(%)
+    pub pct_min: f64,
+    /// 竞价涨幅上限(%)
+    pub pct_max: f64,
+    /// 匹配金额下限（元）
+    pub amount_min: f64,
+    /// 匹配量下限（手）
+    pub volume_min: i64,
+    /// 未匹配量下限（手），仅对已抓取明细的记录生效
+    pub unmatched_min: Option<i64>,
+    pub concurrency: usize,
+    pub pz: i32,
+}
+
+impl Default for AuctionFilterParams {
+    fn default() -> Self {
+        Self {
+            pct_min: 2.0,
+            pct_max: 9.5,
+            amount_min: 5_000_000.0,
+            volume_min: 0,
+            unmatched_min: None,
+            concurrency: 8,
+            pz: 1000,
+        }
+    }
+}
+
+impl AuctionFilterParams {
+    pub fn matches(&self, quote: &AuctionQuote) -> bool {
+        quote.change_pct >= self.pct_min
+            && quote.change_pct <= self.pct_max
+            && quote.matched_amount >= self.amount_min
+            && quote.matched_volume >= self.volume_min
+            && match (self.unmatched_min, quote.unmatched_volume) {
+                (Some(min), Some(v)) => v >= min,
+                _ => true,
+            }
+    }
+}
+
+/// 采样范围
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuctionUniverse {
+    /// 沪深 A 股全市场
+    Market,
+    /// 观察表中的股票
+    Watchlist,
+    /// 指定代码（如某策略的股票池）
+    Codes(Vec<String>),
+}
+
+impl AuctionUniverse {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuctionUniverse::Market => "market",
+            AuctionUniverse::Watchlist => "watchlist",
+            AuctionUniverse::Codes(_) => "codes",
+        }
+    }
+}
+
+/// 单只股票的竞价数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuctionQuote {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub matched_price: f64,
+    pub prev_close: f64,
+    pub change_pct: f64,
+    /// 匹配量（手）
+    pub matched_volume: i64,
+    /// 匹配金额（元）
+    pub matched_amount: f64,
+    /// 未匹配量（手），买一量 - 卖一量
+    pub unmatched_volume: Option<i64>,
+}
+
+/// 东财 `fltt=2` 返回的数值字段：无数据时为 "-"
+fn num(v: &Value, key: &str) -> Option<f64> {
+    match v.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(v: &Value, key: &str) -> String {
+    v.get(key)
+        .and_then(|x| x.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 解析列表接口（clist）的一行；尚无匹配价（停牌或未参与竞价）时返回 None
+fn parse_list_item(item: &Value) -> Option<AuctionQuote> {
+    let matched_price = num(item, "f2").filter(|p| *p > 0.0)?;
+    let prev_close = num(item, "f18").filter(|p| *p > 0.0)?;
+    Some(AuctionQuote {
+        stock_code: text(item, "f12"),
+        stock_name: text(item, "f14"),
+        matched_price,
+        prev_close,
+        change_pct: num(item, "f3").unwrap_or((matched_price / prev_close - 1.0) * 100.0),
+        matched_volume: num(item, "f5").unwrap_or(0.0) as i64,
+        matched_amount: num(item, "f6").unwrap_or(0.0),
+        unmatched_volume: None,
+    })
+}
+
+/// 解析明细接口（stock/get）的 data 字段
+fn parse_detail(data: &Value) -> Option<AuctionQuote> {
+    let matched_price = num(data, "f43").filter(|p| *p > 0.0)?;
+    let prev_close = num(data, "f60").filter(|p| *p > 0.0)?;
+    let unmatched_volume = match (num(data, "f20"), num(data, "f40")) {
+        (Some(buy1), Some(sell1)) => Some((buy1 - sell1) as i64),
+        _ => None,
+    };
+    Some(AuctionQuote {
+        stock_code: text(data, "f57"),
+        stock_name: text(data, "f58"),
+        matched_price,
+        prev_close,
+        change_pct: num(data, "f170").unwrap_or((matched_price / prev_close - 1.0) * 100.0),
+        matched_volume: num(data, "f47").unwrap_or(0.0) as i64,
+        matched_amount: num(data, "f48").unwrap_or(0.0),
+        unmatched_volume,
+    })
+}
+
+fn build_list_url(pn: i32, pz: i32) -> Result<Url, AuctionCaptureError> {
+    let pn = pn.to_string();
+    let pz = pz.to_string();
+    Url::parse_with_params(
+        EM_LIST_URL,
+        [
+            ("fs", "m:0 t:6,m:0 t:80,m:1 t:2,m:1 t:23"),
+            ("fields", "f12,f14,f2,f3,f5,f6,f18"),
+            ("fid", "f6"),
+            ("po", "1"),
+            ("np", "1"),
+            ("fltt", "2"),
+            ("invt", "2"),
+            ("ut", EM_UT),
+            ("pn", pn.as_str()),
+            ("pz", pz.as_str()),
+        ],
+    )
+    .map_err(|err| AuctionCaptureError::Url(err.to_string()))
+}
+
+fn build_detail_url(secid: &str) -> Result<Url, AuctionCaptureError> {
+    Url::parse_with_params(
+        EM_DETAIL_URL,
+        [
+            ("secid", secid),
+            ("fields", "f57,f58,f43,f60,f170,f47,f48,f20,f40"),
+            ("fltt", "2"),
+            ("invt", "2"),
+            ("ut", EM_UT),
+        ],
+    )
+    .map_err(|err| AuctionCaptureError::Url(err.to_string()))
+}
+
+/// 拉取全市场竞价列表（不含未匹配量）
+async fn fetch_market_quotes(
+    proxy_client: &Arc<Mutex<ProxyClient>>,
+    headers: &HeaderMap,
+    pz: i32,
+) -> Result<Vec<AuctionQuote>, AuctionCaptureError> {
+    let first = proxy_get_json(proxy_client, build_list_url(1, pz)?, headers).await?;
+    let data = first.get("data").cloned().unwrap_or(Value::Null);
+    let total = data.get("total").and_then(|v| v.as_i64()).unwrap_or(0);
+    let mut rows: Vec<Value> = data
+        .get("diff")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let pages = ((total as i32) + pz - 1) / pz;
+    for pn in 2..=pages {
+        match proxy_get_json(proxy_client, build_list_url(pn, pz)?, headers).await {
+            Ok(v) => {
+                if let Some(arr) = v
+                    .get("data")
+                    .and_then(|d| d.get("diff"))
+                    .and_then(|x| x.as_array())
+                {
+                    rows.extend_from_slice(arr);
+                }
+            }
+            Err(e) => tracing::warn!("竞价列表第 {} 页拉取失败: {}", pn, e),
+        }
+    }
+
+    Ok(rows.iter().filter_map(parse_list_item).collect())
+}
+
+/// 并发拉取个股竞价明细（含未匹配量），失败的代码跳过
+async fn fetch_detail_quotes(
+    proxy_client: &Arc<Mutex<ProxyClient>>,
+    headers: &HeaderMap,
+    codes: Vec<String>,
+    concurrency: usize,
+) -> Vec<AuctionQuote> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.clamp(1, 64)));
+    let mut handles = Vec::new();
+    for code in codes {
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let proxy = proxy_client.clone();
+        let headers = headers.clone();
+        handles.push(tokio::spawn(async move {
+            let _p = permit;
+            let url = build_detail_url(&code_to_secid(&code)).ok()?;
+            let v = proxy_get_json(&proxy, url, &headers).await.ok()?;
+            parse_detail(v.get("data")?)
+        }));
+    }
+
+    let mut quotes = Vec::new();
+    for h in handles {
+        if let Ok(Some(q)) = h.await {
+            quotes.push(q);
+        }
+    }
+    quotes
+}
+
+/// 采样竞价数据：
+/// - 全市场：返回全部有匹配价的股票，其中满足 `params` 的补齐未匹配量；
+/// - 指定代码：逐只拉取明细（含未匹配量），不做过滤。
+pub async fn capture_auction_quotes(
+    codes: Option<Vec<String>>,
+    params: &AuctionFilterParams,
+) -> Result<Vec<AuctionQuote>, AuctionCaptureError> {
+    let proxy_client = shared_proxy_client()?;
+    let headers = crate::utils::http_client::em_headers();
+
+    let Some(codes) = codes else {
+        let mut quotes =
+            fetch_market_quotes(&proxy_client, &headers, params.pz.clamp(100, 5000)).await?;
+        let candidates: Vec<String> = quotes
+            .iter()
+            .filter(|q| params.matches(q))
+            .map(|q| q.stock_code.clone())
+            .collect();
+        let details =
+            fetch_detail_quotes(&proxy_client, &headers, candidates, params.concurrency).await;
+        for detail in details {
+            if let Some(q) = quotes
+                .iter_mut()
+                .find(|q| q.stock_code == detail.stock_code)
+            {
+                q.unmatched_volume = detail.unmatched_volume;
+            }
+        }
+        return Ok(quotes);
+    };
+
+    Ok(fetch_detail_quotes(&proxy_client, &headers, codes, params.concurrency).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_item_and_skips_unmatched_stocks() {
+        let item = serde_json::json!({
+            "f12": "600519", "f14": "贵州茅台", "f2": 1520.0, "f3": 2.36,
+            "f5": 1234, "f6": 187_000_000.0, "f18": 1485.0
+        });
+        let q = parse_list_item(&item).unwrap();
+        assert_eq!(q.matched_volume, 1234);
+        assert!(AuctionFilterParams::default().matches(&q));
+
+        let suspended =
+            serde_json::json!({"f12": "000001", "f14": "平安银行", "f2": "-", "f18": 10.0});
+        assert!(parse_list_item(&suspended).is_none());
+    }
+
+    #[test]
+    fn unmatched_volume_is_buy1_minus_sell1() {
+        let data = serde_json::json!({
+            "f57": "002816", "f58": "和科达", "f43": 10.5, "f60": 10.0, "f170": 5.0,
+            "f47": 800, "f48": 840_000.0, "f20": 1500, "f40": 200
+        });
+        let q = parse_detail(&data).unwrap();
+        assert_eq!(q.unmatched_volume, Some(1300));
+
+        let params = AuctionFilterParams {
+            amount_min: 0.0,
+            unmatched_min: Some(2000),
+            ..Default::default()
+        };
+        assert!(!params.matches(&q));
+    }
+}
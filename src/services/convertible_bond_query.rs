@@ -1,8 +1,10 @@
+use bigdecimal::BigDecimal;
 use rand::Rng;
 use reqwest::header::HeaderMap;
 use reqwest::{Client, Url};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::Mutex;
@@ -17,6 +19,9 @@ use crate::utils::proxy::{proxy_get_json, shared_proxy_client, ProxyClient, Prox
 /// 数据中心可转债列表 GET 路径（与东方财富 WEB 客户端一致）。
 const EM_CB_DATACENTER_GET: &str = "https://datacenter-web.eastmoney.com/api/data/v1/get";
 
+/// 集思录可转债列表，提供剩余规模 `curr_iss_amt`（亿元），东财数据中心列表不含该字段。
+const JSL_CB_LIST: &str = "https://www.jisilu.cn/data/cbnew/cb_list_new/";
+
 /// DELIST_DATE 非空债券并发请求「重要日期」接口的并行度。
 const IMPORTANT_DATE_CONCURRENCY: usize = 8;
 
@@ -28,6 +33,8 @@ pub enum ConvertibleBondError {
     MissingResultData,
     #[error("url parse error: {0}")]
     Url(String),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
 }
 
 fn build_convertible_list_url(
@@ -219,6 +226,56 @@ fn build_convertible_item(
     }
 }
 
+/// 入库用的一支可转债（不做规模与溢价率筛选）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertibleBondRecord {
+    pub bond_code: String,
+    pub bond_short_name: String,
+    pub stock_code: String,
+    pub stock_name: String,
+    pub rating: Option<String>,
+    pub issue_scale: Option<BigDecimal>,
+    pub listing_date: Option<NaiveDate>,
+    pub expire_date: Option<NaiveDate>,
+    pub delist_date: Option<NaiveDate>,
+    pub bond_price: Option<BigDecimal>,
+    pub stock_price: Option<BigDecimal>,
+    pub transfer_price: Option<BigDecimal>,
+    pub transfer_value: Option<BigDecimal>,
+    pub transfer_premium_ratio: Option<BigDecimal>,
+    pub resale_trig_price: Option<BigDecimal>,
+    pub redeem_trig_price: Option<BigDecimal>,
+    pub pbv_ratio: Option<BigDecimal>,
+}
+
+/// 解析列表中的一行，缺少债券代码时返回 None
+pub fn parse_bond_record(row: &Value) -> Option<ConvertibleBondRecord> {
+    let bond_code = parse_string(row.get("SECURITY_CODE"));
+    if bond_code.is_empty() {
+        return None;
+    }
+    let rating = parse_string(row.get("RATING"));
+    Some(ConvertibleBondRecord {
+        bond_code,
+        bond_short_name: parse_string(row.get("SECURITY_NAME_ABBR")),
+        stock_code: parse_string(row.get("CONVERT_STOCK_CODE")),
+        stock_name: parse_string(row.get("SECURITY_SHORT_NAME")),
+        rating: (!rating.is_empty() && rating != "-").then_some(rating),
+        issue_scale: parse_decimal(row.get("ACTUAL_ISSUE_SCALE")),
+        listing_date: parse_em_date_field(row.get("LISTING_DATE")),
+        expire_date: parse_em_date_field(row.get("EXPIRE_DATE")),
+        delist_date: parse_em_date_field(row.get("DELIST_DATE")),
+        bond_price: parse_decimal(row.get("CURRENT_BOND_PRICE")),
+        stock_price: parse_decimal(row.get("CONVERT_STOCK_PRICE")),
+        transfer_price: parse_decimal(row.get("TRANSFER_PRICE")),
+        transfer_value: parse_decimal(row.get("TRANSFER_VALUE")),
+        transfer_premium_ratio: parse_decimal(row.get("TRANSFER_PREMIUM_RATIO")),
+        resale_trig_price: parse_decimal(row.get("RESALE_TRIG_PRICE")),
+        redeem_trig_price: parse_decimal(row.get("REDEEM_TRIG_PRICE")),
+        pbv_ratio: parse_decimal(row.get("PBV_RATIO")),
+    })
+}
+
 /// 解析重要日期接口中「最后交易日」的 START_DATE。
 fn parse_last_trading_day_start(root: &Value) -> Option<NaiveDate> {
     let arr = root.pointer("/result/data")?.as_array()?;
@@ -249,7 +306,8 @@ fn parse_em_date_field(value: Option<&Value>) -> Option<NaiveDate> {
     }
 }
 
-async fn fetch_last_trade_dates_by_codes(
+/// 并发查询一组债券的「最后交易日」，查询失败的代码不在结果中
+pub async fn fetch_last_trade_dates_by_codes(
     proxy_client: Arc<Mutex<ProxyClient>>,
     headers: HeaderMap,
     codes: &[String],
@@ -304,23 +362,23 @@ async fn fetch_last_trade_dates_by_codes(
     map
 }
 
-pub async fn fetch_filtered_convertible_bonds(
-    _client: &Client,
-) -> Result<Vec<ConvertibleBondItem>, ConvertibleBondError> {
-    let proxy_client = shared_proxy_client()?;
-
-    fetch_filtered_convertible_bonds_with_proxy_client(proxy_client, _client).await
+/// 数据中心可转债列表的全部行
+pub struct ConvertibleBondList {
+    pub rows: Vec<Value>,
+    /// 接口返回的总条数（缺省时为实际拉取条数）
+    pub market_count: i64,
+    pub total_pages: i64,
 }
 
-pub async fn fetch_filtered_convertible_bonds_with_proxy_client(
-    proxy_client: Arc<Mutex<ProxyClient>>,
-    _client: &Client,
-) -> Result<Vec<ConvertibleBondItem>, ConvertibleBondError> {
-    let headers = HeaderMap::new();
+/// 逐页拉取 `RPT_BOND_CB_LIST` 全部数据
+pub async fn fetch_convertible_list(
+    proxy_client: &Arc<Mutex<ProxyClient>>,
+    headers: &HeaderMap,
+) -> Result<ConvertibleBondList, ConvertibleBondError> {
     let page_size = "500";
 
     let url_first = build_convertible_list_url("1", page_size)?;
-    let root_first = fetch_convertible_page_json(&proxy_client, &headers, url_first, 1).await?;
+    let root_first = fetch_convertible_page_json(proxy_client, headers, url_first, 1).await?;
 
     root_first
         .pointer("/result/data")
@@ -328,14 +386,88 @@ pub async fn fetch_filtered_convertible_bonds_with_proxy_client(
         .ok_or(ConvertibleBondError::MissingResultData)?;
 
     let total_pages = resolve_total_pages(&root_first, page_size);
-    let mut all_rows: Vec<Value> = Vec::new();
-    append_rows_from_result(&mut all_rows, &root_first);
+    let mut rows: Vec<Value> = Vec::new();
+    append_rows_from_result(&mut rows, &root_first);
 
     for pn in 2..=total_pages {
         let url = build_convertible_list_url(&pn.to_string(), page_size)?;
-        let root = fetch_convertible_page_json(&proxy_client, &headers, url, pn).await?;
-        append_rows_from_result(&mut all_rows, &root);
+        let root = fetch_convertible_page_json(proxy_client, headers, url, pn).await?;
+        append_rows_from_result(&mut rows, &root);
+    }
+
+    let market_count = root_first
+        .pointer("/result/count")
+        .and_then(Value::as_i64)
+        .unwrap_or(rows.len() as i64);
+    Ok(ConvertibleBondList {
+        rows,
+        market_count,
+        total_pages,
+    })
+}
+
+/// 从集思录拉取各债券的剩余规模（亿元）。未登录时集思录只返回部分债券，
+/// 可通过 `JISILU_COOKIE` 传入登录后的 Cookie 取得全量
+pub async fn fetch_remaining_scales(
+    client: &Client,
+) -> Result<HashMap<String, BigDecimal>, ConvertibleBondError> {
+    let mut request = client
+        .post(JSL_CB_LIST)
+        .query(&[(
+            "___jsl",
+            format!("LST___t={}", Local::now().timestamp_millis()),
+        )])
+        .header("X-Requested-With", "XMLHttpRequest")
+        .form(&[("is_search", "N"), ("listed", "Y")]);
+    if let Some(cookie) = std::env::var("JISILU_COOKIE")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    {
+        request = request.header(reqwest::header::COOKIE, cookie);
     }
+    let root: Value = request.send().await?.error_for_status()?.json().await?;
+    Ok(parse_remaining_scales(&root))
+}
+
+/// 解析集思录列表 `rows[].cell` 中的 `bond_id` 与 `curr_iss_amt`
+fn parse_remaining_scales(root: &Value) -> HashMap<String, BigDecimal> {
+    root.get("rows")
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    let cell = row.get("cell")?;
+                    let code = parse_string(cell.get("bond_id"));
+                    let scale = parse_decimal(cell.get("curr_iss_amt"))?;
+                    (!code.is_empty()).then_some((code, scale))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `known_last_trade` 为已入库的最后交易日，命中的债券不再请求重要日期接口
+pub async fn fetch_filtered_convertible_bonds(
+    _client: &Client,
+    known_last_trade: &HashMap<String, NaiveDate>,
+) -> Result<Vec<ConvertibleBondItem>, ConvertibleBondError> {
+    let proxy_client = shared_proxy_client()?;
+
+    fetch_filtered_convertible_bonds_with_proxy_client(proxy_client, _client, known_last_trade)
+        .await
+}
+
+pub async fn fetch_filtered_convertible_bonds_with_proxy_client(
+    proxy_client: Arc<Mutex<ProxyClient>>,
+    _client: &Client,
+    known_last_trade: &HashMap<String, NaiveDate>,
+) -> Result<Vec<ConvertibleBondItem>, ConvertibleBondError> {
+    let headers = HeaderMap::new();
+    let ConvertibleBondList {
+        rows: all_rows,
+        market_count,
+        total_pages,
+    } = fetch_convertible_list(&proxy_client, &headers).await?;
 
     let today = Local::now().date_naive();
 
@@ -356,14 +488,15 @@ pub async fn fetch_filtered_convertible_bonds_with_proxy_client(
     let mut codes_unique: Vec<String> = delist_rows
         .iter()
         .map(|r| parse_string(r.get("SECURITY_CODE")))
-        .filter(|c| !c.is_empty())
+        .filter(|c| !c.is_empty() && !known_last_trade.contains_key(c))
         .collect::<HashSet<_>>()
         .into_iter()
         .collect();
     codes_unique.sort();
 
-    let last_trade_map =
+    let mut last_trade_map =
         fetch_last_trade_dates_by_codes(proxy_client.clone(), headers.clone(), &codes_unique).await;
+    last_trade_map.extend(known_last_trade.iter().map(|(k, v)| (k.clone(), *v)));
 
     let mut delist_near_items: Vec<ConvertibleBondItem> = Vec::new();
     for row in delist_rows {
@@ -384,10 +517,6 @@ pub async fn fetch_filtered_convertible_bonds_with_proxy_client(
     let matched_len = plain_items.len() + delist_near_items.len();
     plain_items.extend(delist_near_items);
 
-    let market_count = root_first
-        .pointer("/result/count")
-        .and_then(Value::as_i64)
-        .unwrap_or(all_rows.len() as i64);
     let fetched = all_rows.len();
     tracing::info!(
         target: "convertible_bond",
@@ -440,3 +569,72 @@ fn parse_number(value: Option<&Value>) -> Option<f64> {
         _ => None,
     }
 }
+
+/// 与 [`parse_number`] 相同的占位符处理，按原文转为十进制，避免浮点误差
+fn parse_decimal(value: Option<&Value>) -> Option<BigDecimal> {
+    match value? {
+        Value::Number(num) => BigDecimal::from_str(&num.to_string()).ok(),
+        Value::String(text) => {
+            let cleaned = text.trim();
+            if cleaned.is_empty() || cleaned == "-" || cleaned.eq_ignore_ascii_case("null") {
+                return None;
+            }
+            BigDecimal::from_str(cleaned).ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_bond_record() {
+        let row = json!({
+            "SECURITY_CODE": "113050",
+            "SECURITY_NAME_ABBR": "南银转债",
+            "CONVERT_STOCK_CODE": "601009",
+            "SECURITY_SHORT_NAME": "南京银行",
+            "RATING": "AAA",
+            "ACTUAL_ISSUE_SCALE": 200,
+            "LISTING_DATE": "2021-07-15 00:00:00",
+            "DELIST_DATE": null,
+            "CURRENT_BOND_PRICE": 131.05,
+            "TRANSFER_PRICE": "8.72",
+            "TRANSFER_PREMIUM_RATIO": "-",
+        });
+        let record = parse_bond_record(&row).unwrap();
+        assert_eq!(record.bond_code, "113050");
+        assert_eq!(record.rating.as_deref(), Some("AAA"));
+        assert_eq!(record.listing_date, NaiveDate::from_ymd_opt(2021, 7, 15));
+        assert_eq!(record.delist_date, None);
+        assert_eq!(record.bond_price, BigDecimal::from_str("131.05").ok());
+        assert_eq!(record.transfer_price, BigDecimal::from_str("8.72").ok());
+        assert_eq!(record.transfer_premium_ratio, None);
+        assert!(parse_bond_record(&json!({ "SECURITY_CODE": " " })).is_none());
+    }
+
+    #[test]
+    fn parses_remaining_scales() {
+        let root = json!({
+            "rows": [
+                { "id": "113050", "cell": { "bond_id": "113050", "curr_iss_amt": "199.989" } },
+                { "id": "123001", "cell": { "bond_id": "123001", "curr_iss_amt": 1.5 } },
+                { "id": "127001", "cell": { "bond_id": "127001", "curr_iss_amt": "-" } },
+            ]
+        });
+        let scales = parse_remaining_scales(&root);
+        assert_eq!(scales.len(), 2);
+        assert_eq!(
+            scales.get("113050"),
+            BigDecimal::from_str("199.989").ok().as_ref()
+        );
+        assert_eq!(
+            scales.get("123001"),
+            BigDecimal::from_str("1.5").ok().as_ref()
+        );
+        assert!(parse_remaining_scales(&json!({})).is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+//! 可转债主数据与每日行情同步：全量拉取数据中心列表，写入 `convertible_bonds`，
+//! 当日处于上市期间且有收盘价的债券写入当日 `convertible_bond_daily`。
+//! 剩余规模取自集思录列表，拉取失败时只记录日志，主数据保留原值。
+//!
+//! 「最后交易日」只对有摘牌日期、尚未摘牌且库中没有记录的债券查询一次，
+//! 查到后不再重复请求。
+
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use diesel::r2d2::PoolError;
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+use thiserror::Error;
+
+use crate::app::DbPool;
+use crate::models::{NewConvertibleBond, NewConvertibleBondDaily};
+use crate::repositories::convertible_bond;
+use crate::services::convertible_bond_query::{self, ConvertibleBondError, ConvertibleBondRecord};
+use crate::services::trading_calendar::shanghai_now;
+use crate::utils::proxy::shared_proxy_client;
+
+#[derive(Debug, Error)]
+pub enum ConvertibleBondSyncError {
+    #[error("fetch error: {0}")]
+    Fetch(#[from] ConvertibleBondError),
+    #[error("connection pool error: {0}")]
+    Pool(#[from] PoolError),
+    #[error("database error: {0}")]
+    Db(#[from] diesel::result::Error),
+}
+
+/// 一次同步的结果
+#[derive(Debug)]
+pub struct SyncSummary {
+    /// 数据中心返回的债券数
+    pub fetched: usize,
+    /// 写入主数据的债券数
+    pub bonds: usize,
+    /// 写入当日行情的债券数（未上市、已摘牌或无收盘价的债券不计）
+    pub quotes: usize,
+    /// 取到剩余规模的债券数
+    pub remaining_scales: usize,
+    /// 本次查到最后交易日的债券数
+    pub last_trading_dates: usize,
+    /// 需要查询但未查到最后交易日的债券
+    pub missing_last_trading_dates: Vec<String>,
+}
+
+/// 需要查询最后交易日的债券：有摘牌日期且 `trade_date` 时尚未摘牌，库中也没有记录
+pub fn codes_needing_last_trading_date(
+    records: &[ConvertibleBondRecord],
+    known: &HashMap<String, NaiveDate>,
+    trade_date: NaiveDate,
+) -> Vec<String> {
+    let mut codes: Vec<String> = records
+        .iter()
+        .filter(|r| r.delist_date.is_some_and(|d| d >= trade_date))
+        .filter(|r| !known.contains_key(&r.bond_code))
+        .map(|r| r.bond_code.clone())
+        .collect();
+    codes.sort();
+    codes.dedup();
+    codes
+}
+
+fn to_master(
+    record: &ConvertibleBondRecord,
+    last_trading_date: Option<NaiveDate>,
+    remaining_scale: Option<BigDecimal>,
+) -> NewConvertibleBond {
+    NewConvertibleBond {
+        bond_code: record.bond_code.clone(),
+        bond_short_name: record.bond_short_name.clone(),
+        stock_code: record.stock_code.clone(),
+        stock_name: record.stock_name.clone(),
+        rating: record.rating.clone(),
+        issue_scale: record.issue_scale.clone(),
+        remaining_scale,
+        transfer_price: record.transfer_price.clone(),
+        resale_trig_price: record.resale_trig_price.clone(),
+        redeem_trig_price: record.redeem_trig_price.clone(),
+        listing_date: record.listing_date,
+        expire_date: record.expire_date,
+        delist_date: record.delist_date,
+        last_trading_date,
+        updated_at: shanghai_now(),
+    }
+}
+
+/// `trade_date` 当日的行情：未上市（上市日期在其后）或已摘牌（摘牌日期在其前）的债券
+/// 即使列表仍带有价格也不写入
+fn to_daily(
+    record: &ConvertibleBondRecord,
+    trade_date: NaiveDate,
+    remaining_scale: Option<BigDecimal>,
+) -> Option<NewConvertibleBondDaily> {
+    if record.listing_date.is_some_and(|d| d > trade_date)
+        || record.delist_date.is_some_and(|d| d < trade_date)
+    {
+        return None;
+    }
+    Some(NewConvertibleBondDaily {
+        bond_code: record.bond_code.clone(),
+        trade_date,
+        bond_price: record.bond_price.clone()?,
+        stock_price: record.stock_price.clone(),
+        transfer_price: record.transfer_price.clone(),
+        transfer_value: record.transfer_value.clone(),
+        transfer_premium_ratio: record.transfer_premium_ratio.clone(),
+        resale_trig_price: record.resale_trig_price.clone(),
+        redeem_trig_price: record.redeem_trig_price.clone(),
+        pbv_ratio: record.pbv_ratio.clone(),
+        rating: record.rating.clone(),
+        remaining_scale,
+    })
+}
+
+/// 同步全部可转债，行情记为 `trade_date` 当日
+pub async fn sync(
+    db_pool: &DbPool,
+    trade_date: NaiveDate,
+) -> Result<SyncSummary, ConvertibleBondSyncError> {
+    let proxy_client = shared_proxy_client().map_err(ConvertibleBondError::from)?;
+    let headers = HeaderMap::new();
+    let list = convertible_bond_query::fetch_convertible_list(&proxy_client, &headers).await?;
+    let records: Vec<ConvertibleBondRecord> = list
+        .rows
+        .iter()
+        .filter_map(convertible_bond_query::parse_bond_record)
+        .collect();
+    tracing::info!(
+        "可转债列表共 {} 条（{} 页），解析 {} 支",
+        list.market_count,
+        list.total_pages,
+        records.len()
+    );
+
+    let remaining_scales = convertible_bond_query::fetch_remaining_scales(&Client::new())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("拉取可转债剩余规模失败，本次不更新剩余规模: {}", e);
+            HashMap::new()
+        });
+
+    let known = convertible_bond::list_last_trading_dates(&mut *db_pool.get()?)?;
+    let codes = codes_needing_last_trading_date(&records, &known, trade_date);
+    let found = convertible_bond_query::fetch_last_trade_dates_by_codes(
+        proxy_client.clone(),
+        headers,
+        &codes,
+    )
+    .await;
+    let missing: Vec<String> = codes
+        .iter()
+        .filter(|c| !found.contains_key(*c))
+        .cloned()
+        .collect();
+
+    let masters: Vec<NewConvertibleBond> = records
+        .iter()
+        .map(|r| {
+            to_master(
+                r,
+                found.get(&r.bond_code).copied(),
+                remaining_scales.get(&r.bond_code).cloned(),
+            )
+        })
+        .collect();
+    let quotes: Vec<NewConvertibleBondDaily> = records
+        .iter()
+        .filter_map(|r| to_daily(r, trade_date, remaining_scales.get(&r.bond_code).cloned()))
+        .collect();
+
+    let mut conn = db_pool.get()?;
+    let bonds = convertible_bond::upsert_bonds(&mut conn, &masters)?;
+    let quote_count = convertible_bond::upsert_daily(&mut conn, &quotes)?;
+
+    Ok(SyncSummary {
+        fetched: list.rows.len(),
+        bonds,
+        quotes: quote_count,
+        remaining_scales: remaining_scales.len(),
+        last_trading_dates: found.len(),
+        missing_last_trading_dates: missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn record(code: &str, delist_date: Option<NaiveDate>) -> ConvertibleBondRecord {
+        ConvertibleBondRecord {
+            bond_code: code.to_string(),
+            bond_short_name: String::new(),
+            stock_code: String::new(),
+            stock_name: String::new(),
+            rating: None,
+            issue_scale: None,
+            listing_date: None,
+            expire_date: None,
+            delist_date,
+            bond_price: None,
+            stock_price: None,
+            transfer_price: None,
+            transfer_value: None,
+            transfer_premium_ratio: None,
+            resale_trig_price: None,
+            redeem_trig_price: None,
+            pbv_ratio: None,
+        }
+    }
+
+    #[test]
+    fn selects_codes_needing_last_trading_date() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let records = vec![
+            record("113001", None),
+            record("113002", Some(day(10))),
+            record("113003", Some(day(23))),
+            record("113004", Some(day(19))),
+            record("113005", Some(day(30))),
+        ];
+        let known = HashMap::from([("113005".to_string(), day(27))]);
+        assert_eq!(
+            codes_needing_last_trading_date(&records, &known, day(19)),
+            vec!["113003".to_string(), "113004".to_string()]
+        );
+        assert!(to_daily(&records[0], day(19), None).is_none());
+    }
+
+    #[test]
+    fn skips_quotes_outside_listing_period() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let quoted = |listing_date, delist_date| ConvertibleBondRecord {
+            listing_date,
+            bond_price: BigDecimal::from_str("120.5").ok(),
+            ..record("113010", delist_date)
+        };
+        let scale = BigDecimal::from_str("3.2").ok();
+
+        let daily = to_daily(&quoted(Some(day(1)), None), day(19), scale.clone()).unwrap();
+        assert_eq!(daily.remaining_scale, scale);
+        // 摘牌当天仍记行情，之后不再写入
+        assert!(to_daily(&quoted(Some(day(1)), Some(day(19))), day(19), None).is_some());
+        assert!(to_daily(&quoted(Some(day(1)), Some(day(16))), day(19), None).is_none());
+        // 上市前不写入
+        assert!(to_daily(&quoted(Some(day(20)), None), day(19), None).is_none());
+    }
+}
@@ -0,0 +1,328 @@
+//! 沪深交易所年度休市安排的导入：内置 JSON（`asset/calendar`）、外部 JSON / CSV 文件
+//! 均解析为 [`HolidayYear`]，再展开为全年逐日的 `stock_trading_calendar` 记录。
+//!
+//! 新增下一年度的休市安排只需在 `TRADING_HOLIDAY_DIR` 目录放入文件或调用导入接口，无需写 SQL。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use chrono::{Datelike, NaiveDate, Weekday};
+use diesel::Connection;
+use serde::Deserialize;
+
+use crate::app::DbPool;
+use crate::models::{NewStockTradingCalendar, StockTradingCalendar};
+use crate::repositories::stock_trading_calendar;
+
+/// 外部休市文件目录的环境变量名（目录下的 `*.json` / `*.csv` 会在启动时导入）
+pub const HOLIDAY_DIR_ENV: &str = "TRADING_HOLIDAY_DIR";
+
+/// 非法定休市的周末记录使用的名称
+const WEEKEND_NAME: &str = "周末";
+
+const EMBEDDED_HOLIDAYS_JSON: &str = include_str!("../asset/calendar/sse_szse_holidays.json");
+
+/// 单段休市区间，`end` 缺省时只休 `start` 一天
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct HolidayRange {
+    pub name: String,
+    pub start: NaiveDate,
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+}
+
+impl HolidayRange {
+    fn last_day(&self) -> NaiveDate {
+        self.end.unwrap_or(self.start)
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        date >= self.start && date <= self.last_day()
+    }
+}
+
+/// 某一年度的休市安排
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct HolidayYear {
+    pub year: i32,
+    pub holidays: Vec<HolidayRange>,
+}
+
+impl HolidayYear {
+    fn validate(&self) -> anyhow::Result<()> {
+        for h in &self.holidays {
+            if h.name.trim().is_empty() {
+                bail!("{} 年存在未命名的休市区间（{}）", self.year, h.start);
+            }
+            if h.last_day() < h.start {
+                bail!("{} 的结束日期早于开始日期", h.name);
+            }
+            if (h.last_day() - h.start).num_days() > 31 {
+                bail!("{} 的休市区间超过 31 天，请检查日期", h.name);
+            }
+            if h.start.year() != self.year && h.last_day().year() != self.year {
+                bail!("{}（{}）不属于 {} 年", h.name, h.start, self.year);
+            }
+        }
+        Ok(())
+    }
+
+    /// 展开为全年逐日记录：休市区间按名称记为休市，其余周末记为「周末」，剩余为交易日
+    pub fn build_rows(&self) -> anyhow::Result<Vec<NewStockTradingCalendar>> {
+        self.validate()?;
+        let first = NaiveDate::from_ymd_opt(self.year, 1, 1)
+            .ok_or_else(|| anyhow!("无效年份: {}", self.year))?;
+        let rows = first
+            .iter_days()
+            .take_while(|d| d.year() == self.year)
+            .map(|d| {
+                let holiday = self.holidays.iter().find(|h| h.contains(d));
+                let weekend = matches!(d.weekday(), Weekday::Sat | Weekday::Sun);
+                let holiday_name = match holiday {
+                    Some(h) => Some(h.name.trim().to_string()),
+                    None if weekend => Some(WEEKEND_NAME.to_string()),
+                    None => None,
+                };
+                NewStockTradingCalendar {
+                    trade_date: d,
+                    is_holiday: holiday_name.is_some(),
+                    holiday_name,
+                }
+            })
+            .collect();
+        Ok(rows)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HolidayJson {
+    Many(Vec<HolidayYear>),
+    One(HolidayYear),
+}
+
+/// 解析 JSON：支持单个 `{year, holidays}` 对象或其数组
+pub fn parse_holiday_json(content: &str) -> anyhow::Result<Vec<HolidayYear>> {
+    let parsed: HolidayJson = serde_json::from_str(content).context("休市 JSON 格式错误")?;
+    let years = match parsed {
+        HolidayJson::Many(v) => v,
+        HolidayJson::One(y) => vec![y],
+    };
+    for y in &years {
+        y.validate()?;
+    }
+    Ok(years)
+}
+
+/// 解析 CSV：每行 `start_date,end_date,name`（日期 `YYYY-MM-DD`，`end_date` 可留空），
+/// 首行表头与 `#` 开头的注释行会被忽略，按开始日期所在年份分组
+pub fn parse_holiday_csv(content: &str) -> anyhow::Result<Vec<HolidayYear>> {
+    let mut by_year: BTreeMap<i32, Vec<HolidayRange>> = BTreeMap::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let cols: Vec<&str> = line.splitn(3, ',').map(str::trim).collect();
+        let Ok(start) = NaiveDate::parse_from_str(cols[0], "%Y-%m-%d") else {
+            if idx == 0 {
+                continue; // 表头
+            }
+            bail!("第 {} 行开始日期无效: {}", idx + 1, cols[0]);
+        };
+        let end = match cols.get(1).copied().filter(|s| !s.is_empty()) {
+            Some(s) => Some(
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .with_context(|| format!("第 {} 行结束日期无效: {}", idx + 1, s))?,
+            ),
+            None => None,
+        };
+        let name = cols.get(2).copied().unwrap_or_default().to_string();
+        by_year
+            .entry(start.year())
+            .or_default()
+            .push(HolidayRange { name, start, end });
+    }
+
+    let years: Vec<HolidayYear> = by_year
+        .into_iter()
+        .map(|(year, holidays)| HolidayYear { year, holidays })
+        .collect();
+    for y in &years {
+        y.validate()?;
+    }
+    Ok(years)
+}
+
+/// 随程序发布的内置休市安排
+pub fn embedded_holiday_years() -> Vec<HolidayYear> {
+    parse_holiday_json(EMBEDDED_HOLIDAYS_JSON).expect("内置休市安排 JSON 无效")
+}
+
+/// 读取目录下的 `*.json` / `*.csv` 休市文件
+fn load_holiday_dir(dir: &Path) -> anyhow::Result<Vec<HolidayYear>> {
+    let mut years = Vec::new();
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("读取目录失败: {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        let parser: fn(&str) -> anyhow::Result<Vec<HolidayYear>> = match ext.as_deref() {
+            Some("json") => parse_holiday_json,
+            Some("csv") => parse_holiday_csv,
+            _ => continue,
+        };
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("读取文件失败: {}", path.display()))?;
+        years.extend(parser(&content).with_context(|| format!("解析失败: {}", path.display()))?);
+    }
+    Ok(years)
+}
+
+/// 将年度休市安排写入日历表（覆盖这些年份已有的记录），返回写入的行数
+pub fn import_years(db_pool: &DbPool, years: &[HolidayYear]) -> anyhow::Result<usize> {
+    let mut rows = Vec::new();
+    for y in years {
+        rows.extend(y.build_rows()?);
+    }
+    let mut conn = db_pool.get()?;
+    let affected = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        stock_trading_calendar::upsert_days(conn, &rows)
+    })?;
+    Ok(affected)
+}
+
+/// 将一年的安排与已有记录合并，返回需要写入的行：缺失的日期按安排补齐；
+/// 法定休市日与已有记录不一致时改为休市；其余已有记录（周末、手工维护的数据）保持不变
+pub fn merge_with_existing(
+    rows: Vec<NewStockTradingCalendar>,
+    existing: &[StockTradingCalendar],
+) -> Vec<NewStockTradingCalendar> {
+    let by_date: BTreeMap<NaiveDate, &StockTradingCalendar> =
+        existing.iter().map(|r| (r.trade_date, r)).collect();
+    rows.into_iter()
+        .filter(|row| match by_date.get(&row.trade_date) {
+            None => true,
+            Some(current) => {
+                let statutory = row.is_holiday && row.holiday_name.as_deref() != Some(WEEKEND_NAME);
+                statutory && (!current.is_holiday || current.holiday_name != row.holiday_name)
+            }
+        })
+        .collect()
+}
+
+/// 启动时补齐日历：内置安排与 `TRADING_HOLIDAY_DIR` 中的文件（同一年份以文件为准），
+/// 按 [`merge_with_existing`] 合并进已有记录。返回有写入的年份。
+pub fn bootstrap(db_pool: &DbPool) -> anyhow::Result<Vec<i32>> {
+    let mut by_year: BTreeMap<i32, HolidayYear> = embedded_holiday_years()
+        .into_iter()
+        .map(|y| (y.year, y))
+        .collect();
+    if let Ok(dir) = std::env::var(HOLIDAY_DIR_ENV) {
+        for y in load_holiday_dir(Path::new(&dir))? {
+            by_year.insert(y.year, y);
+        }
+    }
+
+    let mut imported = Vec::new();
+    for (year, holidays) in by_year {
+        let first = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| anyhow!("无效年份"))?;
+        let last = NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(|| anyhow!("无效年份"))?;
+        let mut conn = db_pool.get()?;
+        let existing = stock_trading_calendar::list_range(&mut conn, first, last)?;
+        let rows = merge_with_existing(holidays.build_rows()?, &existing);
+        if rows.is_empty() {
+            continue;
+        }
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            stock_trading_calendar::upsert_days(conn, &rows)
+        })?;
+        imported.push(year);
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn embedded_years_expand_to_full_year() {
+        let years = embedded_holiday_years();
+        let y2026 = years.iter().find(|y| y.year == 2026).expect("2026 missing");
+        let rows = y2026.build_rows().unwrap();
+        assert_eq!(rows.len(), 365);
+
+        let day = |date| rows.iter().find(|r| r.trade_date == date).unwrap();
+        assert_eq!(day(d(2026, 10, 5)).holiday_name.as_deref(), Some("国庆节"));
+        assert_eq!(day(d(2026, 10, 10)).holiday_name.as_deref(), Some("周末"));
+        assert!(!day(d(2026, 10, 8)).is_holiday);
+    }
+
+    #[test]
+    fn merges_statutory_holidays_into_existing_rows() {
+        let existing_row = |date, is_holiday, name: Option<&str>| StockTradingCalendar {
+            id: 0,
+            trade_date: date,
+            is_holiday,
+            holiday_name: name.map(str::to_string),
+            created_at: None,
+            updated_at: None,
+        };
+        let year = HolidayYear {
+            year: 2026,
+            holidays: vec![HolidayRange {
+                name: "国庆节".to_string(),
+                start: d(2026, 10, 1),
+                end: Some(d(2026, 10, 7)),
+            }],
+        };
+        let existing = vec![
+            // `/sync` 种子数据把节假日记成了交易日
+            existing_row(d(2026, 10, 1), false, None),
+            existing_row(d(2026, 10, 2), true, Some("国庆节")),
+            // 法定休市安排之外的已有记录（手工维护的临时休市日、交易日）保持不变
+            existing_row(d(2026, 10, 8), true, Some("临时休市")),
+            existing_row(d(2026, 10, 10), false, None),
+        ];
+        let rows = merge_with_existing(year.build_rows().unwrap(), &existing);
+        let written = |date| rows.iter().find(|r| r.trade_date == date);
+
+        assert_eq!(
+            written(d(2026, 10, 1)).and_then(|r| r.holiday_name.as_deref()),
+            Some("国庆节")
+        );
+        assert!(written(d(2026, 10, 2)).is_none());
+        assert!(written(d(2026, 10, 8)).is_none());
+        assert!(written(d(2026, 10, 10)).is_none());
+        assert!(written(d(2026, 10, 3)).is_some());
+        assert_eq!(rows.len(), 365 - 3);
+    }
+
+    #[test]
+    fn parses_csv_with_header_and_optional_end() {
+        let csv =
+            "start_date,end_date,name\n2027-01-01,,元旦\n# 注释\n2027-02-06,2027-02-14,春节\n";
+        let years = parse_holiday_csv(csv).unwrap();
+        assert_eq!(years.len(), 1);
+        assert_eq!(years[0].year, 2027);
+        assert_eq!(years[0].holidays[0].end, None);
+        assert_eq!(years[0].holidays[1].end, Some(d(2027, 2, 14)));
+    }
+
+    #[test]
+    fn rejects_inverted_range() {
+        let json = r#"{"year": 2027, "holidays": [{"name": "春节", "start": "2027-02-14", "end": "2027-02-06"}]}"#;
+        assert!(parse_holiday_json(json).is_err());
+    }
+}
@@ -0,0 +1,544 @@
+//! 日K线数据质量审计：`daily_klines` 原样写入 `parse_kline_json` 的结果，解析失败的价格会被记为 0，
+//! 这里对照交易日历逐股检查，发现的问题写入 `kline_audit_findings`，并可按股票重新拉取受影响区间修复。
+//!
+//! K线按前复权（fqt=1）拉取，复权价以拉取当天为准。除权除息后此前入库的K线不会重新复权，
+//! 新旧数据交界处会出现超出涨跌幅限制的跳空——正常交易与已复权的序列都不会出现这种跳空，
+//! 因此超限跳空即视为数据问题，修复时从该股最早的K线重新拉取到跳空日。
+
+use std::collections::HashSet;
+
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::NaiveDate;
+use diesel::result::Error as DieselError;
+use serde_json::json;
+
+use crate::app::DbPool;
+use crate::models::{DailyKline, KlineAuditFinding, NewDailyKline, NewKlineAuditFinding};
+use crate::repositories::daily_kline::{self, PgPoolConn};
+use crate::repositories::kline_audit;
+use crate::services::kline_backfill;
+use crate::services::kline_service;
+use crate::services::trading_calendar::{shanghai_now, TradingDays};
+use crate::utils::http_client;
+
+/// 涨跌幅超出限制的容差（价格按分取整，低价股的实际涨跌幅可能略超限制）
+const JUMP_TOLERANCE: f64 = 0.02;
+
+/// 新股上市后前几个交易日不设涨跌幅限制（创业板 / 科创板前 5 日，主板首日）
+const LISTING_EXEMPT_DAYS: usize = 5;
+
+/// 创业板涨跌幅限制调整为 20% 的日期（注册制改革首日）
+const CHINEXT_REFORM_DATE: (i32, u32, u32) = (2020, 8, 24);
+
+/// 审计检查项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckType {
+    /// 价格非正、最高价低于最低价、开盘 / 收盘价超出高低价范围
+    OhlcInvalid,
+    /// 交易日成交量为 0（停牌日接口通常不返回K线）
+    ZeroVolume,
+    /// 与上一根K线的价格、成交量完全相同
+    DuplicateBar,
+    /// K线日期不是交易日
+    NonTradingDay,
+    /// 上市后的交易日缺少K线
+    Gap,
+    /// 涨跌幅超出板块限制
+    PriceJump,
+}
+
+impl CheckType {
+    pub const ALL: [CheckType; 6] = [
+        CheckType::OhlcInvalid,
+        CheckType::ZeroVolume,
+        CheckType::DuplicateBar,
+        CheckType::NonTradingDay,
+        CheckType::Gap,
+        CheckType::PriceJump,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckType::OhlcInvalid => "ohlc_invalid",
+            CheckType::ZeroVolume => "zero_volume",
+            CheckType::DuplicateBar => "duplicate_bar",
+            CheckType::NonTradingDay => "non_trading_day",
+            CheckType::Gap => "gap",
+            CheckType::PriceJump => "price_jump",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+
+    /// error 为确定的数据错误，warning 可能是停牌等正常情况
+    pub fn severity(&self) -> &'static str {
+        match self {
+            CheckType::OhlcInvalid | CheckType::NonTradingDay | CheckType::PriceJump => "error",
+            CheckType::ZeroVolume | CheckType::DuplicateBar | CheckType::Gap => "warning",
+        }
+    }
+}
+
+/// 问题状态
+pub const STATUS_OPEN: &str = "open";
+pub const STATUS_RESOLVED: &str = "resolved";
+pub const STATUS_IGNORED: &str = "ignored";
+
+/// 按代码与日期判断板块的涨跌幅限制。ST 股票为 5%，比所在板块更严，按板块判断不会误报
+pub fn price_limit(code: &str, date: NaiveDate) -> f64 {
+    let (y, m, d) = CHINEXT_REFORM_DATE;
+    let chinext_reform = NaiveDate::from_ymd_opt(y, m, d).unwrap_or(NaiveDate::MIN);
+    if code.starts_with("688") || code.starts_with("689") {
+        0.20
+    } else if code.starts_with("300") || code.starts_with("301") {
+        if date >= chinext_reform {
+            0.20
+        } else {
+            0.10
+        }
+    } else if code.starts_with('4') || code.starts_with('8') || code.starts_with("92") {
+        0.30
+    } else {
+        0.10
+    }
+}
+
+fn to_f64(v: &BigDecimal) -> f64 {
+    v.to_f64().unwrap_or(0.0)
+}
+
+fn bar_context(bar: &DailyKline) -> serde_json::Value {
+    json!({
+        "open": to_f64(&bar.open_price),
+        "high": to_f64(&bar.high_price),
+        "low": to_f64(&bar.low_price),
+        "close": to_f64(&bar.close_price),
+        "volume": bar.volume,
+        "amount": to_f64(&bar.amount),
+    })
+}
+
+fn same_bar(a: &DailyKline, b: &DailyKline) -> bool {
+    a.open_price == b.open_price
+        && a.high_price == b.high_price
+        && a.low_price == b.low_price
+        && a.close_price == b.close_price
+        && a.volume == b.volume
+        && a.amount == b.amount
+}
+
+/// 单根K线的 OHLC 问题描述
+fn ohlc_problem(bar: &DailyKline) -> Option<String> {
+    let prices = [
+        &bar.open_price,
+        &bar.high_price,
+        &bar.low_price,
+        &bar.close_price,
+    ];
+    if prices.iter().any(|p| **p <= BigDecimal::zero()) {
+        return Some("存在非正的价格".to_string());
+    }
+    if bar.high_price < bar.low_price {
+        return Some(format!(
+            "最高价 {} 低于最低价 {}",
+            bar.high_price, bar.low_price
+        ));
+    }
+    for (name, price) in [("开盘价", &bar.open_price), ("收盘价", &bar.close_price)] {
+        if *price > bar.high_price || *price < bar.low_price {
+            return Some(format!(
+                "{name} {price} 超出最高 / 最低价范围 [{}, {}]",
+                bar.low_price, bar.high_price
+            ));
+        }
+    }
+    None
+}
+
+/// 审计一只股票的K线（按日期升序）。
+/// `bars` 可以包含审计区间前的一根K线，仅用作跳空与重复的对照；
+/// `listed_from` 为该股最早的K线日期，其后 [`LISTING_EXEMPT_DAYS`] 个交易日不检查跳空，之前不检查缺口
+pub fn audit_bars(
+    code: &str,
+    bars: &[DailyKline],
+    days: &TradingDays,
+    start: NaiveDate,
+    end: NaiveDate,
+    listed_from: NaiveDate,
+) -> Vec<NewKlineAuditFinding> {
+    let now = shanghai_now();
+    let finding = |check: CheckType, from: NaiveDate, to: NaiveDate, detail: String, context| {
+        NewKlineAuditFinding {
+            stock_code: code.to_string(),
+            check_type: check.as_str().to_string(),
+            severity: check.severity().to_string(),
+            trade_date: from,
+            end_date: to,
+            detail,
+            context: Some(context),
+            last_detected_at: now,
+        }
+    };
+    let jump_exempt_until =
+        (1..LISTING_EXEMPT_DAYS).fold(listed_from, |d, _| days.next_trading_day(d));
+
+    let mut findings = Vec::new();
+    let mut prev: Option<&DailyKline> = None;
+    for bar in bars {
+        let date = bar.trade_date;
+        if date < start || date > end {
+            prev = Some(bar);
+            continue;
+        }
+        if let Some(problem) = ohlc_problem(bar) {
+            findings.push(finding(
+                CheckType::OhlcInvalid,
+                date,
+                date,
+                problem,
+                bar_context(bar),
+            ));
+        }
+        if !days.is_trading_day(date) {
+            findings.push(finding(
+                CheckType::NonTradingDay,
+                date,
+                date,
+                format!("{date} 不是交易日"),
+                bar_context(bar),
+            ));
+        } else if bar.volume == 0 {
+            findings.push(finding(
+                CheckType::ZeroVolume,
+                date,
+                date,
+                "交易日成交量为 0".to_string(),
+                bar_context(bar),
+            ));
+        }
+
+        if let Some(p) = prev {
+            if bar.volume > 0 && same_bar(p, bar) {
+                findings.push(finding(
+                    CheckType::DuplicateBar,
+                    date,
+                    date,
+                    format!("与 {} 的K线完全相同", p.trade_date),
+                    bar_context(bar),
+                ));
+            }
+            let prev_close = to_f64(&p.close_price);
+            let close = to_f64(&bar.close_price);
+            // 中间有缺口时不判断跳空（缺口单独记录）
+            let consecutive = days.prev_trading_day(date) == p.trade_date;
+            if consecutive && date > jump_exempt_until && prev_close > 0.0 && close > 0.0 {
+                let change = close / prev_close - 1.0;
+                let limit = price_limit(code, date);
+                if change.abs() > limit + JUMP_TOLERANCE {
+                    findings.push(finding(
+                        CheckType::PriceJump,
+                        date,
+                        date,
+                        format!(
+                            "收盘价较 {} 变动 {:.2}%，超出 ±{:.0}% 的涨跌幅限制",
+                            p.trade_date,
+                            change * 100.0,
+                            limit * 100.0
+                        ),
+                        json!({
+                            "prev_date": p.trade_date,
+                            "prev_close": prev_close,
+                            "close": close,
+                            "change_pct": change * 100.0,
+                            "limit_pct": limit * 100.0,
+                        }),
+                    ));
+                }
+            }
+        }
+        prev = Some(bar);
+    }
+
+    // 缺口：只看上市后、最后一根K线之前的交易日（之后可能是停牌或尚未导入），
+    // 且限于交易日历覆盖的范围，范围外按工作日推断会把节假日误判为缺口
+    let last = bars.last().map(|b| b.trade_date);
+    if let (Some(last), Some((cal_first, cal_last))) = (last, days.covered_range()) {
+        let from = start.max(listed_from).max(cal_first);
+        let to = end.min(last).min(cal_last);
+        if from <= to {
+            let expected = days.trading_days_between(from, to);
+            let present: HashSet<NaiveDate> = bars.iter().map(|b| b.trade_date).collect();
+            for range in kline_backfill::missing_ranges(&expected, &present) {
+                findings.push(finding(
+                    CheckType::Gap,
+                    range.start,
+                    range.end,
+                    format!(
+                        "{} ~ {} 缺少 {} 个交易日的K线",
+                        range.start, range.end, range.days
+                    ),
+                    json!({ "days": range.days }),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// 单只股票的审计结果
+#[derive(Debug, Default)]
+pub struct StockAudit {
+    pub bars: usize,
+    pub findings: usize,
+    pub resolved: usize,
+}
+
+/// 审计一只股票在 `[start, end]` 内的K线并同步问题记录：
+/// 新发现的写入，仍存在的刷新，区间内已不存在的标记为 resolved
+pub fn audit_stock(
+    conn: &mut PgPoolConn,
+    days: &TradingDays,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<StockAudit, DieselError> {
+    let Some(listed_from) = daily_kline::first_trade_date(conn, code)? else {
+        let resolved = kline_audit::sync_stock(conn, code, start, end, &[], shanghai_now())?;
+        return Ok(StockAudit {
+            resolved,
+            ..Default::default()
+        });
+    };
+    // 多取区间前一个交易日的K线用于跳空对照
+    let load_from = days.prev_trading_day(start);
+    let bars = daily_kline::list_range(conn, code, load_from, end)?;
+    let findings = audit_bars(code, &bars, days, start, end, listed_from);
+    let now = findings
+        .first()
+        .map(|f| f.last_detected_at)
+        .unwrap_or_else(shanghai_now);
+    let resolved = kline_audit::sync_stock(conn, code, start, end, &findings, now)?;
+    Ok(StockAudit {
+        bars: bars.iter().filter(|b| b.trade_date >= start).count(),
+        findings: findings.len(),
+        resolved,
+    })
+}
+
+/// 修复区间：覆盖全部未处理的问题；有跳空时从该股最早的K线开始（前复权需要整体重新拉取）
+pub fn repair_range(
+    findings: &[KlineAuditFinding],
+    listed_from: Option<NaiveDate>,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let start = findings.iter().map(|f| {
+        if f.check_type == CheckType::PriceJump.as_str() {
+            listed_from.map_or(f.trade_date, |d| d.min(f.trade_date))
+        } else {
+            f.trade_date
+        }
+    });
+    let start = start.min()?;
+    let end = findings.iter().map(|f| f.end_date).max()?;
+    Some((start, end))
+}
+
+/// 单只股票的修复结果
+#[derive(Debug)]
+pub struct StockRepair {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub deleted: usize,
+    pub inserted: usize,
+    pub audit: StockAudit,
+}
+
+/// 由拉取结果得到用于替换 `[start, end]` 的K线。接口未返回数据或有K线解析失败时放弃修复：
+/// 区间替换会删除区间内全部已有K线，解析失败的日期将因此丢失
+fn replacement_rows(
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    result: Result<kline_service::KlineParseResult, kline_service::KlineServiceError>,
+) -> anyhow::Result<Vec<NewDailyKline>> {
+    let result = match result {
+        Ok(r) => r,
+        Err(kline_service::KlineServiceError::NoData) => {
+            anyhow::bail!("接口未返回 {} ~ {} 的K线，保留现有数据", start, end)
+        }
+        Err(e) => anyhow::bail!("拉取 {} ~ {} 的K线失败: {}", start, end, e),
+    };
+    if !result.errors.is_empty() {
+        for e in &result.errors {
+            tracing::warn!("股票 {} 修复时K线解析失败: {}", code, e);
+        }
+        anyhow::bail!(
+            "{} ~ {} 有 {} 条K线解析失败，保留现有数据: {}",
+            start,
+            end,
+            result.errors.len(),
+            result.errors[0]
+        );
+    }
+    if result.parsed.is_empty() {
+        anyhow::bail!("接口未返回 {} ~ {} 的K线，保留现有数据", start, end);
+    }
+    Ok(result.parsed)
+}
+
+/// 重新拉取 `[start, end]` 的K线替换库中数据，然后复检该区间。
+/// 先拉取再在事务中替换，拉取失败或有K线解析失败时不改动已有数据
+pub async fn repair_stock(
+    db_pool: &DbPool,
+    days: &TradingDays,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> anyhow::Result<StockRepair> {
+    let client = http_client::create_em_client()?;
+    let result = kline_service::fetch_and_parse_kline_data(
+        &client,
+        code,
+        &start.format("%Y%m%d").to_string(),
+        &end.format("%Y%m%d").to_string(),
+    )
+    .await;
+    let parsed = replacement_rows(code, start, end, result)?;
+
+    let mut conn = db_pool.get()?;
+    let (deleted, inserted) = daily_kline::replace_range(&mut conn, code, start, end, &parsed)?;
+    tracing::info!(
+        "股票 {} 修复 {} ~ {}：删除 {} 条，写入 {} 条K线",
+        code,
+        start,
+        end,
+        deleted,
+        inserted
+    );
+    let audit = audit_stock(&mut conn, days, code, start, end)?;
+    Ok(StockRepair {
+        start,
+        end,
+        deleted,
+        inserted,
+        audit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn d(m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, day).unwrap()
+    }
+
+    fn bar(
+        date: NaiveDate,
+        open: &str,
+        high: &str,
+        low: &str,
+        close: &str,
+        volume: i64,
+    ) -> DailyKline {
+        let p = |s: &str| BigDecimal::from_str(s).unwrap();
+        DailyKline {
+            stock_code: "600000".to_string(),
+            trade_date: date,
+            open_price: p(open),
+            high_price: p(high),
+            low_price: p(low),
+            close_price: p(close),
+            volume,
+            amount: p("1000"),
+        }
+    }
+
+    fn checks(findings: &[NewKlineAuditFinding]) -> Vec<(&str, NaiveDate)> {
+        findings
+            .iter()
+            .map(|f| (f.check_type.as_str(), f.trade_date))
+            .collect()
+    }
+
+    #[test]
+    fn flags_inconsistent_bars() {
+        // 未登记日历时按工作日推断：6/6、6/7 为周末
+        let days = TradingDays::default();
+        let bars = vec![
+            bar(d(6, 1), "10.00", "10.50", "9.80", "10.20", 100),
+            bar(d(6, 2), "10.20", "10.10", "10.30", "10.20", 100),
+            bar(d(6, 3), "10.20", "10.40", "10.00", "10.30", 0),
+            bar(d(6, 4), "10.20", "10.40", "10.00", "10.30", 0),
+            bar(d(6, 5), "10.30", "13.00", "10.30", "12.80", 100),
+            bar(d(6, 6), "12.80", "12.90", "12.70", "12.80", 100),
+            bar(d(6, 8), "12.80", "12.90", "12.70", "12.80", 100),
+        ];
+        let findings = audit_bars("600000", &bars, &days, d(6, 1), d(6, 8), d(1, 5));
+        assert_eq!(
+            checks(&findings),
+            vec![
+                ("ohlc_invalid", d(6, 2)),
+                ("zero_volume", d(6, 3)),
+                ("zero_volume", d(6, 4)),
+                ("price_jump", d(6, 5)),
+                ("non_trading_day", d(6, 6)),
+                ("duplicate_bar", d(6, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn exempts_new_listings_and_wider_boards() {
+        let days = TradingDays::default();
+        let bars = vec![
+            bar(d(6, 1), "10.00", "10.50", "9.80", "10.00", 100),
+            bar(d(6, 2), "11.90", "11.90", "11.90", "11.90", 100),
+        ];
+        assert!(audit_bars("300750", &bars, &days, d(6, 1), d(6, 2), d(1, 5)).is_empty());
+        // 上市首日起 5 个交易日内不检查
+        assert!(audit_bars("600000", &bars, &days, d(6, 1), d(6, 2), d(6, 1)).is_empty());
+        assert_eq!(
+            checks(&audit_bars(
+                "600000",
+                &bars,
+                &days,
+                d(6, 1),
+                d(6, 2),
+                d(1, 5)
+            )),
+            vec![("price_jump", d(6, 2))]
+        );
+    }
+
+    #[test]
+    fn repair_keeps_existing_bars_on_parse_error() {
+        let fetched = |lines: &[&str]| {
+            kline_service::parse_kline_json(&json!({
+                "data": { "code": "600000", "name": "浦发银行", "klines": lines }
+            }))
+        };
+
+        // 10-15 这一行解析失败：不产生待写入的K线，修复中止，库中 10-15 的K线不会被区间替换删除
+        let result = fetched(&[
+            "2026-10-14,10.00,10.10,10.20,9.90,1000,1010000",
+            "2026-10-15,10.10",
+            "2026-10-16,10.10,10.30,10.40,10.00,1200,1236000",
+        ]);
+        let err = replacement_rows("600000", d(10, 14), d(10, 16), result).unwrap_err();
+        assert!(err.to_string().contains("1 条K线解析失败"));
+
+        let result = fetched(&[
+            "2026-10-14,10.00,10.10,10.20,9.90,1000,1010000",
+            "2026-10-15,10.10,10.05,10.15,10.00,900,905000",
+        ]);
+        let rows = replacement_rows("600000", d(10, 14), d(10, 16), result).unwrap();
+        assert_eq!(
+            rows.iter().map(|r| r.trade_date).collect::<Vec<_>>(),
+            vec![d(10, 14), d(10, 15)]
+        );
+        assert!(replacement_rows("600000", d(10, 14), d(10, 16), fetched(&[])).is_err());
+    }
+}
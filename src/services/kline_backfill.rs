@@ -0,0 +1,357 @@
+//! 历史K线回补：按股票集合与日期区间，对照交易日历检测 `daily_klines` 的缺口，
+//! 只拉取缺失的区间。相距较近的缺口合并为一次请求，减少接口调用。
+//!
+//! 回补后仍缺失的交易日通常是停牌或尚未上市（接口本身没有数据），不视为失败。
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+
+use crate::app::DbPool;
+use crate::models::{KlineBackfillRun, NewKlineBackfillRun};
+use crate::repositories::daily_kline::{self, PgPoolConn};
+use crate::repositories::{kline_backfill, stock_plate_stock_table, stock_table, stock_watchlist};
+use crate::services::kline_service;
+use crate::services::trading_calendar::TradingDays;
+
+/// 两段缺口相隔不超过该自然日数时合并为一次请求（重复的日期写入时忽略）
+const MERGE_GAP_DAYS: i64 = 30;
+
+/// 回补的股票集合
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StockSet {
+    /// stock_table 中未退市的股票
+    All,
+    /// 某个板块（板块代码）内的股票
+    Plate(String),
+    /// 观察表
+    Watchlist,
+    /// 指定代码
+    Codes(Vec<String>),
+}
+
+impl StockSet {
+    pub fn parse(
+        stock_set: &str,
+        plate_code: Option<String>,
+        codes: Option<Vec<String>>,
+    ) -> Result<Self, String> {
+        match stock_set {
+            "all" => Ok(StockSet::All),
+            "watchlist" => Ok(StockSet::Watchlist),
+            "plate" => plate_code
+                .filter(|c| !c.trim().is_empty())
+                .map(|c| StockSet::Plate(c.trim().to_string()))
+                .ok_or_else(|| "plate_code is required when stock_set = plate".to_string()),
+            "codes" => codes
+                .filter(|c| !c.is_empty())
+                .map(StockSet::Codes)
+                .ok_or_else(|| "codes must not be empty when stock_set = codes".to_string()),
+            other => Err(format!(
+                "invalid stock_set: {other} (expected all / plate / watchlist / codes)"
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StockSet::All => "all",
+            StockSet::Plate(_) => "plate",
+            StockSet::Watchlist => "watchlist",
+            StockSet::Codes(_) => "codes",
+        }
+    }
+
+    /// 集合的附加标识（板块代码）
+    pub fn key(&self) -> Option<String> {
+        match self {
+            StockSet::Plate(code) => Some(code.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// 统一为 daily_klines 使用的纯数字代码
+fn normalize_code(code: &str) -> String {
+    code.trim()
+        .trim_start_matches("SH")
+        .trim_start_matches("SZ")
+        .trim_start_matches("sh")
+        .trim_start_matches("sz")
+        .to_string()
+}
+
+/// 展开股票集合为去重排序后的代码列表
+pub fn resolve_codes(conn: &mut PgPoolConn, set: &StockSet) -> Result<Vec<String>, DieselError> {
+    let raw: Vec<String> = match set {
+        StockSet::All => stock_table::list_active(conn)?
+            .into_iter()
+            .map(|s| s.stock_code)
+            .collect(),
+        StockSet::Plate(code) => {
+            stock_plate_stock_table::list_stock_codes_by_plate_code(conn, code)?
+        }
+        StockSet::Watchlist => stock_watchlist::list_all(conn)?
+            .into_iter()
+            .map(|s| s.stock_code)
+            .collect(),
+        StockSet::Codes(codes) => codes.clone(),
+    };
+    let mut codes: Vec<String> = raw
+        .iter()
+        .map(|c| normalize_code(c))
+        .filter(|c| !c.is_empty())
+        .collect();
+    codes.sort();
+    codes.dedup();
+    Ok(codes)
+}
+
+/// 校验回补区间：起止有序且不晚于今天
+pub fn validate_range(start: NaiveDate, end: NaiveDate, today: NaiveDate) -> Result<(), String> {
+    if start > end {
+        return Err("start_date must not be after end_date".to_string());
+    }
+    if end > today {
+        return Err(format!("end_date must not be after today ({today})"));
+    }
+    Ok(())
+}
+
+/// 为 [`resolve_codes`] 展开后的股票创建回补任务（逐股进度记录初始为 pending）
+pub fn create_run(
+    conn: &mut PgPoolConn,
+    set: &StockSet,
+    codes: &[String],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<KlineBackfillRun, DieselError> {
+    let data = NewKlineBackfillRun {
+        stock_set: set.as_str().to_string(),
+        set_key: set.key(),
+        start_date: start,
+        end_date: end,
+        status: "pending".to_string(),
+        total_stocks: codes.len() as i32,
+    };
+    kline_backfill::create_run(conn, &data, codes)
+}
+
+/// 连续缺失的交易日区间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// 区间内缺失的交易日数
+    pub days: usize,
+}
+
+/// 单只股票在区间内的K线覆盖情况
+#[derive(Debug, Clone, Serialize)]
+pub struct StockCoverage {
+    pub stock_code: String,
+    /// 区间内的交易日数
+    pub expected_days: usize,
+    /// 已有K线的交易日数
+    pub present_days: usize,
+    pub first_date: Option<NaiveDate>,
+    pub last_date: Option<NaiveDate>,
+    pub missing_ranges: Vec<DateRange>,
+}
+
+impl StockCoverage {
+    /// 覆盖率（0 ~ 1），区间内没有交易日时为 1
+    pub fn ratio(&self) -> f64 {
+        if self.expected_days == 0 {
+            1.0
+        } else {
+            self.present_days as f64 / self.expected_days as f64
+        }
+    }
+}
+
+/// 按交易日序列把缺失的日期分组为连续区间
+pub fn missing_ranges(expected: &[NaiveDate], present: &HashSet<NaiveDate>) -> Vec<DateRange> {
+    let mut ranges: Vec<DateRange> = Vec::new();
+    let mut prev_missing = false;
+    for day in expected {
+        if present.contains(day) {
+            prev_missing = false;
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if prev_missing => {
+                range.end = *day;
+                range.days += 1;
+            }
+            _ => ranges.push(DateRange {
+                start: *day,
+                end: *day,
+                days: 1,
+            }),
+        }
+        prev_missing = true;
+    }
+    ranges
+}
+
+/// 需要请求的区间：相隔不超过 [`MERGE_GAP_DAYS`] 个自然日的缺口合并为一次
+pub fn fetch_windows(missing: &[DateRange]) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    for range in missing {
+        match windows.last_mut() {
+            Some((_, end)) if (range.start - *end).num_days() <= MERGE_GAP_DAYS => {
+                *end = range.end;
+            }
+            _ => windows.push((range.start, range.end)),
+        }
+    }
+    windows
+}
+
+/// 统计单只股票在 `[start, end]` 内的覆盖情况
+pub fn coverage(
+    conn: &mut PgPoolConn,
+    days: &TradingDays,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<StockCoverage, DieselError> {
+    let expected = days.trading_days_between(start, end);
+    let dates = daily_kline::list_trade_dates(conn, code, start, end)?;
+    let present: HashSet<NaiveDate> = dates.iter().copied().collect();
+    Ok(StockCoverage {
+        stock_code: code.to_string(),
+        expected_days: expected.len(),
+        present_days: expected.iter().filter(|d| present.contains(d)).count(),
+        first_date: dates.first().copied(),
+        last_date: dates.last().copied(),
+        missing_ranges: missing_ranges(&expected, &present),
+    })
+}
+
+/// 单只股票的回补结果
+#[derive(Debug)]
+pub struct StockBackfill {
+    pub before: StockCoverage,
+    pub after: StockCoverage,
+    pub inserted: usize,
+    /// 拉取失败的区间及原因
+    pub errors: Vec<String>,
+}
+
+/// 回补单只股票：检测缺口、逐个区间拉取并写入，返回前后覆盖情况
+pub async fn backfill_stock(
+    client: &reqwest::Client,
+    db_pool: &DbPool,
+    days: &TradingDays,
+    code: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> anyhow::Result<StockBackfill> {
+    let before = {
+        let mut conn = db_pool.get()?;
+        coverage(&mut conn, days, code, start, end)?
+    };
+    if before.missing_ranges.is_empty() {
+        return Ok(StockBackfill {
+            after: before.clone(),
+            before,
+            inserted: 0,
+            errors: Vec::new(),
+        });
+    }
+
+    let mut inserted = 0;
+    let mut errors = Vec::new();
+    for (from, to) in fetch_windows(&before.missing_ranges) {
+        let result = kline_service::fetch_and_parse_kline_data(
+            client,
+            code,
+            &from.format("%Y%m%d").to_string(),
+            &to.format("%Y%m%d").to_string(),
+        )
+        .await;
+        let parsed = match result {
+            Ok(r) => r.parsed,
+            Err(kline_service::KlineServiceError::NoData) => continue,
+            Err(e) => {
+                tracing::warn!("股票 {} 拉取 {} ~ {} 的K线失败: {}", code, from, to, e);
+                errors.push(format!("{from} ~ {to}: {e}"));
+                continue;
+            }
+        };
+
+        let mut conn = db_pool.get()?;
+        for kline in parsed {
+            match daily_kline::create(&mut conn, &kline) {
+                Ok(_) => inserted += 1,
+                Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {}
+                Err(e) => {
+                    tracing::warn!("写入股票 {} 在 {} 的K线失败: {}", code, kline.trade_date, e)
+                }
+            }
+        }
+    }
+
+    let after = {
+        let mut conn = db_pool.get()?;
+        coverage(&mut conn, days, code, start, end)?
+    };
+    Ok(StockBackfill {
+        before,
+        after,
+        inserted,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, m, day).unwrap()
+    }
+
+    #[test]
+    fn groups_missing_days_and_merges_close_windows() {
+        // 9/28 ~ 10/16 的交易日（10/1 ~ 10/7 国庆休市）
+        let expected = vec![
+            d(9, 28),
+            d(9, 29),
+            d(9, 30),
+            d(10, 8),
+            d(10, 9),
+            d(10, 12),
+            d(10, 13),
+            d(10, 14),
+            d(10, 15),
+            d(10, 16),
+        ];
+        let present: HashSet<NaiveDate> = [d(9, 28), d(10, 12), d(10, 13), d(10, 16)]
+            .into_iter()
+            .collect();
+
+        let ranges = missing_ranges(&expected, &present);
+        assert_eq!(
+            ranges,
+            vec![
+                DateRange {
+                    start: d(9, 29),
+                    end: d(10, 9),
+                    days: 4
+                },
+                DateRange {
+                    start: d(10, 14),
+                    end: d(10, 15),
+                    days: 2
+                },
+            ]
+        );
+        assert_eq!(fetch_windows(&ranges), vec![(d(9, 29), d(10, 15))]);
+        assert!(missing_ranges(&expected, &expected.iter().copied().collect()).is_empty());
+    }
+}
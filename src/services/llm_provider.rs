@@ -0,0 +1,758 @@
+//! 大模型调用抽象：按请求中的 `model` 选择后端，同一套分析流程可切换 DashScope（通义千问）、
+//! OpenAI 兼容接口、Anthropic Messages 接口、本地 OpenAI 兼容服务（llama.cpp / vLLM 等）或离线桩。
+//!
+//! `model` 写作 `provider/model`（如 `qwen/qwen3-max-2026-01-23`、`local/qwen2.5-7b`），
+//! 只写 provider 时使用该后端在环境变量中配置的默认模型；缺省时使用 `LLM_DEFAULT_PROVIDER`（默认 qwen）。
+//!
+//! 流式调用（[`LlmProvider::chat_stream`]）读取接口的 SSE 响应，逐段回调增量文本；
+//! 不支持流式的后端（离线桩）退化为一次性返回。
+
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::services::ai_service::AiServiceError;
+
+pub const PROVIDER_QWEN: &str = "qwen";
+pub const PROVIDER_OPENAI: &str = "openai";
+pub const PROVIDER_ANTHROPIC: &str = "anthropic";
+pub const PROVIDER_LOCAL: &str = "local";
+pub const PROVIDER_STUB: &str = "stub";
+
+const DEFAULT_QWEN_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1/chat/completions";
+const DEFAULT_QWEN_MODEL: &str = "qwen3-max-2026-01-23";
+const DEFAULT_OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o";
+const DEFAULT_ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const DEFAULT_ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_LOCAL_URL: &str = "http://127.0.0.1:8080/v1/chat/completions";
+const DEFAULT_LOCAL_MODEL: &str = "local";
+const DEFAULT_TIMEOUT_SECS: u64 = 180;
+
+/// 对话消息（role 为 system / user / assistant）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// 生成参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatOptions {
+    pub temperature: f64,
+    pub max_tokens: u32,
+}
+
+impl Default for ChatOptions {
+    fn default() -> Self {
+        Self {
+            temperature: 0.3,
+            max_tokens: 8192,
+        }
+    }
+}
+
+impl ChatOptions {
+    /// 默认值可由 `LLM_TEMPERATURE` / `LLM_MAX_TOKENS` 覆盖
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            temperature: env_parse("LLM_TEMPERATURE").unwrap_or(default.temperature),
+            max_tokens: env_parse("LLM_MAX_TOKENS").unwrap_or(default.max_tokens),
+        }
+    }
+}
+
+/// 一次调用消耗的 token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> i64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    pub fn add(&mut self, other: TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+
+    /// 接口未返回用量时按字符数粗略估算（中文约 1 字 1 token，英文约 4 字符 1 token，取折中 2 字符）
+    pub fn estimate(messages: &[ChatMessage], content: &str) -> Self {
+        let chars = |s: &str| (s.chars().count() as i64 + 1) / 2;
+        Self {
+            prompt_tokens: messages.iter().map(|m| chars(&m.content)).sum(),
+            completion_tokens: chars(content),
+        }
+    }
+}
+
+/// 一次调用的返回
+#[derive(Debug, Clone)]
+pub struct ChatCompletion {
+    pub content: String,
+    pub usage: TokenUsage,
+}
+
+/// 大模型后端
+pub trait LlmProvider: Send + Sync {
+    /// 后端标识，如 qwen / anthropic / local
+    fn provider(&self) -> &str;
+
+    /// 实际请求的模型名
+    fn model(&self) -> &str;
+
+    /// 写入分析记录的模型名：`provider/model`
+    fn model_name(&self) -> String {
+        format!("{}/{}", self.provider(), self.model())
+    }
+
+    fn chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>>;
+
+    /// 流式调用：增量文本依次交给 `on_delta`，结束后返回完整内容与用量。
+    /// 默认实现为一次性调用，完整内容作为唯一的一段增量
+    fn chat_stream<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+        on_delta: &'a (dyn Fn(&str) + Send + Sync),
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            let completion = self.chat(messages, options).await?;
+            on_delta(&completion.content);
+            Ok(completion)
+        })
+    }
+}
+
+/// OpenAI Chat Completions 兼容接口（DashScope 兼容模式、OpenAI、llama.cpp server 等）
+pub struct OpenAiCompatibleProvider {
+    provider: String,
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: i64,
+    #[serde(default)]
+    completion_tokens: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+fn openai_body(
+    model: &str,
+    messages: &[ChatMessage],
+    options: &ChatOptions,
+    stream: bool,
+) -> JsonValue {
+    let mut body = json!({
+        "model": model,
+        "messages": messages,
+        "temperature": options.temperature,
+        "max_tokens": options.max_tokens,
+    });
+    if stream {
+        body["stream"] = JsonValue::Bool(true);
+        // 最后一个分片附带用量（不支持该参数的服务会忽略）
+        body["stream_options"] = json!({"include_usage": true});
+    }
+    body
+}
+
+impl OpenAiCompatibleProvider {
+    fn request(&self, body: &JsonValue) -> reqwest::RequestBuilder {
+        let request = self.client.post(&self.api_url).json(body);
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+}
+
+impl LlmProvider for OpenAiCompatibleProvider {
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            let request = self.request(&openai_body(&self.model, messages, options, false));
+            let text = send(&self.provider, request).await?;
+            let parsed: OpenAiResponse = parse_response(&self.provider, &text)?;
+            let content = parsed
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.message.content)
+                .unwrap_or_default();
+            let usage = match parsed.usage {
+                Some(u) => TokenUsage {
+                    prompt_tokens: u.prompt_tokens,
+                    completion_tokens: u.completion_tokens,
+                },
+                None => TokenUsage::estimate(messages, &content),
+            };
+            Ok(ChatCompletion { content, usage })
+        })
+    }
+
+    fn chat_stream<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+        on_delta: &'a (dyn Fn(&str) + Send + Sync),
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            let request = self.request(&openai_body(&self.model, messages, options, true));
+            let mut content = String::new();
+            let mut usage = None;
+            read_sse(&self.provider, request, |data| {
+                if data == "[DONE]" {
+                    return Ok(());
+                }
+                let chunk: JsonValue = parse_response(&self.provider, data)?;
+                if let Some(text) = chunk["choices"][0]["delta"]["content"].as_str() {
+                    if !text.is_empty() {
+                        content.push_str(text);
+                        on_delta(text);
+                    }
+                }
+                if let Some(u) = chunk.get("usage").filter(|u| u.is_object()) {
+                    usage = Some(TokenUsage {
+                        prompt_tokens: u["prompt_tokens"].as_i64().unwrap_or(0),
+                        completion_tokens: u["completion_tokens"].as_i64().unwrap_or(0),
+                    });
+                }
+                Ok(())
+            })
+            .await?;
+            let usage = usage.unwrap_or_else(|| TokenUsage::estimate(messages, &content));
+            Ok(ChatCompletion { content, usage })
+        })
+    }
+}
+
+/// Anthropic Messages 接口：system 单独传参，回复内容为 text 块数组
+pub struct AnthropicProvider {
+    api_url: String,
+    api_key: String,
+    version: String,
+    model: String,
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: i64,
+    #[serde(default)]
+    output_tokens: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// 构造 Messages 请求体：多条 system 消息合并为 system 参数
+fn anthropic_body(
+    model: &str,
+    messages: &[ChatMessage],
+    options: &ChatOptions,
+    stream: bool,
+) -> JsonValue {
+    let system: Vec<&str> = messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect();
+    let turns: Vec<&ChatMessage> = messages.iter().filter(|m| m.role != "system").collect();
+    let mut body = json!({
+        "model": model,
+        "messages": turns,
+        "temperature": options.temperature,
+        "max_tokens": options.max_tokens,
+    });
+    if !system.is_empty() {
+        body["system"] = JsonValue::String(system.join("\n\n"));
+    }
+    if stream {
+        body["stream"] = JsonValue::Bool(true);
+    }
+    body
+}
+
+impl AnthropicProvider {
+    fn request(&self, body: &JsonValue) -> reqwest::RequestBuilder {
+        self.client
+            .post(&self.api_url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.version)
+            .json(body)
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn provider(&self) -> &str {
+        PROVIDER_ANTHROPIC
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            let request = self.request(&anthropic_body(&self.model, messages, options, false));
+            let text = send(PROVIDER_ANTHROPIC, request).await?;
+            let parsed: AnthropicResponse = parse_response(PROVIDER_ANTHROPIC, &text)?;
+            let content = parsed
+                .content
+                .into_iter()
+                .filter(|c| c.kind == "text")
+                .filter_map(|c| c.text)
+                .collect::<Vec<_>>()
+                .join("");
+            let usage = match parsed.usage {
+                Some(u) => TokenUsage {
+                    prompt_tokens: u.input_tokens,
+                    completion_tokens: u.output_tokens,
+                },
+                None => TokenUsage::estimate(messages, &content),
+            };
+            Ok(ChatCompletion { content, usage })
+        })
+    }
+
+    fn chat_stream<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        options: &'a ChatOptions,
+        on_delta: &'a (dyn Fn(&str) + Send + Sync),
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            let request = self.request(&anthropic_body(&self.model, messages, options, true));
+            let mut content = String::new();
+            let mut usage = TokenUsage::default();
+            read_sse(PROVIDER_ANTHROPIC, request, |data| {
+                let event: JsonValue = parse_response(PROVIDER_ANTHROPIC, data)?;
+                match event["type"].as_str().unwrap_or_default() {
+                    "message_start" => {
+                        let u = &event["message"]["usage"];
+                        usage.prompt_tokens = u["input_tokens"].as_i64().unwrap_or(0);
+                        usage.completion_tokens = u["output_tokens"].as_i64().unwrap_or(0);
+                    }
+                    "content_block_delta" => {
+                        if let Some(text) = event["delta"]["text"].as_str() {
+                            content.push_str(text);
+                            on_delta(text);
+                        }
+                    }
+                    "message_delta" => {
+                        if let Some(output) = event["usage"]["output_tokens"].as_i64() {
+                            usage.completion_tokens = output;
+                        }
+                    }
+                    "error" => {
+                        return Err(AiServiceError::ApiError(format!(
+                            "{PROVIDER_ANTHROPIC} stream error: {}",
+                            event["error"]["message"].as_str().unwrap_or_default()
+                        )));
+                    }
+                    _ => {}
+                }
+                Ok(())
+            })
+            .await?;
+            if usage.total() == 0 {
+                usage = TokenUsage::estimate(messages, &content);
+            }
+            Ok(ChatCompletion { content, usage })
+        })
+    }
+}
+
+/// 离线桩：不发网络请求，返回 `STUB_LLM_RESPONSE` 或固定的 JSON，用于本地联调与测试
+pub struct StubProvider {
+    model: String,
+    response: String,
+}
+
+impl StubProvider {
+    pub fn new(model: impl Into<String>, response: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            response: response.into(),
+        }
+    }
+}
+
+/// 满足趋势分析结果 Schema 的固定输出
+fn default_stub_response() -> String {
+    json!({
+        "overview": {
+            "stock_name": "stub",
+            "signal_count": 0,
+            "analysis_period": "",
+            "data_completeness": {"is_sufficient": false}
+        },
+        "signal_evaluations": [],
+        "current_diagnosis": {
+            "status": "离线桩",
+            "rating": "D",
+            "action": "watch",
+            "action_suggestion": "离线桩返回的固定分析结果",
+            "support_level": 1.0,
+            "resistance_level": 1.0
+        },
+        "optimal_trade_signals": [],
+        "risk_warnings": ["本结论仅用于复盘验证，不构成投资建议"]
+    })
+    .to_string()
+}
+
+impl LlmProvider for StubProvider {
+    fn provider(&self) -> &str {
+        PROVIDER_STUB
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn chat<'a>(
+        &'a self,
+        messages: &'a [ChatMessage],
+        _options: &'a ChatOptions,
+    ) -> BoxFuture<'a, Result<ChatCompletion, AiServiceError>> {
+        Box::pin(async move {
+            Ok(ChatCompletion {
+                content: self.response.clone(),
+                usage: TokenUsage::estimate(messages, &self.response),
+            })
+        })
+    }
+}
+
+async fn send(provider: &str, request: reqwest::RequestBuilder) -> Result<String, AiServiceError> {
+    tracing::info!("Calling {} LLM API...", provider);
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        tracing::error!(
+            "{} API returned error status {}: {}",
+            provider,
+            status,
+            text
+        );
+        return Err(AiServiceError::ApiError(format!(
+            "{provider} API returned status {status}: {text}"
+        )));
+    }
+    Ok(text)
+}
+
+/// 按行切分 SSE 响应体，取出 `data:` 行的内容（分片可能在行中间甚至 UTF-8 字符中间断开）
+#[derive(Debug, Default)]
+struct SseDataBuffer {
+    pending: Vec<u8>,
+}
+
+impl SseDataBuffer {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut data = Vec::new();
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(rest) = line.trim_end_matches(['\r', '\n']).strip_prefix("data:") {
+                data.push(rest.trim_start().to_string());
+            }
+        }
+        data
+    }
+}
+
+/// 发送流式请求并逐条处理 SSE 的 `data:` 内容
+async fn read_sse(
+    provider: &str,
+    request: reqwest::RequestBuilder,
+    mut on_data: impl FnMut(&str) -> Result<(), AiServiceError>,
+) -> Result<(), AiServiceError> {
+    tracing::info!("Calling {} LLM API (stream)...", provider);
+    let mut response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await?;
+        tracing::error!(
+            "{} API returned error status {}: {}",
+            provider,
+            status,
+            text
+        );
+        return Err(AiServiceError::ApiError(format!(
+            "{provider} API returned status {status}: {text}"
+        )));
+    }
+    let mut buffer = SseDataBuffer::default();
+    while let Some(chunk) = response.chunk().await? {
+        for data in buffer.push(&chunk) {
+            on_data(&data)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_response<T: serde::de::DeserializeOwned>(
+    provider: &str,
+    text: &str,
+) -> Result<T, AiServiceError> {
+    serde_json::from_str(text).map_err(|e| {
+        tracing::error!(
+            "Failed to parse {} API response: {} | raw: {}",
+            provider,
+            e,
+            text
+        );
+        AiServiceError::ParseError(format!("Failed to parse {provider} API response: {e}"))
+    })
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    std::env::var(key).ok().and_then(|v| v.trim().parse().ok())
+}
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn env_required(key: &str) -> Result<String, AiServiceError> {
+    std::env::var(key)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| AiServiceError::EnvError(format!("{key} not set")))
+}
+
+/// 分析记录中的 `model_name` 转为可传给 [`resolve_provider`] 的说明：
+/// 接入多后端之前的记录只存了 Qwen 的模型名（不含 `/`），按 `qwen/<model>` 处理
+pub fn stored_model_spec(model_name: &str) -> String {
+    let model_name = model_name.trim();
+    if model_name.contains('/') {
+        model_name.to_string()
+    } else {
+        format!("{PROVIDER_QWEN}/{model_name}")
+    }
+}
+
+/// 拆分 `provider/model`，只写 provider 时模型为 None
+pub fn parse_model_spec(spec: &str) -> (String, Option<String>) {
+    let spec = spec.trim();
+    match spec.split_once('/') {
+        Some((provider, model)) if !model.trim().is_empty() => (
+            provider.trim().to_ascii_lowercase(),
+            Some(model.trim().to_string()),
+        ),
+        Some((provider, _)) => (provider.trim().to_ascii_lowercase(), None),
+        None => (spec.to_ascii_lowercase(), None),
+    }
+}
+
+fn http_client() -> Result<Client, AiServiceError> {
+    let timeout = env_parse("LLM_TIMEOUT_SECS").unwrap_or(DEFAULT_TIMEOUT_SECS);
+    Ok(Client::builder()
+        .timeout(std::time::Duration::from_secs(timeout))
+        .build()?)
+}
+
+/// 按请求的 `model` 选择后端，未配置的 API Key 或未知 provider 返回错误
+pub fn resolve_provider(spec: Option<&str>) -> Result<Box<dyn LlmProvider>, AiServiceError> {
+    let spec = spec
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| env_or("LLM_DEFAULT_PROVIDER", PROVIDER_QWEN));
+    let (provider, model) = parse_model_spec(&spec);
+    let model_or = |key: &str, default: &str| model.clone().unwrap_or_else(|| env_or(key, default));
+
+    let resolved: Box<dyn LlmProvider> = match provider.as_str() {
+        PROVIDER_QWEN => Box::new(OpenAiCompatibleProvider {
+            provider: PROVIDER_QWEN.to_string(),
+            api_url: env_or("QWEN_API_URL", DEFAULT_QWEN_URL),
+            api_key: Some(env_required("QWEN_API_KEY")?),
+            model: model_or("QWEN_MODEL", DEFAULT_QWEN_MODEL),
+            client: http_client()?,
+        }),
+        PROVIDER_OPENAI => Box::new(OpenAiCompatibleProvider {
+            provider: PROVIDER_OPENAI.to_string(),
+            api_url: env_or("OPENAI_API_URL", DEFAULT_OPENAI_URL),
+            api_key: Some(env_required("OPENAI_API_KEY")?),
+            model: model_or("OPENAI_MODEL", DEFAULT_OPENAI_MODEL),
+            client: http_client()?,
+        }),
+        PROVIDER_ANTHROPIC => Box::new(AnthropicProvider {
+            api_url: env_or("ANTHROPIC_API_URL", DEFAULT_ANTHROPIC_URL),
+            api_key: env_required("ANTHROPIC_API_KEY")?,
+            version: env_or("ANTHROPIC_VERSION", DEFAULT_ANTHROPIC_VERSION),
+            model: model_or("ANTHROPIC_MODEL", ""),
+            client: http_client()?,
+        }),
+        PROVIDER_LOCAL => Box::new(OpenAiCompatibleProvider {
+            provider: PROVIDER_LOCAL.to_string(),
+            api_url: env_or("LOCAL_LLM_API_URL", DEFAULT_LOCAL_URL),
+            // 本地服务通常不校验 Key
+            api_key: std::env::var("LOCAL_LLM_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            model: model_or("LOCAL_LLM_MODEL", DEFAULT_LOCAL_MODEL),
+            client: http_client()?,
+        }),
+        PROVIDER_STUB => Box::new(StubProvider::new(
+            model.unwrap_or_else(|| "stub".to_string()),
+            env_or("STUB_LLM_RESPONSE", &default_stub_response()),
+        )),
+        other => {
+            return Err(AiServiceError::UnsupportedModel(format!(
+                "unknown provider: {other} (expected qwen / openai / anthropic / local / stub)"
+            )))
+        }
+    };
+    if resolved.model().is_empty() {
+        return Err(AiServiceError::UnsupportedModel(format!(
+            "no model given for provider {provider}"
+        )));
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_model_spec_and_resolves_stub() {
+        assert_eq!(
+            parse_model_spec("Qwen/qwen3-max"),
+            ("qwen".to_string(), Some("qwen3-max".to_string()))
+        );
+        assert_eq!(parse_model_spec("local/"), ("local".to_string(), None));
+        assert_eq!(parse_model_spec(" stub "), ("stub".to_string(), None));
+        assert_eq!(
+            stored_model_spec("qwen3-max-2026-01-23"),
+            "qwen/qwen3-max-2026-01-23"
+        );
+        assert_eq!(stored_model_spec("openai/gpt-4o"), "openai/gpt-4o");
+
+        let stub = resolve_provider(Some("stub/offline")).unwrap();
+        assert_eq!(stub.model_name(), "stub/offline");
+        let output: JsonValue = serde_json::from_str(&default_stub_response()).unwrap();
+        assert!(crate::services::ai_result::validate(&output).is_ok());
+        assert!(matches!(
+            resolve_provider(Some("unknown/x")),
+            Err(AiServiceError::UnsupportedModel(_))
+        ));
+    }
+
+    #[test]
+    fn anthropic_body_moves_system_out_of_messages() {
+        let messages = vec![ChatMessage::system("规则"), ChatMessage::user("数据")];
+        let body = anthropic_body("claude-x", &messages, &ChatOptions::default(), false);
+        assert_eq!(body["system"], "规则");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["max_tokens"], 8192);
+        assert!(body.get("stream").is_none());
+    }
+
+    #[test]
+    fn splits_sse_data_across_chunks() {
+        let mut buffer = SseDataBuffer::default();
+        let text = "event: delta\ndata: {\"t\":\"趋势\"}\r\n\ndata: [DONE]\n";
+        let bytes = text.as_bytes();
+        // 在「趋」字的 UTF-8 字节中间断开
+        let split = text.find('趋').unwrap() + 1;
+        assert!(buffer.push(&bytes[..split]).is_empty());
+        assert_eq!(
+            buffer.push(&bytes[split..]),
+            vec!["{\"t\":\"趋势\"}".to_string(), "[DONE]".to_string()]
+        );
+        assert!(buffer.push(b"data: partial").is_empty());
+    }
+}
@@ -0,0 +1,578 @@
+//! AI 每日复盘：收盘后汇总当日信号支数、热门板块、连续出现信号的股票与上一交易日信号表现，
+//! 按 `market_recap` 模板交给大模型生成复盘，结果按日期写入 `ai_market_reports`。
+//!
+//! 统计部分不依赖模型：模型未配置、调用失败或用量已达预算时仍保存统计（`stats_only`），
+//! 之后可重新执行任务补上复盘。当天已有生成好的复盘时不会被仅统计的结果覆盖。
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{NaiveDate, Utc};
+use diesel::r2d2::PoolError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::app::DbPool;
+use crate::models::{AiMarketReport, NewAiMarketReport};
+use crate::repositories::ai_market_report::{self, SignalOutcomeRow};
+use crate::repositories::basic_data_analysis::{self, PgPoolConn};
+use crate::repositories::stock_snapshot;
+use crate::services::ai_service::AiServiceError;
+use crate::services::ai_usage;
+use crate::services::llm_provider::{self, ChatMessage, ChatOptions, TokenUsage};
+use crate::services::prompt_template::{self, PromptTemplateError, TEMPLATE_MARKET_RECAP};
+use crate::services::trading_calendar::TradingDays;
+
+pub const REPORT_COMPLETED: &str = "completed";
+/// 只有统计部分
+pub const REPORT_STATS_ONLY: &str = "stats_only";
+
+/// 热门板块数
+const HOT_PLATE_LIMIT: usize = 10;
+/// 每个热门板块列出的股票数
+const PLATE_STOCK_LIMIT: usize = 8;
+/// 信号支数与连续信号的统计窗口（交易日，含当天）
+const WINDOW_TRADING_DAYS: usize = 5;
+const REPEATED_SIGNAL_LIMIT: i64 = 20;
+/// 上一交易日信号中列出的表现最好 / 最差的股票数
+const TOP_OUTCOMES: usize = 5;
+
+#[derive(Debug, Error)]
+pub enum MarketReportError {
+    #[error("{0}")]
+    Llm(#[from] AiServiceError),
+    #[error("{0}")]
+    Template(#[from] PromptTemplateError),
+    #[error("{0}，暂停新的 AI 分析")]
+    BudgetExceeded(String),
+    #[error("connection pool error: {0}")]
+    Pool(#[from] PoolError),
+    #[error("database error: {0}")]
+    Db(#[from] diesel::result::Error),
+    #[error("serde_json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+impl MarketReportError {
+    /// 只影响复盘生成、统计部分仍可保存的错误
+    fn is_recap_only(&self) -> bool {
+        matches!(
+            self,
+            Self::Llm(_) | Self::Template(_) | Self::BudgetExceeded(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailySignalCount {
+    pub date: NaiveDate,
+    pub stock_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotPlate {
+    pub plate_code: String,
+    pub plate_name: String,
+    pub stock_count: i64,
+    /// `代码 名称`，最多 [`PLATE_STOCK_LIMIT`] 只
+    pub stocks: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepeatedSignal {
+    pub stock_code: String,
+    pub stock_name: String,
+    pub signal_days: i64,
+    pub first_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalReturn {
+    pub stock_code: String,
+    pub stock_name: String,
+    /// 相对信号价的收盘涨跌幅（%）
+    pub close_return_pct: f64,
+    pub profit_rate: Option<i64>,
+}
+
+/// 上一交易日信号在报告日的表现
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalPerformance {
+    pub signal_date: Option<NaiveDate>,
+    pub signal_count: usize,
+    /// 报告日有K线的股票数
+    pub with_kline: usize,
+    /// 盈利分析评级 2 / 1 / 0 的股票数
+    pub strong_count: usize,
+    pub touched_count: usize,
+    pub weak_count: usize,
+    pub avg_close_return_pct: Option<f64>,
+    /// 收涨占比（%）
+    pub up_ratio_pct: Option<f64>,
+    pub best: Vec<SignalReturn>,
+    pub worst: Vec<SignalReturn>,
+}
+
+/// 发送给模型并保存在 `ai_market_reports.statistics` 的统计数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketStatistics {
+    pub trade_date: NaiveDate,
+    pub signal_stock_count: i64,
+    /// 有板块归属的信号股票数
+    pub classified_stock_count: i64,
+    pub daily_counts: Vec<DailySignalCount>,
+    pub hot_plates: Vec<HotPlate>,
+    pub repeated_signals: Vec<RepeatedSignal>,
+    pub previous_performance: SignalPerformance,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// 汇总上一交易日信号的表现
+pub fn summarize_performance(
+    signal_date: NaiveDate,
+    rows: &[SignalOutcomeRow],
+) -> SignalPerformance {
+    let rate_count = |rate: i64| rows.iter().filter(|r| r.profit_rate == Some(rate)).count();
+    let mut returns: Vec<SignalReturn> = rows
+        .iter()
+        .filter(|r| r.entry_price > BigDecimal::from(0))
+        .filter_map(|r| {
+            let close = r.close_price.as_ref()?;
+            let pct = (close - &r.entry_price) * BigDecimal::from(100) / &r.entry_price;
+            Some(SignalReturn {
+                stock_code: r.stock_code.clone(),
+                stock_name: r.stock_name.clone(),
+                close_return_pct: round2(pct.to_f64()?),
+                profit_rate: r.profit_rate,
+            })
+        })
+        .collect();
+    returns.sort_by(|a, b| b.close_return_pct.total_cmp(&a.close_return_pct));
+
+    let n = returns.len();
+    let (avg, up_ratio) = if n == 0 {
+        (None, None)
+    } else {
+        let sum: f64 = returns.iter().map(|r| r.close_return_pct).sum();
+        let up = returns.iter().filter(|r| r.close_return_pct > 0.0).count();
+        (
+            Some(round2(sum / n as f64)),
+            Some(round2(up as f64 * 100.0 / n as f64)),
+        )
+    };
+    let best: Vec<SignalReturn> = returns.iter().take(TOP_OUTCOMES).cloned().collect();
+    let worst: Vec<SignalReturn> = returns
+        .iter()
+        .rev()
+        .take(TOP_OUTCOMES.min(n.saturating_sub(best.len())))
+        .cloned()
+        .collect();
+    SignalPerformance {
+        signal_date: Some(signal_date),
+        signal_count: rows.len(),
+        with_kline: n,
+        strong_count: rate_count(2),
+        touched_count: rate_count(1),
+        weak_count: rate_count(0),
+        avg_close_return_pct: avg,
+        up_ratio_pct: up_ratio,
+        best,
+        worst,
+    }
+}
+
+/// 汇总报告日的统计数据
+pub fn collect_statistics(
+    conn: &mut PgPoolConn,
+    days: &TradingDays,
+    date: NaiveDate,
+) -> Result<MarketStatistics, diesel::result::Error> {
+    let window_start = days.nth_trading_day_before(date, WINDOW_TRADING_DAYS - 1);
+    let summary = basic_data_analysis::query_plate_statistics_summary(conn, date)?;
+    let daily_counts =
+        stock_snapshot::count_distinct_codes_by_date_range(conn, window_start, date, None)?
+            .into_iter()
+            .map(|r| DailySignalCount {
+                date: r.stat_date,
+                stock_count: r.stock_count,
+            })
+            .collect();
+
+    let hot_plates = basic_data_analysis::query_plate_statistics(conn, date)?
+        .into_iter()
+        .take(HOT_PLATE_LIMIT)
+        .map(|p| {
+            let stocks = p
+                .stocks
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .take(PLATE_STOCK_LIMIT)
+                        .map(|s| {
+                            let field =
+                                |k: &str| s.get(k).and_then(JsonValue::as_str).unwrap_or("");
+                            format!("{} {}", field("stock_code"), field("stock_name"))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            HotPlate {
+                plate_code: p.plate_code,
+                plate_name: p.plate_name,
+                stock_count: p.stock_count,
+                stocks,
+            }
+        })
+        .collect();
+
+    let repeated_signals =
+        ai_market_report::list_repeated_signals(conn, window_start, date, REPEATED_SIGNAL_LIMIT)?
+            .into_iter()
+            .map(|r| RepeatedSignal {
+                stock_code: r.stock_code,
+                stock_name: r.stock_name,
+                signal_days: r.signal_days,
+                first_date: r.first_date,
+            })
+            .collect();
+
+    let signal_date = days.prev_trading_day(date);
+    let outcomes = ai_market_report::list_signal_outcomes(conn, signal_date, date)?;
+
+    Ok(MarketStatistics {
+        trade_date: date,
+        signal_stock_count: summary.total_stock_count,
+        classified_stock_count: summary.classified_stock_count,
+        daily_counts,
+        hot_plates,
+        repeated_signals,
+        previous_performance: summarize_performance(signal_date, &outcomes),
+    })
+}
+
+fn pct_text(v: Option<f64>) -> String {
+    v.map(|v| format!("{v:.2}%"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn return_list(items: &[SignalReturn]) -> String {
+    items
+        .iter()
+        .map(|r| {
+            format!(
+                "{} {}（{:+.2}%）",
+                r.stock_code, r.stock_name, r.close_return_pct
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+/// 统计部分的 Markdown 文本：作为模型输入，模型不可用时即为报告内容
+pub fn render_statistics(stats: &MarketStatistics) -> String {
+    let mut lines = vec![
+        format!("## {} 收盘统计", stats.trade_date),
+        String::new(),
+        format!(
+            "- 当日出现信号的股票 {} 只，其中有板块归属 {} 只",
+            stats.signal_stock_count, stats.classified_stock_count
+        ),
+    ];
+    if !stats.daily_counts.is_empty() {
+        let counts: Vec<String> = stats
+            .daily_counts
+            .iter()
+            .map(|c| format!("{} {} 只", c.date.format("%m-%d"), c.stock_count))
+            .collect();
+        lines.push(format!(
+            "- 近 {} 个交易日信号支数：{}",
+            WINDOW_TRADING_DAYS,
+            counts.join("，")
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("### 热门板块".to_string());
+    if stats.hot_plates.is_empty() {
+        lines.push("- 无".to_string());
+    } else {
+        lines.push(String::new());
+        lines.push("| 板块 | 信号股票数 | 股票 |".to_string());
+        lines.push("| --- | --- | --- |".to_string());
+        for p in &stats.hot_plates {
+            lines.push(format!(
+                "| {} | {} | {} |",
+                p.plate_name,
+                p.stock_count,
+                p.stocks.join("、")
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(format!(
+        "### 近 {WINDOW_TRADING_DAYS} 个交易日连续出现信号的股票"
+    ));
+    if stats.repeated_signals.is_empty() {
+        lines.push("- 无".to_string());
+    } else {
+        lines.push(String::new());
+        lines.push("| 股票 | 出现天数 | 首次出现 |".to_string());
+        lines.push("| --- | --- | --- |".to_string());
+        for r in &stats.repeated_signals {
+            lines.push(format!(
+                "| {} {} | {} | {} |",
+                r.stock_code, r.stock_name, r.signal_days, r.first_date
+            ));
+        }
+    }
+
+    let perf = &stats.previous_performance;
+    lines.push(String::new());
+    match perf.signal_date {
+        Some(date) => lines.push(format!("### 上一交易日（{date}）信号表现")),
+        None => lines.push("### 上一交易日信号表现".to_string()),
+    }
+    if perf.signal_count == 0 {
+        lines.push("- 上一交易日没有信号".to_string());
+    } else {
+        lines.push(format!(
+            "- 信号股票 {} 只，当日有K线 {} 只",
+            perf.signal_count, perf.with_kline
+        ));
+        lines.push(format!(
+            "- 盈利分析：冲高 10% 且收盘不低于 5% {} 只，冲高 5% 未站稳 {} 只，未达 5% {} 只",
+            perf.strong_count, perf.touched_count, perf.weak_count
+        ));
+        lines.push(format!(
+            "- 相对信号价平均收盘涨幅 {}，收涨占比 {}",
+            pct_text(perf.avg_close_return_pct),
+            pct_text(perf.up_ratio_pct)
+        ));
+        if !perf.best.is_empty() {
+            lines.push(format!("- 表现最好：{}", return_list(&perf.best)));
+        }
+        if !perf.worst.is_empty() {
+            lines.push(format!("- 表现最差：{}", return_list(&perf.worst)));
+        }
+    }
+    lines.join("\n")
+}
+
+/// 复盘所用模型：`AI_MARKET_REPORT_MODEL`，未配置时为默认后端
+pub fn default_model() -> Option<String> {
+    std::env::var("AI_MARKET_REPORT_MODEL")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 模型生成的复盘
+struct Recap {
+    summary: String,
+    model_name: String,
+    template_id: i32,
+    version: i32,
+    usage: TokenUsage,
+    duration_ms: i64,
+}
+
+async fn recap(
+    db_pool: &DbPool,
+    stats: &MarketStatistics,
+    model: Option<&str>,
+) -> Result<Recap, MarketReportError> {
+    let provider = llm_provider::resolve_provider(model)?;
+    let template = prompt_template::resolve(
+        &mut *db_pool.get()?,
+        TEMPLATE_MARKET_RECAP,
+        None,
+        Utc::now().naive_utc(),
+    )?;
+    if let Some(reason) = ai_usage::check_budget(&mut db_pool.get()?)? {
+        return Err(MarketReportError::BudgetExceeded(reason));
+    }
+
+    let vars = HashMap::from([
+        ("trade_date", stats.trade_date.to_string()),
+        ("statistics", render_statistics(stats)),
+        ("payload", serde_json::to_string_pretty(stats)?),
+    ]);
+    let prompt = prompt_template::render_template(&template, &vars);
+    let messages = [
+        ChatMessage::system(prompt.system),
+        ChatMessage::user(prompt.user),
+    ];
+    let start = Instant::now();
+    tracing::info!(
+        "Calling {} for market recap of {}",
+        provider.model_name(),
+        stats.trade_date
+    );
+    let completion = provider.chat(&messages, &ChatOptions::from_env()).await?;
+    Ok(Recap {
+        summary: completion.content,
+        model_name: provider.model_name(),
+        template_id: prompt.template_id,
+        version: prompt.version,
+        usage: completion.usage,
+        duration_ms: start.elapsed().as_millis() as i64,
+    })
+}
+
+/// 生成并保存某个交易日的复盘；已有记录时覆盖
+pub async fn generate(
+    db_pool: &DbPool,
+    days: &TradingDays,
+    date: NaiveDate,
+    model: Option<&str>,
+) -> Result<AiMarketReport, MarketReportError> {
+    let stats = collect_statistics(&mut db_pool.get()?, days, date)?;
+    let mut report = NewAiMarketReport {
+        report_date: date,
+        status: REPORT_STATS_ONLY.to_string(),
+        statistics: serde_json::to_value(&stats)?,
+        summary: None,
+        model_name: None,
+        prompt_template_id: None,
+        prompt_version: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+        total_tokens: None,
+        cost: None,
+        duration_ms: None,
+        error_message: None,
+        updated_at: Utc::now(),
+    };
+
+    if stats.signal_stock_count == 0 {
+        report.error_message = Some("当日没有信号数据".to_string());
+    } else {
+        match recap(db_pool, &stats, model).await {
+            Ok(recap) => {
+                let usage = recap.usage;
+                report.cost = ai_usage::cost_for(&mut *db_pool.get()?, &recap.model_name, usage)?;
+                report.status = REPORT_COMPLETED.to_string();
+                report.summary = Some(recap.summary);
+                report.model_name = Some(recap.model_name);
+                report.prompt_template_id = Some(recap.template_id);
+                report.prompt_version = Some(recap.version);
+                report.prompt_tokens = Some(usage.prompt_tokens);
+                report.completion_tokens = Some(usage.completion_tokens);
+                report.total_tokens = Some(usage.total());
+                report.duration_ms = Some(recap.duration_ms);
+            }
+            Err(e) if e.is_recap_only() => {
+                tracing::warn!("{} 复盘未生成，仅保存统计: {}", date, e);
+                report.error_message = Some(e.to_string());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut conn = db_pool.get()?;
+    let existing = ai_market_report::find_by_date(&mut conn, date)?;
+    if keeps_existing_recap(existing.as_ref().map(|r| r.status.as_str()), &report.status) {
+        tracing::info!("{} 已有复盘，本次仅更新统计", date);
+        return Ok(ai_market_report::update_statistics(
+            &mut conn,
+            date,
+            &report.statistics,
+            report.error_message.as_deref(),
+            report.updated_at,
+        )?);
+    }
+    Ok(ai_market_report::upsert(&mut conn, &report)?)
+}
+
+/// 本次只得到统计而当天已有生成好的复盘时，保留原复盘（只刷新统计与失败原因）
+fn keeps_existing_recap(existing_status: Option<&str>, new_status: &str) -> bool {
+    new_status == REPORT_STATS_ONLY && existing_status == Some(REPORT_COMPLETED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn outcome(
+        code: &str,
+        entry: &str,
+        close: Option<&str>,
+        rate: Option<i64>,
+    ) -> SignalOutcomeRow {
+        let dec = |s: &str| BigDecimal::from_str(s).unwrap();
+        SignalOutcomeRow {
+            stock_code: code.to_string(),
+            stock_name: format!("股票{code}"),
+            entry_price: dec(entry),
+            profit_rate: rate,
+            close_price: close.map(dec),
+        }
+    }
+
+    #[test]
+    fn stats_only_rerun_keeps_completed_recap() {
+        assert!(keeps_existing_recap(
+            Some(REPORT_COMPLETED),
+            REPORT_STATS_ONLY
+        ));
+        assert!(!keeps_existing_recap(
+            Some(REPORT_COMPLETED),
+            REPORT_COMPLETED
+        ));
+        assert!(!keeps_existing_recap(
+            Some(REPORT_STATS_ONLY),
+            REPORT_STATS_ONLY
+        ));
+        assert!(!keeps_existing_recap(None, REPORT_STATS_ONLY));
+    }
+
+    #[test]
+    fn summarizes_previous_signals() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let rows = [
+            outcome("600001", "10", Some("11"), Some(2)),
+            outcome("600002", "20", Some("19"), Some(0)),
+            outcome("600003", "10", Some("10.5"), Some(1)),
+            outcome("600004", "10", None, None),
+        ];
+        let perf = summarize_performance(date, &rows);
+        assert_eq!(perf.signal_count, 4);
+        assert_eq!(perf.with_kline, 3);
+        assert_eq!(
+            (perf.strong_count, perf.touched_count, perf.weak_count),
+            (1, 1, 1)
+        );
+        // (10 - 5 + 5) / 3
+        assert_eq!(perf.avg_close_return_pct, Some(3.33));
+        assert_eq!(perf.up_ratio_pct, Some(66.67));
+        assert_eq!(perf.best[0].stock_code, "600001");
+        assert_eq!(perf.best.len(), 3);
+        assert!(perf.worst.is_empty());
+
+        let stats = MarketStatistics {
+            trade_date: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+            signal_stock_count: 3,
+            classified_stock_count: 2,
+            daily_counts: vec![],
+            hot_plates: vec![],
+            repeated_signals: vec![],
+            previous_performance: perf,
+        };
+        let text = render_statistics(&stats);
+        assert!(text.contains("## 2026-10-19 收盘统计"));
+        assert!(text.contains("### 上一交易日（2026-10-16）信号表现"));
+        assert!(text.contains("600001 股票600001（+10.00%）"));
+        assert!(render_statistics(&MarketStatistics {
+            previous_performance: SignalPerformance::default(),
+            ..stats
+        })
+        .contains("上一交易日没有信号"));
+    }
+}
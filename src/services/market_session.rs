@@ -0,0 +1,124 @@
+//! A 股日内交易时段划分：开盘集合竞价、连续竞价、午间休市、收盘集合竞价。
+//!
+//! 时段按「时:分」归属（忽略秒），使每分钟的 cron 触发与随后写入的快照落在同一时段，
+//! 例如 11:30 触发仍属上午连续竞价，15:00 触发属收盘集合竞价。
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSession {
+    /// 开盘集合竞价 9:15-9:29（9:25 撮合出开盘价）
+    PreOpenAuction,
+    /// 连续竞价 9:30-11:30、13:00-14:56
+    Continuous,
+    /// 午间休市 11:31-12:59
+    LunchBreak,
+    /// 收盘集合竞价 14:57-15:00
+    ClosingAuction,
+    /// 非交易时段（含非交易日）
+    Closed,
+}
+
+impl MarketSession {
+    pub const ALL: [MarketSession; 5] = [
+        MarketSession::PreOpenAuction,
+        MarketSession::Continuous,
+        MarketSession::LunchBreak,
+        MarketSession::ClosingAuction,
+        MarketSession::Closed,
+    ];
+
+    /// 交易日内某一时刻所处的时段
+    pub fn at(time: NaiveTime) -> Self {
+        let minute = time.hour() * 60 + time.minute();
+        let hm = |h: u32, m: u32| h * 60 + m;
+        match minute {
+            m if m >= hm(9, 15) && m < hm(9, 30) => MarketSession::PreOpenAuction,
+            m if m >= hm(9, 30) && m <= hm(11, 30) => MarketSession::Continuous,
+            m if m > hm(11, 30) && m < hm(13, 0) => MarketSession::LunchBreak,
+            m if m >= hm(13, 0) && m < hm(14, 57) => MarketSession::Continuous,
+            m if m >= hm(14, 57) && m <= hm(15, 0) => MarketSession::ClosingAuction,
+            _ => MarketSession::Closed,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketSession::PreOpenAuction => "pre_open_auction",
+            MarketSession::Continuous => "continuous",
+            MarketSession::LunchBreak => "lunch_break",
+            MarketSession::ClosingAuction => "closing_auction",
+            MarketSession::Closed => "closed",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            MarketSession::PreOpenAuction => "开盘集合竞价",
+            MarketSession::Continuous => "连续竞价",
+            MarketSession::LunchBreak => "午间休市",
+            MarketSession::ClosingAuction => "收盘集合竞价",
+            MarketSession::Closed => "休市",
+        }
+    }
+
+    /// 是否有成交撮合（连续竞价或收盘集合竞价），即盘中筛选是否有意义
+    pub fn is_trading(&self) -> bool {
+        matches!(
+            self,
+            MarketSession::Continuous | MarketSession::ClosingAuction
+        )
+    }
+}
+
+impl fmt::Display for MarketSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MarketSession {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MarketSession::ALL
+            .into_iter()
+            .find(|session| session.as_str() == s)
+            .ok_or_else(|| format!("unknown session: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32, s: u32) -> MarketSession {
+        MarketSession::at(NaiveTime::from_hms_opt(h, m, s).unwrap())
+    }
+
+    #[test]
+    fn classifies_by_minute_boundaries() {
+        assert_eq!(at(9, 14, 59), MarketSession::Closed);
+        assert_eq!(at(9, 25, 3), MarketSession::PreOpenAuction);
+        assert_eq!(at(9, 30, 0), MarketSession::Continuous);
+        assert_eq!(at(11, 30, 40), MarketSession::Continuous);
+        assert_eq!(at(11, 31, 0), MarketSession::LunchBreak);
+        assert_eq!(at(14, 56, 59), MarketSession::Continuous);
+        assert_eq!(at(14, 57, 0), MarketSession::ClosingAuction);
+        assert_eq!(at(15, 0, 30), MarketSession::ClosingAuction);
+        assert_eq!(at(15, 1, 0), MarketSession::Closed);
+    }
+
+    #[test]
+    fn round_trips_through_str() {
+        for session in MarketSession::ALL {
+            assert_eq!(session.as_str().parse::<MarketSession>(), Ok(session));
+        }
+        assert!("auction".parse::<MarketSession>().is_err());
+    }
+}
@@ -1,9 +1,27 @@
+pub mod ai_batch;
+pub mod ai_conversation;
+pub mod ai_evaluation;
+pub mod ai_result;
 pub mod ai_service;
+pub mod ai_usage;
 pub mod almanac;
+pub mod auction_capture;
 pub mod convertible_bond_query;
+pub mod convertible_bond_sync;
 pub mod daily_ma_cross;
+pub mod holiday_calendar;
+pub mod kline_audit;
+pub mod kline_backfill;
 pub mod kline_service;
+pub mod llm_provider;
+pub mod market_report;
+pub mod market_session;
 pub mod monthly_ma_cross;
 pub mod monthly_ma_cross_screen_cache;
+pub mod prompt_template;
 pub mod stock_filter;
+pub mod stock_master;
+pub mod stock_name_history;
 pub mod stock_plate_em;
+pub mod trading_calendar;
+pub mod trend_analysis;
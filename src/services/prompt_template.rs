@@ -0,0 +1,251 @@
+//! AI 分析提示词模板：模板存于 `prompt_templates`，按用途（template_key）分版本，
+//! 内容中的 `{{变量}}` 在调用前替换为本次分析的数据。
+//!
+//! 某用途尚无任何版本时，以内置提示词作为版本 1 写入并启用（首次调用或首次新建版本之前），
+//! 之后的修改都以新版本保存，每条分析记录都能对应到一个具体的模板版本。
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use diesel::PgConnection;
+use thiserror::Error;
+
+use crate::models::{NewPromptTemplate, PromptTemplate};
+use crate::repositories::prompt_template;
+
+/// 单股趋势分析
+pub const TEMPLATE_TREND_PREDICTION: &str = "trend_prediction";
+/// 趋势分析结果的追问对话
+pub const TEMPLATE_TREND_FOLLOWUP: &str = "trend_followup";
+/// 每日市场与板块复盘
+pub const TEMPLATE_MARKET_RECAP: &str = "market_recap";
+
+/// 趋势分析可用的变量
+const TREND_PREDICTION_VARIABLES: &[&str] = &[
+    "stock_code",
+    "stock_name",
+    "klines",
+    "signals",
+    "payload",
+    "kline_start_date",
+    "kline_end_date",
+    "signal_count",
+];
+
+/// 追问对话可用的变量
+const TREND_FOLLOWUP_VARIABLES: &[&str] = &[
+    "stock_code",
+    "stock_name",
+    "payload",
+    "analysis_date",
+    "model_name",
+];
+
+/// 每日复盘可用的变量
+const MARKET_RECAP_VARIABLES: &[&str] = &["trade_date", "statistics", "payload"];
+
+#[derive(Debug, Error)]
+pub enum PromptTemplateError {
+    #[error("prompt template not found: {0}")]
+    NotFound(String),
+    #[error("no active prompt template for {0}")]
+    NoActive(String),
+    #[error("database error: {0}")]
+    Db(#[from] diesel::result::Error),
+}
+
+/// 用途可用的变量，未知用途返回 None
+pub fn variables_for(key: &str) -> Option<&'static [&'static str]> {
+    match key {
+        TEMPLATE_TREND_PREDICTION => Some(TREND_PREDICTION_VARIABLES),
+        TEMPLATE_TREND_FOLLOWUP => Some(TREND_FOLLOWUP_VARIABLES),
+        TEMPLATE_MARKET_RECAP => Some(MARKET_RECAP_VARIABLES),
+        _ => None,
+    }
+}
+
+/// 内置的初始模板
+fn builtin(key: &str) -> Option<NewPromptTemplate> {
+    match key {
+        TEMPLATE_TREND_PREDICTION => Some(NewPromptTemplate {
+            template_key: key.to_string(),
+            version: 1,
+            name: "盘中启动信号全息回溯与趋势诊断".to_string(),
+            description: Some("内置提示词".to_string()),
+            system_prompt: include_str!(
+                "../asset/prompt/盘中启动信号全息回溯与趋势诊断系统 Prompt.md"
+            )
+            .to_string(),
+            user_template: "{{payload}}".to_string(),
+            is_active: true,
+        }),
+        TEMPLATE_TREND_FOLLOWUP => Some(NewPromptTemplate {
+            template_key: key.to_string(),
+            version: 1,
+            name: "趋势分析追问".to_string(),
+            description: Some("内置提示词".to_string()),
+            system_prompt: include_str!("../asset/prompt/趋势分析追问系统 Prompt.md").to_string(),
+            user_template: "{{payload}}".to_string(),
+            is_active: true,
+        }),
+        TEMPLATE_MARKET_RECAP => Some(NewPromptTemplate {
+            template_key: key.to_string(),
+            version: 1,
+            name: "每日市场与板块复盘".to_string(),
+            description: Some("内置提示词".to_string()),
+            system_prompt: include_str!("../asset/prompt/每日市场与板块复盘系统 Prompt.md")
+                .to_string(),
+            user_template: "{{statistics}}".to_string(),
+            is_active: true,
+        }),
+        _ => None,
+    }
+}
+
+/// 模板中出现的变量名（去重，按首次出现顺序）
+pub fn template_variables(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// 模板中该用途不支持的变量
+pub fn unknown_variables(key: &str, texts: &[&str]) -> Vec<String> {
+    let known = variables_for(key).unwrap_or_default();
+    let mut unknown: Vec<String> = Vec::new();
+    for text in texts {
+        for name in template_variables(text) {
+            if !known.contains(&name.as_str()) && !unknown.contains(&name) {
+                unknown.push(name);
+            }
+        }
+    }
+    unknown
+}
+
+/// 替换 `{{变量}}`（变量名两侧允许空格），未提供值的变量原样保留
+pub fn render(text: &str, vars: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        match vars.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 替换变量后的提示词
+#[derive(Debug, Clone)]
+pub struct RenderedPrompt {
+    pub template_id: i32,
+    pub version: i32,
+    pub system: String,
+    pub user: String,
+}
+
+pub fn render_template(template: &PromptTemplate, vars: &HashMap<&str, String>) -> RenderedPrompt {
+    RenderedPrompt {
+        template_id: template.id,
+        version: template.version,
+        system: render(&template.system_prompt, vars),
+        user: render(&template.user_template, vars),
+    }
+}
+
+/// 取指定版本，未指定时取启用版本；该用途还没有任何版本时写入内置模板
+pub fn resolve(
+    conn: &mut PgConnection,
+    key: &str,
+    version: Option<i32>,
+    now: NaiveDateTime,
+) -> Result<PromptTemplate, PromptTemplateError> {
+    if let Some(version) = version {
+        return prompt_template::find_by_version(conn, key, version)?
+            .ok_or_else(|| PromptTemplateError::NotFound(format!("{key} v{version}")));
+    }
+    if let Some(active) = prompt_template::find_active(conn, key)? {
+        return Ok(active);
+    }
+    if let Some(seeded) = seed_builtin(conn, key, now)? {
+        return Ok(seeded);
+    }
+    // 已有版本（或刚由其他请求写入），但没有启用的版本
+    prompt_template::find_active(conn, key)?
+        .ok_or_else(|| PromptTemplateError::NoActive(key.to_string()))
+}
+
+/// 该用途还没有任何版本时写入内置模板作为版本 1 并启用；已有版本时返回 None。
+/// 新建版本前先调用，使内置模板始终是版本 1，用户新建的版本可与之对照
+pub fn seed_builtin(
+    conn: &mut PgConnection,
+    key: &str,
+    now: NaiveDateTime,
+) -> Result<Option<PromptTemplate>, PromptTemplateError> {
+    let builtin = builtin(key).ok_or_else(|| PromptTemplateError::NotFound(key.to_string()))?;
+    let seeded = prompt_template::create_first_version(conn, &builtin, now)?;
+    if seeded.is_some() {
+        tracing::info!("写入内置提示词模板 {} 作为版本 1", key);
+    }
+    Ok(seeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_and_checks_variables() {
+        let vars = HashMap::from([
+            ("stock_name", "600519 贵州茅台".to_string()),
+            ("signal_count", "3".to_string()),
+        ]);
+        assert_eq!(
+            render(
+                "分析 {{ stock_name }}，共 {{signal_count}} 个信号{{unknown}}",
+                &vars
+            ),
+            "分析 600519 贵州茅台，共 3 个信号{{unknown}}"
+        );
+        assert_eq!(render("未闭合 {{stock_name", &vars), "未闭合 {{stock_name");
+
+        assert_eq!(
+            template_variables("{{klines}} {{ signals }} {{klines}}"),
+            vec!["klines", "signals"]
+        );
+        assert_eq!(
+            unknown_variables(TEMPLATE_TREND_PREDICTION, &["{{payload}}", "{{price}}"]),
+            vec!["price"]
+        );
+        for key in [
+            TEMPLATE_TREND_PREDICTION,
+            TEMPLATE_TREND_FOLLOWUP,
+            TEMPLATE_MARKET_RECAP,
+        ] {
+            assert!(builtin(key).is_some_and(|t| unknown_variables(
+                key,
+                &[&t.system_prompt, &t.user_template]
+            )
+            .is_empty()));
+        }
+    }
+}
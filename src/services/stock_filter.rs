@@ -10,6 +10,7 @@ use thiserror::Error;
 use tokio::sync::Semaphore;
 
 use crate::api_models::stock::FilteredStockItem;
+use crate::services::market_session::MarketSession;
 use crate::utils::percent::normalize_percent_scalar;
 use crate::utils::proxy::{proxy_get_json, shared_proxy_client, ProxyError};
 use crate::utils::secid::code_to_secid;
@@ -17,6 +18,11 @@ use crate::utils::secid::code_to_secid;
 const EM_LIST_URL: &str = "https://push2.eastmoney.com/api/qt/clist/get";
 const EM_DETAIL_URL: &str = "https://push2.eastmoney.com/api/qt/stock/get";
 
+/// 筛选使用的市场范围：沪深主板、创业板、科创板
+const FILTER_MARKETS: &str = "m:0 t:6,m:0 t:80,m:1 t:2,m:1 t:23";
+/// 筛选列表的字段：代码、名称、最高价、涨跌幅、量比、换手率、总市值
+const FILTER_FIELDS: &str = "f12,f14,f15,f3,f10,f8,f20";
+
 #[derive(Debug, Error)]
 pub enum StockFilterError {
     #[error("http error: {0}")]
@@ -36,6 +42,10 @@ pub struct FilterParams {
     pub lb_min: f64,
     pub hs_min: f64,
     pub wb_min: f64,
+    /// 总市值下限（亿元）
+    pub market_cap_min: Option<f64>,
+    /// 总市值上限（亿元）
+    pub market_cap_max: Option<f64>,
     pub concurrency: usize,
     pub limit: usize,
     pub pz: i32,
@@ -49,6 +59,8 @@ impl Default for FilterParams {
             lb_min: 5.0,
             hs_min: 1.0,
             wb_min: 20.0,
+            market_cap_min: None,
+            market_cap_max: None,
             concurrency: 8,
             limit: 0,
             pz: 1000,
@@ -56,6 +68,30 @@ impl Default for FilterParams {
     }
 }
 
+/// 按交易时段生效的定时筛选策略：仅当触发时刻所处时段在 `sessions` 中时执行，
+/// 结果以 `name` 作为 `stock_requests.strategy_name` 入库
+#[derive(Debug, Clone)]
+pub struct SessionFilterStrategy {
+    pub name: &'static str,
+    pub sessions: &'static [MarketSession],
+    pub params: FilterParams,
+}
+
+impl SessionFilterStrategy {
+    pub fn applies_to(&self, session: MarketSession) -> bool {
+        self.sessions.contains(&session)
+    }
+}
+
+/// 定时筛选任务使用的策略列表（连续竞价与收盘集合竞价沿用默认参数）
+pub fn session_filter_strategies() -> Vec<SessionFilterStrategy> {
+    vec![SessionFilterStrategy {
+        name: "filtered_param",
+        sessions: &[MarketSession::Continuous, MarketSession::ClosingAuction],
+        params: FilterParams::default(),
+    }]
+}
+
 pub async fn get_filtered_stocks_param(
     _client: &Client,
     params: FilterParams,
@@ -65,51 +101,9 @@ pub async fn get_filtered_stocks_param(
     let pz = params.pz.clamp(100, 5000);
     let headers = em_headers();
     let proxy_client = shared_proxy_client()?;
-
-    // page 1 for total and first diff
-    let first_url = build_list_url(1, pz)?;
-    let first = proxy_get_json(&proxy_client, first_url, &headers).await?;
-
-    let data = first.get("data").cloned().unwrap_or(Value::Null);
-    let total = data.get("total").and_then(|v| v.as_i64()).unwrap_or(0);
-    let mut all = Vec::new();
-    if let Some(diff) = data.get("diff").and_then(|v| v.as_array()) {
-        all.extend_from_slice(diff);
-    }
-    let pages = if total <= 0 {
-        1
-    } else {
-        (total as i32 + pz - 1) / pz
-    };
-
-    // fetch rest pages with limited concurrency
-    let semaphore = Arc::new(Semaphore::new(concurrency));
-    let mut handles = Vec::new();
-    for pn in 2..=pages {
-        let permit = semaphore.clone().acquire_owned().await.unwrap();
-        let proxy = proxy_client.clone();
-        let headers = headers.clone();
-        let h = tokio::spawn(async move {
-            let _p = permit;
-            let url = match build_list_url(pn, pz) {
-                Ok(url) => url,
-                Err(_) => return None,
-            };
-            (proxy_get_json(&proxy, url, &headers).await).ok()
-        });
-        handles.push(h);
-    }
-    for h in handles {
-        if let Ok(Some(v)) = h.await {
-            if let Some(arr) = v
-                .get("data")
-                .and_then(|d| d.get("diff"))
-                .and_then(|x| x.as_array())
-            {
-                all.extend_from_slice(arr);
-            }
-        }
-    }
+    let all = fetch_list(FILTER_MARKETS, FILTER_FIELDS, pz, concurrency)
+        .await?
+        .items;
 
     // build columns for polars
     let mut col_f12: Vec<Option<String>> = Vec::with_capacity(all.len());
@@ -118,6 +112,7 @@ pub async fn get_filtered_stocks_param(
     let mut col_f3: Vec<Option<f64>> = Vec::with_capacity(all.len());
     let mut col_f10: Vec<Option<f64>> = Vec::with_capacity(all.len());
     let mut col_f8: Vec<Option<f64>> = Vec::with_capacity(all.len());
+    let mut col_f20: Vec<Option<f64>> = Vec::with_capacity(all.len());
     for item in &all {
         let code = item
             .get("f12")
@@ -149,6 +144,7 @@ pub async fn get_filtered_stocks_param(
         col_f3.push(f3_v);
         col_f10.push(f10_v);
         col_f8.push(f8_v);
+        col_f20.push(item.get("f20").and_then(|v| v.as_f64()));
     }
 
     let df = DataFrame::new(vec![
@@ -158,9 +154,10 @@ pub async fn get_filtered_stocks_param(
         Series::new("f3", col_f3),
         Series::new("f10", col_f10),
         Series::new("f8", col_f8),
+        Series::new("f20", col_f20),
     ])?;
 
-    let lf = df
+    let mut lf = df
         .lazy()
         .filter(
             col("f3")
@@ -169,6 +166,13 @@ pub async fn get_filtered_stocks_param(
         )
         .filter(col("f10").gt(params.lb_min))
         .filter(col("f8").gt(params.hs_min));
+    // 总市值单位为元，参数为亿元
+    if let Some(min) = params.market_cap_min {
+        lf = lf.filter(col("f20").gt_eq(lit(min * 1e8)));
+    }
+    if let Some(max) = params.market_cap_max {
+        lf = lf.filter(col("f20").lt_eq(lit(max * 1e8)));
+    }
     let filtered = lf.collect()?;
 
     // collect codes
@@ -282,13 +286,86 @@ fn em_headers() -> HeaderMap {
     headers
 }
 
-fn build_list_url(pn: i32, pz: i32) -> Result<Url, StockFilterError> {
+/// 列表接口的分页拉取结果
+#[derive(Debug)]
+pub struct ListFetch {
+    /// 接口返回的总条数
+    pub total: i64,
+    pub items: Vec<Value>,
+    /// 请求失败的页数（大于 0 时 `items` 不完整）
+    pub failed_pages: usize,
+}
+
+/// 分页拉取东财列表接口：`fs` 为市场范围，`fields` 为返回字段，首页之外的页按 `concurrency` 并发
+pub async fn fetch_list(
+    fs: &str,
+    fields: &str,
+    pz: i32,
+    concurrency: usize,
+) -> Result<ListFetch, StockFilterError> {
+    let headers = em_headers();
+    let proxy_client = shared_proxy_client()?;
+
+    // page 1 for total and first diff
+    let first_url = build_list_url(fs, fields, 1, pz)?;
+    let first = proxy_get_json(&proxy_client, first_url, &headers).await?;
+
+    let data = first.get("data").cloned().unwrap_or(Value::Null);
+    let total = data.get("total").and_then(|v| v.as_i64()).unwrap_or(0);
+    let mut all = Vec::new();
+    if let Some(diff) = data.get("diff").and_then(|v| v.as_array()) {
+        all.extend_from_slice(diff);
+    }
+    let pages = if total <= 0 {
+        1
+    } else {
+        (total as i32 + pz - 1) / pz
+    };
+
+    // fetch rest pages with limited concurrency
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut handles = Vec::new();
+    for pn in 2..=pages {
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let proxy = proxy_client.clone();
+        let headers = headers.clone();
+        let (fs, fields) = (fs.to_string(), fields.to_string());
+        let h = tokio::spawn(async move {
+            let _p = permit;
+            let url = match build_list_url(&fs, &fields, pn, pz) {
+                Ok(url) => url,
+                Err(_) => return None,
+            };
+            (proxy_get_json(&proxy, url, &headers).await).ok()
+        });
+        handles.push(h);
+    }
+    let mut failed_pages = 0;
+    for h in handles {
+        match h.await {
+            Ok(Some(v)) => {
+                if let Some(arr) = v
+                    .get("data")
+                    .and_then(|d| d.get("diff"))
+                    .and_then(|x| x.as_array())
+                {
+                    all.extend_from_slice(arr);
+                }
+            }
+            _ => failed_pages += 1,
+        }
+    }
+    Ok(ListFetch {
+        total,
+        items: all,
+        failed_pages,
+    })
+}
+
+fn build_list_url(fs: &str, fields: &str, pn: i32, pz: i32) -> Result<Url, StockFilterError> {
     let params = vec![
-        (
-            "fs".to_string(),
-            "m:0 t:6,m:0 t:80,m:1 t:2,m:1 t:23".to_string(),
-        ),
-        ("fields".to_string(), "f12,f14,f15,f3,f10,f8".to_string()),
+        ("fs".to_string(), fs.to_string()),
+        ("fields".to_string(), fields.to_string()),
         ("fid".to_string(), "f3".to_string()),
         ("po".to_string(), "1".to_string()),
         ("np".to_string(), "1".to_string()),
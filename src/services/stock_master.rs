@@ -0,0 +1,238 @@
+//! 证券主数据：从东财 A 股列表（与 [`super::stock_filter`] 同一接口）全量同步 stock_table，
+//! 补充交易所、板块、上市日期、行业、股本、市值、ST 与上市状态。
+//!
+//! 列表只包含在市的股票：停牌股票的最新价为 `-`，记为 suspended；
+//! 此前同步过、本次不在列表中的代码记为 delisted。只有列表完整拉取时才做退市判断，
+//! 避免接口部分分页失败时把大量股票误标为退市。
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::Value;
+
+use crate::models::{StockMasterData, StockTable};
+use crate::services::stock_filter::{self, StockFilterError};
+use crate::utils::stock_name_filter::is_st_special_stock_name;
+
+/// 全部 A 股：沪深主板、创业板、科创板、北交所
+const MASTER_MARKETS: &str = "m:0 t:6,m:0 t:80,m:1 t:2,m:1 t:23,m:0 t:81 s:2048";
+/// 代码、市场、名称、最新价、总市值、流通市值、上市日期、总股本、流通股本、行业
+const MASTER_FIELDS: &str = "f12,f13,f14,f2,f20,f21,f26,f38,f39,f100";
+const MASTER_PAGE_SIZE: i32 = 1000;
+const MASTER_CONCURRENCY: usize = 4;
+/// 列表条数低于该值视为接口异常，不做退市判断
+const MIN_COMPLETE_LIST: usize = 4000;
+
+pub const STATUS_LISTED: &str = "listed";
+pub const STATUS_SUSPENDED: &str = "suspended";
+pub const STATUS_DELISTED: &str = "delisted";
+
+/// 列表中的一只股票
+#[derive(Debug, Clone, PartialEq)]
+pub struct MasterRecord {
+    pub code: String,
+    pub name: String,
+    pub exchange: &'static str,
+    pub board: &'static str,
+    pub listing_date: Option<NaiveDate>,
+    pub industry: Option<String>,
+    pub total_shares: Option<i64>,
+    pub float_shares: Option<i64>,
+    pub total_market_cap: Option<f64>,
+    pub float_market_cap: Option<f64>,
+    pub is_st: bool,
+    pub suspended: bool,
+}
+
+/// 按代码判断交易所与板块
+pub fn classify(code: &str) -> (&'static str, &'static str) {
+    if code.starts_with("688") || code.starts_with("689") {
+        ("SH", "star")
+    } else if code.starts_with('6') {
+        ("SH", "main")
+    } else if code.starts_with("300") || code.starts_with("301") {
+        ("SZ", "chinext")
+    } else if code.starts_with('4') || code.starts_with('8') || code.starts_with("92") {
+        ("BJ", "bse")
+    } else {
+        ("SZ", "main")
+    }
+}
+
+/// 东财在无数据时返回 `-`
+fn number(v: Option<&Value>) -> Option<f64> {
+    match v? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(v: Option<&Value>) -> Option<String> {
+    v.and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != "-")
+        .map(str::to_string)
+}
+
+/// 解析列表中的一条记录，代码或名称缺失时返回 None
+pub fn parse_record(item: &Value) -> Option<MasterRecord> {
+    let code = text(item.get("f12"))?;
+    let name = text(item.get("f14"))?;
+    let (exchange, board) = classify(&code);
+    let listing_date = number(item.get("f26"))
+        .filter(|d| *d > 0.0)
+        .and_then(|d| NaiveDate::parse_from_str(&format!("{}", d as i64), "%Y%m%d").ok());
+    Some(MasterRecord {
+        exchange,
+        board,
+        listing_date,
+        industry: text(item.get("f100")),
+        total_shares: number(item.get("f38")).map(|v| v as i64),
+        float_shares: number(item.get("f39")).map(|v| v as i64),
+        total_market_cap: number(item.get("f20")),
+        float_market_cap: number(item.get("f21")),
+        is_st: is_st_special_stock_name(&name),
+        suspended: number(item.get("f2")).is_none(),
+        code,
+        name,
+    })
+}
+
+/// 拉取的 A 股列表
+#[derive(Debug)]
+pub struct MasterList {
+    pub records: Vec<MasterRecord>,
+    /// 列表是否完整（可据此判断退市）
+    pub complete: bool,
+}
+
+pub async fn fetch_master_list() -> Result<MasterList, StockFilterError> {
+    let fetched = stock_filter::fetch_list(
+        MASTER_MARKETS,
+        MASTER_FIELDS,
+        MASTER_PAGE_SIZE,
+        MASTER_CONCURRENCY,
+    )
+    .await?;
+    let mut seen = HashSet::new();
+    let records: Vec<MasterRecord> = fetched
+        .items
+        .iter()
+        .filter_map(parse_record)
+        .filter(|r| seen.insert(r.code.clone()))
+        .collect();
+    let complete = fetched.failed_pages == 0
+        && records.len() as i64 >= fetched.total
+        && records.len() >= MIN_COMPLETE_LIST;
+    Ok(MasterList { records, complete })
+}
+
+/// 统一为纯数字代码（stock_table 中早期由快照写入的代码可能带市场前缀）
+pub fn normalize_code(code: &str) -> String {
+    code.trim()
+        .trim_start_matches(['S', 'H', 'Z', 's', 'h', 'z'])
+        .to_string()
+}
+
+fn decimal(v: Option<f64>) -> Option<BigDecimal> {
+    v.and_then(|v| BigDecimal::from_str(&format!("{v:.2}")).ok())
+}
+
+/// 由列表记录生成写入数据；已有记录时保留其代码写法，接口缺失的静态字段沿用旧值
+pub fn to_master_data(
+    record: &MasterRecord,
+    existing: Option<&StockTable>,
+    now: NaiveDateTime,
+) -> StockMasterData {
+    let status = if record.suspended {
+        STATUS_SUSPENDED
+    } else {
+        STATUS_LISTED
+    };
+    StockMasterData {
+        stock_code: existing.map_or_else(|| record.code.clone(), |s| s.stock_code.clone()),
+        stock_name: record.name.clone(),
+        exchange: Some(record.exchange.to_string()),
+        board: Some(record.board.to_string()),
+        listing_date: record
+            .listing_date
+            .or_else(|| existing.and_then(|s| s.listing_date)),
+        industry: record
+            .industry
+            .clone()
+            .or_else(|| existing.and_then(|s| s.industry.clone())),
+        total_shares: record
+            .total_shares
+            .or_else(|| existing.and_then(|s| s.total_shares)),
+        float_shares: record
+            .float_shares
+            .or_else(|| existing.and_then(|s| s.float_shares)),
+        total_market_cap: decimal(record.total_market_cap)
+            .or_else(|| existing.and_then(|s| s.total_market_cap.clone())),
+        float_market_cap: decimal(record.float_market_cap)
+            .or_else(|| existing.and_then(|s| s.float_market_cap.clone())),
+        is_st: record.is_st,
+        list_status: status.to_string(),
+        delisted_at: None,
+        master_synced_at: Some(now),
+        updated_at: now,
+    }
+}
+
+/// 已有记录中本次不在列表里、且尚未标记退市的股票 ID
+pub fn missing_ids(existing: &[StockTable], records: &[MasterRecord]) -> Vec<i32> {
+    let listed: HashSet<&str> = records.iter().map(|r| r.code.as_str()).collect();
+    existing
+        .iter()
+        .filter(|s| s.list_status != STATUS_DELISTED)
+        .filter(|s| !listed.contains(normalize_code(&s.stock_code).as_str()))
+        .map(|s| s.id)
+        .collect()
+}
+
+/// 已有记录按纯数字代码索引
+pub fn index_by_code(existing: &[StockTable]) -> HashMap<String, &StockTable> {
+    existing
+        .iter()
+        .map(|s| (normalize_code(&s.stock_code), s))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_list_items() {
+        let item = json!({
+            "f12": "688981", "f13": 1, "f14": "中芯国际", "f2": 88.5,
+            "f20": 7.05e11, "f21": 1.6e11, "f26": 20200716,
+            "f38": 7.96e9, "f39": 1.98e9, "f100": "半导体"
+        });
+        let r = parse_record(&item).unwrap();
+        assert_eq!((r.exchange, r.board), ("SH", "star"));
+        assert_eq!(r.listing_date, NaiveDate::from_ymd_opt(2020, 7, 16));
+        assert_eq!(r.industry.as_deref(), Some("半导体"));
+        assert_eq!(r.total_shares, Some(7_960_000_000));
+        assert!(!r.is_st && !r.suspended);
+
+        let suspended = json!({
+            "f12": "000004", "f14": "*ST国华", "f2": "-", "f20": "-", "f26": "-", "f100": "-"
+        });
+        let r = parse_record(&suspended).unwrap();
+        assert_eq!((r.exchange, r.board), ("SZ", "main"));
+        assert!(r.is_st && r.suspended);
+        assert_eq!(
+            (r.listing_date, r.industry, r.total_market_cap),
+            (None, None, None)
+        );
+
+        assert_eq!(classify("300750"), ("SZ", "chinext"));
+        assert_eq!(classify("920001"), ("BJ", "bse"));
+        assert_eq!(normalize_code("SH600519"), "600519");
+    }
+}
@@ -0,0 +1,148 @@
+//! 股票简称变更历史：快照中的名称停留在采集当时，stock_table 中的名称只反映最新状态，
+//! 按历史交易日判断 ST 需要当时有效的简称。
+//!
+//! 首次同步时由历史快照按「每天最后一条快照的名称」回填时间线，之后由证券主数据同步在名称变化时
+//! 关闭旧记录、写入新记录。快照回填的区间边界为首次观察到新名称的日期，两次采集之间的改名日期无法精确得知。
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use diesel::PgConnection;
+
+use crate::models::{NewStockNameHistory, StockNameHistory};
+use crate::repositories::{stock_name_history, stock_snapshot};
+use crate::utils::stock_name_filter::is_st_special_stock_name;
+
+pub const SOURCE_SNAPSHOT: &str = "snapshot";
+pub const SOURCE_MASTER: &str = "master";
+
+pub fn new_entry(
+    code: &str,
+    name: &str,
+    valid_from: NaiveDate,
+    source: &str,
+) -> NewStockNameHistory {
+    NewStockNameHistory {
+        stock_code: code.to_string(),
+        stock_name: name.to_string(),
+        is_st: is_st_special_stock_name(name),
+        valid_from,
+        valid_to: None,
+        source: source.to_string(),
+    }
+}
+
+/// 由一只股票按日期升序的每日名称生成时间线：连续相同的名称合并为一段，
+/// 每段的最后有效日为下一段生效日的前一天，最后一段保持开放
+pub fn build_ranges(code: &str, daily: &[(NaiveDate, String)]) -> Vec<NewStockNameHistory> {
+    let mut ranges: Vec<NewStockNameHistory> = Vec::new();
+    for (date, name) in daily {
+        if ranges.last().is_some_and(|r| &r.stock_name == name) {
+            continue;
+        }
+        if let Some(prev) = ranges.last_mut() {
+            prev.valid_to = date.pred_opt();
+        }
+        ranges.push(new_entry(code, name, *date, SOURCE_SNAPSHOT));
+    }
+    ranges
+}
+
+/// 名称历史为空时由历史快照回填，返回写入的记录数
+pub fn seed_from_snapshots(conn: &mut PgConnection) -> Result<usize, diesel::result::Error> {
+    if stock_name_history::count(conn)? > 0 {
+        return Ok(0);
+    }
+    let daily = stock_snapshot::list_daily_names(conn)?;
+    let mut by_code: Vec<(String, Vec<(NaiveDate, String)>)> = Vec::new();
+    for row in daily {
+        match by_code.last_mut() {
+            Some((code, names)) if *code == row.stock_code => {
+                names.push((row.trade_date, row.stock_name))
+            }
+            _ => by_code.push((row.stock_code, vec![(row.trade_date, row.stock_name)])),
+        }
+    }
+    let rows: Vec<NewStockNameHistory> = by_code
+        .iter()
+        .flat_map(|(code, names)| build_ranges(code, names))
+        .collect();
+    stock_name_history::insert_batch(conn, &rows)
+}
+
+/// 一次名称变化
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameChange {
+    pub stock_code: String,
+    /// 首次记录时为 None
+    pub old_name: Option<String>,
+    pub new_name: String,
+}
+
+/// 与当前名称比较，名称变化时写入新记录（`date` 起生效）；名称未变时返回 None
+pub fn record_name(
+    conn: &mut PgConnection,
+    open: &HashMap<String, StockNameHistory>,
+    code: &str,
+    name: &str,
+    date: NaiveDate,
+) -> Result<Option<NameChange>, diesel::result::Error> {
+    let row = new_entry(code, name, date, SOURCE_MASTER);
+    let old_name = match open.get(code) {
+        Some(current) if current.stock_name == name => return Ok(None),
+        Some(current) => {
+            stock_name_history::rename(conn, current, &row)?;
+            Some(current.stock_name.clone())
+        }
+        None => {
+            stock_name_history::insert(conn, &row)?;
+            None
+        }
+    };
+    Ok(Some(NameChange {
+        stock_code: code.to_string(),
+        old_name,
+        new_name: name.to_string(),
+    }))
+}
+
+/// 当前有效名称按代码索引
+pub fn index_open(rows: Vec<StockNameHistory>) -> HashMap<String, StockNameHistory> {
+    rows.into_iter()
+        .map(|r| (r.stock_code.clone(), r))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn merges_daily_names_into_ranges() {
+        let daily = vec![
+            (d(2), "华塑控股".to_string()),
+            (d(3), "华塑控股".to_string()),
+            (d(9), "*ST华塑".to_string()),
+            (d(10), "*ST华塑".to_string()),
+            (d(16), "华塑控股".to_string()),
+        ];
+        let ranges = build_ranges("000509", &daily);
+        let spans: Vec<_> = ranges
+            .iter()
+            .map(|r| (r.stock_name.as_str(), r.is_st, r.valid_from, r.valid_to))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("华塑控股", false, d(2), Some(d(8))),
+                ("*ST华塑", true, d(9), Some(d(15))),
+                ("华塑控股", false, d(16), None),
+            ]
+        );
+        assert!(build_ranges("000509", &[]).is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! A 股交易日历：以 `stock_trading_calendar` 表为准，加载到内存后提供前后交易日、
+//! 区间交易日计数、当前所处交易时段等查询。
+//!
+//! 表中未覆盖的日期退化为「周一至周五为交易日」，保证空表部署时调度仍可运行。
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Utc, Weekday};
+use chrono_tz::Asia::Shanghai;
+use tokio::sync::RwLock;
+
+use crate::app::DbPool;
+use crate::models::StockTradingCalendar;
+use crate::repositories::stock_trading_calendar;
+use crate::services::market_session::MarketSession;
+
+/// 缓存有效期：超过后下一次查询会重新从数据库加载
+const CALENDAR_CACHE_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// 向前 / 向后查找交易日的最大自然日跨度（春节长假 + 周末也远小于此值）
+const MAX_SCAN_DAYS: i64 = 60;
+
+/// 当前上海时间（不带时区）
+pub fn shanghai_now() -> NaiveDateTime {
+    Utc::now().with_timezone(&Shanghai).naive_local()
+}
+
+/// 某一时刻的交易日历视图（不可变，可在同步代码中直接使用）
+#[derive(Debug, Default)]
+pub struct TradingDays {
+    /// 表中登记为交易日（is_holiday = false）的日期
+    trading_days: BTreeSet<NaiveDate>,
+    /// 休市日名称（法定节假日 / 周末）
+    holiday_names: BTreeMap<NaiveDate, String>,
+    /// 表中登记过的日期范围（含首尾），范围外按工作日推断
+    covered: Option<(NaiveDate, NaiveDate)>,
+}
+
+impl TradingDays {
+    pub fn from_rows(rows: &[StockTradingCalendar]) -> Self {
+        let trading_days = rows
+            .iter()
+            .filter(|r| !r.is_holiday)
+            .map(|r| r.trade_date)
+            .collect();
+        let holiday_names = rows
+            .iter()
+            .filter(|r| r.is_holiday)
+            .filter_map(|r| r.holiday_name.clone().map(|name| (r.trade_date, name)))
+            .collect();
+        let first = rows.iter().map(|r| r.trade_date).min();
+        let last = rows.iter().map(|r| r.trade_date).max();
+        Self {
+            trading_days,
+            holiday_names,
+            covered: first.zip(last),
+        }
+    }
+
+    /// 日历表覆盖的日期范围
+    pub fn covered_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        self.covered
+    }
+
+    /// 休市日在日历表中登记的名称
+    pub fn holiday_name(&self, date: NaiveDate) -> Option<&str> {
+        self.holiday_names.get(&date).map(String::as_str)
+    }
+
+    /// 是否为交易日（日历覆盖范围外按工作日推断）
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        match self.covered {
+            Some((first, last)) if date >= first && date <= last => {
+                self.trading_days.contains(&date)
+            }
+            _ => !matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+        }
+    }
+
+    /// 严格晚于 `date` 的下一个交易日
+    pub fn next_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut d = date;
+        for _ in 0..MAX_SCAN_DAYS {
+            d = d.succ_opt().unwrap_or(d);
+            if self.is_trading_day(d) {
+                return d;
+            }
+        }
+        d
+    }
+
+    /// 严格早于 `date` 的上一个交易日
+    pub fn prev_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut d = date;
+        for _ in 0..MAX_SCAN_DAYS {
+            d = d.pred_opt().unwrap_or(d);
+            if self.is_trading_day(d) {
+                return d;
+            }
+        }
+        d
+    }
+
+    /// `date` 本身是交易日则返回它，否则返回之前最近的交易日
+    pub fn trading_day_on_or_before(&self, date: NaiveDate) -> NaiveDate {
+        if self.is_trading_day(date) {
+            date
+        } else {
+            self.prev_trading_day(date)
+        }
+    }
+
+    /// `date` 之前（不含当天）的第 `n` 个交易日；`n = 0` 时返回 `date`
+    pub fn nth_trading_day_before(&self, date: NaiveDate, n: usize) -> NaiveDate {
+        (0..n).fold(date, |d, _| self.prev_trading_day(d))
+    }
+
+    /// `date` 之后（不含当天）的第 `n` 个交易日；`n = 0` 时返回 `date`
+    pub fn nth_trading_day_after(&self, date: NaiveDate, n: usize) -> NaiveDate {
+        (0..n).fold(date, |d, _| self.next_trading_day(d))
+    }
+
+    /// `[start, end]` 闭区间内的交易日数量；`start > end` 时为 0
+    pub fn count_trading_days(&self, start: NaiveDate, end: NaiveDate) -> usize {
+        start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_trading_day(*d))
+            .count()
+    }
+
+    /// `[start, end]` 闭区间内的全部交易日（升序）
+    pub fn trading_days_between(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_trading_day(*d))
+            .collect()
+    }
+
+    /// 给定上海时间所处的交易时段，非交易日恒为 [`MarketSession::Closed`]
+    pub fn session_at(&self, at: NaiveDateTime) -> MarketSession {
+        if !self.is_trading_day(at.date()) {
+            return MarketSession::Closed;
+        }
+        MarketSession::at(at.time())
+    }
+
+    /// 给定上海时间是否处于可成交时段（9:30-11:30、13:00-15:00，首尾均含，含收盘集合竞价）
+    pub fn is_session_open_at(&self, at: NaiveDateTime) -> bool {
+        self.session_at(at).is_trading()
+    }
+}
+
+struct CalendarCache {
+    days: Arc<TradingDays>,
+    loaded_at: Option<Instant>,
+}
+
+/// 带缓存的交易日历服务，克隆后共享同一份缓存
+#[derive(Clone)]
+pub struct TradingCalendar {
+    inner: Arc<RwLock<CalendarCache>>,
+}
+
+impl Default for TradingCalendar {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(CalendarCache {
+                days: Arc::new(TradingDays::default()),
+                loaded_at: None,
+            })),
+        }
+    }
+}
+
+impl TradingCalendar {
+    /// 获取当前日历视图；缓存过期或尚未加载时先从数据库刷新。
+    /// 刷新失败时沿用旧缓存（或工作日推断），不会向调用方报错。
+    pub async fn days(&self, db_pool: &DbPool) -> Arc<TradingDays> {
+        {
+            let guard = self.inner.read().await;
+            if matches!(guard.loaded_at, Some(t) if t.elapsed() < CALENDAR_CACHE_TTL) {
+                return guard.days.clone();
+            }
+        }
+        if let Err(e) = self.reload(db_pool).await {
+            tracing::warn!("加载交易日历失败，沿用缓存/工作日推断: {}", e);
+        }
+        self.inner.read().await.days.clone()
+    }
+
+    /// 强制从数据库重新加载，返回加载到的记录数
+    pub async fn reload(&self, db_pool: &DbPool) -> anyhow::Result<usize> {
+        let rows = {
+            let mut conn = db_pool.get()?;
+            stock_trading_calendar::list_all(&mut conn)?
+        };
+        let days = TradingDays::from_rows(&rows);
+        let mut guard = self.inner.write().await;
+        guard.days = Arc::new(days);
+        guard.loaded_at = Some(Instant::now());
+        tracing::info!("交易日历已加载，共 {} 条记录", rows.len());
+        Ok(rows.len())
+    }
+
+    /// 今天（上海时间）是否为交易日
+    pub async fn is_trading_day_today(&self, db_pool: &DbPool) -> bool {
+        self.days(db_pool)
+            .await
+            .is_trading_day(shanghai_now().date())
+    }
+
+    /// 当前（上海时间）所处的交易时段
+    pub async fn session_now(&self, db_pool: &DbPool) -> MarketSession {
+        self.days(db_pool).await.session_at(shanghai_now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn row(date: NaiveDate, is_holiday: bool) -> StockTradingCalendar {
+        StockTradingCalendar {
+            id: 0,
+            trade_date: date,
+            is_holiday,
+            holiday_name: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// 2026-09-28 ~ 2026-10-11，其中 10-01 ~ 10-07 国庆休市
+    fn national_day_2026() -> TradingDays {
+        let rows: Vec<_> = d(2026, 9, 28)
+            .iter_days()
+            .take_while(|x| *x <= d(2026, 10, 11))
+            .map(|x| {
+                let weekend = matches!(x.weekday(), Weekday::Sat | Weekday::Sun);
+                let golden_week = x >= d(2026, 10, 1) && x <= d(2026, 10, 7);
+                row(x, weekend || golden_week)
+            })
+            .collect();
+        TradingDays::from_rows(&rows)
+    }
+
+    #[test]
+    fn skips_statutory_holidays_inside_covered_range() {
+        let days = national_day_2026();
+        assert!(!days.is_trading_day(d(2026, 10, 5)));
+        assert_eq!(days.next_trading_day(d(2026, 9, 30)), d(2026, 10, 8));
+        assert_eq!(days.prev_trading_day(d(2026, 10, 8)), d(2026, 9, 30));
+        assert_eq!(
+            days.trading_day_on_or_before(d(2026, 10, 4)),
+            d(2026, 9, 30)
+        );
+        assert_eq!(days.count_trading_days(d(2026, 9, 28), d(2026, 10, 11)), 5);
+    }
+
+    #[test]
+    fn falls_back_to_weekdays_outside_covered_range() {
+        let days = TradingDays::default();
+        // 2026-10-16 为周五
+        assert_eq!(days.next_trading_day(d(2026, 10, 16)), d(2026, 10, 19));
+        assert_eq!(
+            days.nth_trading_day_before(d(2026, 10, 19), 2),
+            d(2026, 10, 15)
+        );
+        assert_eq!(
+            days.nth_trading_day_after(d(2026, 10, 15), 2),
+            d(2026, 10, 19)
+        );
+    }
+
+    #[test]
+    fn session_window_boundaries() {
+        let days = TradingDays::default();
+        let at = |h, m| d(2026, 10, 16).and_hms_opt(h, m, 0).unwrap();
+        assert!(!days.is_session_open_at(at(9, 29)));
+        assert!(days.is_session_open_at(at(11, 30)));
+        assert!(!days.is_session_open_at(at(12, 0)));
+        assert!(days.is_session_open_at(at(15, 0)));
+        assert!(!days.is_session_open_at(d(2026, 10, 17).and_hms_opt(10, 0, 0).unwrap()));
+    }
+}
@@ -0,0 +1,483 @@
+//! 单股趋势分析流程：查询信号与K线（不完整时自动补齐）、按模板生成提示词、
+//! 写入分析记录并调用模型。单次分析接口与批量分析任务共用。
+//!
+//! 输入内容（模型、提示词模板版本、K线与信号数据）的哈希存于 `input_hash`，
+//! 相同输入已有成功结果时直接返回该记录，不再调用模型（`force` 时跳过缓存）。
+//! 调用模型前检查用量预算，调用后记录 token 用量与费用，见 [`ai_usage`]。
+
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use chrono::{FixedOffset, NaiveDate, Utc};
+use diesel::r2d2::PoolError;
+use diesel::PgConnection;
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::app::DbPool;
+use crate::models::{AiTrendAnalysis, NewAiTrendAnalysis, PromptTemplate};
+use crate::repositories::{ai_trend_analysis, daily_kline};
+use crate::services::ai_service::{AnalysisEvent, EventSink};
+use crate::services::llm_provider::{LlmProvider, TokenUsage};
+use crate::services::prompt_template;
+use crate::services::trading_calendar::TradingCalendar;
+use crate::services::{ai_service, ai_usage, kline_service};
+use crate::utils::http_client;
+
+#[derive(Debug, Error)]
+pub enum TrendAnalysisError {
+    #[error("股票 {0} 没有信号数据（stock_snapshots 中无记录）")]
+    NoSignals(String),
+    #[error("connection pool error: {0}")]
+    Pool(#[from] PoolError),
+    #[error("database error: {0}")]
+    Db(#[from] diesel::result::Error),
+    #[error("kline fill error: {0}")]
+    KlineFill(String),
+    #[error("{0}，暂停新的 AI 分析")]
+    BudgetExceeded(String),
+}
+
+/// 一次分析的结果：已更新的分析记录（状态可能为 failed / invalid）及 token 用量；
+/// 命中缓存时为此前的记录，用量为 0
+#[derive(Debug)]
+pub struct StockAnalysis {
+    pub record: AiTrendAnalysis,
+    pub usage: TokenUsage,
+    pub cache_hit: bool,
+}
+
+/// 输入内容哈希：模型、提示词模板（ID 与版本）与发送给模型的数据
+pub fn input_hash(model_name: &str, template: &PromptTemplate, payload: &JsonValue) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "v1|{model_name}|{}|{}|",
+        template.id, template.version
+    ));
+    hasher.update(payload.to_string());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 对一只股票执行完整的趋势分析，并把结果写入 `ai_trend_analysis`；
+/// 传入 `events` 时以流式调用模型，过程事件交给回调
+pub async fn analyze_stock(
+    db_pool: &DbPool,
+    calendar: &TradingCalendar,
+    provider: &dyn LlmProvider,
+    template: &PromptTemplate,
+    stock_code: &str,
+    force: bool,
+    events: Option<EventSink<'_>>,
+) -> Result<StockAnalysis, TrendAnalysisError> {
+    let start_time = std::time::Instant::now();
+
+    // 1. 查询 stock_snapshots 获取该股票的信号数据
+    let (signals, stock_name) = query_stock_signals(&mut *db_pool.get()?, stock_code)?;
+    if signals.is_empty() {
+        return Err(TrendAnalysisError::NoSignals(stock_code.to_string()));
+    }
+
+    // 2. 找到最早信号日期
+    let earliest_signal_date = signals.iter().map(|s| s.signal_date).min().unwrap();
+
+    // 3. 基于交易日历，从最早信号日往前找20个交易日
+    let kline_start_date = calendar
+        .days(db_pool)
+        .await
+        .nth_trading_day_before(earliest_signal_date, 20);
+
+    // 4. 结束日期为当前日期（UTC+8）
+    let utc_plus_8 = FixedOffset::east_opt(8 * 3600).unwrap();
+    let today = Utc::now().with_timezone(&utc_plus_8).date_naive();
+
+    // 5. 查询 daily_klines 获取K线数据
+    let klines = query_kline_range(&mut *db_pool.get()?, stock_code, kline_start_date, today)?;
+
+    // 6. 检查K线数据是否完整，不完整则自动补齐
+    let klines = if klines.is_empty() || needs_kline_fill(&klines, kline_start_date, today) {
+        tracing::info!(
+            "K线数据不完整，自动补齐 {} 从 {} 到 {}",
+            stock_code,
+            kline_start_date,
+            today
+        );
+        fill_missing_klines(db_pool, stock_code, kline_start_date, today).await?;
+        // 重新查询完整数据
+        query_kline_range(&mut *db_pool.get()?, stock_code, kline_start_date, today)?
+    } else {
+        klines
+    };
+
+    // 7. 组装 send_to_ai.json 格式的 payload
+    let kline_strings: Vec<String> = klines
+        .iter()
+        .map(|k| {
+            format!(
+                "{},{},{},{},{},{}",
+                k.trade_date, k.open_price, k.close_price, k.high_price, k.low_price, k.volume
+            )
+        })
+        .collect();
+
+    let signal_json: Vec<serde_json::Value> = signals
+        .iter()
+        .map(|s| {
+            json!({
+                "datetime": s.datetime,
+                "change_pct": s.change_pct,
+                "volume_ratio": s.volume_ratio,
+                "turnover_rate": s.turnover_rate,
+                "bid_ask_ratio": s.bid_ask_ratio,
+            })
+        })
+        .collect();
+
+    let display_name = format!("{} {}", stock_code, stock_name.as_deref().unwrap_or(""));
+    let ai_payload = json!({
+        "stock_name": display_name,
+        "klines": kline_strings,
+        "signals": signal_json,
+    });
+    let pretty = |v: &serde_json::Value| serde_json::to_string_pretty(v).unwrap_or_default();
+    let vars = HashMap::from([
+        ("stock_code", stock_code.to_string()),
+        ("stock_name", display_name.clone()),
+        ("klines", kline_strings.join("\n")),
+        ("signals", pretty(&json!(signal_json))),
+        ("payload", pretty(&ai_payload)),
+        ("kline_start_date", kline_start_date.to_string()),
+        ("kline_end_date", today.to_string()),
+        ("signal_count", signals.len().to_string()),
+    ]);
+    let prompt = prompt_template::render_template(template, &vars);
+
+    // 相同输入已有成功结果时直接复用
+    let model_name = provider.model_name();
+    let hash = input_hash(&model_name, template, &ai_payload);
+    if !force {
+        if let Some(cached) = ai_trend_analysis::find_cached(&mut db_pool.get()?, &hash)? {
+            tracing::info!(
+                "{} 输入未变化，复用分析记录 #{}（{}）",
+                stock_code,
+                cached.id,
+                model_name
+            );
+            return Ok(StockAnalysis {
+                record: cached,
+                usage: TokenUsage::default(),
+                cache_hit: true,
+            });
+        }
+    }
+
+    if let Some(reason) = ai_usage::check_budget(&mut db_pool.get()?)? {
+        return Err(TrendAnalysisError::BudgetExceeded(reason));
+    }
+
+    // 8. 先创建一条 processing 状态的记录
+    let new_record = NewAiTrendAnalysis {
+        stock_code: stock_code.to_string(),
+        stock_name: stock_name.clone(),
+        model_name,
+        status: "processing".to_string(),
+        request_payload: ai_payload,
+        response_json: None,
+        raw_response: None,
+        signal_count: Some(signals.len() as i32),
+        kline_start_date: Some(kline_start_date),
+        kline_end_date: Some(today),
+        error_message: None,
+        duration_ms: None,
+        prompt_template_id: Some(prompt.template_id),
+        prompt_version: Some(prompt.version),
+        input_hash: Some(hash),
+    };
+    let record = ai_trend_analysis::create(&mut db_pool.get()?, &new_record)?;
+    if let Some(sink) = events {
+        sink(AnalysisEvent::Started {
+            analysis_id: record.id,
+            model_name: record.model_name.clone(),
+        });
+    }
+
+    // 9. 调用模型
+    let ai_result = ai_service::run_trend_analysis(provider, &prompt, events).await;
+    let duration_ms = start_time.elapsed().as_millis() as i64;
+
+    // 10. 更新记录
+    let usage = match &ai_result {
+        Ok(result) => result.usage,
+        Err(e) => {
+            tracing::error!("AI analysis failed for {}: {}", stock_code, e);
+            TokenUsage::default()
+        }
+    };
+    let mut update_data = ai_service::to_update(ai_result, duration_ms);
+    if usage.total() > 0 {
+        update_data.prompt_tokens = Some(usage.prompt_tokens);
+        update_data.completion_tokens = Some(usage.completion_tokens);
+        update_data.total_tokens = Some(usage.total());
+        update_data.cost = ai_usage::cost_for(&mut *db_pool.get()?, &record.model_name, usage)?;
+    }
+    let record = ai_trend_analysis::update_by_id(&mut db_pool.get()?, record.id, &update_data)?;
+    Ok(StockAnalysis {
+        record,
+        usage,
+        cache_hit: false,
+    })
+}
+
+/// 信号数据
+struct SignalData {
+    datetime: String,
+    signal_date: NaiveDate,
+    change_pct: f64,
+    volume_ratio: f64,
+    turnover_rate: f64,
+    bid_ask_ratio: f64,
+}
+
+/// K线数据
+struct KlineData {
+    trade_date: NaiveDate,
+    open_price: String,
+    close_price: String,
+    high_price: String,
+    low_price: String,
+    volume: i64,
+}
+
+/// 查询股票的信号数据（从 stock_snapshots）
+fn query_stock_signals(
+    conn: &mut PgConnection,
+    stock_code_input: &str,
+) -> Result<(Vec<SignalData>, Option<String>), diesel::result::Error> {
+    use chrono::{DateTime, Utc as ChronoUtc};
+    use diesel::prelude::*;
+    use diesel::sql_types::{Numeric, Text, Timestamptz};
+
+    #[derive(Debug, QueryableByName)]
+    struct SnapshotSignal {
+        #[diesel(sql_type = Timestamptz)]
+        created_at: DateTime<ChronoUtc>,
+        #[diesel(sql_type = Numeric)]
+        change_pct: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        volume_ratio: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        turnover_rate: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        bid_ask_ratio: BigDecimal,
+        #[diesel(sql_type = Text)]
+        stock_name: String,
+    }
+
+    let query = r#"
+        SELECT
+            created_at,
+            change_pct,
+            volume_ratio,
+            turnover_rate,
+            bid_ask_ratio,
+            stock_name
+        FROM stock_snapshots
+        WHERE stock_code = $1
+        ORDER BY created_at ASC
+    "#;
+
+    let results: Vec<SnapshotSignal> = diesel::sql_query(query)
+        .bind::<Text, _>(stock_code_input)
+        .load(conn)
+        .inspect_err(|e| tracing::error!("Failed to query stock signals: {}", e))?;
+
+    let stock_name = results.first().map(|r| r.stock_name.clone());
+
+    let utc_plus_8 = FixedOffset::east_opt(8 * 3600).unwrap();
+    let signals: Vec<SignalData> = results
+        .into_iter()
+        .map(|r| {
+            let local_time = r.created_at.with_timezone(&utc_plus_8);
+            let signal_date = local_time.date_naive();
+            let datetime = local_time.format("%Y-%m-%d %H:%M:%S%.3f %z").to_string();
+            SignalData {
+                datetime,
+                signal_date,
+                change_pct: bigdecimal_to_f64(&r.change_pct),
+                volume_ratio: bigdecimal_to_f64(&r.volume_ratio),
+                turnover_rate: bigdecimal_to_f64(&r.turnover_rate),
+                bid_ask_ratio: bigdecimal_to_f64(&r.bid_ask_ratio),
+            }
+        })
+        .collect();
+
+    Ok((signals, stock_name))
+}
+
+/// 查询 K线数据范围
+fn query_kline_range(
+    conn: &mut PgConnection,
+    stock_code_input: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<KlineData>, diesel::result::Error> {
+    use diesel::prelude::*;
+    use diesel::sql_types::{BigInt, Date, Numeric, Text};
+
+    #[derive(Debug, QueryableByName)]
+    struct KlineRow {
+        #[diesel(sql_type = Date)]
+        trade_date: NaiveDate,
+        #[diesel(sql_type = Numeric)]
+        open_price: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        close_price: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        high_price: BigDecimal,
+        #[diesel(sql_type = Numeric)]
+        low_price: BigDecimal,
+        #[diesel(sql_type = BigInt)]
+        volume: i64,
+    }
+
+    let query = r#"
+        SELECT trade_date, open_price, close_price, high_price, low_price, volume
+        FROM daily_klines
+        WHERE stock_code = $1
+          AND trade_date >= $2
+          AND trade_date <= $3
+        ORDER BY trade_date ASC
+    "#;
+
+    let results: Vec<KlineRow> = diesel::sql_query(query)
+        .bind::<Text, _>(stock_code_input)
+        .bind::<Date, _>(start)
+        .bind::<Date, _>(end)
+        .load(conn)
+        .inspect_err(|e| tracing::error!("Failed to query kline range: {}", e))?;
+
+    Ok(results
+        .into_iter()
+        .map(|r| KlineData {
+            trade_date: r.trade_date,
+            open_price: r.open_price.to_string(),
+            close_price: r.close_price.to_string(),
+            high_price: r.high_price.to_string(),
+            low_price: r.low_price.to_string(),
+            volume: r.volume,
+        })
+        .collect())
+}
+
+/// 检查K线数据是否需要补齐
+/// 判断依据：数据条数少于5，或者首条K线日期晚于期望起始日期（说明前面的数据缺失）
+fn needs_kline_fill(klines: &[KlineData], start: NaiveDate, _end: NaiveDate) -> bool {
+    if klines.len() < 5 {
+        return true;
+    }
+    // 如果首条K线的日期比期望起始日期晚超过3个自然日，说明前面的数据缺失
+    if let Some(first) = klines.first() {
+        if first.trade_date > start + chrono::Duration::days(3) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 自动补齐K线数据
+async fn fill_missing_klines(
+    db_pool: &DbPool,
+    stock_code_input: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<(), TrendAnalysisError> {
+    let client = http_client::create_em_client().map_err(|e| {
+        tracing::error!("Failed to create HTTP client: {}", e);
+        TrendAnalysisError::KlineFill(e.to_string())
+    })?;
+
+    let start_str = start.format("%Y%m%d").to_string();
+    let end_str = end.format("%Y%m%d").to_string();
+
+    let kline_result =
+        kline_service::fetch_and_parse_kline_data(&client, stock_code_input, &start_str, &end_str)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to fetch kline data for {}: {}", stock_code_input, e);
+                TrendAnalysisError::KlineFill(e.to_string())
+            })?;
+
+    tracing::info!(
+        "Fetched {} klines for {}, inserting into DB",
+        kline_result.parsed.len(),
+        stock_code_input
+    );
+
+    // 批量写入数据库
+    for kline_data in &kline_result.parsed {
+        let mut conn = db_pool.get()?;
+        match daily_kline::create(&mut conn, kline_data) {
+            Ok(_) => {}
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => {
+                // 已存在，跳过
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to insert kline for {} on {}: {}",
+                    stock_code_input,
+                    kline_data.trade_date,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// BigDecimal 转 f64
+fn bigdecimal_to_f64(val: &BigDecimal) -> f64 {
+    use std::str::FromStr;
+    f64::from_str(&val.to_string()).unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn input_hash_changes_with_model_template_and_data() {
+        let template = PromptTemplate {
+            id: 1,
+            template_key: prompt_template::TEMPLATE_TREND_PREDICTION.to_string(),
+            version: 1,
+            name: "t".to_string(),
+            description: None,
+            system_prompt: String::new(),
+            user_template: "{{payload}}".to_string(),
+            is_active: true,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        };
+        let payload = json!({"klines": ["2026-10-16,1,2,3,1,100"], "signals": []});
+        let hash = input_hash("qwen/qwen3-max", &template, &payload);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, input_hash("qwen/qwen3-max", &template, &payload));
+        assert_ne!(hash, input_hash("local/qwen2.5", &template, &payload));
+        let v2 = PromptTemplate {
+            version: 2,
+            ..template.clone()
+        };
+        assert_ne!(hash, input_hash("qwen/qwen3-max", &v2, &payload));
+        let newer = json!({"klines": ["2026-10-17,1,2,3,1,100"], "signals": []});
+        assert_ne!(hash, input_hash("qwen/qwen3-max", &template, &newer));
+    }
+}
@@ -3,9 +3,8 @@ use reqwest::{
     Client,
 };
 
-/// 创建用于东方财富 API 的 HTTP 客户端
-/// 包含必要的请求头模拟浏览器访问
-pub fn create_em_client() -> Result<Client, reqwest::Error> {
+/// 东方财富 API 所需的请求头（模拟浏览器访问）
+pub fn em_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(
         USER_AGENT,
@@ -22,6 +21,11 @@ pub fn create_em_client() -> Result<Client, reqwest::Error> {
         HeaderValue::from_static("https://quote.eastmoney.com"),
     );
     headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+    headers
+}
 
-    Client::builder().default_headers(headers).build()
+/// 创建用于东方财富 API 的 HTTP 客户端
+/// 包含必要的请求头模拟浏览器访问
+pub fn create_em_client() -> Result<Client, reqwest::Error> {
+    Client::builder().default_headers(em_headers()).build()
 }
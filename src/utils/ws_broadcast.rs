@@ -6,6 +6,15 @@ pub struct TaskStatusMessage {
     pub job_name: String,
     pub status: String,
     pub timestamp: i64,
+    /// 运行中的进度（仅 running 状态的进度消息携带）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<TaskProgress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskProgress {
+    pub done: usize,
+    pub total: usize,
 }
 
 pub type TaskStatusSender = broadcast::Sender<TaskStatusMessage>;
@@ -23,6 +32,7 @@ pub fn broadcast_task_status(sender: &TaskStatusSender, job_name: String, status
         job_name,
         status,
         timestamp: chrono::Utc::now().timestamp_millis(),
+        progress: None,
     };
 
     let job_name = msg.job_name.clone();
@@ -30,3 +40,18 @@ pub fn broadcast_task_status(sender: &TaskStatusSender, job_name: String, status
     let _ = sender.send(msg);
     tracing::debug!("广播任务状态: {} -> {}", job_name, status);
 }
+
+pub fn broadcast_task_progress(
+    sender: &TaskStatusSender,
+    job_name: String,
+    done: usize,
+    total: usize,
+) {
+    let msg = TaskStatusMessage {
+        job_name,
+        status: "running".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        progress: Some(TaskProgress { done, total }),
+    };
+    let _ = sender.send(msg);
+}